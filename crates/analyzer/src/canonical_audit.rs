@@ -0,0 +1,435 @@
+//! Canonical audit - `<link rel=canonical>` consistency across a site
+//!
+//! Resolves each HTML page's canonical against the page's own URL and maps it
+//! back to an analyzed page. Page URLs follow from paths under the root:
+//! `blog/index.html` is served at `/blog/`, `about.html` at `/about.html` or
+//! `/about`. Without a site URL, the origin most canonicals point at stands in.
+
+use crate::link_audit::bare_host;
+use crate::{is_html_file, FileAnalysis};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use url::Url;
+
+/// Origin used when neither a site URL nor an absolute canonical is known
+const FALLBACK_ORIGIN: &str = "https://localhost/";
+
+/// Pages with less text than this are too thin to call duplicates
+const MIN_DUPLICATE_TEXT: usize = 100;
+
+/// Problem found with a page's canonical
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum CanonicalIssue {
+    /// Canonical points to a page that isn't in the site
+    MissingTarget,
+    /// Canonical points to a page that canonicalizes elsewhere
+    Chain,
+    /// Following canonicals leads back to an earlier page
+    Loop,
+    /// Canonical points to another host
+    CrossDomain,
+    /// `noindex` combined with a canonical to another page
+    NoindexConflict,
+    /// Page has the same content as others but not the same canonical
+    InconsistentDuplicate,
+}
+
+impl CanonicalIssue {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::MissingTarget => "canonical points to a missing page",
+            Self::Chain => "canonical chain",
+            Self::Loop => "canonical loop",
+            Self::CrossDomain => "cross-domain canonical",
+            Self::NoindexConflict => "canonical conflicts with noindex",
+            Self::InconsistentDuplicate => "duplicate pages with different canonicals",
+        }
+    }
+}
+
+/// A page with one of its canonical issues
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CanonicalFinding {
+    pub page: PathBuf,
+    pub issue: CanonicalIssue,
+    pub detail: String,
+}
+
+/// Canonical of one page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageCanonical {
+    pub page: PathBuf,
+
+    /// URL path the page is served at
+    pub path: String,
+
+    /// Canonical as written in the page
+    pub canonical: Option<String>,
+
+    /// Analyzed page the canonical resolves to
+    pub target: Option<PathBuf>,
+
+    pub noindex: bool,
+}
+
+/// Site-wide canonical audit
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CanonicalAudit {
+    pub pages: Vec<PageCanonical>,
+
+    /// Groups of pages with the same text
+    pub duplicates: Vec<Vec<PathBuf>>,
+
+    pub findings: Vec<CanonicalFinding>,
+}
+
+/// Where a canonical resolves to
+enum Resolved {
+    None,
+    Page(usize),
+    Missing(String),
+    External(String),
+}
+
+impl CanonicalAudit {
+    /// Audit the canonicals of the HTML pages in `files`, served from `root`
+    pub fn new(root: &Path, files: &[FileAnalysis], site_url: Option<&str>) -> Self {
+        let files: Vec<&FileAnalysis> = files.iter().filter(|f| is_html_file(&f.path)).collect();
+        let origin = site_url
+            .and_then(|u| Url::parse(u).ok())
+            .or_else(|| common_origin(&files))
+            .unwrap_or_else(|| Url::parse(FALLBACK_ORIGIN).unwrap());
+        let site_host = origin.host_str().map(bare_host);
+
+        let paths: Vec<String> = files.iter().map(|f| url_path(root, &f.path)).collect();
+        let mut by_path: BTreeMap<String, usize> = BTreeMap::new();
+        for (i, path) in paths.iter().enumerate() {
+            for alias in aliases(path) {
+                // Normalized the way the resolved canonical will be
+                if let Ok(url) = origin.join(&alias) {
+                    by_path.entry(url.path().to_string()).or_insert(i);
+                }
+            }
+        }
+
+        let resolved: Vec<Resolved> = files
+            .iter()
+            .zip(&paths)
+            .map(|(file, path)| {
+                let Some(href) = file.result.existing_seo.canonical.as_deref() else {
+                    return Resolved::None;
+                };
+                let Ok(url) = origin.join(path).and_then(|page| page.join(href)) else {
+                    return Resolved::Missing(href.to_string());
+                };
+                if url.host_str().map(bare_host) != site_host {
+                    return Resolved::External(url.to_string());
+                }
+                match by_path.get(url.path()) {
+                    Some(&i) => Resolved::Page(i),
+                    None => Resolved::Missing(url.path().to_string()),
+                }
+            })
+            .collect();
+
+        let noindex = |i: usize| files[i].result.existing_seo.noindex;
+        let mut findings = Vec::new();
+        let mut finding = |i: usize, issue, detail: String| {
+            findings.push(CanonicalFinding {
+                page: files[i].path.clone(),
+                issue,
+                detail,
+            })
+        };
+
+        for (i, target) in resolved.iter().enumerate() {
+            match *target {
+                Resolved::None => {}
+                Resolved::Missing(ref path) => finding(
+                    i,
+                    CanonicalIssue::MissingTarget,
+                    format!("{} has no matching page", path),
+                ),
+                Resolved::External(ref url) => finding(i, CanonicalIssue::CrossDomain, url.clone()),
+                Resolved::Page(t) if t == i => {}
+                Resolved::Page(t) => {
+                    let (hops, looped) = follow(&resolved, i);
+                    let route: Vec<&str> = hops.iter().map(|&h| paths[h].as_str()).collect();
+                    if looped {
+                        finding(i, CanonicalIssue::Loop, route.join(" → "));
+                    } else if hops.len() > 2 {
+                        finding(i, CanonicalIssue::Chain, route.join(" → "));
+                    }
+
+                    if noindex(i) {
+                        finding(
+                            i,
+                            CanonicalIssue::NoindexConflict,
+                            format!("noindex page canonicalizes to {}", paths[t]),
+                        );
+                    } else if noindex(t) {
+                        finding(
+                            i,
+                            CanonicalIssue::NoindexConflict,
+                            format!("canonical target {} is noindex", paths[t]),
+                        );
+                    }
+                }
+            }
+        }
+
+        // Pages with the same text should all name one canonical
+        let mut by_text: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for (i, file) in files.iter().enumerate() {
+            let text = file.result.raw_text.as_deref().unwrap_or("");
+            let text = text
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase();
+            if text.len() >= MIN_DUPLICATE_TEXT {
+                by_text.entry(text).or_default().push(i);
+            }
+        }
+        let mut duplicates = Vec::new();
+        for cluster in by_text.into_values().filter(|c| c.len() > 1) {
+            let canonicals: BTreeSet<String> = cluster
+                .iter()
+                .map(|&i| {
+                    let (hops, _) = follow(&resolved, i);
+                    let last = *hops.last().unwrap();
+                    match resolved[last] {
+                        Resolved::Missing(ref target) | Resolved::External(ref target) => {
+                            target.clone()
+                        }
+                        _ => paths[last].clone(),
+                    }
+                })
+                .collect();
+            if canonicals.len() > 1 {
+                let canonicals: Vec<String> = canonicals.into_iter().collect();
+                for &i in &cluster {
+                    let others: Vec<&str> = cluster
+                        .iter()
+                        .filter(|&&o| o != i)
+                        .map(|&o| paths[o].as_str())
+                        .collect();
+                    finding(
+                        i,
+                        CanonicalIssue::InconsistentDuplicate,
+                        format!(
+                            "same content as {}; canonicals: {}",
+                            others.join(", "),
+                            canonicals.join(", ")
+                        ),
+                    );
+                }
+            }
+            duplicates.push(cluster.iter().map(|&i| files[i].path.clone()).collect());
+        }
+
+        let pages = files
+            .iter()
+            .zip(paths)
+            .zip(&resolved)
+            .map(|((file, path), target)| PageCanonical {
+                page: file.path.clone(),
+                path,
+                canonical: file.result.existing_seo.canonical.clone(),
+                target: match *target {
+                    Resolved::Page(t) => Some(files[t].path.clone()),
+                    _ => None,
+                },
+                noindex: file.result.existing_seo.noindex,
+            })
+            .collect();
+
+        Self {
+            pages,
+            duplicates,
+            findings,
+        }
+    }
+
+    /// Findings per issue across the site
+    pub fn issue_counts(&self) -> BTreeMap<CanonicalIssue, u32> {
+        let mut counts = BTreeMap::new();
+        for finding in &self.findings {
+            *counts.entry(finding.issue).or_default() += 1;
+        }
+        counts
+    }
+}
+
+/// Pages visited following canonicals from `start`, and whether they loop
+fn follow(resolved: &[Resolved], start: usize) -> (Vec<usize>, bool) {
+    let mut hops = vec![start];
+    let mut current = start;
+    while let Resolved::Page(next) = resolved[current] {
+        if next == current {
+            break;
+        }
+        if hops.contains(&next) {
+            hops.push(next);
+            return (hops, true);
+        }
+        hops.push(next);
+        current = next;
+    }
+    (hops, false)
+}
+
+/// URL path of the page at `file` under `root`
+pub(crate) fn url_path(root: &Path, file: &Path) -> String {
+    let relative = file.strip_prefix(root).unwrap_or(file);
+    let mut parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    let is_index = parts
+        .last()
+        .is_some_and(|name| name == "index.html" || name == "index.htm");
+    if is_index {
+        parts.pop();
+        parts.push(String::new());
+    }
+    format!("/{}", parts.join("/"))
+}
+
+/// URL paths that serve the same page: `/blog/` as `/blog` and
+/// `/blog/index.html`, `/about.html` as `/about`
+pub(crate) fn aliases(path: &str) -> Vec<String> {
+    let mut aliases = vec![path.to_string()];
+    if let Some(dir) = path.strip_suffix('/') {
+        if !dir.is_empty() {
+            aliases.push(dir.to_string());
+        }
+        aliases.push(format!("{}/index.html", dir));
+    } else if let Some(stem) = path
+        .strip_suffix(".html")
+        .or_else(|| path.strip_suffix(".htm"))
+    {
+        aliases.push(stem.to_string());
+        aliases.push(format!("{}/", stem));
+    }
+    aliases
+}
+
+/// Origin most absolute canonicals point at
+fn common_origin(files: &[&FileAnalysis]) -> Option<Url> {
+    let mut counts: BTreeMap<String, u32> = BTreeMap::new();
+    for file in files {
+        let canonical = file.result.existing_seo.canonical.as_deref();
+        if let Some(url) = canonical.and_then(|c| Url::parse(c).ok()) {
+            if let Some(host) = url.host_str() {
+                *counts
+                    .entry(format!("{}://{}/", url.scheme(), host))
+                    .or_default() += 1;
+            }
+        }
+    }
+    let (origin, _) = counts.into_iter().max_by_key(|(_, count)| *count)?;
+    Url::parse(&origin).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::page;
+
+    const TEXT: &str = "Emergency plumbing in Springfield. Burst pipes, blocked drains and water heaters repaired the same day by licensed plumbers.";
+
+    /// Page below `/site` with distinct text and the given canonical
+    fn canonical(path: &str, target: Option<&str>) -> FileAnalysis {
+        page("/site", path, |r| {
+            r.existing_seo.canonical = target.map(str::to_string);
+            r.raw_text = Some(format!("Page {}", path));
+        })
+    }
+
+    fn issues(audit: &CanonicalAudit, path: &str) -> Vec<CanonicalIssue> {
+        audit
+            .findings
+            .iter()
+            .filter(|f| f.page == Path::new("/site").join(path))
+            .map(|f| f.issue)
+            .collect()
+    }
+
+    #[test]
+    fn test_resolution_issues() {
+        let mut draft = canonical("draft.html", Some("/about"));
+        draft.result.existing_seo.noindex = true;
+        let files = vec![
+            canonical("index.html", Some("https://www.acme.com/")),
+            canonical("about.html", Some("https://acme.com/about")),
+            canonical("team/index.html", Some("../old-team.html")),
+            canonical("a.html", Some("/b.html")),
+            canonical("b.html", Some("/c")),
+            canonical("c.html", Some("a.html")),
+            canonical("promo.html", Some("/sale")),
+            canonical("sale.html", Some("/offers.html")),
+            canonical("offers.html", Some("/offers")),
+            canonical("mirror.html", Some("https://partner.example/mirror")),
+            draft,
+        ];
+        let audit = CanonicalAudit::new(Path::new("/site"), &files, Some("https://acme.com"));
+
+        assert!(issues(&audit, "index.html").is_empty());
+        assert!(issues(&audit, "about.html").is_empty());
+        assert_eq!(audit.pages[0].path, "/");
+        assert_eq!(
+            issues(&audit, "team/index.html"),
+            vec![CanonicalIssue::MissingTarget]
+        );
+        assert_eq!(issues(&audit, "a.html"), vec![CanonicalIssue::Loop]);
+        assert_eq!(issues(&audit, "promo.html"), vec![CanonicalIssue::Chain]);
+        assert!(issues(&audit, "sale.html").is_empty());
+        assert_eq!(
+            issues(&audit, "mirror.html"),
+            vec![CanonicalIssue::CrossDomain]
+        );
+        assert_eq!(
+            issues(&audit, "draft.html"),
+            vec![CanonicalIssue::NoindexConflict]
+        );
+        assert_eq!(
+            audit.pages[6].target.as_deref(),
+            Some(Path::new("/site/sale.html"))
+        );
+    }
+
+    #[test]
+    fn test_duplicate_clusters() {
+        let mut files = vec![
+            canonical("index.html", Some("https://acme.com/")),
+            canonical("home.html", Some("https://acme.com/")),
+            canonical("services.html", None),
+            canonical("services-copy.html", Some("/services.html")),
+            canonical("landing.html", None),
+        ];
+        for file in &mut files {
+            file.result.raw_text = Some(TEXT.to_string());
+        }
+        files[2].result.raw_text = Some(TEXT.replace("Springfield", "Shelbyville"));
+        files[3].result.raw_text = files[2].result.raw_text.clone();
+
+        // No site URL: the canonicals' own origin stands in
+        let audit = CanonicalAudit::new(Path::new("/site"), &files, None);
+
+        assert_eq!(audit.duplicates.len(), 2);
+        // index, home and landing share text; landing has no canonical
+        assert_eq!(
+            issues(&audit, "landing.html"),
+            vec![CanonicalIssue::InconsistentDuplicate]
+        );
+        assert_eq!(
+            issues(&audit, "index.html"),
+            vec![CanonicalIssue::InconsistentDuplicate]
+        );
+        // The copy canonicalizes to the original
+        assert!(issues(&audit, "services.html").is_empty());
+        assert!(issues(&audit, "services-copy.html").is_empty());
+    }
+}
@@ -0,0 +1,432 @@
+//! Analyzer configuration file
+//!
+//! Every strategy reads its own section, so a team can tune keyword
+//! extraction or business classification per client without forking the
+//! crate. Unknown keys are rejected rather than silently ignored.
+//!
+//! ```toml
+//! disabled = ["spa_shell_analyzer"]
+//!
+//! [keywords]
+//! extra_stop_words = ["acme"]
+//! max_keywords = 30
+//!
+//! [business]
+//! taxonomy = "taxonomy.toml"
+//!
+//! [audit]
+//! schema = 30
+//!
+//! [article]
+//! date_fallback = "git"
+//! ```
+
+use crate::strategies::calling_code;
+use crate::{AnalyzerError, TaxonomyNode};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Strategy names accepted in `disabled`
+pub const STRATEGY_NAMES: [&str; 20] = [
+    "keyword_analyzer",
+    "business_type_analyzer",
+    "seo_audit_analyzer",
+    "next_metadata_analyzer",
+    "vue_head_analyzer",
+    "nuxt_config_analyzer",
+    "svelte_head_analyzer",
+    "markdown_analyzer",
+    "spa_shell_analyzer",
+    "entity_analyzer",
+    "faq_analyzer",
+    "how_to_analyzer",
+    "product_analyzer",
+    "article_analyzer",
+    "performance_analyzer",
+    "link_analyzer",
+    "snippet_analyzer",
+    "nap_analyzer",
+    "icon_analyzer",
+    "content_depth_analyzer",
+];
+
+#[rustfmt::skip]
+const DEFAULT_STOP_WORDS: [&str; 103] = [
+    "the", "a", "an", "and", "or", "but", "in", "on", "at", "to", "for",
+    "of", "with", "by", "from", "as", "is", "was", "are", "were", "been",
+    "be", "have", "has", "had", "do", "does", "did", "will", "would",
+    "could", "should", "may", "might", "must", "shall", "can", "need",
+    "this", "that", "these", "those", "i", "you", "he", "she", "it",
+    "we", "they", "what", "which", "who", "when", "where", "why", "how",
+    "all", "each", "every", "both", "few", "more", "most", "other",
+    "some", "such", "no", "nor", "not", "only", "own", "same", "so",
+    "than", "too", "very", "just", "also", "now", "here", "there",
+    "then", "once", "any", "about", "into", "through", "during",
+    "before", "after", "above", "below", "between", "under", "again",
+    "further", "because", "if", "else", "until", "while", "our", "your",
+];
+
+/// Configuration for the default analyzer pipeline
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnalyzerConfig {
+    /// Strategies to leave out of the pipeline, by name
+    pub disabled: Vec<String>,
+
+    pub keywords: KeywordConfig,
+    pub business: BusinessConfig,
+    pub audit: AuditWeights,
+    pub spa: SpaConfig,
+    pub entities: EntityConfig,
+    pub article: ArticleConfig,
+}
+
+/// `[keywords]` section, read by `KeywordAnalyzer`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeywordConfig {
+    /// Replaces the built-in stop word list
+    pub stop_words: Vec<String>,
+
+    /// Added to `stop_words`
+    pub extra_stop_words: Vec<String>,
+
+    pub min_word_length: usize,
+    pub max_keywords: usize,
+
+    /// Capitalized phrases kept in addition to single words
+    pub max_phrases: usize,
+
+    /// Score per occurrence of a phrase
+    pub phrase_boost: f32,
+}
+
+impl Default for KeywordConfig {
+    fn default() -> Self {
+        Self {
+            stop_words: DEFAULT_STOP_WORDS.iter().map(|w| w.to_string()).collect(),
+            extra_stop_words: Vec::new(),
+            min_word_length: 3,
+            max_keywords: 50,
+            max_phrases: 10,
+            phrase_boost: 5.0,
+        }
+    }
+}
+
+/// `[business]` section, read by `BusinessTypeAnalyzer`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BusinessConfig {
+    /// Taxonomy file extending the built-in one, relative to the config file
+    pub taxonomy: Option<PathBuf>,
+
+    /// Inline taxonomy nodes (`[[business.node]]`), applied after `taxonomy`
+    pub node: Vec<TaxonomyNode>,
+
+    /// Evidence a non-root node needs of its own to be chosen as subtype
+    pub min_subtype_score: f32,
+
+    /// Repeated matches in one location stop adding evidence after this many
+    pub max_counted_matches: usize,
+
+    pub weights: LocationWeights,
+}
+
+impl Default for BusinessConfig {
+    fn default() -> Self {
+        Self {
+            taxonomy: None,
+            node: Vec::new(),
+            min_subtype_score: 2.0,
+            max_counted_matches: 5,
+            weights: LocationWeights::default(),
+        }
+    }
+}
+
+/// `[business.weights]`, weight of an indicator by where it appears
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LocationWeights {
+    pub title: f32,
+    pub h1: f32,
+    pub description: f32,
+    pub heading: f32,
+    pub nav: f32,
+    pub body: f32,
+}
+
+impl Default for LocationWeights {
+    fn default() -> Self {
+        Self {
+            title: 3.0,
+            h1: 2.5,
+            description: 2.0,
+            heading: 1.5,
+            nav: 1.5,
+            body: 1.0,
+        }
+    }
+}
+
+/// `[audit]` section, weight of each element in the SEO completeness score
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuditWeights {
+    pub title: u32,
+    pub description: u32,
+    pub og_tags: u32,
+    pub twitter_cards: u32,
+    pub schema: u32,
+    pub canonical: u32,
+    pub viewport: u32,
+    pub charset: u32,
+}
+
+impl Default for AuditWeights {
+    fn default() -> Self {
+        Self {
+            title: 15,
+            description: 15,
+            og_tags: 20,
+            twitter_cards: 15,
+            schema: 20,
+            canonical: 5,
+            viewport: 5,
+            charset: 5,
+        }
+    }
+}
+
+impl AuditWeights {
+    pub fn total(&self) -> u32 {
+        self.title
+            + self.description
+            + self.og_tags
+            + self.twitter_cards
+            + self.schema
+            + self.canonical
+            + self.viewport
+            + self.charset
+    }
+}
+
+/// `[spa]` section, read by `SpaShellAnalyzer`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SpaConfig {
+    /// Pages with fewer visible words than this are candidates for an empty shell
+    pub shell_max_words: u32,
+}
+
+impl Default for SpaConfig {
+    fn default() -> Self {
+        Self {
+            shell_max_words: 20,
+        }
+    }
+}
+
+/// `[entities]` section, read by `EntityAnalyzer` and `NapAnalyzer`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EntityConfig {
+    /// Country assumed for phone numbers without a country code when the
+    /// page's language tag and address don't name one (ISO 3166-1 alpha-2)
+    pub default_country: String,
+}
+
+impl Default for EntityConfig {
+    fn default() -> Self {
+        Self {
+            default_country: "US".to_string(),
+        }
+    }
+}
+
+/// `[article]` section, dates for article pages that don't state them
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ArticleConfig {
+    pub date_fallback: DateFallback,
+}
+
+/// Where to take article dates from when the page has none
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DateFallback {
+    /// Leave the dates unset
+    #[default]
+    None,
+    /// File creation and modification times
+    Mtime,
+    /// First and last commit touching the file
+    Git,
+}
+
+impl AnalyzerConfig {
+    /// Parse and validate a TOML configuration
+    pub fn from_toml(content: &str) -> Result<Self, AnalyzerError> {
+        let config: Self =
+            toml::from_str(content).map_err(|e| AnalyzerError::InvalidConfig(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Parse and validate a JSON configuration
+    pub fn from_json(content: &str) -> Result<Self, AnalyzerError> {
+        let config: Self = serde_json::from_str(content)
+            .map_err(|e| AnalyzerError::InvalidConfig(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Load a `.json` or `.toml` file; a relative taxonomy path is resolved
+    /// against the file's directory
+    pub fn load(path: &Path) -> Result<Self, AnalyzerError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| AnalyzerError::FileRead(path.to_path_buf(), e))?;
+
+        let is_json = path.extension().map(|e| e == "json").unwrap_or(false);
+        let mut config = if is_json {
+            Self::from_json(&content)
+        } else {
+            Self::from_toml(&content)
+        }
+        .map_err(|e| match e {
+            AnalyzerError::InvalidConfig(msg) => {
+                AnalyzerError::InvalidConfig(format!("{}: {}", path.display(), msg))
+            }
+            other => other,
+        })?;
+
+        if let (Some(taxonomy), Some(base)) = (&config.business.taxonomy, path.parent()) {
+            if taxonomy.is_relative() {
+                config.business.taxonomy = Some(base.join(taxonomy));
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Whether the strategy named `name` runs
+    pub fn is_enabled(&self, name: &str) -> bool {
+        !self.disabled.iter().any(|d| d == name)
+    }
+
+    fn validate(&self) -> Result<(), AnalyzerError> {
+        let invalid = |msg: String| Err(AnalyzerError::InvalidConfig(msg));
+
+        for name in &self.disabled {
+            if !STRATEGY_NAMES.contains(&name.as_str()) {
+                return invalid(format!(
+                    "unknown strategy '{}' in `disabled`, expected one of: {}",
+                    name,
+                    STRATEGY_NAMES.join(", ")
+                ));
+            }
+        }
+
+        let keywords = &self.keywords;
+        if keywords.min_word_length == 0 {
+            return invalid("`keywords.min_word_length` must be at least 1".to_string());
+        }
+        if keywords.max_keywords == 0 {
+            return invalid("`keywords.max_keywords` must be at least 1".to_string());
+        }
+        if keywords.phrase_boost < 0.0 {
+            return invalid("`keywords.phrase_boost` must not be negative".to_string());
+        }
+
+        let w = &self.business.weights;
+        let weights = [
+            ("title", w.title),
+            ("h1", w.h1),
+            ("description", w.description),
+            ("heading", w.heading),
+            ("nav", w.nav),
+            ("body", w.body),
+        ];
+        if let Some((key, _)) = weights.iter().find(|(_, v)| *v < 0.0) {
+            return invalid(format!("`business.weights.{}` must not be negative", key));
+        }
+        if self.business.max_counted_matches == 0 {
+            return invalid("`business.max_counted_matches` must be at least 1".to_string());
+        }
+
+        if calling_code(&self.entities.default_country).is_none() {
+            return invalid(format!(
+                "unsupported `entities.default_country` '{}'",
+                self.entities.default_country
+            ));
+        }
+
+        if self.audit.total() == 0 {
+            return invalid("`audit` weights must not all be zero".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sections_override_defaults() {
+        let config = AnalyzerConfig::from_toml(
+            r#"
+disabled = ["spa_shell_analyzer"]
+
+[keywords]
+extra_stop_words = ["acme"]
+max_keywords = 20
+
+[business.weights]
+title = 5.0
+
+[audit]
+schema = 40
+"#,
+        )
+        .unwrap();
+
+        assert!(!config.is_enabled("spa_shell_analyzer"));
+        assert_eq!(config.keywords.max_keywords, 20);
+        assert_eq!(config.keywords.min_word_length, 3);
+        assert!(config.keywords.stop_words.contains(&"the".to_string()));
+        assert_eq!(config.business.weights.title, 5.0);
+        assert_eq!(config.business.weights.body, 1.0);
+        assert_eq!(config.audit.schema, 40);
+        assert_eq!(config.audit.title, 15);
+    }
+
+    #[test]
+    fn test_json_config() {
+        let config = AnalyzerConfig::from_json(r#"{"keywords": {"min_word_length": 4}}"#).unwrap();
+        assert_eq!(config.keywords.min_word_length, 4);
+    }
+
+    #[test]
+    fn test_unknown_keys_are_rejected() {
+        let err = AnalyzerConfig::from_toml("[keywords]\nmax_keyword = 10\n").unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("max_keyword"), "{}", msg);
+        assert!(msg.contains("max_keywords"), "{}", msg);
+
+        let err = AnalyzerConfig::from_json(r#"{"seo": {}}"#).unwrap_err();
+        assert!(err.to_string().contains("unknown field `seo`"));
+
+        let err = AnalyzerConfig::from_toml("disabled = [\"keywords\"]\n").unwrap_err();
+        assert!(err.to_string().contains("unknown strategy 'keywords'"));
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        assert!(AnalyzerConfig::from_toml("[keywords]\nmax_keywords = 0\n").is_err());
+        assert!(AnalyzerConfig::from_toml("[business.weights]\nnav = -1.0\n").is_err());
+        assert!(AnalyzerConfig::from_toml("[entities]\ndefault_country = \"XX\"\n").is_err());
+    }
+}
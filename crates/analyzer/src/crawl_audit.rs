@@ -0,0 +1,628 @@
+//! Crawl audit - how search bots crawl the site, from server access logs
+//!
+//! Reads nginx and Apache logs in the combined format, keeps the requests of
+//! the major search engine bots and joins them with the analyzed pages the
+//! way the canonical audit maps URLs to pages. Bots are told apart by user
+//! agent only; without reverse DNS, which needs the network, a spoofed agent
+//! counts as the bot it claims to be.
+
+use crate::canonical_audit::{aliases, url_path, CanonicalAudit};
+use crate::redirect_audit::{RedirectAudit, RedirectMap};
+use crate::{is_html_file, AnalyzerError, FileAnalysis};
+use chrono::{DateTime, FixedOffset};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use url::Url;
+
+/// Origin request paths are normalized against
+const LOG_ORIGIN: &str = "https://localhost/";
+
+/// Query string variants named in a finding before the rest are counted
+const MAX_VARIANTS_LISTED: usize = 3;
+
+/// Search engine crawler, identified by user agent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum SearchBot {
+    Googlebot,
+    Bingbot,
+    YandexBot,
+    Baiduspider,
+    DuckDuckBot,
+    Applebot,
+    YahooSlurp,
+}
+
+impl SearchBot {
+    /// Lowercase user agent tokens of each bot, including its image, video
+    /// and mobile variants
+    const TOKENS: [(Self, &'static [&'static str]); 7] = [
+        (Self::Googlebot, &["googlebot", "google-inspectiontool"]),
+        (Self::Bingbot, &["bingbot", "adidxbot", "bingpreview"]),
+        (
+            Self::YandexBot,
+            &["yandexbot", "yandeximages", "yandex.com/bots"],
+        ),
+        (Self::Baiduspider, &["baiduspider"]),
+        (Self::DuckDuckBot, &["duckduckbot"]),
+        (Self::Applebot, &["applebot"]),
+        (Self::YahooSlurp, &["yahoo! slurp"]),
+    ];
+
+    /// Bot claiming `user_agent`, if any
+    pub fn from_user_agent(user_agent: &str) -> Option<Self> {
+        let user_agent = user_agent.to_lowercase();
+        Self::TOKENS
+            .iter()
+            .find(|(_, tokens)| tokens.iter().any(|t| user_agent.contains(t)))
+            .map(|(bot, _)| *bot)
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Googlebot => "Googlebot",
+            Self::Bingbot => "Bingbot",
+            Self::YandexBot => "YandexBot",
+            Self::Baiduspider => "Baiduspider",
+            Self::DuckDuckBot => "DuckDuckBot",
+            Self::Applebot => "Applebot",
+            Self::YahooSlurp => "Yahoo! Slurp",
+        }
+    }
+}
+
+/// One search bot request from an access log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BotHit {
+    pub bot: SearchBot,
+    pub time: DateTime<FixedOffset>,
+    pub method: String,
+
+    /// Request target: path and query string
+    pub target: String,
+
+    pub status: u16,
+}
+
+/// Search bot requests read from access logs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AccessLog {
+    pub hits: Vec<BotHit>,
+
+    /// Lines read, bots or not
+    pub lines: u64,
+
+    /// Lines that aren't in the common or combined log format
+    pub unparsed: u64,
+}
+
+impl AccessLog {
+    /// Add the bot requests of the log file at `path`
+    pub fn read(&mut self, path: &Path) -> Result<(), AnalyzerError> {
+        let file = std::fs::File::open(path)
+            .map_err(|e| AnalyzerError::FileRead(path.to_path_buf(), e))?;
+        let mut reader = std::io::BufReader::new(file);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            let read = reader
+                .read_until(b'\n', &mut line)
+                .map_err(|e| AnalyzerError::FileRead(path.to_path_buf(), e))?;
+            if read == 0 {
+                return Ok(());
+            }
+            // User agents and paths aren't always valid UTF-8
+            self.add_line(&String::from_utf8_lossy(&line));
+        }
+    }
+
+    /// Add the bot requests of a log's `text`
+    pub fn parse(&mut self, text: &str) {
+        for line in text.lines() {
+            self.add_line(line);
+        }
+    }
+
+    fn add_line(&mut self, line: &str) {
+        let line = line.trim_end();
+        if line.is_empty() {
+            return;
+        }
+        self.lines += 1;
+        match parse_line(line) {
+            Some(hit) => self.hits.extend(hit),
+            None => self.unparsed += 1,
+        }
+    }
+
+    /// Days between the first and the last bot request, at least one
+    pub fn days(&self) -> u32 {
+        let times = self.hits.iter().map(|h| h.time);
+        match (times.clone().min(), times.max()) {
+            (Some(first), Some(last)) => ((last - first).num_days() + 1) as u32,
+            _ => 1,
+        }
+    }
+}
+
+/// Bot request of a log line; `Some(None)` for a request by anyone else
+fn parse_line(line: &str) -> Option<Option<BotHit>> {
+    static LINE: OnceLock<Regex> = OnceLock::new();
+    let line_re = LINE.get_or_init(|| {
+        Regex::new(
+            r#"^\S+ \S+ .*?\[([^\]]+)\] "([A-Z]+) (\S+)[^"]*" (\d{3}) \S+(?: "(?:[^"\\]|\\.)*" "((?:[^"\\]|\\.)*)")?"#,
+        )
+        .unwrap()
+    });
+
+    let caps = line_re.captures(line)?;
+    let time = DateTime::parse_from_str(&caps[1], "%d/%b/%Y:%H:%M:%S %z").ok()?;
+    let status = caps[4].parse().ok()?;
+    let Some(bot) = caps
+        .get(5)
+        .and_then(|ua| SearchBot::from_user_agent(ua.as_str()))
+    else {
+        return Some(None);
+    };
+
+    // Proxies log the absolute form, `GET http://host/path`
+    let target = match Url::parse(&caps[3]) {
+        Ok(url) => match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        },
+        Err(_) => caps[3].to_string(),
+    };
+    Some(Some(BotHit {
+        bot,
+        time,
+        method: caps[2].to_string(),
+        target,
+        status,
+    }))
+}
+
+/// How bots crawled one HTML page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageCrawl {
+    pub page: PathBuf,
+
+    /// URL path the page is served at
+    pub path: String,
+
+    /// Successful bot requests for the page, query string variants included
+    pub hits: u32,
+
+    /// Requests per bot, most first
+    pub bots: Vec<(SearchBot, u32)>,
+
+    pub last_crawled: Option<DateTime<FixedOffset>>,
+}
+
+/// Problem found with how bots spend their crawls
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum CrawlIssue {
+    /// Indexable page no bot requested
+    NeverCrawled,
+    /// Bots requested a URL that answered 404 or 410
+    NotFound,
+    /// Bots requested a URL that redirects
+    Redirected,
+    /// Bots requested a page that canonicalizes to another page
+    NonCanonical,
+    /// Bots requested a page with query strings
+    QueryVariants,
+    /// Bots got a 5xx answer
+    ServerError,
+}
+
+impl CrawlIssue {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::NeverCrawled => "never crawled",
+            Self::NotFound => "crawled 404",
+            Self::Redirected => "crawl wasted on a redirect",
+            Self::NonCanonical => "crawl wasted on a non-canonical URL",
+            Self::QueryVariants => "crawl wasted on query string variants",
+            Self::ServerError => "server error served to bots",
+        }
+    }
+}
+
+/// A URL with one of its crawl issues
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrawlFinding {
+    /// Requested URL path, or the page's path for never-crawled pages
+    pub url: String,
+
+    pub issue: CrawlIssue,
+
+    /// Bot requests affected
+    pub hits: u32,
+
+    pub detail: String,
+}
+
+/// Site-wide crawl audit
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CrawlAudit {
+    /// Bot requests in the logs
+    pub hits: u32,
+
+    /// Days the logs cover
+    pub days: u32,
+
+    /// HTML pages, most crawled first
+    pub pages: Vec<PageCrawl>,
+
+    pub findings: Vec<CrawlFinding>,
+}
+
+/// Bot requests for one URL path
+#[derive(Default)]
+struct PathHits<'a> {
+    hits: Vec<&'a BotHit>,
+    queries: BTreeSet<&'a str>,
+}
+
+impl CrawlAudit {
+    /// Join the bot requests of `log` with the HTML pages in `files`,
+    /// served from `root`
+    pub fn new(
+        root: &Path,
+        files: &[FileAnalysis],
+        log: &AccessLog,
+        site_url: Option<&str>,
+    ) -> Self {
+        let origin = Url::parse(LOG_ORIGIN).unwrap();
+        let normalized = |path: &str| {
+            origin
+                .join(path)
+                .map(|url| url.path().to_string())
+                .unwrap_or_else(|_| path.to_string())
+        };
+
+        let pages: Vec<&FileAnalysis> = files.iter().filter(|f| is_html_file(&f.path)).collect();
+        let canonicals = CanonicalAudit::new(root, files, site_url);
+        let mut by_path: BTreeMap<String, usize> = BTreeMap::new();
+        for (i, page) in pages.iter().enumerate() {
+            for alias in aliases(&url_path(root, &page.path)) {
+                by_path.entry(normalized(&alias)).or_insert(i);
+            }
+        }
+        // Page another page canonicalizes to, if not itself
+        let canonical_of = |i: usize| {
+            canonicals
+                .pages
+                .iter()
+                .find(|c| c.page == pages[i].path)
+                .and_then(|c| c.target.as_ref())
+                .filter(|target| **target != pages[i].path)
+                .map(|target| url_path(root, target))
+        };
+
+        let redirects = RedirectAudit::new(root, files, site_url);
+        let map = RedirectMap::new(root, &redirects.rules, &pages, site_url);
+
+        let mut by_request: BTreeMap<String, PathHits> = BTreeMap::new();
+        for hit in &log.hits {
+            let (path, query) = match hit.target.split_once('?') {
+                Some((path, query)) => (path, Some(query)),
+                None => (hit.target.as_str(), None),
+            };
+            let entry = by_request.entry(normalized(path)).or_default();
+            entry.hits.push(hit);
+            entry.queries.extend(query);
+        }
+
+        let mut crawled: Vec<Vec<&BotHit>> = vec![Vec::new(); pages.len()];
+        let mut requested = vec![false; pages.len()];
+        let mut findings = Vec::new();
+        for (path, requests) in &by_request {
+            let page = by_path.get(path).copied();
+            let with_status = |statuses: &dyn Fn(u16) -> bool| -> Vec<&BotHit> {
+                requests
+                    .hits
+                    .iter()
+                    .copied()
+                    .filter(|h| statuses(h.status))
+                    .collect()
+            };
+            let mut finding = |issue, hits: &[&BotHit], detail: String| {
+                if !hits.is_empty() {
+                    findings.push(CrawlFinding {
+                        url: path.clone(),
+                        issue,
+                        hits: hits.len() as u32,
+                        detail: format!("{}; {}", detail, bot_counts(hits)),
+                    });
+                }
+            };
+
+            // 304 Not Modified is a successful recrawl, not a redirect
+            let ok = with_status(&|s| (200..300).contains(&s) || s == 304);
+            let redirected = with_status(&|s| (300..400).contains(&s) && s != 304);
+            let not_found = with_status(&|s| s == 404 || s == 410);
+            let errors = with_status(&|s| s >= 500);
+
+            finding(
+                CrawlIssue::NotFound,
+                &not_found,
+                match page {
+                    Some(i) => format!("now served by {}", pages[i].path.display()),
+                    None => "no page at this URL".to_string(),
+                },
+            );
+            finding(
+                CrawlIssue::Redirected,
+                &redirected,
+                match map.redirect(path) {
+                    Some((rule, target)) => format!(
+                        "redirects to {} ({})",
+                        target,
+                        redirects.rules[rule].source.description()
+                    ),
+                    None => "redirect not declared in the site's hosting config".to_string(),
+                },
+            );
+            finding(CrawlIssue::ServerError, &errors, statuses(&errors));
+
+            let Some(i) = page else {
+                continue;
+            };
+            requested[i] = true;
+            if let Some(canonical) = canonical_of(i) {
+                finding(
+                    CrawlIssue::NonCanonical,
+                    &ok,
+                    format!("canonical is {}", canonical),
+                );
+            } else if !requests.queries.is_empty() {
+                let variants: Vec<&BotHit> = ok
+                    .iter()
+                    .copied()
+                    .filter(|h| h.target.contains('?'))
+                    .collect();
+                let mut listed: Vec<String> = requests
+                    .queries
+                    .iter()
+                    .take(MAX_VARIANTS_LISTED)
+                    .map(|q| format!("?{}", q))
+                    .collect();
+                if requests.queries.len() > MAX_VARIANTS_LISTED {
+                    listed.push(format!(
+                        "{} more",
+                        requests.queries.len() - MAX_VARIANTS_LISTED
+                    ));
+                }
+                finding(CrawlIssue::QueryVariants, &variants, listed.join(", "));
+            }
+            crawled[i].extend(ok);
+        }
+
+        let days = log.days();
+        let mut page_crawls: Vec<PageCrawl> = pages
+            .iter()
+            .zip(crawled)
+            .map(|(page, hits)| {
+                let mut bots: BTreeMap<SearchBot, u32> = BTreeMap::new();
+                for hit in &hits {
+                    *bots.entry(hit.bot).or_default() += 1;
+                }
+                let mut bots: Vec<(SearchBot, u32)> = bots.into_iter().collect();
+                bots.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+                PageCrawl {
+                    page: page.path.clone(),
+                    path: url_path(root, &page.path),
+                    hits: hits.len() as u32,
+                    bots,
+                    last_crawled: hits.iter().map(|h| h.time).max(),
+                }
+            })
+            .collect();
+
+        // Pages that are noindex, redirect or canonicalize elsewhere aren't
+        // meant to be crawled for their own sake
+        for (i, crawl) in page_crawls.iter().enumerate() {
+            let seo = &pages[i].result.existing_seo;
+            if !requested[i]
+                && !seo.noindex
+                && map.redirect(&crawl.path).is_none()
+                && canonical_of(i).is_none()
+            {
+                findings.push(CrawlFinding {
+                    url: crawl.path.clone(),
+                    issue: CrawlIssue::NeverCrawled,
+                    hits: 0,
+                    detail: format!("no bot request in {} days of logs", days),
+                });
+            }
+        }
+
+        page_crawls.sort_by_key(|c| std::cmp::Reverse(c.hits));
+        findings.sort_by(|a, b| a.issue.cmp(&b.issue).then(b.hits.cmp(&a.hits)));
+        Self {
+            hits: log.hits.len() as u32,
+            days,
+            pages: page_crawls,
+            findings,
+        }
+    }
+
+    /// Bot requests spent on 404s, redirects, non-canonical URLs, query
+    /// string variants and server errors
+    pub fn wasted_hits(&self) -> u32 {
+        self.findings
+            .iter()
+            .filter(|f| f.issue != CrawlIssue::NeverCrawled)
+            .map(|f| f.hits)
+            .sum()
+    }
+}
+
+impl PageCrawl {
+    /// Average bot requests per day over the `days` the logs cover
+    pub fn per_day(&self, days: u32) -> f32 {
+        self.hits as f32 / days.max(1) as f32
+    }
+}
+
+/// `Googlebot 12, Bingbot 3`
+fn bot_counts(hits: &[&BotHit]) -> String {
+    let mut counts: BTreeMap<SearchBot, u32> = BTreeMap::new();
+    for hit in hits {
+        *counts.entry(hit.bot).or_default() += 1;
+    }
+    counts
+        .iter()
+        .map(|(bot, count)| format!("{} {}", bot.description(), count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// `HTTP 500, 503`
+fn statuses(hits: &[&BotHit]) -> String {
+    let statuses: BTreeSet<u16> = hits.iter().map(|h| h.status).collect();
+    let statuses: Vec<String> = statuses.iter().map(u16::to_string).collect();
+    format!("HTTP {}", statuses.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::page;
+
+    const GOOGLEBOT: &str =
+        "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)";
+    const BINGBOT: &str = "Mozilla/5.0 (compatible; bingbot/2.0; +http://www.bing.com/bingbot.htm)";
+
+    fn line(day: u32, target: &str, status: u16, user_agent: &str) -> String {
+        format!(
+            r#"66.249.66.1 - - [{:02}/Mar/2024:10:15:32 +0000] "GET {} HTTP/1.1" {} 5120 "-" "{}""#,
+            day, target, status, user_agent
+        )
+    }
+
+    #[test]
+    fn test_parse_combined_log() {
+        let mut log = AccessLog::default();
+        log.parse(
+            &[
+                line(1, "/", 200, GOOGLEBOT),
+                line(3, "http://acme.com/about?ref=nav", 200, BINGBOT),
+                line(
+                    3,
+                    "/",
+                    200,
+                    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) Firefox/123.0",
+                ),
+                r#"10.0.0.1 - - [03/Mar/2024:10:15:32 +0000] "GET /about HTTP/1.1" 200 512"#
+                    .to_string(),
+                "not a log line".to_string(),
+            ]
+            .join("\n"),
+        );
+
+        assert_eq!(log.lines, 5);
+        assert_eq!(log.unparsed, 1);
+        assert_eq!(log.hits.len(), 2);
+        assert_eq!(log.hits[0].bot, SearchBot::Googlebot);
+        assert_eq!(log.hits[1].bot, SearchBot::Bingbot);
+        assert_eq!(log.hits[1].target, "/about?ref=nav");
+        assert_eq!(log.days(), 3);
+        assert_eq!(
+            SearchBot::from_user_agent("Googlebot-Image/1.0"),
+            Some(SearchBot::Googlebot)
+        );
+    }
+
+    #[test]
+    fn test_unparsable_lines() {
+        let mut log = AccessLog::default();
+        log.parse(
+            &[
+                // Escaped quote in the user agent and nginx fields after it
+                r#"66.249.66.1 - - [04/Mar/2024:08:00:00 +0000] "GET /a HTTP/1.1" 200 - "-" "Mozilla/5.0 \"x\" (compatible; Googlebot/2.1)" 0.012 upstream"#
+                    .to_string(),
+                format!("{}\r", line(5, "/b", 200, GOOGLEBOT)),
+                String::new(),
+                // Impossible date, no status, truncated request, JSON
+                line(32, "/c", 200, GOOGLEBOT),
+                r#"66.249.66.1 - - [04/Mar/2024:08:00:00 +0000] "GET /d HTTP/1.1" - 512"#
+                    .to_string(),
+                r#"66.249.66.1 - - [04/Mar/2024:08:00:00 +0000] "GET"#.to_string(),
+                r#"{"time": "2024-03-04T08:00:00Z", "path": "/e", "status": 200}"#.to_string(),
+            ]
+            .join("\n"),
+        );
+
+        assert_eq!((log.lines, log.unparsed), (6, 4));
+        let targets: Vec<&str> = log.hits.iter().map(|h| h.target.as_str()).collect();
+        assert_eq!(targets, ["/a", "/b"]);
+        assert_eq!(log.days(), 2);
+    }
+
+    #[test]
+    fn test_crawl_findings() {
+        let mut log = AccessLog::default();
+        log.parse(
+            &[
+                line(1, "/", 200, GOOGLEBOT),
+                line(2, "/", 304, BINGBOT),
+                line(2, "/about", 200, GOOGLEBOT),
+                line(3, "/about.html?utm_source=x", 200, GOOGLEBOT),
+                line(3, "/print/about.html", 200, GOOGLEBOT),
+                line(4, "/old-page", 301, GOOGLEBOT),
+                line(4, "/gone", 404, GOOGLEBOT),
+                line(5, "/gone", 404, BINGBOT),
+                line(5, "/contact.html", 503, GOOGLEBOT),
+            ]
+            .join("\n"),
+        );
+        let files = vec![
+            page("/site", "index.html", |_| {}),
+            page("/site", "about.html", |_| {}),
+            page("/site", "print/about.html", |r| {
+                r.existing_seo.canonical = Some("/about.html".to_string())
+            }),
+            page("/site", "contact.html", |_| {}),
+            page("/site", "services.html", |_| {}),
+        ];
+        let audit = CrawlAudit::new(Path::new("/site"), &files, &log, None);
+        let issues: Vec<(CrawlIssue, &str, u32)> = audit
+            .findings
+            .iter()
+            .map(|f| (f.issue, f.url.as_str(), f.hits))
+            .collect();
+
+        assert_eq!(
+            issues,
+            vec![
+                (CrawlIssue::NeverCrawled, "/services.html", 0),
+                (CrawlIssue::NotFound, "/gone", 2),
+                (CrawlIssue::Redirected, "/old-page", 1),
+                (CrawlIssue::NonCanonical, "/print/about.html", 1),
+                (CrawlIssue::QueryVariants, "/about.html", 1),
+                (CrawlIssue::ServerError, "/contact.html", 1),
+            ]
+        );
+        assert_eq!(
+            audit.findings[1].detail,
+            "no page at this URL; Googlebot 1, Bingbot 1"
+        );
+        assert_eq!(audit.wasted_hits(), 6);
+
+        let home = &audit.pages[0];
+        assert_eq!(home.path, "/");
+        assert_eq!((home.hits, home.per_day(audit.days)), (2, 0.4));
+        assert_eq!(
+            home.bots,
+            vec![(SearchBot::Googlebot, 1), (SearchBot::Bingbot, 1)]
+        );
+        assert_eq!(audit.pages[1].path, "/about.html");
+        assert_eq!(audit.pages[1].hits, 2);
+    }
+}
@@ -19,4 +19,13 @@ pub enum AnalyzerError {
 
     #[error("No HTML files found in directory: {0}")]
     NoHtmlFiles(PathBuf),
+
+    #[error("Invalid business taxonomy: {0}")]
+    InvalidTaxonomy(String),
+
+    #[error("Invalid analyzer config: {0}")]
+    InvalidConfig(String),
+
+    #[error("Invalid Search Console export: {0}")]
+    InvalidSearchConsole(String),
 }
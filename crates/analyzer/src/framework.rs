@@ -0,0 +1,278 @@
+//! Framework and static site generator detection
+//!
+//! Detection is evidence-based: config files and `package.json` dependencies
+//! each contribute a weighted signal. Meta-frameworks (Next, Nuxt, SvelteKit, ...)
+//! and static site generators win over the UI libraries they are built on,
+//! so a Next.js app is never reported as plain React.
+
+use crate::{Framework, FrameworkDetection};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// A single detection signal
+enum Signal {
+    /// Any of these files exists in the project root
+    File(&'static [&'static str]),
+    /// All of these paths exist in the project root
+    AllPaths(&'static [&'static str]),
+    /// Any of these packages is a `package.json` dependency
+    Dependency(&'static [&'static str]),
+    /// File exists and contains the given marker
+    FileContains(&'static str, &'static str),
+}
+
+struct Rule {
+    framework: Framework,
+    signal: Signal,
+    weight: f32,
+}
+
+/// Detection rules, in priority order.
+/// The first framework with any evidence wins; later rules only matter
+/// when nothing more specific matched.
+#[rustfmt::skip]
+fn rules() -> Vec<Rule> {
+    use Framework::*;
+    use Signal::*;
+
+    vec![
+        // Documentation / content generators built on top of React
+        Rule { framework: Docusaurus, signal: File(&["docusaurus.config.js", "docusaurus.config.ts", "docusaurus.config.mjs"]), weight: 0.7 },
+        Rule { framework: Docusaurus, signal: Dependency(&["@docusaurus/core"]), weight: 0.6 },
+        Rule { framework: Gatsby, signal: File(&["gatsby-config.js", "gatsby-config.ts", "gatsby-config.mjs"]), weight: 0.7 },
+        Rule { framework: Gatsby, signal: Dependency(&["gatsby"]), weight: 0.6 },
+        // Meta-frameworks
+        Rule { framework: Remix, signal: File(&["remix.config.js", "remix.config.mjs"]), weight: 0.7 },
+        Rule { framework: Remix, signal: Dependency(&["@remix-run/react", "@remix-run/node", "@remix-run/dev"]), weight: 0.6 },
+        Rule { framework: Astro, signal: File(&["astro.config.mjs", "astro.config.js", "astro.config.ts"]), weight: 0.7 },
+        Rule { framework: Astro, signal: Dependency(&["astro"]), weight: 0.6 },
+        Rule { framework: NextJs, signal: File(&["next.config.js", "next.config.mjs", "next.config.ts"]), weight: 0.7 },
+        Rule { framework: NextJs, signal: Dependency(&["next"]), weight: 0.6 },
+        Rule { framework: Nuxt, signal: File(&["nuxt.config.ts", "nuxt.config.js", "nuxt.config.mjs"]), weight: 0.7 },
+        Rule { framework: Nuxt, signal: Dependency(&["nuxt", "nuxt3"]), weight: 0.6 },
+        Rule { framework: SvelteKit, signal: Dependency(&["@sveltejs/kit"]), weight: 0.7 },
+        Rule { framework: SvelteKit, signal: FileContains("svelte.config.js", "@sveltejs/adapter"), weight: 0.5 },
+        Rule { framework: Angular, signal: File(&["angular.json"]), weight: 0.7 },
+        Rule { framework: Angular, signal: Dependency(&["@angular/core"]), weight: 0.6 },
+        // Static site generators
+        Rule { framework: Hugo, signal: File(&["hugo.toml", "hugo.yaml", "hugo.json"]), weight: 0.7 },
+        Rule { framework: Hugo, signal: AllPaths(&["config.toml", "archetypes"]), weight: 0.6 },
+        Rule { framework: Hugo, signal: AllPaths(&["config.toml", "layouts", "content"]), weight: 0.4 },
+        Rule { framework: Jekyll, signal: FileContains("Gemfile", "jekyll"), weight: 0.6 },
+        Rule { framework: Jekyll, signal: File(&["_config.yml", "_config.yaml"]), weight: 0.5 },
+        Rule { framework: Eleventy, signal: File(&[".eleventy.js", "eleventy.config.js", "eleventy.config.cjs", "eleventy.config.mjs"]), weight: 0.7 },
+        Rule { framework: Eleventy, signal: Dependency(&["@11ty/eleventy"]), weight: 0.6 },
+        // UI libraries
+        Rule { framework: Vue, signal: Dependency(&["vue"]), weight: 0.6 },
+        Rule { framework: Svelte, signal: Dependency(&["svelte"]), weight: 0.6 },
+        Rule { framework: Svelte, signal: File(&["svelte.config.js", "svelte.config.mjs"]), weight: 0.4 },
+        Rule { framework: React, signal: Dependency(&["react", "react-dom"]), weight: 0.6 },
+        // Build tools
+        Rule { framework: Vite, signal: File(&["vite.config.js", "vite.config.ts", "vite.config.mjs"]), weight: 0.6 },
+        Rule { framework: Vite, signal: Dependency(&["vite"]), weight: 0.4 },
+    ]
+}
+
+/// Detect the framework used by the project rooted at `dir`
+pub fn detect_framework(dir: &Path) -> FrameworkDetection {
+    let dependencies = read_package_dependencies(dir);
+    let mut best: Option<(Framework, Vec<(String, f32)>)> = None;
+
+    for rule in rules() {
+        // Once a framework is chosen, only collect more evidence for it
+        if let Some((ref framework, _)) = best {
+            if *framework != rule.framework {
+                continue;
+            }
+        }
+
+        if let Some(evidence) = evaluate(&rule.signal, dir, dependencies.as_ref()) {
+            best.get_or_insert_with(|| (rule.framework.clone(), Vec::new()))
+                .1
+                .push((evidence, rule.weight));
+        }
+    }
+
+    if let Some((framework, evidence)) = best {
+        return FrameworkDetection {
+            framework,
+            confidence: combine_weights(evidence.iter().map(|(_, w)| *w)),
+            evidence: evidence.into_iter().map(|(e, _)| e).collect(),
+        };
+    }
+
+    if dependencies.is_some() {
+        FrameworkDetection {
+            framework: Framework::Unknown,
+            confidence: 0.2,
+            evidence: vec!["package.json without a recognized framework dependency".to_string()],
+        }
+    } else if dir.join("index.html").exists() || dir.join("index.htm").exists() {
+        FrameworkDetection {
+            framework: Framework::VanillaHtml,
+            confidence: 0.6,
+            evidence: vec!["index.html without a package.json".to_string()],
+        }
+    } else {
+        FrameworkDetection {
+            framework: Framework::VanillaHtml,
+            confidence: 0.3,
+            evidence: vec!["no framework config files found".to_string()],
+        }
+    }
+}
+
+fn evaluate(
+    signal: &Signal,
+    dir: &Path,
+    dependencies: Option<&BTreeSet<String>>,
+) -> Option<String> {
+    match signal {
+        Signal::File(names) => names
+            .iter()
+            .find(|name| dir.join(name).exists())
+            .map(|name| format!("found {}", name)),
+        Signal::AllPaths(names) => names
+            .iter()
+            .all(|name| dir.join(name).exists())
+            .then(|| format!("found {}", names.join(" + "))),
+        Signal::Dependency(packages) => {
+            let deps = dependencies?;
+            packages
+                .iter()
+                .find(|pkg| deps.contains(**pkg))
+                .map(|pkg| format!("package.json dependency \"{}\"", pkg))
+        }
+        Signal::FileContains(name, marker) => std::fs::read_to_string(dir.join(name))
+            .ok()
+            .filter(|content| content.contains(marker))
+            .map(|_| format!("{} references {}", name, marker)),
+    }
+}
+
+/// Read all dependency names declared in `package.json`.
+/// Returns `None` when there is no readable `package.json`.
+fn read_package_dependencies(dir: &Path) -> Option<BTreeSet<String>> {
+    let content = std::fs::read_to_string(dir.join("package.json")).ok()?;
+
+    let manifest: serde_json::Value = match serde_json::from_str(&content) {
+        Ok(value) => value,
+        Err(e) => {
+            tracing::debug!("Ignoring malformed package.json: {}", e);
+            return Some(BTreeSet::new());
+        }
+    };
+
+    let mut deps = BTreeSet::new();
+    for section in ["dependencies", "devDependencies", "peerDependencies"] {
+        if let Some(map) = manifest.get(section).and_then(|v| v.as_object()) {
+            deps.extend(map.keys().cloned());
+        }
+    }
+    Some(deps)
+}
+
+/// Combine independent evidence weights: 1 - Π(1 - w)
+fn combine_weights(weights: impl Iterator<Item = f32>) -> f32 {
+    1.0 - weights.fold(1.0, |acc, w| acc * (1.0 - w))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn project(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, content) in files {
+            let path = dir.path().join(name);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).unwrap();
+            }
+            fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_next_without_config_is_not_react() {
+        let dir = project(&[(
+            "package.json",
+            r#"{"dependencies": {"next": "14.0.0", "react": "18.2.0", "react-dom": "18.2.0"}}"#,
+        )]);
+
+        let detection = detect_framework(dir.path());
+        assert_eq!(detection.framework, Framework::NextJs);
+        assert!(detection.evidence.iter().any(|e| e.contains("\"next\"")));
+    }
+
+    #[test]
+    fn test_nuxt_and_angular_detected() {
+        let nuxt = project(&[
+            ("nuxt.config.ts", "export default defineNuxtConfig({})"),
+            (
+                "package.json",
+                r#"{"devDependencies": {"nuxt": "^3.8.0", "vue": "^3.3.0"}}"#,
+            ),
+        ]);
+        let detection = detect_framework(nuxt.path());
+        assert_eq!(detection.framework, Framework::Nuxt);
+        assert_eq!(detection.evidence.len(), 2);
+        assert!(detection.confidence > 0.85);
+
+        let angular = project(&[("angular.json", "{}")]);
+        assert_eq!(
+            detect_framework(angular.path()).framework,
+            Framework::Angular
+        );
+    }
+
+    #[test]
+    fn test_react_substring_does_not_match() {
+        let dir = project(&[(
+            "package.json",
+            r#"{"description": "not \"react\" at all", "dependencies": {"preact": "10.0.0"}}"#,
+        )]);
+
+        assert_eq!(detect_framework(dir.path()).framework, Framework::Unknown);
+    }
+
+    #[test]
+    fn test_static_site_generators() {
+        let hugo = project(&[("hugo.toml", "baseURL = 'https://example.com/'")]);
+        assert_eq!(detect_framework(hugo.path()).framework, Framework::Hugo);
+
+        let jekyll = project(&[
+            ("_config.yml", "title: Blog"),
+            ("Gemfile", "gem \"jekyll\""),
+        ]);
+        assert_eq!(detect_framework(jekyll.path()).framework, Framework::Jekyll);
+
+        let eleventy = project(&[(".eleventy.js", "module.exports = {}")]);
+        assert_eq!(
+            detect_framework(eleventy.path()).framework,
+            Framework::Eleventy
+        );
+
+        let docusaurus = project(&[
+            ("docusaurus.config.js", "module.exports = {}"),
+            (
+                "package.json",
+                r#"{"dependencies": {"@docusaurus/core": "3.0.0", "react": "18.2.0"}}"#,
+            ),
+        ]);
+        assert_eq!(
+            detect_framework(docusaurus.path()).framework,
+            Framework::Docusaurus
+        );
+    }
+
+    #[test]
+    fn test_vanilla_html() {
+        let dir = project(&[("index.html", "<html></html>")]);
+        let detection = detect_framework(dir.path());
+
+        assert_eq!(detection.framework, Framework::VanillaHtml);
+        assert!(!detection.evidence.is_empty());
+    }
+}
@@ -0,0 +1,365 @@
+//! Shared collection of head declarations found in framework sources
+//!
+//! Source analyzers see `<head>` content in many shapes: HTML-like tag
+//! fragments (`<svelte:head>`, `<Head>`), Unhead objects (`useHead`,
+//! `app.head`) and flat `useSeoMeta` keys. `HeadCollector` normalizes all of
+//! them to element names like `meta[name=description]` and maps those onto
+//! `ExistingSeo`, recording the line each value came from.
+
+use crate::js_object::{line_of, JsValue};
+use crate::{ExistingSeo, SeoSource};
+use regex::Regex;
+use std::sync::OnceLock;
+
+struct TagPatterns {
+    title: Regex,
+    void_tag: Regex,
+    json_ld: Regex,
+    attr: Regex,
+    h1: Regex,
+    img: Regex,
+    img_alt: Regex,
+}
+
+fn patterns() -> &'static TagPatterns {
+    static PATTERNS: OnceLock<TagPatterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| TagPatterns {
+        title: Regex::new(r"(?is)<title\b[^>]*>(.*?)</title>").unwrap(),
+        void_tag: Regex::new(r"(?i)<(meta|link)\b([^>]*)>").unwrap(),
+        json_ld: Regex::new(r"(?i)<script\b[^>]*application/ld\+json").unwrap(),
+        attr: Regex::new(r#"([\w:@.-]+)\s*=\s*(?:"([^"]*)"|'([^']*)'|\{([^}]*)\})"#).unwrap(),
+        h1: Regex::new(r"(?i)<h1[\s>]").unwrap(),
+        img: Regex::new(r"<(?:img|Image|NuxtImg|NuxtPicture)\b[^>]*>").unwrap(),
+        img_alt: Regex::new(r#"\s(?::|v-bind:)?alt\s*=\s*(?:"[^"]+"|'[^']+'|\{[^}]+\})"#).unwrap(),
+    })
+}
+
+/// Collects head declarations from a single source file
+pub(crate) struct HeadCollector<'a> {
+    source: &'a str,
+    pub seo: ExistingSeo,
+    pub language: Option<String>,
+}
+
+impl<'a> HeadCollector<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            seo: ExistingSeo::default(),
+            language: None,
+        }
+    }
+
+    /// Record a declared element found at byte `offset` of the source
+    pub fn record(&mut self, element: String, value: Option<String>, offset: usize) {
+        let seo = &mut self.seo;
+        match element.as_str() {
+            "title" => {
+                seo.has_title = true;
+                if value.is_some() {
+                    seo.title = value.clone();
+                }
+            }
+            "meta[name=description]" => {
+                seo.has_description = true;
+                if value.is_some() {
+                    seo.description = value.clone();
+                }
+            }
+            "link[rel=canonical]" => {
+                seo.has_canonical = true;
+                if value.is_some() {
+                    seo.canonical = value.clone();
+                }
+            }
+            "meta[name=robots]" => {
+                seo.noindex |= value
+                    .as_deref()
+                    .is_some_and(|v| v.to_ascii_lowercase().contains("noindex"))
+            }
+            "meta[property=og:site_name]" => {
+                seo.has_og_tags = true;
+                if value.is_some() {
+                    seo.site_name = value.clone();
+                }
+            }
+            "meta[name=viewport]" => seo.has_viewport = true,
+            "meta[charset]" => seo.has_charset = true,
+            "script[type=application/ld+json]" => seo.has_schema = true,
+            e if e.starts_with("meta[property=og:") => seo.has_og_tags = true,
+            e if e.starts_with("meta[name=twitter:") || e.starts_with("meta[property=twitter:") => {
+                seo.has_twitter_cards = true
+            }
+            _ => {}
+        }
+
+        self.seo.sources.push(SeoSource {
+            element,
+            value,
+            file: None,
+            line: line_of(self.source, offset),
+        });
+    }
+
+    /// Scan an HTML-like fragment (`<svelte:head>`, `<Head>`) starting at `base`
+    pub fn html_fragment(&mut self, base: usize, fragment: &str) {
+        let p = patterns();
+
+        for caps in p.title.captures_iter(fragment) {
+            let text = caps[1].trim();
+            let value = (!text.is_empty() && !text.contains('{')).then(|| text.to_string());
+            self.record(
+                "title".to_string(),
+                value,
+                base + caps.get(0).unwrap().start(),
+            );
+        }
+
+        for caps in p.void_tag.captures_iter(fragment) {
+            let tag = caps[1].to_lowercase();
+            let attrs = parse_attrs(&caps[2]);
+            let offset = base + caps.get(0).unwrap().start();
+            if let Some((element, value)) = tag_element(&tag, &attrs) {
+                self.record(element, value, offset);
+            }
+        }
+
+        for m in p.json_ld.find_iter(fragment) {
+            self.record(
+                "script[type=application/ld+json]".to_string(),
+                None,
+                base + m.start(),
+            );
+        }
+    }
+
+    /// Apply an Unhead-style head object (`useHead({...})`, `app.head`)
+    pub fn unhead(&mut self, head: &JsValue) {
+        let JsValue::Object(props) = head else {
+            return;
+        };
+
+        for prop in props {
+            match prop.key.as_str() {
+                "title" => {
+                    let value = prop.value.as_str().map(String::from);
+                    self.record("title".to_string(), value, prop.offset);
+                }
+                "charset" => self.record(
+                    "meta[charset]".to_string(),
+                    prop.value.as_str().map(String::from),
+                    prop.offset,
+                ),
+                "viewport" => self.record(
+                    "meta[name=viewport]".to_string(),
+                    prop.value.as_str().map(String::from),
+                    prop.offset,
+                ),
+                "htmlAttrs" => {
+                    if let Some(lang) = prop.value.get("lang").and_then(JsValue::as_str) {
+                        self.language = Some(lang.split('-').next().unwrap_or(lang).to_string());
+                    }
+                }
+                "meta" | "link" => {
+                    for item in items(&prop.value) {
+                        let attrs = object_attrs(item);
+                        if let Some((element, value)) = tag_element(&prop.key, &attrs) {
+                            self.record(element, value, item_offset(item, prop.offset));
+                        }
+                    }
+                }
+                "script" => {
+                    for item in items(&prop.value) {
+                        if item.get("type").and_then(JsValue::as_str) == Some("application/ld+json")
+                        {
+                            self.record(
+                                "script[type=application/ld+json]".to_string(),
+                                None,
+                                item_offset(item, prop.offset),
+                            );
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Apply flat `useSeoMeta` keys (`ogTitle`, `twitterCard`, ...)
+    pub fn seo_meta(&mut self, meta: &JsValue) {
+        let JsValue::Object(props) = meta else {
+            return;
+        };
+
+        for prop in props {
+            let value = prop.value.as_str().map(String::from);
+            let element = match prop.key.as_str() {
+                "title" => "title".to_string(),
+                "charset" => "meta[charset]".to_string(),
+                key => {
+                    let name = camel_to_colon(key);
+                    if name.starts_with("og:") || name.starts_with("fb:") {
+                        format!("meta[property={}]", name)
+                    } else {
+                        format!("meta[name={}]", name)
+                    }
+                }
+            };
+            self.record(element, value, prop.offset);
+        }
+    }
+
+    /// Count headings and images in template markup
+    pub fn template_signals(&mut self, markup: &str) {
+        let p = patterns();
+        self.seo.h1_count += p.h1.find_iter(markup).count() as u32;
+        self.seo.img_without_alt += p
+            .img
+            .find_iter(markup)
+            .filter(|m| !p.img_alt.is_match(m.as_str()))
+            .count() as u32;
+    }
+}
+
+/// Element name and literal value for a `<meta>`/`<link>` declaration
+fn tag_element(tag: &str, attrs: &[(String, Option<String>)]) -> Option<(String, Option<String>)> {
+    let attr = |name: &str| {
+        attrs
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.clone())
+    };
+
+    match tag {
+        "meta" => {
+            let content = attr("content").flatten();
+            if let Some(Some(name)) = attr("name") {
+                Some((format!("meta[name={}]", name.to_lowercase()), content))
+            } else if let Some(Some(property)) = attr("property") {
+                Some((
+                    format!("meta[property={}]", property.to_lowercase()),
+                    content,
+                ))
+            } else if let Some(charset) = attr("charset") {
+                Some(("meta[charset]".to_string(), charset))
+            } else {
+                attr("http-equiv")
+                    .flatten()
+                    .map(|v| (format!("meta[http-equiv={}]", v.to_lowercase()), content))
+            }
+        }
+        "link" => {
+            let rel = attr("rel").flatten()?;
+            Some((
+                format!("link[rel={}]", rel.to_lowercase()),
+                attr("href").flatten(),
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Parse tag attributes; bound/interpolated values (`:content`, `{expr}`) become `None`
+fn parse_attrs(raw: &str) -> Vec<(String, Option<String>)> {
+    patterns()
+        .attr
+        .captures_iter(raw)
+        .map(|caps| {
+            let mut name = caps[1].to_lowercase();
+            let mut dynamic = caps.get(4).is_some();
+            for prefix in [":", "v-bind:"] {
+                if let Some(stripped) = name.strip_prefix(prefix) {
+                    name = stripped.to_string();
+                    dynamic = true;
+                }
+            }
+            let value = caps
+                .get(2)
+                .or_else(|| caps.get(3))
+                .map(|m| m.as_str().to_string());
+            (name, if dynamic { None } else { value })
+        })
+        .collect()
+}
+
+/// Attributes of an Unhead `meta`/`link` entry object
+fn object_attrs(item: &JsValue) -> Vec<(String, Option<String>)> {
+    match item {
+        JsValue::Object(props) => props
+            .iter()
+            .map(|p| (p.key.to_lowercase(), p.value.as_str().map(String::from)))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn items(value: &JsValue) -> &[JsValue] {
+    match value {
+        JsValue::Array(items) => items,
+        _ => &[],
+    }
+}
+
+/// Offset of the first key of an entry object, or the owning property
+fn item_offset(item: &JsValue, fallback: usize) -> usize {
+    match item {
+        JsValue::Object(props) => props.first().map(|p| p.offset).unwrap_or(fallback),
+        _ => fallback,
+    }
+}
+
+/// `ogImageAlt` -> `og:image:alt`
+fn camel_to_colon(key: &str) -> String {
+    let mut out = String::with_capacity(key.len() + 4);
+    for c in key.chars() {
+        if c.is_ascii_uppercase() {
+            out.push(':');
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::js_object::parse_object_at;
+
+    #[test]
+    fn test_html_fragment_records_lines() {
+        let source = "<div>\n<title>Home</title>\n<meta name=\"description\" content=\"Hi\">\n<meta property=\"og:title\" :content=\"title\">";
+        let mut collector = HeadCollector::new(source);
+        collector.html_fragment(0, source);
+
+        let seo = collector.seo;
+        assert_eq!(seo.title.as_deref(), Some("Home"));
+        assert_eq!(seo.description.as_deref(), Some("Hi"));
+        assert!(seo.has_og_tags);
+
+        let og = seo
+            .sources
+            .iter()
+            .find(|s| s.element == "meta[property=og:title]")
+            .unwrap();
+        assert_eq!(og.line, 4);
+        assert_eq!(og.value, None);
+    }
+
+    #[test]
+    fn test_seo_meta_keys() {
+        let source = "{ title: 'Shop', ogImage: '/og.png', twitterCard: 'summary' }";
+        let (meta, _) = parse_object_at(source, 0).unwrap();
+        let mut collector = HeadCollector::new(source);
+        collector.seo_meta(&meta);
+
+        assert!(collector.seo.has_og_tags);
+        assert!(collector.seo.has_twitter_cards);
+        assert!(collector
+            .seo
+            .sources
+            .iter()
+            .any(|s| s.element == "meta[property=og:image]"));
+    }
+}
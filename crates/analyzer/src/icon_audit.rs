@@ -0,0 +1,663 @@
+//! Icon audit - favicons, app icons and the web app manifest across a site
+//!
+//! Checks what browsers, bookmarks, home screens and search results need to
+//! show the site's icon: a favicon on every page, the `/favicon.ico` fallback,
+//! a square favicon Google accepts (a multiple of 48px, or SVG), a 180×180
+//! `apple-touch-icon`, a readable manifest with 192 and 512 pixel icons, a
+//! maskable icon and a theme color. Icon files are compared with what the
+//! markup and manifest declare.
+
+use crate::strategies::root_icon;
+use crate::{is_html_file, FileAnalysis, IconFormat, IconRef, IconRel, PageIcons};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Google shows favicons in search results at multiples of this edge
+const SEARCH_FAVICON_EDGE: u32 = 48;
+
+/// Edge of the `apple-touch-icon` iOS uses on current devices
+const APPLE_TOUCH_EDGE: u32 = 180;
+
+/// Manifest icon edges Chrome needs for install and the splash screen
+const MANIFEST_EDGES: [u32; 2] = [192, 512];
+
+/// An icon requirement checked across the site
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum IconCheck {
+    /// Declared favicon, or `/favicon.ico` to fall back on
+    Favicon,
+    /// `/favicon.ico` at the site root
+    IcoFallback,
+    /// Square favicon of a multiple of 48px, or SVG
+    SearchFavicon,
+    /// 180×180 PNG `apple-touch-icon`
+    AppleTouchIcon,
+    /// Linked, readable manifest with a name
+    Manifest,
+    /// 192 and 512 pixel manifest icons
+    ManifestIcons,
+    /// Manifest icon with `purpose: maskable`
+    MaskableIcon,
+    /// `theme-color` meta tag or manifest `theme_color`
+    ThemeColor,
+}
+
+impl IconCheck {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Favicon => "favicon",
+            Self::IcoFallback => "/favicon.ico fallback",
+            Self::SearchFavicon => "favicon of 48px multiples",
+            Self::AppleTouchIcon => "apple-touch-icon",
+            Self::Manifest => "web app manifest",
+            Self::ManifestIcons => "192px and 512px manifest icons",
+            Self::MaskableIcon => "maskable manifest icon",
+            Self::ThemeColor => "theme color",
+        }
+    }
+
+    /// Where browsers and search engines show what this check covers
+    pub fn display(&self) -> &'static str {
+        match self {
+            Self::Favicon => "browser tabs and history",
+            Self::IcoFallback => "bookmarks, feed readers and crawlers",
+            Self::SearchFavicon => "search results",
+            Self::AppleTouchIcon => "iOS home screen and bookmarks",
+            Self::Manifest | Self::ManifestIcons => "app install and Android home screen",
+            Self::MaskableIcon => "Android adaptive icons",
+            Self::ThemeColor => "mobile browser toolbar",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IconStatus {
+    Present,
+    /// Found on some pages but missing on others, or only partly met
+    Partial,
+    Missing,
+    /// Doesn't apply, e.g. manifest icons without a manifest
+    NotApplicable,
+}
+
+/// One line of the icon scorecard
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IconItem {
+    pub check: IconCheck,
+    pub status: IconStatus,
+
+    /// Pages (or files) that meet it
+    pub found: Vec<PathBuf>,
+
+    /// Pages that should meet it but don't
+    pub missing: Vec<PathBuf>,
+
+    /// What is missing, when it isn't obvious from the check
+    pub detail: Option<String>,
+}
+
+/// Problem with a declared icon or the manifest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum IconIssue {
+    /// Declared file doesn't exist
+    FileNotFound,
+    /// `sizes` doesn't match the image
+    SizeMismatch,
+    /// Image isn't square
+    NotSquare,
+    /// `type` doesn't match the image format
+    TypeMismatch,
+    /// `apple-touch-icon` that isn't a 180×180 PNG
+    AppleTouchFormat,
+    /// Manifest missing or not valid JSON
+    ManifestUnreadable,
+    /// Manifest without `name`, `start_url` or `display`
+    ManifestFields,
+}
+
+impl IconIssue {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::FileNotFound => "icon file not found",
+            Self::SizeMismatch => "declared sizes don't match the image",
+            Self::NotSquare => "icon isn't square",
+            Self::TypeMismatch => "declared type doesn't match the image",
+            Self::AppleTouchFormat => "apple-touch-icon isn't a 180×180 PNG",
+            Self::ManifestUnreadable => "manifest can't be read",
+            Self::ManifestFields => "manifest fields missing",
+        }
+    }
+}
+
+/// An icon or manifest with one of its issues, on the first page declaring it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IconFinding {
+    pub page: PathBuf,
+    pub url: String,
+    pub issue: IconIssue,
+    pub detail: String,
+}
+
+/// Site-wide favicon, app icon and manifest audit
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IconAudit {
+    pub items: Vec<IconItem>,
+    pub findings: Vec<IconFinding>,
+}
+
+impl IconAudit {
+    /// Audit the icons of the HTML pages in `files`, served from `root`
+    pub fn new(root: &Path, files: &[FileAnalysis]) -> Self {
+        let empty = PageIcons::default();
+        let pages: Vec<(&Path, &PageIcons)> = files
+            .iter()
+            .filter(|f| is_html_file(&f.path))
+            .map(|f| (f.path.as_path(), f.result.icons.first().unwrap_or(&empty)))
+            .collect();
+        let root_favicon = root_icon(root, "favicon.ico", IconRel::Icon);
+        let root_touch_icon = root_icon(root, "apple-touch-icon.png", IconRel::AppleTouchIcon);
+
+        let usable = |icon: &&IconRef| icon.exists != Some(false);
+        let favicons: Vec<&IconRef> = pages
+            .iter()
+            .flat_map(|(_, p)| p.icons_of(IconRel::Icon))
+            .chain(&root_favicon)
+            .filter(usable)
+            .collect();
+        let app_icons: Vec<&IconRef> = pages
+            .iter()
+            .flat_map(|(_, p)| p.icons_of(IconRel::Manifest))
+            .filter(usable)
+            .collect();
+        let has_manifest = pages
+            .iter()
+            .any(|(_, p)| p.manifest.as_ref().is_some_and(|m| m.error.is_none()));
+
+        let mut favicon = page_item(IconCheck::Favicon, &pages, |p| {
+            p.icons_of(IconRel::Icon).any(|i| usable(&i)) || root_favicon.is_some()
+        });
+        if favicon.status != IconStatus::Missing
+            && pages
+                .iter()
+                .all(|(_, p)| p.icons_of(IconRel::Icon).next().is_none())
+        {
+            favicon.detail = Some("not declared; browsers fall back to /favicon.ico".to_string());
+        }
+
+        let ico_fallback = match &root_favicon {
+            Some(_) => site_item(IconCheck::IcoFallback, vec![root.join("favicon.ico")], None),
+            None => site_item(
+                IconCheck::IcoFallback,
+                Vec::new(),
+                Some("add /favicon.ico (16, 32 and 48px) for clients that request it directly"),
+            ),
+        };
+
+        let search_favicons: Vec<PathBuf> = favicons
+            .iter()
+            .filter(|i| search_ready(i))
+            .map(|i| PathBuf::from(&i.url))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let largest = favicons.iter().filter_map(|i| i.max_edge()).max();
+        let search_detail = match largest {
+            _ if !search_favicons.is_empty() => None,
+            Some(edge) => Some(format!(
+                "largest favicon is {}px; add a square favicon of 48px multiples (48, 96, 144...) or an SVG",
+                edge
+            )),
+            None => Some("add a square favicon of 48px multiples (48, 96, 144...) or an SVG".to_string()),
+        };
+        let search_favicon = IconItem {
+            detail: search_detail,
+            ..site_item(IconCheck::SearchFavicon, search_favicons, None)
+        };
+
+        let apple_touch_icon = page_item(IconCheck::AppleTouchIcon, &pages, |p| {
+            p.icons_of(IconRel::AppleTouchIcon).any(|i| usable(&i)) || root_touch_icon.is_some()
+        });
+
+        let manifest = page_item(IconCheck::Manifest, &pages, |p| {
+            p.manifest.as_ref().is_some_and(|m| m.error.is_none())
+                || p.manifest_url.as_deref().is_some_and(|u| u.contains("://"))
+        });
+
+        let manifest_icons = if has_manifest {
+            let missing: Vec<String> = MANIFEST_EDGES
+                .iter()
+                .filter(|&&edge| !app_icons.iter().any(|i| i.has_size(edge)))
+                .map(|edge| format!("{0}×{0}", edge))
+                .collect();
+            let mut item = site_item(IconCheck::ManifestIcons, manifest_paths(&pages), None);
+            if !missing.is_empty() {
+                item.status = if missing.len() < MANIFEST_EDGES.len() {
+                    IconStatus::Partial
+                } else {
+                    IconStatus::Missing
+                };
+                item.detail = Some(format!("no {} icon in the manifest", missing.join(" or ")));
+            }
+            item
+        } else {
+            not_applicable(IconCheck::ManifestIcons)
+        };
+
+        let maskable = if has_manifest {
+            if app_icons.iter().any(|i| i.has_purpose("maskable")) {
+                site_item(IconCheck::MaskableIcon, manifest_paths(&pages), None)
+            } else {
+                site_item(
+                    IconCheck::MaskableIcon,
+                    Vec::new(),
+                    Some("add an icon with \"purpose\": \"maskable\" and its content in the central 80%"),
+                )
+            }
+        } else {
+            not_applicable(IconCheck::MaskableIcon)
+        };
+
+        let theme_color = page_item(IconCheck::ThemeColor, &pages, |p| {
+            p.theme_color.is_some() || p.manifest.as_ref().is_some_and(|m| m.theme_color.is_some())
+        });
+
+        Self {
+            items: vec![
+                favicon,
+                ico_fallback,
+                search_favicon,
+                apple_touch_icon,
+                manifest,
+                manifest_icons,
+                maskable,
+                theme_color,
+            ],
+            findings: findings(&pages),
+        }
+    }
+
+    /// Items that are missing or only partly met
+    pub fn gaps(&self) -> impl Iterator<Item = &IconItem> {
+        self.items
+            .iter()
+            .filter(|i| matches!(i.status, IconStatus::Missing | IconStatus::Partial))
+    }
+
+    pub fn item(&self, check: IconCheck) -> Option<&IconItem> {
+        self.items.iter().find(|i| i.check == check)
+    }
+}
+
+/// Check met page by page
+fn page_item(
+    check: IconCheck,
+    pages: &[(&Path, &PageIcons)],
+    meets: impl Fn(&PageIcons) -> bool,
+) -> IconItem {
+    let (found, missing): (Vec<_>, Vec<_>) = pages.iter().partition(|(_, p)| meets(p));
+    let status = match (found.is_empty(), missing.is_empty()) {
+        (false, true) => IconStatus::Present,
+        (false, false) => IconStatus::Partial,
+        (true, _) => IconStatus::Missing,
+    };
+    IconItem {
+        check,
+        status,
+        found: found
+            .into_iter()
+            .map(|(page, _)| page.to_path_buf())
+            .collect(),
+        missing: missing
+            .into_iter()
+            .map(|(page, _)| page.to_path_buf())
+            .collect(),
+        detail: None,
+    }
+}
+
+/// Check met once for the whole site, by the files in `found`
+fn site_item(check: IconCheck, found: Vec<PathBuf>, detail: Option<&str>) -> IconItem {
+    IconItem {
+        check,
+        status: if found.is_empty() {
+            IconStatus::Missing
+        } else {
+            IconStatus::Present
+        },
+        found,
+        missing: Vec::new(),
+        detail: detail.map(str::to_string),
+    }
+}
+
+fn not_applicable(check: IconCheck) -> IconItem {
+    IconItem {
+        check,
+        status: IconStatus::NotApplicable,
+        found: Vec::new(),
+        missing: Vec::new(),
+        detail: None,
+    }
+}
+
+fn manifest_paths(pages: &[(&Path, &PageIcons)]) -> Vec<PathBuf> {
+    pages
+        .iter()
+        .filter_map(|(_, p)| p.manifest.as_ref())
+        .filter(|m| m.error.is_none())
+        .map(|m| m.path.clone())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// SVG, or a square image of a multiple of 48px
+fn search_ready(icon: &IconRef) -> bool {
+    if icon.format == Some(IconFormat::Svg) {
+        return true;
+    }
+    let fits =
+        |(w, h): (u32, u32)| w == h && w >= SEARCH_FAVICON_EDGE && w % SEARCH_FAVICON_EDGE == 0;
+    if icon.actual_sizes.is_empty() {
+        icon.declared_sizes().any(fits)
+    } else {
+        icon.actual_sizes.iter().copied().any(fits)
+    }
+}
+
+/// Problems of each declared icon and manifest, reported once per URL
+fn findings(pages: &[(&Path, &PageIcons)]) -> Vec<IconFinding> {
+    let mut findings = Vec::new();
+    let mut seen: BTreeSet<(IconIssue, String)> = BTreeSet::new();
+    let mut finding = |page: &Path, url: &str, issue: IconIssue, detail: String| {
+        if seen.insert((issue, url.to_string())) {
+            findings.push(IconFinding {
+                page: page.to_path_buf(),
+                url: url.to_string(),
+                issue,
+                detail,
+            });
+        }
+    };
+
+    for (page, icons) in pages {
+        for icon in &icons.icons {
+            if icon.exists == Some(false) {
+                finding(page, &icon.url, IconIssue::FileNotFound, String::new());
+                continue;
+            }
+            let is_svg = icon.format == Some(IconFormat::Svg);
+
+            let declared: Vec<(u32, u32)> = icon.declared_sizes().collect();
+            let mismatched: Vec<String> = declared
+                .iter()
+                .filter(|s| !icon.actual_sizes.contains(s))
+                .map(|(w, h)| format!("{}x{}", w, h))
+                .collect();
+            if !is_svg && !icon.actual_sizes.is_empty() && !mismatched.is_empty() {
+                finding(
+                    page,
+                    &icon.url,
+                    IconIssue::SizeMismatch,
+                    format!(
+                        "declares {}, image is {}",
+                        mismatched.join(" "),
+                        pixel_sizes(icon)
+                    ),
+                );
+            }
+            if let Some(&(w, h)) = icon.actual_sizes.iter().find(|(w, h)| w != h) {
+                finding(
+                    page,
+                    &icon.url,
+                    IconIssue::NotSquare,
+                    format!("{}x{}", w, h),
+                );
+            }
+
+            if let (Some(mime), Some(format)) = (icon.mime.as_deref(), icon.format) {
+                if icon.exists == Some(true) && !mime_matches(mime, format) {
+                    finding(
+                        page,
+                        &icon.url,
+                        IconIssue::TypeMismatch,
+                        format!("declares {}, file is {}", mime, format.mime_type()),
+                    );
+                }
+            }
+
+            if icon.rel == IconRel::AppleTouchIcon
+                && icon.exists == Some(true)
+                && (icon.format != Some(IconFormat::Png) || !icon.has_size(APPLE_TOUCH_EDGE))
+            {
+                let format = icon
+                    .format
+                    .map(|f| f.mime_type())
+                    .unwrap_or("unknown format");
+                finding(
+                    page,
+                    &icon.url,
+                    IconIssue::AppleTouchFormat,
+                    format!("{} {}", format, pixel_sizes(icon)),
+                );
+            }
+        }
+
+        let Some(manifest) = &icons.manifest else {
+            continue;
+        };
+        let url = icons.manifest_url.as_deref().unwrap_or_default();
+        if let Some(error) = &manifest.error {
+            finding(page, url, IconIssue::ManifestUnreadable, error.clone());
+            continue;
+        }
+        let mut fields = Vec::new();
+        if manifest.name.is_none() && manifest.short_name.is_none() {
+            fields.push("name");
+        }
+        if manifest.start_url.is_none() {
+            fields.push("start_url");
+        }
+        if manifest.display.is_none() {
+            fields.push("display");
+        }
+        if !fields.is_empty() {
+            finding(page, url, IconIssue::ManifestFields, fields.join(", "));
+        }
+    }
+
+    findings
+}
+
+/// `16x16 32x32`, or `unknown size`
+fn pixel_sizes(icon: &IconRef) -> String {
+    if icon.actual_sizes.is_empty() {
+        return "unknown size".to_string();
+    }
+    icon.actual_sizes
+        .iter()
+        .map(|(w, h)| format!("{}x{}", w, h))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn mime_matches(mime: &str, format: IconFormat) -> bool {
+    match format {
+        IconFormat::Ico => matches!(
+            mime,
+            "image/x-icon" | "image/vnd.microsoft.icon" | "image/ico"
+        ),
+        IconFormat::Jpeg => matches!(mime, "image/jpeg" | "image/jpg"),
+        _ => mime == format.mime_type(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::page;
+    use crate::WebManifest;
+
+    fn icon(url: &str, rel: IconRel, sizes: &[(u32, u32)], format: IconFormat) -> IconRef {
+        IconRef {
+            url: url.to_string(),
+            rel,
+            sizes: sizes.iter().map(|(w, h)| format!("{}x{}", w, h)).collect(),
+            mime: None,
+            purpose: None,
+            exists: Some(true),
+            format: Some(format),
+            actual_sizes: sizes.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_small_favicon_without_manifest() {
+        let root = tempfile::tempdir().unwrap();
+        let favicon = PageIcons {
+            icons: vec![icon(
+                "/favicon-32.png",
+                IconRel::Icon,
+                &[(32, 32)],
+                IconFormat::Png,
+            )],
+            ..Default::default()
+        };
+        let files = vec![
+            page("site", "index.html", |r| r.icons = vec![favicon]),
+            page("site", "about.html", |r| {
+                r.icons = vec![PageIcons::default()]
+            }),
+        ];
+        let audit = IconAudit::new(root.path(), &files);
+
+        let status = |check| audit.item(check).unwrap().status;
+        assert_eq!(status(IconCheck::Favicon), IconStatus::Partial);
+        assert_eq!(
+            audit.item(IconCheck::Favicon).unwrap().missing,
+            vec![PathBuf::from("site/about.html")]
+        );
+        assert_eq!(status(IconCheck::IcoFallback), IconStatus::Missing);
+        assert_eq!(status(IconCheck::SearchFavicon), IconStatus::Missing);
+        assert!(audit
+            .item(IconCheck::SearchFavicon)
+            .unwrap()
+            .detail
+            .as_deref()
+            .unwrap()
+            .contains("largest favicon is 32px"));
+        assert_eq!(status(IconCheck::ManifestIcons), IconStatus::NotApplicable);
+        assert_eq!(status(IconCheck::AppleTouchIcon), IconStatus::Missing);
+    }
+
+    #[test]
+    fn test_manifest_icons_and_findings() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join("favicon.ico"), [0, 0, 1, 0, 0, 0]).unwrap();
+        let mut declared_512 = icon(
+            "icon-512.png",
+            IconRel::Manifest,
+            &[(256, 256)],
+            IconFormat::Png,
+        );
+        declared_512.sizes = vec!["512x512".to_string()];
+        let mut touch = icon(
+            "/touch.png",
+            IconRel::AppleTouchIcon,
+            &[(152, 152)],
+            IconFormat::Png,
+        );
+        touch.mime = Some("image/jpeg".to_string());
+        let icons = PageIcons {
+            icons: vec![
+                icon("/icon.svg", IconRel::Icon, &[], IconFormat::Svg),
+                touch,
+                icon(
+                    "icon-192.png",
+                    IconRel::Manifest,
+                    &[(192, 192)],
+                    IconFormat::Png,
+                ),
+                declared_512,
+            ],
+            manifest_url: Some("/site.webmanifest".to_string()),
+            manifest: Some(WebManifest {
+                name: Some("Acme".to_string()),
+                theme_color: Some("#0a0".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let audit = IconAudit::new(
+            root.path(),
+            &[page("site", "index.html", |r| r.icons = vec![icons])],
+        );
+
+        let status = |check| audit.item(check).unwrap().status;
+        assert_eq!(status(IconCheck::Favicon), IconStatus::Present);
+        assert_eq!(status(IconCheck::IcoFallback), IconStatus::Present);
+        assert_eq!(status(IconCheck::SearchFavicon), IconStatus::Present);
+        assert_eq!(status(IconCheck::ManifestIcons), IconStatus::Partial);
+        assert_eq!(status(IconCheck::MaskableIcon), IconStatus::Missing);
+        assert_eq!(status(IconCheck::ThemeColor), IconStatus::Present);
+
+        let issues: Vec<IconIssue> = audit.findings.iter().map(|f| f.issue).collect();
+        assert_eq!(
+            issues,
+            vec![
+                IconIssue::TypeMismatch,
+                IconIssue::AppleTouchFormat,
+                IconIssue::SizeMismatch,
+                IconIssue::ManifestFields,
+            ]
+        );
+        assert_eq!(audit.findings[3].detail, "start_url, display");
+    }
+
+    #[test]
+    fn test_search_ready_sizes() {
+        let png = |sizes: &[(u32, u32)]| icon("/i.png", IconRel::Icon, sizes, IconFormat::Png);
+        assert!(search_ready(&png(&[(96, 96)])));
+        assert!(search_ready(&icon(
+            "/i.svg",
+            IconRel::Icon,
+            &[],
+            IconFormat::Svg
+        )));
+        assert!(!search_ready(&png(&[(96, 48)])));
+        assert!(!search_ready(&png(&[(32, 32), (40, 40)])));
+
+        // Declared sizes count until the file has been measured
+        let mut declared = png(&[(144, 144)]);
+        declared.actual_sizes.clear();
+        assert!(search_ready(&declared));
+        declared.actual_sizes = vec![(100, 100)];
+        assert!(!search_ready(&declared));
+    }
+
+    #[test]
+    fn test_missing_icon_files_are_not_favicons() {
+        let root = tempfile::tempdir().unwrap();
+        let mut broken = icon(
+            "/favicon-96.png",
+            IconRel::Icon,
+            &[(96, 96)],
+            IconFormat::Png,
+        );
+        broken.exists = Some(false);
+        let icons = PageIcons {
+            icons: vec![broken],
+            ..Default::default()
+        };
+        let audit = IconAudit::new(
+            root.path(),
+            &[page("site", "index.html", |r| r.icons = vec![icons])],
+        );
+
+        let status = |check| audit.item(check).unwrap().status;
+        assert_eq!(status(IconCheck::Favicon), IconStatus::Missing);
+        assert_eq!(status(IconCheck::SearchFavicon), IconStatus::Missing);
+        assert_eq!(status(IconCheck::MaskableIcon), IconStatus::NotApplicable);
+    }
+}
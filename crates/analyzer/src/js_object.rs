@@ -0,0 +1,383 @@
+//! Minimal JavaScript/TypeScript object-literal reader
+//!
+//! Source analyzers only need the literal parts of metadata declarations
+//! (`export const metadata = {...}`, `useHead({...})`, ...). Anything that is
+//! not a plain string, object or array is kept verbatim as an expression.
+
+/// A parsed JavaScript value
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum JsValue {
+    Str(String),
+    Object(Vec<JsProperty>),
+    Array(Vec<JsValue>),
+    /// Non-literal expression, kept as source text
+    Expr(String),
+}
+
+/// A single `key: value` property of an object literal
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct JsProperty {
+    pub key: String,
+    pub value: JsValue,
+    /// Byte offset of the key in the source
+    pub offset: usize,
+}
+
+impl JsValue {
+    /// Look up a property of an object literal
+    pub fn get(&self, key: &str) -> Option<&JsValue> {
+        self.property(key).map(|p| &p.value)
+    }
+
+    /// Look up a property of an object literal, including its offset
+    pub fn property(&self, key: &str) -> Option<&JsProperty> {
+        match self {
+            Self::Object(props) => props.iter().find(|p| p.key == key),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+/// Parse the object literal starting at `start` (which must point at `{`).
+/// Returns the value and the byte offset just past the closing brace.
+pub(crate) fn parse_object_at(src: &str, start: usize) -> Option<(JsValue, usize)> {
+    if src.as_bytes().get(start) != Some(&b'{') {
+        return None;
+    }
+    let mut parser = Parser { src, pos: start };
+    let value = parser.parse_object()?;
+    Some((value, parser.pos))
+}
+
+/// Find the index of the bracket closing the one at `open`,
+/// skipping over strings and comments.
+pub(crate) fn find_closing(src: &str, open: usize) -> Option<usize> {
+    let bytes = src.as_bytes();
+    let (open_ch, close_ch) = match bytes.get(open)? {
+        b'{' => (b'{', b'}'),
+        b'(' => (b'(', b')'),
+        b'[' => (b'[', b']'),
+        _ => return None,
+    };
+
+    let mut depth = 0usize;
+    let mut i = open;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' | b'\'' | b'`' => i = skip_string(bytes, i)?,
+            b'/' if bytes.get(i + 1) == Some(&b'/') || bytes.get(i + 1) == Some(&b'*') => {
+                i = skip_comment(bytes, i);
+            }
+            c if c == open_ch => {
+                depth += 1;
+                i += 1;
+            }
+            c if c == close_ch => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// 1-based line number of a byte offset
+pub(crate) fn line_of(src: &str, offset: usize) -> u32 {
+    src[..offset.min(src.len())].matches('\n').count() as u32 + 1
+}
+
+/// Returns the index just past the closing quote of the string at `start`
+fn skip_string(bytes: &[u8], start: usize) -> Option<usize> {
+    let quote = bytes[start];
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            c if c == quote => return Some(i + 1),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Returns the index just past the comment at `start`
+fn skip_comment(bytes: &[u8], start: usize) -> usize {
+    if bytes.get(start + 1) == Some(&b'/') {
+        bytes[start..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map(|p| start + p + 1)
+            .unwrap_or(bytes.len())
+    } else {
+        bytes[start + 2..]
+            .windows(2)
+            .position(|w| w == b"*/")
+            .map(|p| start + 2 + p + 2)
+            .unwrap_or(bytes.len())
+    }
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn bytes(&self) -> &'a [u8] {
+        self.src.as_bytes()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes().get(self.pos).copied()
+    }
+
+    fn skip_trivia(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_ascii_whitespace() {
+                self.pos += 1;
+            } else if c == b'/' && matches!(self.bytes().get(self.pos + 1), Some(b'/' | b'*')) {
+                self.pos = skip_comment(self.bytes(), self.pos);
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn parse_value(&mut self) -> Option<JsValue> {
+        self.skip_trivia();
+        let start = self.pos;
+        let value = match self.peek()? {
+            b'{' => self.parse_object()?,
+            b'[' => self.parse_array()?,
+            b'"' | b'\'' | b'`' => self.parse_string()?,
+            _ => return self.parse_expression(start),
+        };
+
+        // `'a' + b`, `{...} as const` and friends are expressions
+        self.skip_trivia();
+        match self.peek() {
+            Some(b',' | b'}' | b']' | b')') | None => Some(value),
+            _ if matches!(value, JsValue::Str(_)) => self.parse_expression(start),
+            _ => {
+                self.parse_expression(self.pos)?;
+                Some(value)
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Option<JsValue> {
+        let start = self.pos;
+        let quote = self.bytes()[start];
+        let end = skip_string(self.bytes(), start)?;
+        self.pos = end;
+        let raw = &self.src[start + 1..end - 1];
+
+        if quote == b'`' && raw.contains("${") {
+            return Some(JsValue::Expr(self.src[start..end].to_string()));
+        }
+        Some(JsValue::Str(unescape(raw)))
+    }
+
+    /// Consume an expression up to the next `,` or closing bracket at depth 0
+    fn parse_expression(&mut self, start: usize) -> Option<JsValue> {
+        let bytes = self.bytes();
+        let mut i = self.pos;
+        while i < bytes.len() {
+            match bytes[i] {
+                b',' | b'}' | b']' | b')' => break,
+                b'{' | b'[' | b'(' => i = find_closing(self.src, i)? + 1,
+                b'"' | b'\'' | b'`' => i = skip_string(bytes, i)?,
+                b'/' if matches!(bytes.get(i + 1), Some(b'/' | b'*')) => {
+                    i = skip_comment(bytes, i);
+                }
+                _ => i += 1,
+            }
+        }
+        self.pos = i;
+        Some(JsValue::Expr(self.src[start..i].trim().to_string()))
+    }
+
+    fn parse_object(&mut self) -> Option<JsValue> {
+        self.pos += 1; // '{'
+        let mut props = Vec::new();
+
+        loop {
+            self.skip_trivia();
+            match self.peek()? {
+                b'}' => {
+                    self.pos += 1;
+                    return Some(JsValue::Object(props));
+                }
+                b',' => {
+                    self.pos += 1;
+                    continue;
+                }
+                _ => {}
+            }
+
+            let offset = self.pos;
+
+            // Spread: `...base`
+            if self.src[self.pos..].starts_with("...") {
+                self.pos += 3;
+                self.parse_expression(self.pos)?;
+                continue;
+            }
+
+            let key = self.parse_key()?;
+            self.skip_trivia();
+
+            let value = match self.peek()? {
+                b':' => {
+                    self.pos += 1;
+                    self.parse_value()?
+                }
+                // Method shorthand: `title() { ... }`
+                b'(' => self.parse_expression(self.pos)?,
+                // Property shorthand: `{ title }`
+                _ => JsValue::Expr(key.clone()),
+            };
+
+            props.push(JsProperty { key, value, offset });
+            // A stray closing bracket would otherwise be parsed forever
+            if self.pos == offset {
+                return None;
+            }
+        }
+    }
+
+    fn parse_key(&mut self) -> Option<String> {
+        match self.peek()? {
+            b'"' | b'\'' => match self.parse_string()? {
+                JsValue::Str(s) => Some(s),
+                _ => None,
+            },
+            b'[' => {
+                let end = find_closing(self.src, self.pos)?;
+                let key = self.src[self.pos..=end].to_string();
+                self.pos = end + 1;
+                Some(key)
+            }
+            _ => {
+                let start = self.pos;
+                while let Some(c) = self.peek() {
+                    if c.is_ascii_alphanumeric() || c == b'_' || c == b'$' {
+                        self.pos += 1;
+                    } else {
+                        break;
+                    }
+                }
+                if self.pos == start {
+                    // Unknown token, skip it to guarantee progress
+                    self.pos += 1;
+                }
+                Some(self.src[start..self.pos].to_string())
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Option<JsValue> {
+        self.pos += 1; // '['
+        let mut items = Vec::new();
+
+        loop {
+            self.skip_trivia();
+            match self.peek()? {
+                b']' => {
+                    self.pos += 1;
+                    return Some(JsValue::Array(items));
+                }
+                b',' => self.pos += 1,
+                _ => {
+                    let start = self.pos;
+                    items.push(self.parse_value()?);
+                    // A stray closing bracket would otherwise be parsed forever
+                    if self.pos == start {
+                        return None;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn unescape(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some(other) => out.push(other),
+                None => {}
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nested_object() {
+        let src = r#"{
+            title: { default: 'Acme', template: "%s | Acme" },
+            // comment
+            description: `Plain template`,
+            keywords: ['a', "b"],
+            openGraph: { images: [siteImage] },
+            alternates: { canonical: baseUrl + '/' },
+            ...shared,
+        } satisfies Metadata"#;
+
+        let (value, end) = parse_object_at(src, 0).unwrap();
+        assert_eq!(&src[end..], " satisfies Metadata");
+
+        let title = value.get("title").unwrap();
+        assert_eq!(title.get("default").and_then(JsValue::as_str), Some("Acme"));
+        assert_eq!(
+            value.get("description").and_then(JsValue::as_str),
+            Some("Plain template")
+        );
+        assert!(matches!(value.get("keywords"), Some(JsValue::Array(items)) if items.len() == 2));
+        assert_eq!(
+            value.get("alternates").unwrap().get("canonical"),
+            Some(&JsValue::Expr("baseUrl + '/'".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_template_with_interpolation_is_expression() {
+        let (value, _) = parse_object_at("{ title: `${post.title} | Blog` }", 0).unwrap();
+        assert!(matches!(value.get("title"), Some(JsValue::Expr(_))));
+    }
+
+    #[test]
+    fn test_brackets_in_comments() {
+        let src = "{\n  keywords: [\n    brand // :)\n  ],\n  title: 'Acme' /* } */,\n}";
+        let (value, end) = parse_object_at(src, 0).unwrap();
+
+        assert_eq!(end, src.len());
+        assert!(matches!(value.get("keywords"), Some(JsValue::Array(items)) if items.len() == 1));
+        assert_eq!(value.get("title").and_then(JsValue::as_str), Some("Acme"));
+
+        // Unbalanced input fails instead of looping
+        assert!(parse_object_at("{ a: [b )", 0).is_none());
+    }
+}
@@ -8,12 +8,38 @@
 //! - Boxed strategies allow runtime polymorphism
 //! - Multiple analyzers can be composed via `AnalyzerPipeline`
 
+mod canonical_audit;
+mod config;
+mod crawl_audit;
 mod error;
+mod framework;
+mod head_tags;
+mod icon_audit;
+mod js_object;
+mod link_audit;
+mod nap_audit;
+mod redirect_audit;
+mod search_console;
+mod site_comparison;
 mod strategies;
+mod taxonomy;
+mod trust_audit;
 mod types;
 
+pub use canonical_audit::*;
+pub use config::*;
+pub use crawl_audit::*;
 pub use error::AnalyzerError;
+pub use framework::detect_framework;
+pub use icon_audit::*;
+pub use link_audit::*;
+pub use nap_audit::*;
+pub use redirect_audit::*;
+pub use search_console::*;
+pub use site_comparison::*;
 pub use strategies::*;
+pub use taxonomy::{Taxonomy, TaxonomyNode};
+pub use trust_audit::*;
 pub use types::*;
 
 use std::path::Path;
@@ -27,6 +53,12 @@ pub trait AnalyzerStrategy: Send + Sync {
     /// Analyze HTML content and return results
     fn analyze(&self, content: &str) -> Result<AnalysisResult, AnalyzerError>;
 
+    /// Whether this strategy can analyze the given file (default: HTML files).
+    /// Source-level analyzers override this to claim framework files.
+    fn accepts(&self, path: &Path) -> bool {
+        is_html_file(path)
+    }
+
     /// Optional: Analyze from file path (default reads file)
     fn analyze_file(&self, path: &Path) -> Result<AnalysisResult, AnalyzerError> {
         let content = std::fs::read_to_string(path)
@@ -41,21 +73,59 @@ pub type BoxedAnalyzer = Box<dyn AnalyzerStrategy>;
 /// Pipeline to compose multiple analyzers
 pub struct AnalyzerPipeline {
     analyzers: Vec<BoxedAnalyzer>,
+    audit_weights: AuditWeights,
+    date_fallback: DateFallback,
 }
 
 impl AnalyzerPipeline {
     /// Create empty pipeline
     pub fn new() -> Self {
-        Self { analyzers: Vec::new() }
+        Self {
+            analyzers: Vec::new(),
+            audit_weights: AuditWeights::default(),
+            date_fallback: DateFallback::None,
+        }
     }
 
     /// Create pipeline with default analyzers
     pub fn default_pipeline() -> Self {
+        Self::from_config(&AnalyzerConfig::default()).expect("default config is valid")
+    }
+
+    /// Create the default pipeline tuned by `config`, leaving out disabled strategies
+    pub fn from_config(config: &AnalyzerConfig) -> Result<Self, AnalyzerError> {
+        let analyzers: Vec<BoxedAnalyzer> = vec![
+            Box::new(KeywordAnalyzer::with_config(&config.keywords)),
+            Box::new(BusinessTypeAnalyzer::with_config(&config.business)?),
+            Box::new(SeoAuditAnalyzer::new()),
+            Box::new(NextMetadataAnalyzer::new()),
+            Box::new(VueHeadAnalyzer::new()),
+            Box::new(NuxtConfigAnalyzer::new()),
+            Box::new(SvelteHeadAnalyzer::new()),
+            Box::new(MarkdownAnalyzer::with_config(&config.keywords)),
+            Box::new(SpaShellAnalyzer::with_config(&config.spa, &config.keywords)),
+            Box::new(EntityAnalyzer::with_config(&config.entities)),
+            Box::new(FaqAnalyzer::new()),
+            Box::new(HowToAnalyzer::new()),
+            Box::new(ProductAnalyzer::new()),
+            Box::new(ArticleAnalyzer::new()),
+            Box::new(PerformanceAnalyzer::new()),
+            Box::new(LinkAnalyzer::new()),
+            Box::new(SnippetAnalyzer::new()),
+            Box::new(NapAnalyzer::with_config(&config.entities)),
+            Box::new(IconAnalyzer::new()),
+            Box::new(ContentDepthAnalyzer::new()),
+        ];
+
         let mut pipeline = Self::new();
-        pipeline.add(Box::new(KeywordAnalyzer::new()));
-        pipeline.add(Box::new(BusinessTypeAnalyzer::new()));
-        pipeline.add(Box::new(SeoAuditAnalyzer::new()));
-        pipeline
+        pipeline.audit_weights = config.audit.clone();
+        pipeline.date_fallback = config.article.date_fallback;
+        for analyzer in analyzers {
+            if config.is_enabled(analyzer.name()) {
+                pipeline.add(analyzer);
+            }
+        }
+        Ok(pipeline)
     }
 
     /// Add analyzer to pipeline
@@ -64,11 +134,12 @@ impl AnalyzerPipeline {
         self
     }
 
-    /// Run all analyzers and merge results
+    /// Run the HTML analyzers on `content` and merge results
     pub fn analyze(&self, content: &str) -> Result<AnalysisResult, AnalyzerError> {
         let mut merged = AnalysisResult::default();
+        let html = Path::new("index.html");
 
-        for analyzer in &self.analyzers {
+        for analyzer in self.analyzers.iter().filter(|a| a.accepts(html)) {
             tracing::debug!("Running analyzer: {}", analyzer.name());
             let result = analyzer.analyze(content)?;
             merged.merge(result);
@@ -77,7 +148,55 @@ impl AnalyzerPipeline {
         Ok(merged)
     }
 
-    /// Analyze entire directory (finds HTML files)
+    /// Run the analyzers that accept `path` on its content. `path` is
+    /// matched as given, so pass it relative to the project root when the
+    /// root itself may live under an `app/` or `pages/` directory.
+    /// Returns `None` when no analyzer handles this kind of file or none
+    /// finds anything in it.
+    pub fn analyze_path(
+        &self,
+        path: &Path,
+        content: &str,
+    ) -> Result<Option<AnalysisResult>, AnalyzerError> {
+        self.analyze_source(path, path, content)
+    }
+
+    /// `analyze_path` matching analyzers against `relative`, the path
+    /// below the project root, while results keep the full `path`
+    fn analyze_source(
+        &self,
+        path: &Path,
+        relative: &Path,
+        content: &str,
+    ) -> Result<Option<AnalysisResult>, AnalyzerError> {
+        let mut merged: Option<AnalysisResult> = None;
+
+        for analyzer in self.analyzers.iter().filter(|a| a.accepts(relative)) {
+            tracing::debug!("Running analyzer: {} on {}", analyzer.name(), path.display());
+            let mut result = analyzer.analyze(content)?;
+            if result == AnalysisResult::default() {
+                continue;
+            }
+            // Files next to the page are only known here, before stamping
+            for icons in result.icons.iter_mut().filter(|i| i.page.is_none()) {
+                strategies::inspect_icons(icons, path);
+            }
+            for performance in result.performance.iter_mut().filter(|p| p.page.is_none()) {
+                strategies::measure_assets(performance, path);
+            }
+            result.stamp_page(path);
+            merged.get_or_insert_with(AnalysisResult::default).merge(result);
+        }
+
+        if let Some(article) = merged.as_mut().and_then(|r| r.article.as_mut()) {
+            strategies::fill_missing_dates(article, path, self.date_fallback);
+        }
+
+        Ok(merged)
+    }
+
+    /// Analyze entire directory (HTML files plus any framework sources
+    /// claimed by source-level analyzers)
     pub fn analyze_directory(&self, dir: &Path) -> Result<DirectoryAnalysis, AnalyzerError> {
         use walkdir::WalkDir;
 
@@ -87,19 +206,28 @@ impl AnalyzerPipeline {
         for entry in WalkDir::new(dir)
             .max_depth(5)
             .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !Self::is_ignored_dir(e))
             .filter_map(|e| e.ok())
         {
             let path = entry.path();
-            if Self::is_html_file(path) {
-                // Detect main file
-                if main_file.is_none() && Self::is_main_file(path) {
-                    main_file = Some(path.to_path_buf());
-                }
+            let relative = path.strip_prefix(dir).unwrap_or(path);
+            if !entry.file_type().is_file() || !self.analyzers.iter().any(|a| a.accepts(relative))
+            {
+                continue;
+            }
+
+            // Only HTML files are injection targets
+            if main_file.is_none() && is_html_file(path) && Self::is_main_file(path) {
+                main_file = Some(path.to_path_buf());
+            }
 
-                let content = std::fs::read_to_string(path)
-                    .map_err(|e| AnalyzerError::FileRead(path.to_path_buf(), e))?;
+            let content = std::fs::read_to_string(path)
+                .map_err(|e| AnalyzerError::FileRead(path.to_path_buf(), e))?;
 
-                let result = self.analyze(&content)?;
+            if let Some(mut result) = self.analyze_source(path, relative, &content)? {
+                for depth in &mut result.content_depth {
+                    strategies::infer_role(depth, relative);
+                }
                 results.push(FileAnalysis {
                     path: path.to_path_buf(),
                     result,
@@ -107,48 +235,63 @@ impl AnalyzerPipeline {
             }
         }
 
+        let detection = detect_framework(dir);
+        // The framework's own HTML template wins over the first index.html found
+        let main_file = detection
+            .framework
+            .injection_targets()
+            .iter()
+            .map(|target| dir.join(target))
+            .find(|path| is_html_file(path) && results.iter().any(|f| &f.path == path))
+            .or(main_file);
+
         Ok(DirectoryAnalysis {
             root: dir.to_path_buf(),
             main_file,
             files: results,
-            framework: Self::detect_framework(dir),
+            framework: detection.framework,
+            framework_confidence: detection.confidence,
+            framework_evidence: detection.evidence,
+            audit_weights: self.audit_weights.clone(),
+            search_console: Vec::new(),
         })
     }
 
-    fn is_html_file(path: &Path) -> bool {
-        path.extension()
-            .map(|ext| ext == "html" || ext == "htm")
-            .unwrap_or(false)
+    /// Analyze an old and a new build of a site and compare them
+    pub fn compare_directories(
+        &self,
+        old: &Path,
+        new: &Path,
+    ) -> Result<SiteComparison, AnalyzerError> {
+        let before = self.analyze_directory(old)?;
+        let after = self.analyze_directory(new)?;
+        Ok(SiteComparison::new(&before, &after))
+    }
+
+    /// Dependency and build-cache directories never contain site sources
+    fn is_ignored_dir(entry: &walkdir::DirEntry) -> bool {
+        entry.file_type().is_dir()
+            && entry
+                .file_name()
+                .to_str()
+                .map(|name| {
+                    name.starts_with('.')
+                        || matches!(name, "node_modules" | "vendor" | "target")
+                })
+                .unwrap_or(false)
     }
 
     fn is_main_file(path: &Path) -> bool {
         let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
         matches!(name, "index.html" | "index.htm" | "_document.tsx" | "layout.tsx")
     }
+}
 
-    fn detect_framework(dir: &Path) -> Framework {
-        if dir.join("next.config.js").exists() || dir.join("next.config.mjs").exists() {
-            Framework::NextJs
-        } else if dir.join("vite.config.js").exists() || dir.join("vite.config.ts").exists() {
-            Framework::Vite
-        } else if dir.join("package.json").exists() {
-            // Check for React in package.json
-            if let Ok(content) = std::fs::read_to_string(dir.join("package.json")) {
-                if content.contains("\"react\"") {
-                    return Framework::React;
-                }
-                if content.contains("\"vue\"") {
-                    return Framework::Vue;
-                }
-                if content.contains("\"svelte\"") {
-                    return Framework::Svelte;
-                }
-            }
-            Framework::Unknown
-        } else {
-            Framework::VanillaHtml
-        }
-    }
+/// Whether `path` is an HTML document
+pub fn is_html_file(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext == "html" || ext == "htm")
+        .unwrap_or(false)
 }
 
 impl Default for AnalyzerPipeline {
@@ -157,6 +300,49 @@ impl Default for AnalyzerPipeline {
     }
 }
 
+/// Page and site fixtures shared by the site-level audit tests
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use crate::{AnalysisResult, DirectoryAnalysis, FileAnalysis};
+    use std::path::Path;
+
+    /// Page at `path` below `root`, with the results `build` fills in
+    pub(crate) fn page(
+        root: impl AsRef<Path>,
+        path: &str,
+        build: impl FnOnce(&mut AnalysisResult),
+    ) -> FileAnalysis {
+        let mut result = AnalysisResult::default();
+        build(&mut result);
+        FileAnalysis {
+            path: root.as_ref().join(path),
+            result,
+        }
+    }
+
+    /// Page at `path` below `root` with a `<title>`
+    pub(crate) fn titled(root: impl AsRef<Path>, path: &str, title: &str) -> FileAnalysis {
+        page(root, path, |r| {
+            r.existing_seo.has_title = true;
+            r.existing_seo.title = Some(title.to_string());
+        })
+    }
+
+    /// Analysis of `files` in the directory `root`
+    pub(crate) fn site(root: impl AsRef<Path>, files: Vec<FileAnalysis>) -> DirectoryAnalysis {
+        DirectoryAnalysis {
+            root: root.as_ref().to_path_buf(),
+            main_file: None,
+            files,
+            framework: Default::default(),
+            framework_confidence: 0.0,
+            framework_evidence: Vec::new(),
+            audit_weights: Default::default(),
+            search_console: Vec::new(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -187,6 +373,58 @@ mod tests {
         assert!(result.existing_seo.has_description);
     }
 
+    #[test]
+    fn test_analyze_skips_source_analyzers() {
+        let result = AnalyzerPipeline::default_pipeline()
+            .analyze(SAMPLE_HTML)
+            .unwrap();
+
+        let text = result.raw_text.unwrap_or_default();
+        assert!(text.contains("cloud migration"));
+        assert!(result.front_matter.is_none());
+
+        let mut merged = AnalysisResult {
+            raw_text: Some("Body text".to_string()),
+            ..Default::default()
+        };
+        merged.merge(AnalysisResult {
+            raw_text: Some(String::new()),
+            ..Default::default()
+        });
+        assert_eq!(merged.raw_text.as_deref(), Some("Body text"));
+    }
+
+    #[test]
+    fn test_pipeline_from_config() {
+        let config = AnalyzerConfig::from_toml(
+            r#"
+disabled = ["business_type_analyzer"]
+
+[keywords]
+extra_stop_words = ["services"]
+
+[audit]
+title = 50
+description = 50
+og_tags = 0
+twitter_cards = 0
+schema = 0
+canonical = 0
+viewport = 0
+charset = 0
+"#,
+        )
+        .unwrap();
+        let pipeline = AnalyzerPipeline::from_config(&config).unwrap();
+        let result = pipeline.analyze(SAMPLE_HTML).unwrap();
+
+        assert_eq!(result.business_type, BusinessType::Unknown);
+        assert!(result.keywords.iter().all(|k| k.word != "services"));
+        assert!(result.keywords.iter().any(|k| k.word == "development"));
+        assert_eq!(pipeline.audit_weights.title, 50);
+        assert_eq!(result.existing_seo.weighted_score(&config.audit), 100);
+    }
+
     #[test]
     fn test_keyword_extraction() {
         let analyzer = KeywordAnalyzer::new();
@@ -194,4 +432,113 @@ mod tests {
 
         assert!(result.keywords.iter().any(|k| k.word.contains("service")));
     }
+
+    #[test]
+    fn test_next_app_router_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = dir.path().join("app");
+        std::fs::create_dir_all(&app).unwrap();
+        std::fs::create_dir_all(dir.path().join("node_modules/pkg")).unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"dependencies": {"next": "14.0.0", "react": "18.2.0"}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            app.join("layout.tsx"),
+            "export const metadata = { title: 'Acme', description: 'Widgets' }",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("node_modules/pkg/index.html"), SAMPLE_HTML).unwrap();
+
+        let analysis = AnalyzerPipeline::default_pipeline()
+            .analyze_directory(dir.path())
+            .unwrap();
+
+        assert_eq!(analysis.framework, Framework::NextJs);
+        assert_eq!(analysis.files.len(), 1);
+        assert!(analysis.main_file.is_none());
+
+        let merged = analysis.merged_result();
+        assert_eq!(merged.existing_seo.title.as_deref(), Some("Acme"));
+        assert!(merged.existing_seo.has_description);
+    }
+
+    #[test]
+    fn test_framework_template_is_main_file() {
+        let dir = tempfile::tempdir().unwrap();
+        for sub in ["build", "public"] {
+            std::fs::create_dir_all(dir.path().join(sub)).unwrap();
+        }
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"dependencies": {"react": "18.2.0", "react-dom": "18.2.0"}}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("build/index.html"), SAMPLE_HTML).unwrap();
+        std::fs::write(dir.path().join("public/index.html"), SAMPLE_HTML).unwrap();
+
+        let pipeline = AnalyzerPipeline::default_pipeline();
+        let analysis = pipeline.analyze_directory(dir.path()).unwrap();
+        assert_eq!(analysis.framework, Framework::React);
+        assert_eq!(analysis.main_file, Some(dir.path().join("public/index.html")));
+
+        // Hugo: the base layout, not a head partial or the built site
+        let dir = tempfile::tempdir().unwrap();
+        for sub in ["public", "layouts/_default", "layouts/partials"] {
+            std::fs::create_dir_all(dir.path().join(sub)).unwrap();
+        }
+        std::fs::write(dir.path().join("hugo.toml"), "title = 'Acme'").unwrap();
+        std::fs::write(dir.path().join("public/index.html"), SAMPLE_HTML).unwrap();
+        std::fs::write(dir.path().join("layouts/_default/baseof.html"), SAMPLE_HTML).unwrap();
+        std::fs::write(
+            dir.path().join("layouts/partials/head.html"),
+            "<title>{{ .Title }}</title>",
+        )
+        .unwrap();
+
+        let analysis = pipeline.analyze_directory(dir.path()).unwrap();
+        assert_eq!(analysis.framework, Framework::Hugo);
+        assert_eq!(
+            analysis.main_file,
+            Some(dir.path().join("layouts/_default/baseof.html"))
+        );
+        assert_eq!(
+            analysis.framework.find_injection_target(dir.path()),
+            analysis.main_file
+        );
+    }
+
+    #[test]
+    fn test_project_root_under_app_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("app/site");
+        std::fs::create_dir_all(root.join("components")).unwrap();
+        std::fs::create_dir_all(root.join("pages")).unwrap();
+        // A layout component outside the App Router, only matched through `app/site/`
+        std::fs::write(
+            root.join("components/layout.tsx"),
+            "import Head from 'next/head'\nexport default () => <Head><title>Shell</title></Head>",
+        )
+        .unwrap();
+        std::fs::write(root.join("server.js"), "require('http').createServer()").unwrap();
+        std::fs::write(
+            root.join("pages/about.tsx"),
+            "import Head from 'next/head'\nexport default () => <Head><title>About</title></Head>",
+        )
+        .unwrap();
+        // Claimed by the Next.js analyzer but without any metadata
+        std::fs::write(root.join("pages/plain.tsx"), "export default () => null").unwrap();
+
+        let analysis = AnalyzerPipeline::default_pipeline()
+            .analyze_directory(&root)
+            .unwrap();
+        let files: Vec<_> = analysis
+            .files
+            .iter()
+            .map(|f| f.path.strip_prefix(&root).unwrap())
+            .collect();
+
+        assert_eq!(files, [Path::new("pages/about.tsx")]);
+    }
 }
@@ -0,0 +1,340 @@
+//! Link audit - anchor text and `rel` hygiene across a site
+//!
+//! Built from the links `LinkAnalyzer` records. Links are external when they
+//! point at another host than the site URL; without a site URL every absolute
+//! link counts as external. Findings are grouped by page and by external domain.
+
+use crate::Link;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use url::Url;
+
+/// Anchors that say nothing about the target
+#[rustfmt::skip]
+const GENERIC_ANCHORS: [&str; 16] = [
+    "click here", "here", "click", "read more", "more", "learn more",
+    "this link", "link", "this", "go", "continue", "details", "more info",
+    "find out more", "see more", "view more",
+];
+
+/// Problem found with a link
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum LinkIssue {
+    /// No text, image `alt`, `aria-label` or `title`
+    EmptyAnchor,
+    /// "click here", "read more" and similar
+    GenericAnchor,
+    /// External `target="_blank"` link without `rel=noopener`/`noreferrer`
+    MissingNoopener,
+    /// Affiliate or sponsored link without `rel=sponsored`
+    UnmarkedSponsored,
+    /// Link in user content without `rel=ugc`
+    UnmarkedUgc,
+    /// `http://` link on an `https` site
+    InsecureLink,
+}
+
+impl LinkIssue {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::EmptyAnchor => "empty anchor text",
+            Self::GenericAnchor => "generic anchor text",
+            Self::MissingNoopener => "target=_blank without rel=noopener",
+            Self::UnmarkedSponsored => "sponsored link without rel=sponsored",
+            Self::UnmarkedUgc => "user-generated link without rel=ugc",
+            Self::InsecureLink => "http:// link on an https site",
+        }
+    }
+}
+
+/// A link with one of its issues
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinkFinding {
+    pub issue: LinkIssue,
+    pub href: String,
+    pub anchor: String,
+}
+
+/// How often an anchor text is used
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnchorCount {
+    /// Lowercased anchor text
+    pub text: String,
+    pub count: u32,
+    pub generic: bool,
+}
+
+/// Links and findings of one page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageLinks {
+    pub page: Option<PathBuf>,
+    pub internal: u32,
+    pub external: u32,
+    pub findings: Vec<LinkFinding>,
+}
+
+/// Links to one external domain across the site
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DomainLinks {
+    /// Host without `www.`
+    pub domain: String,
+    pub links: u32,
+
+    /// Pages linking to the domain
+    pub pages: u32,
+
+    pub nofollow: u32,
+    pub sponsored: u32,
+    pub ugc: u32,
+
+    /// Findings per issue
+    pub issues: BTreeMap<LinkIssue, u32>,
+}
+
+/// Site-wide link audit
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LinkAudit {
+    /// Most used first
+    pub anchors: Vec<AnchorCount>,
+    pub pages: Vec<PageLinks>,
+
+    /// Most linked first
+    pub domains: Vec<DomainLinks>,
+}
+
+impl LinkAudit {
+    /// Audit `links`; `site_url` decides which links are internal and whether
+    /// `http://` links are insecure
+    pub fn new(links: &[Link], site_url: Option<&str>) -> Self {
+        let site = site_url.and_then(|u| Url::parse(u).ok());
+        let site_host = site.as_ref().and_then(|u| u.host_str()).map(bare_host);
+        let https_site = site.as_ref().is_some_and(|u| u.scheme() == "https");
+
+        let mut anchors: BTreeMap<String, u32> = BTreeMap::new();
+        let mut pages: Vec<PageLinks> = Vec::new();
+        let mut domains: BTreeMap<String, (DomainLinks, BTreeSet<Option<PathBuf>>)> =
+            BTreeMap::new();
+
+        for link in links {
+            let external_host = external_host(&link.href, site_host.as_deref());
+            let issues = issues(link, external_host.is_some(), https_site);
+
+            if !link.anchor.is_empty() {
+                *anchors.entry(link.anchor.to_lowercase()).or_default() += 1;
+            }
+
+            let page = match pages.iter_mut().position(|p| p.page == link.page) {
+                Some(i) => &mut pages[i],
+                None => {
+                    pages.push(PageLinks {
+                        page: link.page.clone(),
+                        internal: 0,
+                        external: 0,
+                        findings: Vec::new(),
+                    });
+                    pages.last_mut().unwrap()
+                }
+            };
+            match external_host {
+                Some(_) => page.external += 1,
+                None => page.internal += 1,
+            }
+            page.findings
+                .extend(issues.iter().map(|&issue| LinkFinding {
+                    issue,
+                    href: link.href.clone(),
+                    anchor: link.anchor.clone(),
+                }));
+
+            if let Some(host) = external_host {
+                let (domain, linking_pages) = domains.entry(host.clone()).or_insert_with(|| {
+                    let domain = DomainLinks {
+                        domain: host,
+                        links: 0,
+                        pages: 0,
+                        nofollow: 0,
+                        sponsored: 0,
+                        ugc: 0,
+                        issues: BTreeMap::new(),
+                    };
+                    (domain, BTreeSet::new())
+                });
+                domain.links += 1;
+                domain.nofollow += link.has_rel("nofollow") as u32;
+                domain.sponsored += link.has_rel("sponsored") as u32;
+                domain.ugc += link.has_rel("ugc") as u32;
+                for issue in issues {
+                    *domain.issues.entry(issue).or_default() += 1;
+                }
+                linking_pages.insert(link.page.clone());
+                domain.pages = linking_pages.len() as u32;
+            }
+        }
+
+        let mut anchors: Vec<AnchorCount> = anchors
+            .into_iter()
+            .map(|(text, count)| AnchorCount {
+                generic: is_generic(&text),
+                text,
+                count,
+            })
+            .collect();
+        anchors.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.text.cmp(&b.text)));
+
+        let mut domains: Vec<DomainLinks> = domains.into_values().map(|(d, _)| d).collect();
+        domains.sort_by(|a, b| b.links.cmp(&a.links).then_with(|| a.domain.cmp(&b.domain)));
+
+        Self {
+            anchors,
+            pages,
+            domains,
+        }
+    }
+
+    /// Findings per issue across the site
+    pub fn issue_counts(&self) -> BTreeMap<LinkIssue, u32> {
+        let mut counts = BTreeMap::new();
+        for finding in self.pages.iter().flat_map(|p| &p.findings) {
+            *counts.entry(finding.issue).or_default() += 1;
+        }
+        counts
+    }
+}
+
+fn issues(link: &Link, external: bool, https_site: bool) -> Vec<LinkIssue> {
+    let mut issues = Vec::new();
+
+    if link.anchor.is_empty() {
+        issues.push(LinkIssue::EmptyAnchor);
+    } else if is_generic(&link.anchor) {
+        issues.push(LinkIssue::GenericAnchor);
+    }
+    if external && link.new_tab && !link.has_rel("noopener") && !link.has_rel("noreferrer") {
+        issues.push(LinkIssue::MissingNoopener);
+    }
+    if link.sponsored && !link.has_rel("sponsored") {
+        issues.push(LinkIssue::UnmarkedSponsored);
+    }
+    if external && link.user_generated && !link.has_rel("ugc") {
+        issues.push(LinkIssue::UnmarkedUgc);
+    }
+    if https_site && link.href.to_ascii_lowercase().starts_with("http://") {
+        issues.push(LinkIssue::InsecureLink);
+    }
+    issues
+}
+
+/// Host of an absolute link to another site; `None` for internal links
+fn external_host(href: &str, site_host: Option<&str>) -> Option<String> {
+    let absolute = match href.strip_prefix("//") {
+        Some(rest) => format!("https://{}", rest),
+        None => href.to_string(),
+    };
+    let url = Url::parse(&absolute).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    let host = bare_host(url.host_str()?);
+    (Some(host.as_str()) != site_host).then_some(host)
+}
+
+pub(crate) fn bare_host(host: &str) -> String {
+    let host = host.to_ascii_lowercase();
+    host.strip_prefix("www.")
+        .map(str::to_string)
+        .unwrap_or(host)
+}
+
+fn is_generic(anchor: &str) -> bool {
+    let text = anchor
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase();
+    GENERIC_ANCHORS.contains(&text.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(page: &str, href: &str, anchor: &str) -> Link {
+        Link {
+            href: href.to_string(),
+            anchor: anchor.to_string(),
+            page: Some(PathBuf::from(page)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_findings_by_page_and_domain() {
+        let links = vec![
+            link("index.html", "/services", "Drain cleaning"),
+            link("index.html", "https://www.acme.com/about", "Click here!"),
+            Link {
+                new_tab: true,
+                ..link("index.html", "https://supplier.example/pipes", "PEX pipe")
+            },
+            Link {
+                sponsored: true,
+                rel: vec!["nofollow".to_string()],
+                ..link("blog.html", "https://supplier.example/deal", "")
+            },
+            Link {
+                user_generated: true,
+                ..link("blog.html", "http://spam.example/", "my site")
+            },
+        ];
+        let audit = LinkAudit::new(&links, Some("https://acme.com"));
+
+        let home = &audit.pages[0];
+        assert_eq!((home.internal, home.external), (2, 1));
+        assert_eq!(
+            home.findings.iter().map(|f| f.issue).collect::<Vec<_>>(),
+            vec![LinkIssue::GenericAnchor, LinkIssue::MissingNoopener]
+        );
+
+        let blog = &audit.pages[1];
+        assert_eq!(
+            blog.findings.iter().map(|f| f.issue).collect::<Vec<_>>(),
+            vec![
+                LinkIssue::EmptyAnchor,
+                LinkIssue::UnmarkedSponsored,
+                LinkIssue::UnmarkedUgc,
+                LinkIssue::InsecureLink
+            ]
+        );
+
+        let supplier = &audit.domains[0];
+        assert_eq!(supplier.domain, "supplier.example");
+        assert_eq!(
+            (supplier.links, supplier.pages, supplier.nofollow),
+            (2, 2, 1)
+        );
+        assert_eq!(supplier.issues[&LinkIssue::MissingNoopener], 1);
+
+        assert_eq!(audit.issue_counts()[&LinkIssue::InsecureLink], 1);
+        assert!(audit
+            .anchors
+            .iter()
+            .any(|a| a.text == "click here!" && a.generic));
+    }
+
+    #[test]
+    fn test_without_site_url() {
+        let links = vec![
+            Link {
+                new_tab: true,
+                rel: vec!["noreferrer".to_string()],
+                ..link("index.html", "https://acme.com/", "Acme")
+            },
+            link("index.html", "http://old.example/", "Old site"),
+        ];
+        let audit = LinkAudit::new(&links, None);
+
+        // Absolute links are external, but http:// is only flagged on https sites
+        assert_eq!(audit.pages[0].external, 2);
+        assert!(audit.pages[0].findings.is_empty());
+        assert_eq!(audit.domains.len(), 2);
+    }
+}
@@ -0,0 +1,435 @@
+//! NAP audit - name, address and phone consistency for local businesses
+//!
+//! Local rankings reward a business that states its name, address and phone
+//! the same way everywhere. Every mention is normalized (phones to E.164,
+//! street abbreviations expanded, names without legal suffixes) and the value
+//! the most trusted mentions agree on becomes canonical, unless the
+//! configuration states one. Mentions of other values are mismatches; other
+//! spellings of the canonical value are format variants.
+
+use crate::strategies::normalize_phone;
+use crate::trust_audit::name_key;
+use crate::PostalAddress;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Street abbreviations and their spelled-out form
+#[rustfmt::skip]
+const STREET_ABBREVIATIONS: [(&str, &str); 22] = [
+    ("st", "street"), ("ave", "avenue"), ("av", "avenue"), ("rd", "road"),
+    ("blvd", "boulevard"), ("dr", "drive"), ("ln", "lane"), ("ct", "court"),
+    ("pl", "place"), ("pkwy", "parkway"), ("hwy", "highway"), ("cir", "circle"),
+    ("ter", "terrace"), ("sq", "square"), ("ste", "suite"), ("apt", "apartment"),
+    ("fl", "floor"), ("n", "north"), ("s", "south"), ("e", "east"), ("w", "west"),
+    ("no", "number"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum NapField {
+    Name,
+    Address,
+    Phone,
+}
+
+impl NapField {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Name => "business name",
+            Self::Address => "address",
+            Self::Phone => "phone",
+        }
+    }
+}
+
+/// Where a NAP value was stated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum NapSource {
+    /// The injector's `SeoConfig`, canonical whenever set
+    Config,
+    JsonLd,
+    Microdata,
+    /// `tel:` link
+    TelLink,
+    /// Visible page text
+    Text,
+}
+
+impl NapSource {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Config => "config",
+            Self::JsonLd => "JSON-LD",
+            Self::Microdata => "microdata",
+            Self::TelLink => "tel: link",
+            Self::Text => "page text",
+        }
+    }
+
+    /// Vote of a mention when picking the canonical value
+    fn weight(&self) -> u32 {
+        match self {
+            Self::Config => 0,
+            Self::JsonLd | Self::Microdata => 3,
+            Self::TelLink => 2,
+            Self::Text => 1,
+        }
+    }
+}
+
+/// A business name, address or phone as stated somewhere
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NapMention {
+    pub field: NapField,
+
+    /// As written, whitespace collapsed
+    pub value: String,
+
+    /// Normalized value mentions are compared by
+    pub key: String,
+
+    /// Parsed address, for address mentions
+    pub address: Option<PostalAddress>,
+
+    pub source: NapSource,
+    pub page: Option<PathBuf>,
+}
+
+impl NapMention {
+    /// `None` when the name has no letters or digits
+    pub fn name(value: &str, source: NapSource) -> Option<Self> {
+        let key = name_key(value);
+        (!key.is_empty()).then(|| Self::new(NapField::Name, value, key, None, source))
+    }
+
+    /// `None` when `value` isn't a valid number; national numbers are read
+    /// as `country`'s (ISO 3166-1 alpha-2)
+    pub fn phone(value: &str, country: &str, source: NapSource) -> Option<Self> {
+        let key = normalize_phone(value, country)?;
+        Some(Self::new(NapField::Phone, value, key, None, source))
+    }
+
+    pub fn address(value: &str, address: PostalAddress, source: NapSource) -> Self {
+        let key = address_key(&address);
+        Self::new(NapField::Address, value, key, Some(address), source)
+    }
+
+    fn new(
+        field: NapField,
+        value: &str,
+        key: String,
+        address: Option<PostalAddress>,
+        source: NapSource,
+    ) -> Self {
+        Self {
+            field,
+            value: value.split_whitespace().collect::<Vec<_>>().join(" "),
+            key,
+            address,
+            source,
+            page: None,
+        }
+    }
+}
+
+/// Where a spelling was found
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NapSighting {
+    pub source: NapSource,
+    pub page: Option<PathBuf>,
+}
+
+/// One spelling of a NAP field and everywhere it appears
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NapVariant {
+    pub value: String,
+
+    /// Normalizes to the canonical value, only written differently
+    pub consistent: bool,
+
+    pub sightings: Vec<NapSighting>,
+}
+
+/// Canonical value and spellings of one NAP field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NapFieldAudit {
+    pub field: NapField,
+
+    /// Value to apply everywhere: E.164 for phones, the configured or most
+    /// trusted spelling otherwise
+    pub canonical: Option<String>,
+
+    /// Consistent spellings first, then most sighted first
+    pub variants: Vec<NapVariant>,
+}
+
+impl NapFieldAudit {
+    /// Spellings of a different name, address or phone
+    pub fn mismatches(&self) -> impl Iterator<Item = &NapVariant> {
+        self.variants.iter().filter(|v| !v.consistent)
+    }
+
+    /// Other spellings of the canonical value, e.g. `(303) 555-0142` for `+13035550142`
+    pub fn format_variants(&self) -> impl Iterator<Item = &NapVariant> {
+        self.variants
+            .iter()
+            .filter(|v| v.consistent && Some(&v.value) != self.canonical.as_ref())
+    }
+}
+
+/// Site-wide NAP consistency report
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NapAudit {
+    pub name: NapFieldAudit,
+    pub address: NapFieldAudit,
+    pub phone: NapFieldAudit,
+
+    /// Canonical address, parsed for schema.org markup
+    pub canonical_address: Option<PostalAddress>,
+}
+
+impl NapAudit {
+    pub fn new(mentions: &[NapMention]) -> Self {
+        let (address, address_key) = field_audit(mentions, NapField::Address);
+        let canonical_address = address_key.and_then(|key| {
+            let mut matching: Vec<&NapMention> = mentions
+                .iter()
+                .filter(|m| m.field == NapField::Address && m.key == key)
+                .collect();
+            matching.sort_by_key(|m| m.source);
+            matching.into_iter().find_map(|m| m.address.clone())
+        });
+
+        Self {
+            name: field_audit(mentions, NapField::Name).0,
+            address,
+            phone: field_audit(mentions, NapField::Phone).0,
+            canonical_address,
+        }
+    }
+
+    pub fn fields(&self) -> [&NapFieldAudit; 3] {
+        [&self.name, &self.address, &self.phone]
+    }
+
+    /// No mentions of a different name, address or phone
+    pub fn is_consistent(&self) -> bool {
+        self.fields()
+            .iter()
+            .all(|f| f.mismatches().next().is_none())
+    }
+}
+
+/// Audit of `field`, with the canonical key
+fn field_audit(mentions: &[NapMention], field: NapField) -> (NapFieldAudit, Option<String>) {
+    let mentions: Vec<&NapMention> = mentions.iter().filter(|m| m.field == field).collect();
+
+    // Configured value wins, then the most trusted votes, then the first seen
+    let mut votes: Vec<(&str, u32)> = Vec::new();
+    for mention in &mentions {
+        match votes.iter_mut().find(|(key, _)| *key == mention.key) {
+            Some((_, votes)) => *votes += mention.source.weight(),
+            None => votes.push((&mention.key, mention.source.weight())),
+        }
+    }
+    let canonical_key = mentions
+        .iter()
+        .find(|m| m.source == NapSource::Config)
+        .map(|m| m.key.as_str())
+        .or_else(|| {
+            votes
+                .iter()
+                .rev()
+                .max_by_key(|(_, votes)| *votes)
+                .map(|(key, _)| *key)
+        });
+
+    let mut variants: BTreeMap<&str, NapVariant> = BTreeMap::new();
+    for mention in &mentions {
+        let variant = variants
+            .entry(&mention.value)
+            .or_insert_with(|| NapVariant {
+                value: mention.value.clone(),
+                consistent: Some(mention.key.as_str()) == canonical_key,
+                sightings: Vec::new(),
+            });
+        let sighting = NapSighting {
+            source: mention.source,
+            page: mention.page.clone(),
+        };
+        if !variant.sightings.contains(&sighting) {
+            variant.sightings.push(sighting);
+        }
+    }
+    let mut variants: Vec<NapVariant> = variants.into_values().collect();
+    variants.sort_by(|a, b| {
+        b.consistent
+            .cmp(&a.consistent)
+            .then_with(|| b.sightings.len().cmp(&a.sightings.len()))
+    });
+
+    let canonical = canonical_key.and_then(|key| {
+        if field == NapField::Phone {
+            return Some(key.to_string());
+        }
+        // The configured spelling, else the one the most trusted mention uses
+        mentions
+            .iter()
+            .filter(|m| m.key == key)
+            .min_by_key(|m| m.source)
+            .map(|m| m.value.clone())
+    });
+
+    let audit = NapFieldAudit {
+        field,
+        canonical,
+        variants,
+    };
+    (audit, canonical_key.map(str::to_string))
+}
+
+/// Street, city and postal code, lowercased with abbreviations spelled out;
+/// the region follows from the postal code
+fn address_key(address: &PostalAddress) -> String {
+    let words = |s: &str| {
+        s.to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(|w| {
+                STREET_ABBREVIATIONS
+                    .iter()
+                    .find(|(short, _)| *short == w)
+                    .map_or(w.to_string(), |(_, long)| long.to_string())
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let postal = address.postal_code.to_uppercase().replace(' ', "");
+    // ZIP+4 and ZIP name the same address
+    let postal = postal.split('-').next().unwrap_or("");
+    format!(
+        "{}, {}, {}",
+        words(&address.street),
+        address.city.to_lowercase().trim(),
+        postal
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn on(mut mention: NapMention, page: &str) -> NapMention {
+        mention.page = Some(PathBuf::from(page));
+        mention
+    }
+
+    fn larimer(street: &str, postal: &str) -> PostalAddress {
+        PostalAddress {
+            street: street.to_string(),
+            city: "Denver".to_string(),
+            region: Some("CO".to_string()),
+            postal_code: postal.to_string(),
+            country: Some("US".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_canonical_values_and_mismatches() {
+        let phone = |value, source| NapMention::phone(value, "US", source).unwrap();
+        let name = |value, source| NapMention::name(value, source).unwrap();
+        let mentions = vec![
+            on(phone("(303) 555-0142", NapSource::Text), "index.html"),
+            on(phone("+1-303-555-0142", NapSource::TelLink), "index.html"),
+            on(phone("303.555.0199", NapSource::Text), "contact.html"),
+            on(name("Acme Plumbing LLC", NapSource::JsonLd), "index.html"),
+            on(name("Acme Plumbing", NapSource::Text), "about.html"),
+            on(
+                name("Acme Plumbing & Heating", NapSource::Text),
+                "contact.html",
+            ),
+            on(
+                NapMention::address(
+                    "1420 Larimer St, Denver, CO 80202",
+                    larimer("1420 Larimer St", "80202"),
+                    NapSource::Text,
+                ),
+                "index.html",
+            ),
+            on(
+                NapMention::address(
+                    "1420 Larimer Street",
+                    larimer("1420 Larimer Street", "80202-1234"),
+                    NapSource::JsonLd,
+                ),
+                "index.html",
+            ),
+        ];
+        let audit = NapAudit::new(&mentions);
+
+        assert_eq!(audit.phone.canonical.as_deref(), Some("+13035550142"));
+        let mismatched: Vec<_> = audit.phone.mismatches().map(|v| v.value.as_str()).collect();
+        assert_eq!(mismatched, vec!["303.555.0199"]);
+        assert_eq!(audit.phone.format_variants().count(), 2);
+
+        // Structured data outvotes text and sets the spelling
+        assert_eq!(audit.name.canonical.as_deref(), Some("Acme Plumbing LLC"));
+        let mismatched: Vec<_> = audit.name.mismatches().map(|v| v.value.as_str()).collect();
+        assert_eq!(mismatched, vec!["Acme Plumbing & Heating"]);
+
+        assert!(audit.address.mismatches().next().is_none());
+        assert_eq!(
+            audit.canonical_address.as_ref().unwrap().street,
+            "1420 Larimer Street"
+        );
+        assert!(!audit.is_consistent());
+    }
+
+    #[test]
+    fn test_config_is_canonical() {
+        let mentions = vec![
+            NapMention::phone("303-555-0142", "US", NapSource::Text).unwrap(),
+            NapMention::phone("303-555-0142", "US", NapSource::JsonLd).unwrap(),
+            NapMention::phone("(720) 555-0100", "US", NapSource::Config).unwrap(),
+        ];
+        let audit = NapAudit::new(&mentions);
+
+        assert_eq!(audit.phone.canonical.as_deref(), Some("+17205550100"));
+        assert_eq!(audit.phone.mismatches().count(), 1);
+        assert!(audit.name.canonical.is_none());
+    }
+
+    #[test]
+    fn test_unusable_mentions_and_ties() {
+        assert!(NapMention::name(" — ", NapSource::Text).is_none());
+        assert!(NapMention::phone("555", "US", NapSource::Text).is_none());
+
+        let address = |street: &str, postal: &str| {
+            let value = format!("{}, Denver, CO {}", street, postal);
+            NapMention::address(&value, larimer(street, postal), NapSource::Text)
+        };
+        let mentions = vec![
+            NapMention::phone("303-555-0199", "US", NapSource::Text).unwrap(),
+            NapMention::phone("303-555-0142", "US", NapSource::Text).unwrap(),
+            address("1420 Larimer St Ste 4", "80202"),
+            address("1420 Larimer Street, Suite 4", "80202"),
+            address("1420 Larimer St Ste 4", "80205"),
+        ];
+        let audit = NapAudit::new(&mentions);
+
+        // Equal votes: the first value seen is canonical
+        assert_eq!(audit.phone.canonical.as_deref(), Some("+13035550199"));
+        // Abbreviations and punctuation don't matter, the postal code does
+        assert_eq!(audit.address.format_variants().count(), 1);
+        let mismatched: Vec<_> = audit
+            .address
+            .mismatches()
+            .flat_map(|v| &v.sightings)
+            .collect();
+        assert_eq!(mismatched.len(), 1);
+        assert_eq!(
+            audit.canonical_address.as_ref().unwrap().postal_code,
+            "80202"
+        );
+    }
+}
@@ -0,0 +1,932 @@
+//! Redirect audit - hosting redirect rules checked against the site
+//!
+//! Reads the redirects of Netlify's `_redirects` and `netlify.toml`,
+//! `vercel.json` and Apache's `.htaccess` (in the root, `public/` or
+//! `static/`) plus pages that redirect with `<meta http-equiv=refresh>` into
+//! one map, applied first match first in that order. Each rule is followed to
+//! flag chains, loops and targets no page serves, and internal links are
+//! checked for URLs that redirect.
+
+use crate::canonical_audit::{aliases, url_path};
+use crate::link_audit::bare_host;
+use crate::{is_html_file, FileAnalysis};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use url::Url;
+
+/// Directories hosting config is published from, relative to the root
+const CONFIG_DIRS: [&str; 3] = ["", "public", "static"];
+
+/// Hops followed before a chain is cut short
+const MAX_HOPS: usize = 10;
+
+/// Origin used when no site URL is known
+const FALLBACK_ORIGIN: &str = "https://localhost/";
+
+/// Where a redirect rule is declared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum RedirectSource {
+    /// Netlify `_redirects`
+    NetlifyRedirects,
+    NetlifyToml,
+    VercelJson,
+    Htaccess,
+    /// `<meta http-equiv=refresh>` of a page
+    MetaRefresh,
+}
+
+impl RedirectSource {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::NetlifyRedirects => "_redirects",
+            Self::NetlifyToml => "netlify.toml",
+            Self::VercelJson => "vercel.json",
+            Self::Htaccess => ".htaccess",
+            Self::MetaRefresh => "meta refresh",
+        }
+    }
+}
+
+/// How a rule's `from` matches request paths
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RedirectMatch {
+    /// Netlify or Vercel path, with `*` splats and `:name` placeholders
+    Path,
+    /// Apache `Redirect`: the path and everything below it
+    Prefix,
+    /// Apache `RedirectMatch` or `RewriteRule` regular expression
+    Regex,
+}
+
+/// One redirect of the unified map
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RedirectRule {
+    pub from: String,
+    pub to: String,
+
+    /// HTTP status; `None` for meta refresh
+    pub status: Option<u16>,
+
+    pub matching: RedirectMatch,
+    pub source: RedirectSource,
+
+    /// Config file or page declaring the rule
+    pub file: PathBuf,
+}
+
+/// Problem found with a redirect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum RedirectIssue {
+    /// Target redirects again
+    Chain,
+    /// Following the redirects leads back to an earlier URL
+    Loop,
+    /// Target is on the site but no page or file serves it
+    MissingTarget,
+    /// Internal link to a URL that redirects
+    LinkedRedirect,
+    /// Page redirects with `<meta http-equiv=refresh>`
+    MetaRefresh,
+}
+
+impl RedirectIssue {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Chain => "redirect chain",
+            Self::Loop => "redirect loop",
+            Self::MissingTarget => "redirect to a missing page",
+            Self::LinkedRedirect => "internal link to a redirecting URL",
+            Self::MetaRefresh => "meta refresh redirect",
+        }
+    }
+}
+
+/// A rule, link or page with one of its redirect issues
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RedirectFinding {
+    /// Config file declaring the rule, or page with the link or meta refresh
+    pub file: PathBuf,
+
+    /// Redirecting URL or pattern, as written
+    pub from: String,
+
+    pub issue: RedirectIssue,
+    pub detail: String,
+}
+
+/// Site-wide redirect map and its findings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RedirectAudit {
+    pub rules: Vec<RedirectRule>,
+    pub findings: Vec<RedirectFinding>,
+}
+
+impl RedirectAudit {
+    /// Audit the redirects declared under `root` and by the HTML pages in
+    /// `files`; `site_url` decides which absolute targets are internal
+    pub fn new(root: &Path, files: &[FileAnalysis], site_url: Option<&str>) -> Self {
+        let pages: Vec<&FileAnalysis> = files.iter().filter(|f| is_html_file(&f.path)).collect();
+
+        let mut rules = load_rules(root);
+        for page in &pages {
+            if let Some(target) = &page.result.existing_seo.meta_refresh {
+                rules.push(RedirectRule {
+                    from: url_path(root, &page.path),
+                    to: target.clone(),
+                    status: None,
+                    matching: RedirectMatch::Path,
+                    source: RedirectSource::MetaRefresh,
+                    file: page.path.clone(),
+                });
+            }
+        }
+
+        let map = RedirectMap::new(root, &rules, &pages, site_url);
+        let mut findings = Vec::new();
+        let mut loops: BTreeSet<BTreeSet<String>> = BTreeSet::new();
+
+        for (i, rule) in rules.iter().enumerate() {
+            let mut finding = |issue, detail: String| {
+                findings.push(RedirectFinding {
+                    file: rule.file.clone(),
+                    from: rule.from.clone(),
+                    issue,
+                    detail,
+                })
+            };
+            if rule.source == RedirectSource::MetaRefresh {
+                finding(
+                    RedirectIssue::MetaRefresh,
+                    format!("replace with a server-side 301 to {}", rule.to),
+                );
+            }
+            // Targets filled from the request path can't be followed
+            if !map.matchers[i].is_concrete() {
+                continue;
+            }
+
+            let trail = map.follow(&rule.from, &rule.to);
+            match trail.end {
+                TrailEnd::Loop => {
+                    let cycle = trail
+                        .hops
+                        .iter()
+                        .map(|h| normalize(h).to_string())
+                        .collect();
+                    if loops.insert(cycle) {
+                        finding(RedirectIssue::Loop, trail.hops.join(" → "));
+                    }
+                }
+                TrailEnd::Missing if trail.hops.len() == 2 && map.has_pages => {
+                    finding(
+                        RedirectIssue::MissingTarget,
+                        format!("no page serves {}", trail.hops[1]),
+                    );
+                }
+                _ => {}
+            }
+            if trail.hops.len() > 2 && trail.end != TrailEnd::Loop {
+                finding(
+                    RedirectIssue::Chain,
+                    format!("{} ({} hops)", trail.hops.join(" → "), trail.hops.len() - 1),
+                );
+            }
+        }
+
+        for page in &pages {
+            let page_path = url_path(root, &page.path);
+            let mut seen = BTreeSet::new();
+            for link in &page.result.links {
+                let Some(path) = map.internal_path(&page_path, &link.href) else {
+                    continue;
+                };
+                let Some((rule, target)) = map.redirect(&path) else {
+                    continue;
+                };
+                if !seen.insert(link.href.as_str()) {
+                    continue;
+                }
+                let trail = map.follow(&path, &target);
+                findings.push(RedirectFinding {
+                    file: page.path.clone(),
+                    from: link.href.clone(),
+                    issue: RedirectIssue::LinkedRedirect,
+                    detail: format!(
+                        "link to {} directly ({})",
+                        trail.hops.last().unwrap_or(&target),
+                        rules[rule].source.description()
+                    ),
+                });
+            }
+        }
+
+        Self { rules, findings }
+    }
+}
+
+/// Redirect rules compiled for matching
+pub(crate) struct RedirectMap<'a> {
+    rules: &'a [RedirectRule],
+    matchers: Vec<Matcher>,
+    origin: Url,
+    site_host: Option<String>,
+
+    /// URL paths of the HTML pages
+    served: BTreeSet<String>,
+    root: PathBuf,
+    has_pages: bool,
+}
+
+enum Matcher {
+    /// Paths, compared without trailing slashes
+    Exact(Vec<String>),
+    Prefix(String),
+    /// Expression and target template
+    Pattern(Regex, String),
+    /// `from` that couldn't be compiled
+    Never,
+}
+
+impl Matcher {
+    fn new(rule: &RedirectRule) -> Self {
+        match rule.matching {
+            RedirectMatch::Path if rule.source == RedirectSource::MetaRefresh => Self::Exact(
+                aliases(&rule.from)
+                    .iter()
+                    .map(|a| normalize(a).to_string())
+                    .collect(),
+            ),
+            RedirectMatch::Path => path_pattern(&rule.from, &rule.to)
+                .unwrap_or_else(|| Self::Exact(vec![normalize(&rule.from).to_string()])),
+            RedirectMatch::Prefix => Self::Prefix(normalize(&rule.from).to_string()),
+            RedirectMatch::Regex => match Regex::new(&rule.from) {
+                Ok(regex) => Self::Pattern(regex, numbered_template(&rule.to)),
+                Err(_) => Self::Never,
+            },
+        }
+    }
+
+    /// Whether the target is the same for every path the rule matches
+    fn is_concrete(&self) -> bool {
+        match self {
+            Self::Exact(_) | Self::Prefix(_) => true,
+            Self::Pattern(_, template) => !template.contains('$'),
+            Self::Never => false,
+        }
+    }
+
+    /// Target for `path`, if the rule matches it
+    fn apply(&self, path: &str, to: &str) -> Option<String> {
+        match self {
+            Self::Exact(from) => from
+                .iter()
+                .any(|f| f == normalize(path))
+                .then(|| to.to_string()),
+            Self::Prefix(from) => {
+                let rest = path.strip_prefix(from.as_str())?;
+                if !(rest.is_empty() || rest.starts_with('/') || from == "/") {
+                    return None;
+                }
+                // `/` keeps its separator: `/about.html` under `/ → https://new.com/`
+                Some(match rest.trim_start_matches('/') {
+                    "" => to.to_string(),
+                    rest => format!("{}/{}", to.trim_end_matches('/'), rest),
+                })
+            }
+            Self::Pattern(regex, template) => {
+                let caps = regex.captures(path)?;
+                let mut target = String::new();
+                caps.expand(template, &mut target);
+                Some(target)
+            }
+            Self::Never => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum TrailEnd {
+    Page,
+    Missing,
+    External,
+    Loop,
+}
+
+/// URLs visited following a redirect, the first being where it started
+struct Trail {
+    hops: Vec<String>,
+    end: TrailEnd,
+}
+
+impl<'a> RedirectMap<'a> {
+    pub(crate) fn new(
+        root: &Path,
+        rules: &'a [RedirectRule],
+        pages: &[&FileAnalysis],
+        site_url: Option<&str>,
+    ) -> Self {
+        let origin = site_url
+            .and_then(|u| Url::parse(u).ok())
+            .unwrap_or_else(|| Url::parse(FALLBACK_ORIGIN).unwrap());
+        let served = pages
+            .iter()
+            .flat_map(|p| aliases(&url_path(root, &p.path)))
+            .filter_map(|alias| origin.join(&alias).ok())
+            .map(|url| url.path().to_string())
+            .collect();
+        Self {
+            rules,
+            matchers: rules.iter().map(Matcher::new).collect(),
+            site_host: origin.host_str().map(bare_host),
+            origin,
+            served,
+            root: root.to_path_buf(),
+            has_pages: !pages.is_empty(),
+        }
+    }
+
+    /// First rule matching `path`, with its target
+    pub(crate) fn redirect(&self, path: &str) -> Option<(usize, String)> {
+        self.matchers
+            .iter()
+            .zip(self.rules)
+            .enumerate()
+            .find_map(|(i, (matcher, rule))| matcher.apply(path, &rule.to).map(|to| (i, to)))
+    }
+
+    /// Path of `href` when it points at this site, resolved against `from`
+    fn internal_path(&self, from: &str, href: &str) -> Option<String> {
+        let url = self.origin.join(from).ok()?.join(href).ok()?;
+        if !matches!(url.scheme(), "http" | "https")
+            || url.host_str().map(bare_host) != self.site_host
+        {
+            return None;
+        }
+        Some(url.path().to_string())
+    }
+
+    fn is_served(&self, path: &str) -> bool {
+        self.served.contains(path) || {
+            let file = self.root.join(path.trim_start_matches('/'));
+            file.is_file() || file.join("index.html").is_file()
+        }
+    }
+
+    /// Follow the redirect of `from` to `to` until a page, a missing or
+    /// external URL, or a URL seen before
+    fn follow(&self, from: &str, to: &str) -> Trail {
+        let mut hops = vec![from.to_string()];
+        let mut seen = BTreeSet::from([normalize(from).to_string()]);
+        let (mut current, mut target) = (from.to_string(), to.to_string());
+
+        loop {
+            let Some(path) = self.internal_path(&current, &target) else {
+                hops.push(target);
+                return Trail {
+                    hops,
+                    end: TrailEnd::External,
+                };
+            };
+            hops.push(path.clone());
+            if !seen.insert(normalize(&path).to_string()) {
+                return Trail {
+                    hops,
+                    end: TrailEnd::Loop,
+                };
+            }
+            match self.redirect(&path) {
+                Some((_, next)) if hops.len() <= MAX_HOPS => (current, target) = (path, next),
+                Some(_) => {
+                    return Trail {
+                        hops,
+                        end: TrailEnd::Page,
+                    }
+                }
+                None => {
+                    let end = if self.is_served(&path) {
+                        TrailEnd::Page
+                    } else {
+                        TrailEnd::Missing
+                    };
+                    return Trail { hops, end };
+                }
+            }
+        }
+    }
+}
+
+/// `/old/` as `/old`; the root stays `/`
+fn normalize(path: &str) -> &str {
+    match path.trim_end_matches('/') {
+        "" => "/",
+        trimmed => trimmed,
+    }
+}
+
+/// Regex for a Netlify or Vercel path with `*`, `:name`, `:name*` or `(.*)`,
+/// and the target with its placeholders as capture references. `None` for
+/// plain paths.
+fn path_pattern(from: &str, to: &str) -> Option<Matcher> {
+    static TOKEN: OnceLock<Regex> = OnceLock::new();
+    let token = TOKEN.get_or_init(|| Regex::new(r":([A-Za-z_]\w*)(\*)?|\*|\(\.\*\)").unwrap());
+
+    let from = normalize(from);
+    let mut pattern = String::from("^");
+    let mut names = Vec::new();
+    let mut last = 0;
+    for caps in token.captures_iter(from) {
+        let whole = caps.get(0).unwrap();
+        pattern.push_str(&regex::escape(&from[last..whole.start()]));
+        match (caps.get(1), whole.as_str()) {
+            (Some(name), _) => {
+                let segment = if caps.get(2).is_some() { ".*" } else { "[^/]+" };
+                pattern.push_str(&format!("(?P<{}>{})", name.as_str(), segment));
+                names.push(name.as_str());
+            }
+            (None, "*") => {
+                pattern.push_str("(?P<splat>.*)");
+                names.push("splat");
+            }
+            _ => pattern.push_str("(.*)"),
+        }
+        last = whole.end();
+    }
+    if last == 0 {
+        return None;
+    }
+    pattern.push_str(&regex::escape(&from[last..]));
+    pattern.push_str("/?$");
+
+    // Longer names first, so `:id` doesn't eat the start of `:idx`
+    names.sort_by_key(|n| std::cmp::Reverse(n.len()));
+    let mut template = numbered_template(to);
+    for name in names {
+        let reference = format!("${{{}}}", name);
+        template = template
+            .replace(&format!(":{}*", name), &reference)
+            .replace(&format!(":{}", name), &reference);
+    }
+    Some(match Regex::new(&pattern) {
+        Ok(regex) => Matcher::Pattern(regex, template),
+        Err(_) => Matcher::Never,
+    })
+}
+
+/// `$1` as `${1}`, so a following letter isn't read as part of the reference
+fn numbered_template(to: &str) -> String {
+    static NUMBERED: OnceLock<Regex> = OnceLock::new();
+    let numbered = NUMBERED.get_or_init(|| Regex::new(r"\$(\d+)").unwrap());
+    numbered
+        .replace_all(to, |caps: &Captures| format!("${{{}}}", &caps[1]))
+        .into_owned()
+}
+
+/// Redirects of the hosting config files under `root`
+fn load_rules(root: &Path) -> Vec<RedirectRule> {
+    let mut rules = Vec::new();
+    for dir in CONFIG_DIRS {
+        let dir = root.join(dir);
+        let read = |name: &str| {
+            let file = dir.join(name);
+            std::fs::read_to_string(&file)
+                .ok()
+                .map(|content| (file, content))
+        };
+        if let Some((file, content)) = read("_redirects") {
+            rules.extend(netlify_redirects(&content, &file));
+        }
+        if let Some((file, content)) = read("netlify.toml") {
+            rules.extend(netlify_toml(&content, &file));
+        }
+        if let Some((file, content)) = read("vercel.json") {
+            rules.extend(vercel_json(&content, &file));
+        }
+        if let Some((file, content)) = read(".htaccess") {
+            rules.extend(htaccess(&content, &file));
+        }
+    }
+    rules
+}
+
+fn rule(
+    from: &str,
+    to: &str,
+    status: u16,
+    matching: RedirectMatch,
+    source: RedirectSource,
+    file: &Path,
+) -> Option<RedirectRule> {
+    // 200 is a rewrite, 404 a custom error page
+    (300..400).contains(&status).then(|| RedirectRule {
+        from: from.to_string(),
+        to: to.to_string(),
+        status: Some(status),
+        matching,
+        source,
+        file: file.to_path_buf(),
+    })
+}
+
+/// `/from [query=:param] /to [301[!]] [Country=us]`; rules with query or
+/// condition arguments are skipped, as they don't apply to every request
+fn netlify_redirects(content: &str, file: &Path) -> Vec<RedirectRule> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            if tokens.iter().any(|t| t.contains('=') && !t.contains('/')) {
+                return None;
+            }
+            let (from, to) = (tokens.first()?, tokens.get(1)?);
+            let status = match tokens.get(2) {
+                Some(status) => status.trim_end_matches('!').parse().ok()?,
+                None => 301,
+            };
+            rule(
+                from,
+                to,
+                status,
+                RedirectMatch::Path,
+                RedirectSource::NetlifyRedirects,
+                file,
+            )
+        })
+        .collect()
+}
+
+/// `[[redirects]]` tables without `conditions` or `query`
+fn netlify_toml(content: &str, file: &Path) -> Vec<RedirectRule> {
+    let Ok(config) = content.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let redirects = config.get("redirects").and_then(toml::Value::as_array);
+    redirects
+        .into_iter()
+        .flatten()
+        .filter_map(|redirect| {
+            let table = redirect.as_table()?;
+            if table.contains_key("conditions") || table.contains_key("query") {
+                return None;
+            }
+            let from = table.get("from")?.as_str()?;
+            let to = table.get("to")?.as_str()?;
+            let status = table
+                .get("status")
+                .and_then(toml::Value::as_integer)
+                .unwrap_or(301);
+            let status = u16::try_from(status).ok()?;
+            rule(
+                from,
+                to,
+                status,
+                RedirectMatch::Path,
+                RedirectSource::NetlifyToml,
+                file,
+            )
+        })
+        .collect()
+}
+
+/// `redirects` without `has` or `missing` conditions; permanent (308) unless
+/// `permanent: false` (307) or a `statusCode` is given
+fn vercel_json(content: &str, file: &Path) -> Vec<RedirectRule> {
+    let Ok(config) = serde_json::from_str::<Value>(content) else {
+        return Vec::new();
+    };
+    let redirects = config["redirects"].as_array();
+    redirects
+        .into_iter()
+        .flatten()
+        .filter(|r| r.get("has").is_none() && r.get("missing").is_none())
+        .filter_map(|redirect| {
+            let from = redirect["source"].as_str()?;
+            let to = redirect["destination"].as_str()?;
+            let status = match redirect["statusCode"].as_u64() {
+                Some(code) => u16::try_from(code).ok()?,
+                None if redirect["permanent"].as_bool() == Some(false) => 307,
+                None => 308,
+            };
+            rule(
+                from,
+                to,
+                status,
+                RedirectMatch::Path,
+                RedirectSource::VercelJson,
+                file,
+            )
+        })
+        .collect()
+}
+
+/// mod_alias `Redirect`, `RedirectPermanent`, `RedirectTemp` and
+/// `RedirectMatch`, and mod_rewrite `RewriteRule`s with an `R` flag. Rules
+/// after a `RewriteCond` are skipped, as their conditions can't be checked.
+fn htaccess(content: &str, file: &Path) -> Vec<RedirectRule> {
+    let mut rules = Vec::new();
+    let mut conditional = false;
+
+    for line in content.lines() {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let Some(directive) = tokens.first().map(|d| d.to_ascii_lowercase()) else {
+            continue;
+        };
+        let args = &tokens[1..];
+
+        match directive.as_str() {
+            "rewritecond" => conditional = true,
+            "rewriterule" => {
+                if std::mem::take(&mut conditional) {
+                    continue;
+                }
+                let (Some(pattern), Some(&to)) = (args.first(), args.get(1)) else {
+                    continue;
+                };
+                let flags: Vec<String> = args
+                    .get(2)
+                    .map(|f| f.trim_matches(['[', ']']).to_ascii_uppercase())
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(|f| f.trim().to_string())
+                    .collect();
+                let Some(status) = flags.iter().find_map(|f| match f.split_once('=') {
+                    Some(("R" | "REDIRECT", code)) => code.parse().ok(),
+                    None if f == "R" || f == "REDIRECT" => Some(302),
+                    _ => None,
+                }) else {
+                    continue;
+                };
+                if to == "-" {
+                    continue;
+                }
+                // Patterns match the path without its leading slash
+                let mut from = match pattern.strip_prefix('^') {
+                    Some(rest) => format!(
+                        "^/{}",
+                        rest.trim_start_matches("/?").trim_start_matches('/')
+                    ),
+                    None => pattern.to_string(),
+                };
+                if flags.iter().any(|f| f == "NC" || f == "NOCASE") {
+                    from = format!("(?i){}", from);
+                }
+                rules.extend(rule(
+                    &from,
+                    to,
+                    status,
+                    RedirectMatch::Regex,
+                    RedirectSource::Htaccess,
+                    file,
+                ));
+            }
+            "redirect" | "redirectmatch" | "redirectpermanent" | "redirecttemp" => {
+                let (status, args) = match directive.as_str() {
+                    "redirectpermanent" => (301, args),
+                    "redirecttemp" => (302, args),
+                    _ => match args.first().map(|a| a.to_ascii_lowercase()).as_deref() {
+                        Some("permanent") => (301, &args[1..]),
+                        Some("temp") => (302, &args[1..]),
+                        Some("seeother") => (303, &args[1..]),
+                        Some("gone") => (410, &args[1..]),
+                        Some(code) if code.parse::<u16>().is_ok() => {
+                            (code.parse().unwrap(), &args[1..])
+                        }
+                        _ => (302, args),
+                    },
+                };
+                let (Some(from), Some(to)) = (args.first(), args.get(1)) else {
+                    continue;
+                };
+                let matching = if directive == "redirectmatch" {
+                    RedirectMatch::Regex
+                } else {
+                    RedirectMatch::Prefix
+                };
+                rules.extend(rule(
+                    from,
+                    to,
+                    status,
+                    matching,
+                    RedirectSource::Htaccess,
+                    file,
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::page;
+    use crate::Link;
+
+    fn links(hrefs: &[&str]) -> Vec<Link> {
+        hrefs
+            .iter()
+            .map(|href| Link {
+                href: href.to_string(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    /// Findings of `issue` as `(from, detail)`
+    fn findings(audit: &RedirectAudit, issue: RedirectIssue) -> Vec<(&str, &str)> {
+        audit
+            .findings
+            .iter()
+            .filter(|f| f.issue == issue)
+            .map(|f| (f.from.as_str(), f.detail.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_config_formats() {
+        let file = Path::new("x");
+        let netlify = netlify_redirects(
+            "# Moved\n/old  /new\n/blog/*  /news/:splat  302!\n/app/*  /index.html  200\n/store id=:id  /items/:id  301\n",
+            file,
+        );
+        assert_eq!(netlify.len(), 2);
+        assert_eq!(netlify[1].status, Some(302));
+
+        let toml = netlify_toml(
+            "[[redirects]]\nfrom = \"/a\"\nto = \"/b\"\n\n[[redirects]]\nfrom = \"/c\"\nto = \"/d\"\nstatus = 302\nconditions = {Language = [\"en\"]}\n",
+            file,
+        );
+        assert_eq!(toml.len(), 1);
+        assert_eq!(toml[0].status, Some(301));
+
+        let vercel = vercel_json(
+            r#"{"redirects": [{"source": "/docs/:path*", "destination": "/guide/:path*"},
+                {"source": "/tmp", "destination": "/", "permanent": false}]}"#,
+            file,
+        );
+        assert_eq!(
+            vercel.iter().map(|r| r.status).collect::<Vec<_>>(),
+            vec![Some(308), Some(307)]
+        );
+
+        let apache = htaccess(
+            "Redirect permanent /old-page.html /new-page/\nRedirectMatch 301 ^/shop/(.*)$ /store/$1\nRewriteEngine On\nRewriteCond %{HTTP_HOST} ^www\\.\nRewriteRule ^(.*)$ https://acme.com/$1 [R=301,L]\nRewriteRule ^about-us/?$ /about/ [R=301,L,NC]\nRewriteRule ^app/ /index.php [L]\nRedirect gone /retired\n",
+            file,
+        );
+        let apache: Vec<(&str, RedirectMatch)> = apache
+            .iter()
+            .map(|r| (r.from.as_str(), r.matching))
+            .collect();
+        assert_eq!(
+            apache,
+            vec![
+                ("/old-page.html", RedirectMatch::Prefix),
+                ("^/shop/(.*)$", RedirectMatch::Regex),
+                ("(?i)^/about-us/?$", RedirectMatch::Regex),
+            ]
+        );
+
+        let rules = [vercel[0].clone(), netlify[1].clone()];
+        let map = RedirectMap::new(Path::new("site"), &rules, &[], None);
+        assert_eq!(map.redirect("/docs/a/b").unwrap().1, "/guide/a/b");
+        assert_eq!(
+            map.redirect("/blog/2024/post").unwrap().1,
+            "/news/2024/post"
+        );
+        assert!(map.redirect("/documents").is_none());
+    }
+
+    #[test]
+    fn test_chains_loops_and_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(
+            root.join("_redirects"),
+            "/a  /b\n/b  /c/\n/loop-1  /loop-2\n/loop-2  /loop-1\n/gone  /nowhere\n/offsite  https://partner.example/x\n",
+        )
+        .unwrap();
+        let files = vec![
+            page(root, "c/index.html", |r| {
+                r.links = links(&["/a", "/c/", "https://acme.com/b"])
+            }),
+            page(root, "moved.html", |r| {
+                r.existing_seo.meta_refresh = Some("/c/".to_string())
+            }),
+            page(root, "index.html", |r| r.links = links(&["moved"])),
+        ];
+        let audit = RedirectAudit::new(root, &files, Some("https://acme.com"));
+
+        assert_eq!(audit.rules.len(), 7);
+        let of = |issue| findings(&audit, issue);
+        assert_eq!(
+            of(RedirectIssue::Chain),
+            vec![("/a", "/a → /b → /c/ (2 hops)")]
+        );
+        assert_eq!(
+            of(RedirectIssue::Loop),
+            vec![("/loop-1", "/loop-1 → /loop-2 → /loop-1")]
+        );
+        assert_eq!(
+            of(RedirectIssue::MissingTarget),
+            vec![("/gone", "no page serves /nowhere")]
+        );
+        assert_eq!(
+            of(RedirectIssue::MetaRefresh),
+            vec![("/moved.html", "replace with a server-side 301 to /c/")]
+        );
+        assert_eq!(
+            of(RedirectIssue::LinkedRedirect),
+            vec![
+                ("/a", "link to /c/ directly (_redirects)"),
+                ("https://acme.com/b", "link to /c/ directly (_redirects)"),
+                ("moved", "link to /c/ directly (meta refresh)"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_malformed_config_lines() {
+        let file = Path::new("x");
+        let netlify = netlify_redirects(
+            "/lonely\n/a /b abc\n   \n/c /d 301! extra\n/e  /f  418\n",
+            file,
+        );
+        // A line without target, an unparsable status and a non-redirect status are skipped
+        assert_eq!(
+            netlify
+                .iter()
+                .map(|r| (r.from.as_str(), r.to.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("/c", "/d")]
+        );
+
+        assert!(netlify_toml("[[redirects]\nfrom = \"/a\"", file).is_empty());
+        assert!(netlify_toml("[[redirects]]\nfrom = \"/a\"\nstatus = -1\n", file).is_empty());
+        assert!(vercel_json("{\"redirects\": [", file).is_empty());
+        assert!(vercel_json(r#"{"redirects": [{"source": "/a"}]}"#, file).is_empty());
+
+        let apache = htaccess(
+            "Redirect\nRedirect permanent /only-from\nRewriteRule ^a$\nRewriteRule ^b$ - [R=301]\nRedirectMatch 301 ^/(unclosed /x\nRedirect 301 /ok /fine\n",
+            file,
+        );
+        assert_eq!(
+            apache.iter().map(|r| r.from.as_str()).collect::<Vec<_>>(),
+            vec!["^/(unclosed", "/ok"]
+        );
+        // An invalid expression never matches instead of failing the audit
+        let map = RedirectMap::new(Path::new("site"), &apache, &[], None);
+        assert_eq!(map.redirect("/unclosed"), None);
+        assert_eq!(map.redirect("/ok/page").unwrap().1, "/fine/page");
+    }
+
+    #[test]
+    fn test_root_prefix_redirect() {
+        let rules = htaccess(
+            "Redirect 301 /shop/ /store\nRedirect 301 / https://newbrand.com/\n",
+            Path::new("x"),
+        );
+        let map = RedirectMap::new(Path::new("site"), &rules, &[], None);
+        let target = |path| map.redirect(path).unwrap().1;
+
+        assert_eq!(target("/about.html"), "https://newbrand.com/about.html");
+        assert_eq!(target("/blog/post/"), "https://newbrand.com/blog/post/");
+        assert_eq!(target("/"), "https://newbrand.com/");
+        assert_eq!(target("/shop/cart"), "/store/cart");
+        assert_eq!(target("/shop"), "/store");
+    }
+
+    #[test]
+    fn test_long_chain_and_self_loop() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(
+            root.join("_redirects"),
+            "/v1  /v2\n/v2  /v3\n/v3  /v4\n/self/  /self\n/x  /y\n/y  /z\n/z  /x\n",
+        )
+        .unwrap();
+        let files = vec![page(root, "v4.html", |_| {})];
+        let audit = RedirectAudit::new(root, &files, None);
+
+        // Each rule of a chain is reported from where it starts
+        assert_eq!(
+            findings(&audit, RedirectIssue::Chain),
+            vec![
+                ("/v1", "/v1 → /v2 → /v3 → /v4 (3 hops)"),
+                ("/v2", "/v2 → /v3 → /v4 (2 hops)"),
+            ]
+        );
+        // A trailing slash redirecting to itself loops; a three-rule cycle is reported once
+        assert_eq!(
+            findings(&audit, RedirectIssue::Loop),
+            vec![("/self/", "/self/ → /self"), ("/x", "/x → /y → /z → /x")]
+        );
+        assert!(findings(&audit, RedirectIssue::MissingTarget).is_empty());
+    }
+}
@@ -0,0 +1,347 @@
+//! Search Console import - query and page performance from CSV exports
+//!
+//! Reads the `Queries.csv` and `Pages.csv` of a Search Console performance
+//! export, or any CSV with a query or page column next to clicks and
+//! impressions. Columns are found by header, so exports in other column
+//! orders and with both a query and a page column work as well. Page URLs
+//! are matched to the analyzed pages the way the canonical audit maps URLs
+//! to pages.
+
+use crate::canonical_audit::{aliases, url_path};
+use crate::link_audit::bare_host;
+use crate::{is_html_file, AnalyzerError, DirectoryAnalysis, SearchPerformance};
+use std::collections::BTreeMap;
+use std::path::Path;
+use url::Url;
+
+/// Origin page URLs are normalized against
+const URL_ORIGIN: &str = "https://localhost/";
+
+/// Header names of each column, lowercase
+const QUERY_HEADERS: [&str; 4] = ["top queries", "query", "queries", "search query"];
+const PAGE_HEADERS: [&str; 6] = [
+    "top pages",
+    "page",
+    "pages",
+    "landing page",
+    "url",
+    "address",
+];
+const CLICK_HEADERS: [&str; 2] = ["clicks", "url clicks"];
+const IMPRESSION_HEADERS: [&str; 1] = ["impressions"];
+const CTR_HEADERS: [&str; 2] = ["ctr", "url ctr"];
+const POSITION_HEADERS: [&str; 3] = ["position", "average position", "avg. position"];
+
+/// Rows imported from Search Console exports
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchConsoleData {
+    pub rows: Vec<SearchPerformance>,
+}
+
+impl SearchConsoleData {
+    /// Add the rows of the CSV export at `path`
+    pub fn read(&mut self, path: &Path) -> Result<(), AnalyzerError> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| AnalyzerError::FileRead(path.to_path_buf(), e))?;
+        self.parse(&text).map_err(|e| match e {
+            AnalyzerError::InvalidSearchConsole(message) => {
+                AnalyzerError::InvalidSearchConsole(format!("{}: {}", path.display(), message))
+            }
+            e => e,
+        })
+    }
+
+    /// Add the rows of a CSV export's `text`
+    pub fn parse(&mut self, text: &str) -> Result<(), AnalyzerError> {
+        let text = text.trim_start_matches('\u{feff}');
+        let mut lines = text.lines().filter(|l| !l.trim().is_empty());
+        let header = lines
+            .next()
+            .ok_or_else(|| AnalyzerError::InvalidSearchConsole("empty file".to_string()))?;
+        let delimiter = [',', '\t', ';']
+            .into_iter()
+            .find(|d| header.contains(*d))
+            .unwrap_or(',');
+        let header: Vec<String> = split_row(header, delimiter)
+            .iter()
+            .map(|h| h.trim().to_lowercase())
+            .collect();
+        let column = |names: &[&str]| header.iter().position(|h| names.contains(&h.as_str()));
+
+        let query = column(&QUERY_HEADERS);
+        let page = column(&PAGE_HEADERS);
+        if query.is_none() && page.is_none() {
+            return Err(AnalyzerError::InvalidSearchConsole(
+                "expected a query or page column".to_string(),
+            ));
+        }
+        let (Some(clicks), Some(impressions)) =
+            (column(&CLICK_HEADERS), column(&IMPRESSION_HEADERS))
+        else {
+            return Err(AnalyzerError::InvalidSearchConsole(
+                "expected clicks and impressions columns".to_string(),
+            ));
+        };
+        let ctr = column(&CTR_HEADERS);
+        let position = column(&POSITION_HEADERS);
+        // Semicolon-separated files come from spreadsheets with decimal commas
+        let decimal_comma = delimiter == ';';
+
+        for line in lines {
+            let fields = split_row(line, delimiter);
+            let text = |i: Option<usize>| {
+                i.and_then(|i| fields.get(i))
+                    .map(|f| f.trim().to_string())
+                    .filter(|f| !f.is_empty())
+            };
+            let number = |i: Option<usize>| text(i).and_then(|f| parse_number(&f, decimal_comma));
+
+            let clicks = number(Some(clicks)).unwrap_or(0.0) as u32;
+            let impressions = number(Some(impressions)).unwrap_or(0.0) as u32;
+            let ctr = match text(ctr) {
+                Some(ctr) if ctr.ends_with('%') => {
+                    parse_number(&ctr, decimal_comma).map(|c| c / 100.0)
+                }
+                Some(ctr) => {
+                    parse_number(&ctr, decimal_comma).map(|c| if c > 1.0 { c / 100.0 } else { c })
+                }
+                None => None,
+            };
+            self.rows.push(SearchPerformance {
+                page: None,
+                url: text(page),
+                query: text(query),
+                clicks,
+                impressions,
+                ctr: ctr.unwrap_or_else(|| {
+                    if impressions > 0 {
+                        clicks as f32 / impressions as f32
+                    } else {
+                        0.0
+                    }
+                }),
+                position: number(position).unwrap_or(0.0),
+            });
+        }
+        Ok(())
+    }
+
+    /// Attach each row to the HTML page its URL serves, keeping the rest
+    /// site-wide. Rows for URLs on another host than `site_url`, such as
+    /// other subdomains of a domain property, are left out. With page rows
+    /// imported, pages the export leaves out get a row without impressions.
+    pub(crate) fn attach(&self, analysis: &mut DirectoryAnalysis, site_url: Option<&str>) {
+        let origin = Url::parse(URL_ORIGIN).unwrap();
+        let site_host = site_url
+            .and_then(|u| Url::parse(u).ok())
+            .and_then(|u| u.host_str().map(bare_host));
+        let rows: Vec<&SearchPerformance> = self
+            .rows
+            .iter()
+            .filter(|row| {
+                let host = row
+                    .url
+                    .as_deref()
+                    .and_then(|u| Url::parse(u).ok())
+                    .and_then(|u| u.host_str().map(bare_host));
+                match (&site_host, host) {
+                    (Some(site), Some(host)) => *site == host,
+                    _ => true,
+                }
+            })
+            .collect();
+        let pages: Vec<usize> = (0..analysis.files.len())
+            .filter(|&i| is_html_file(&analysis.files[i].path))
+            .collect();
+        let mut by_path: BTreeMap<String, usize> = BTreeMap::new();
+        for &i in &pages {
+            for alias in aliases(&url_path(&analysis.root, &analysis.files[i].path)) {
+                if let Ok(url) = origin.join(&alias) {
+                    by_path.entry(url.path().to_string()).or_insert(i);
+                }
+            }
+        }
+
+        for &row in &rows {
+            let page = row
+                .url
+                .as_deref()
+                .and_then(|u| origin.join(u).ok())
+                .and_then(|url| by_path.get(url.path()).copied());
+            match page {
+                Some(i) => {
+                    let file = &mut analysis.files[i];
+                    file.result.search_performance.push(SearchPerformance {
+                        page: Some(file.path.clone()),
+                        ..row.clone()
+                    });
+                }
+                None => analysis.search_console.push(row.clone()),
+            }
+        }
+
+        if rows.iter().any(|r| r.url.is_some()) {
+            for i in pages {
+                let file = &mut analysis.files[i];
+                if file.result.search_performance.is_empty() {
+                    file.result.search_performance.push(SearchPerformance {
+                        page: Some(file.path.clone()),
+                        url: Some(url_path(&analysis.root, &file.path)),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Fields of a CSV row; quoted fields may hold the delimiter and `""`
+fn split_row(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// `1,234`, `3.5%` or `7.2` as a number, ignoring grouping and the percent
+/// sign; with `decimal_comma`, `3,5%` and `1.234` as spreadsheets in many
+/// locales write them
+fn parse_number(field: &str, decimal_comma: bool) -> Option<f32> {
+    let (group, decimal) = if decimal_comma {
+        ('.', ',')
+    } else {
+        (',', '.')
+    };
+    let digits: String = field
+        .chars()
+        .filter(|c| !matches!(c, '%' | ' ' | '\u{a0}') && *c != group)
+        .map(|c| if c == decimal { '.' } else { c })
+        .collect();
+    digits.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{page, site};
+    use std::path::PathBuf;
+
+    fn analysis(pages: &[&str]) -> DirectoryAnalysis {
+        site(
+            "/site",
+            pages.iter().map(|p| page("/site", p, |_| {})).collect(),
+        )
+    }
+
+    #[test]
+    fn test_parse_exports() {
+        let mut data = SearchConsoleData::default();
+        data.parse(
+            "\u{feff}Top queries,Clicks,Impressions,CTR,Position\n\
+             emergency plumber,120,\"3,400\",3.53%,4.2\n\
+             \"drain cleaning, same day\",4,900,0.44%,11.6\n",
+        )
+        .unwrap();
+        data.parse("Page;Clicks;Impressions;Position\nhttps://acme.com/;10;400;2,5\n")
+            .unwrap();
+
+        assert_eq!(data.rows.len(), 3);
+        let query = &data.rows[1];
+        assert_eq!(query.query.as_deref(), Some("drain cleaning, same day"));
+        assert_eq!((query.clicks, query.impressions), (4, 900));
+        assert!((query.ctr - 0.0044).abs() < 1e-6);
+        assert_eq!(query.position, 11.6);
+        assert_eq!(data.rows[0].impressions, 3400);
+        // CTR computed when the export has none
+        assert_eq!((data.rows[2].ctr, data.rows[2].position), (0.025, 2.5));
+        assert!(SearchConsoleData::default()
+            .parse("Country,Clicks,Impressions\nUSA,1,2\n")
+            .is_err());
+    }
+
+    #[test]
+    fn test_attach_to_pages() {
+        let mut data = SearchConsoleData::default();
+        data.parse(
+            "Top pages,Clicks,Impressions,CTR,Position\n\
+             https://acme.com/,300,9000,3.33%,2.1\n\
+             https://acme.com/blog/drains,12,2100,0.57%,9.8\n\
+             https://acme.com/old-page,1,50,2%,30\n",
+        )
+        .unwrap();
+        data.parse("Top queries,Clicks,Impressions,CTR,Position\nplumber,90,4000,2.25%,6.3\n")
+            .unwrap();
+
+        let mut analysis = analysis(&["index.html", "blog/drains.html", "about.html", "logo.png"]);
+        analysis.attach_search_console(&data, None);
+
+        let rows = |i: usize| &analysis.files[i].result.search_performance;
+        assert_eq!(rows(0)[0].impressions, 9000);
+        assert_eq!(rows(0)[0].page, Some(PathBuf::from("/site/index.html")));
+        assert_eq!(rows(1)[0].clicks, 12);
+        // Left out of the export: no impressions
+        assert_eq!(rows(2)[0].url.as_deref(), Some("/about.html"));
+        assert_eq!(rows(2)[0].impressions, 0);
+        assert!(rows(3).is_empty());
+
+        let site_wide: Vec<_> = analysis
+            .search_console
+            .iter()
+            .map(|r| (r.url.as_deref(), r.query.as_deref()))
+            .collect();
+        assert_eq!(
+            site_wide,
+            vec![
+                (Some("https://acme.com/old-page"), None),
+                (None, Some("plumber"))
+            ]
+        );
+        assert_eq!(analysis.merged_result().search_performance.len(), 5);
+    }
+
+    #[test]
+    fn test_attach_skips_other_hosts() {
+        let mut data = SearchConsoleData::default();
+        data.parse(
+            "Top pages,Clicks,Impressions\n\
+             https://www.acme.com/,300,9000\n\
+             https://shop.acme.com/,40,800\n\
+             https://shop.acme.com/about.html,2,90\n",
+        )
+        .unwrap();
+        data.parse("Top queries,Clicks,Impressions\nplumber,90,4000\n")
+            .unwrap();
+
+        let mut site_host = analysis(&["index.html", "about.html"]);
+        site_host.attach_search_console(&data, Some("https://acme.com"));
+
+        let rows = |i: usize| &site_host.files[i].result.search_performance;
+        assert_eq!(rows(0).len(), 1);
+        assert_eq!(rows(0)[0].impressions, 9000);
+        // The shop subdomain's about page is a different page
+        assert_eq!(rows(1)[0].impressions, 0);
+        assert_eq!(site_host.search_console.len(), 1);
+        assert_eq!(
+            site_host.search_console[0].query.as_deref(),
+            Some("plumber")
+        );
+
+        // Without a site URL every host counts
+        let mut all_hosts = analysis(&["index.html", "about.html"]);
+        all_hosts.attach_search_console(&data, None);
+        assert_eq!(all_hosts.files[0].result.search_performance.len(), 2);
+    }
+}
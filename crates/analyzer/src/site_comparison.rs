@@ -0,0 +1,682 @@
+//! Site comparison - what a new build of a site changed against the old one
+//!
+//! Matches the HTML pages of two analyzed builds by URL path, or by the page
+//! their canonicals name when a page moved, and reports per page changed
+//! titles and descriptions, lost structured data and hreflang alternates,
+//! new `noindex`, keyword shifts and SEO score drops. Old pages the new
+//! build no longer serves are checked against the new build's redirects.
+
+use crate::canonical_audit::{aliases, url_path};
+use crate::redirect_audit::{RedirectAudit, RedirectMap};
+use crate::{is_html_file, DirectoryAnalysis, FileAnalysis, Keyword};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use url::Url;
+
+/// Origin page URLs and canonicals are resolved against
+const COMPARE_ORIGIN: &str = "https://localhost/";
+
+/// Top keywords of a page compared between builds
+const PAGE_KEYWORDS: usize = 10;
+
+/// A keyword only counts as lost when it drops out of this many top keywords
+const KEYWORD_MARGIN: usize = 20;
+
+/// Top keywords of the whole site compared between builds
+const SITE_KEYWORDS: usize = 20;
+
+/// How an old page was matched to a new one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PageMatch {
+    /// Same URL path
+    Path,
+    /// Moved, with the old canonical pointing at the new page or both
+    /// naming the same canonical
+    Canonical,
+}
+
+/// What changed on a page between builds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ChangeKind {
+    Title,
+    Description,
+    /// Schema.org types the old page had and the new one hasn't
+    SchemaLost,
+    /// hreflang languages the old page had and the new one hasn't
+    HreflangLost,
+    /// New page is `noindex`, the old one wasn't
+    Noindexed,
+    /// Top keywords of the old page missing from the new one
+    Keywords,
+    /// SEO completeness score dropped
+    ScoreDropped,
+}
+
+impl ChangeKind {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Title => "title changed",
+            Self::Description => "description changed",
+            Self::SchemaLost => "structured data lost",
+            Self::HreflangLost => "hreflang lost",
+            Self::Noindexed => "now noindex",
+            Self::Keywords => "keywords shifted",
+            Self::ScoreDropped => "SEO score dropped",
+        }
+    }
+}
+
+/// One change of a page, with the old and new value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageChange {
+    pub kind: ChangeKind,
+    pub before: String,
+    pub after: String,
+}
+
+/// An old page and the new page it became
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageComparison {
+    pub old: PathBuf,
+    pub new: PathBuf,
+
+    /// URL path of the old page
+    pub path: String,
+
+    pub matched_by: PageMatch,
+    pub score_before: u32,
+    pub score_after: u32,
+    pub changes: Vec<PageChange>,
+}
+
+/// An old page without a counterpart in the new build
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemovedPage {
+    pub page: PathBuf,
+
+    /// URL path of the old page
+    pub path: String,
+
+    /// Where the new build redirects the path, if anywhere
+    pub redirect: Option<String>,
+}
+
+/// Comparison of an old and a new build of a site
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SiteComparison {
+    pub old_root: PathBuf,
+    pub new_root: PathBuf,
+
+    /// Matched pages, changed or not
+    pub pages: Vec<PageComparison>,
+
+    pub removed: Vec<RemovedPage>,
+
+    /// URL paths of new pages no old page matched
+    pub added: Vec<String>,
+
+    pub score_before: u32,
+    pub score_after: u32,
+
+    /// Top site keywords of the old build missing from the new one
+    pub keywords_lost: Vec<String>,
+
+    /// Top site keywords of the new build the old one didn't have
+    pub keywords_gained: Vec<String>,
+}
+
+/// HTML pages of a build with their URL paths
+struct Build<'a> {
+    pages: Vec<&'a FileAnalysis>,
+    paths: Vec<String>,
+
+    /// Page serving each path alias
+    by_path: BTreeMap<String, usize>,
+
+    /// Path each page's canonical resolves to
+    canonicals: Vec<Option<String>>,
+}
+
+impl<'a> Build<'a> {
+    fn new(analysis: &'a DirectoryAnalysis) -> Self {
+        let origin = Url::parse(COMPARE_ORIGIN).unwrap();
+        let pages: Vec<&FileAnalysis> = analysis
+            .files
+            .iter()
+            .filter(|f| is_html_file(&f.path))
+            .collect();
+        let paths: Vec<String> = pages
+            .iter()
+            .map(|p| url_path(&analysis.root, &p.path))
+            .collect();
+
+        let mut by_path = BTreeMap::new();
+        for (i, path) in paths.iter().enumerate() {
+            for alias in request_paths(path) {
+                by_path.entry(alias).or_insert(i);
+            }
+        }
+        // Hosts are ignored: the builds may be served from different domains
+        let canonicals = pages
+            .iter()
+            .zip(&paths)
+            .map(|(page, path)| {
+                let href = page.result.existing_seo.canonical.as_deref()?;
+                let url = origin.join(path).ok()?.join(href).ok()?;
+                Some(url.path().to_string())
+            })
+            .collect();
+
+        Self {
+            pages,
+            paths,
+            by_path,
+            canonicals,
+        }
+    }
+
+    /// Page serving `path`, or the page whose canonical it is
+    fn page_for(&self, path: &str) -> Option<usize> {
+        self.by_path.get(path).copied().or_else(|| {
+            self.canonicals
+                .iter()
+                .position(|c| c.as_deref() == Some(path))
+        })
+    }
+}
+
+impl SiteComparison {
+    /// Compare the `new` build of a site with the `old` one
+    pub fn new(old: &DirectoryAnalysis, new: &DirectoryAnalysis) -> Self {
+        let before = Build::new(old);
+        let after = Build::new(new);
+
+        let redirects = RedirectAudit::new(&new.root, &new.files, None);
+        let map = RedirectMap::new(&new.root, &redirects.rules, &after.pages, None);
+
+        let mut pages = Vec::new();
+        let mut removed = Vec::new();
+        let mut matched = BTreeSet::new();
+        for (i, page) in before.pages.iter().enumerate() {
+            let path = &before.paths[i];
+            let requested = request_paths(path);
+            let by_path = requested
+                .iter()
+                .find_map(|alias| after.by_path.get(alias.as_str()).copied());
+            let by_canonical = before.canonicals[i]
+                .as_deref()
+                .and_then(|canonical| after.page_for(canonical));
+            let (j, matched_by) = match (by_path, by_canonical) {
+                (Some(j), _) => (j, PageMatch::Path),
+                (None, Some(j)) => (j, PageMatch::Canonical),
+                (None, None) => {
+                    removed.push(RemovedPage {
+                        page: page.path.clone(),
+                        path: path.clone(),
+                        redirect: requested
+                            .iter()
+                            .find_map(|alias| map.redirect(alias))
+                            .map(|(_, target)| target),
+                    });
+                    continue;
+                }
+            };
+            matched.insert(j);
+
+            let new_page = after.pages[j];
+            let score_before = page.result.existing_seo.weighted_score(&old.audit_weights);
+            let score_after = new_page
+                .result
+                .existing_seo
+                .weighted_score(&new.audit_weights);
+            let mut changes = page_changes(page, new_page);
+            if score_after < score_before {
+                changes.push(PageChange {
+                    kind: ChangeKind::ScoreDropped,
+                    before: score_before.to_string(),
+                    after: score_after.to_string(),
+                });
+            }
+            pages.push(PageComparison {
+                old: page.path.clone(),
+                new: new_page.path.clone(),
+                path: path.clone(),
+                matched_by,
+                score_before,
+                score_after,
+                changes,
+            });
+        }
+
+        let added = (0..after.pages.len())
+            .filter(|j| !matched.contains(j))
+            .map(|j| after.paths[j].clone())
+            .collect();
+
+        let old_keywords = site_keywords(&before.pages);
+        let new_keywords = site_keywords(&after.pages);
+        let missing = |from: &[String], within: &[String]| -> Vec<String> {
+            from.iter()
+                .take(SITE_KEYWORDS)
+                .filter(|k| !within.contains(k))
+                .cloned()
+                .collect()
+        };
+
+        Self {
+            old_root: old.root.clone(),
+            new_root: new.root.clone(),
+            pages,
+            removed,
+            added,
+            score_before: old.seo_score(),
+            score_after: new.seo_score(),
+            keywords_lost: missing(&old_keywords, &new_keywords),
+            keywords_gained: missing(&new_keywords, &old_keywords),
+        }
+    }
+
+    /// Removed pages the new build doesn't redirect
+    pub fn unredirected(&self) -> impl Iterator<Item = &RemovedPage> {
+        self.removed.iter().filter(|p| p.redirect.is_none())
+    }
+
+    /// Matched pages with at least one change
+    pub fn changed(&self) -> impl Iterator<Item = &PageComparison> {
+        self.pages.iter().filter(|p| !p.changes.is_empty())
+    }
+
+    /// Change of the site's SEO completeness score
+    pub fn score_delta(&self) -> i32 {
+        self.score_after as i32 - self.score_before as i32
+    }
+}
+
+/// Aliases of a page's URL path as requested, percent-encoded like
+/// `/our%20team.html`
+fn request_paths(path: &str) -> Vec<String> {
+    let origin = Url::parse(COMPARE_ORIGIN).unwrap();
+    aliases(path)
+        .iter()
+        .filter_map(|alias| origin.join(alias).ok())
+        .map(|url| url.path().to_string())
+        .collect()
+}
+
+/// Title, description, structured data, hreflang, indexing and keyword
+/// changes from `old` to `new`
+fn page_changes(old: &FileAnalysis, new: &FileAnalysis) -> Vec<PageChange> {
+    let (before, after) = (&old.result.existing_seo, &new.result.existing_seo);
+    let mut changes = Vec::new();
+    let mut change = |kind, before: String, after: String| {
+        changes.push(PageChange {
+            kind,
+            before,
+            after,
+        })
+    };
+
+    let text = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(|v| v.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|v| !v.is_empty())
+    };
+    let shown = |value: Option<String>| value.unwrap_or_else(|| "(none)".to_string());
+    if text(&before.title) != text(&after.title) {
+        change(
+            ChangeKind::Title,
+            shown(text(&before.title)),
+            shown(text(&after.title)),
+        );
+    }
+    if text(&before.description) != text(&after.description) {
+        change(
+            ChangeKind::Description,
+            shown(text(&before.description)),
+            shown(text(&after.description)),
+        );
+    }
+
+    let lost_types: Vec<&str> = before
+        .schema_types
+        .iter()
+        .filter(|t| !after.schema_types.contains(t))
+        .map(String::as_str)
+        .collect();
+    if !lost_types.is_empty() {
+        change(
+            ChangeKind::SchemaLost,
+            lost_types.join(", "),
+            shown(Some(after.schema_types.join(", ")).filter(|t| !t.is_empty())),
+        );
+    }
+
+    let langs = |seo: &crate::ExistingSeo| -> BTreeSet<String> {
+        seo.hreflang.iter().map(|h| h.lang.clone()).collect()
+    };
+    let (old_langs, new_langs) = (langs(before), langs(after));
+    let lost_langs: Vec<&str> = old_langs
+        .difference(&new_langs)
+        .map(String::as_str)
+        .collect();
+    if !lost_langs.is_empty() {
+        let remaining: Vec<&str> = new_langs.iter().map(String::as_str).collect();
+        change(
+            ChangeKind::HreflangLost,
+            lost_langs.join(", "),
+            shown(Some(remaining.join(", ")).filter(|l| !l.is_empty())),
+        );
+    }
+
+    if after.noindex && !before.noindex {
+        change(
+            ChangeKind::Noindexed,
+            "indexable".to_string(),
+            "noindex".to_string(),
+        );
+    }
+
+    let old_keywords = top_keywords(&old.result.keywords);
+    let new_keywords = top_keywords(&new.result.keywords);
+    let dropped = |from: &[String], within: &[String]| -> Vec<String> {
+        from.iter()
+            .take(PAGE_KEYWORDS)
+            .filter(|k| !within.contains(k))
+            .cloned()
+            .collect()
+    };
+    let lost = dropped(&old_keywords, &new_keywords);
+    if !lost.is_empty() {
+        let gained = dropped(&new_keywords, &old_keywords);
+        change(
+            ChangeKind::Keywords,
+            lost.join(", "),
+            shown(Some(gained.join(", ")).filter(|g| !g.is_empty())),
+        );
+    }
+
+    changes
+}
+
+/// Keywords by score, best first, as far down as a keyword counts as kept
+fn top_keywords(keywords: &[Keyword]) -> Vec<String> {
+    let mut sorted: Vec<&Keyword> = keywords.iter().collect();
+    sorted.sort_by(|a, b| b.score.total_cmp(&a.score));
+    sorted
+        .into_iter()
+        .take(KEYWORD_MARGIN)
+        .map(|k| k.word.to_lowercase())
+        .collect()
+}
+
+/// Keywords of all pages by total frequency, most first
+fn site_keywords(pages: &[&FileAnalysis]) -> Vec<String> {
+    let mut frequency: BTreeMap<String, u32> = BTreeMap::new();
+    for page in pages {
+        for keyword in &page.result.keywords {
+            *frequency.entry(keyword.word.to_lowercase()).or_default() += keyword.frequency;
+        }
+    }
+    let mut sorted: Vec<(String, u32)> = frequency.into_iter().collect();
+    sorted.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    sorted
+        .into_iter()
+        .take(SITE_KEYWORDS + KEYWORD_MARGIN)
+        .map(|(word, _)| word)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{site, titled as page};
+    use crate::Hreflang;
+
+    fn keyword(word: &str, score: f32) -> Keyword {
+        Keyword {
+            word: word.to_string(),
+            frequency: 3,
+            score,
+            is_phrase: false,
+        }
+    }
+
+    #[test]
+    fn test_matching_and_removed_pages() {
+        let mut moved = page("/old", "services/drains.html", "Drain cleaning");
+        moved.result.existing_seo.canonical = Some("/drain-cleaning/".to_string());
+        let old = site(
+            "/old",
+            vec![
+                page("/old", "index.html", "Acme Plumbing"),
+                page("/old", "about.html", "About"),
+                moved,
+                page("/old", "team.html", "Team"),
+                page("/old", "careers.html", "Careers"),
+            ],
+        );
+        let new = site(
+            "/new",
+            vec![
+                page("/new", "index.html", "Acme Plumbing"),
+                // `/about.html` is also served as `/about/`
+                page("/new", "about/index.html", "About"),
+                page("/new", "drain-cleaning/index.html", "Drain cleaning"),
+                page("/new", "blog/index.html", "Blog"),
+            ],
+        );
+        let comparison = SiteComparison::new(&old, &new);
+
+        let matched: Vec<(&str, PageMatch)> = comparison
+            .pages
+            .iter()
+            .map(|p| (p.path.as_str(), p.matched_by))
+            .collect();
+        assert_eq!(
+            matched,
+            vec![
+                ("/", PageMatch::Path),
+                ("/about.html", PageMatch::Path),
+                ("/services/drains.html", PageMatch::Canonical),
+            ]
+        );
+        assert_eq!(comparison.changed().count(), 0);
+        let removed: Vec<&str> = comparison.unredirected().map(|p| p.path.as_str()).collect();
+        assert_eq!(removed, vec!["/team.html", "/careers.html"]);
+        assert_eq!(comparison.added, vec!["/blog/"]);
+    }
+
+    #[test]
+    fn test_page_changes() {
+        let mut before = page("/old", "index.html", "Acme Plumbing | Springfield");
+        let seo = &mut before.result.existing_seo;
+        seo.has_description = true;
+        seo.description = Some("24/7 plumbers in Springfield".to_string());
+        seo.has_schema = true;
+        seo.schema_types = vec!["Plumber".to_string(), "FAQPage".to_string()];
+        seo.hreflang = vec![
+            Hreflang {
+                lang: "en".to_string(),
+                href: "/".to_string(),
+            },
+            Hreflang {
+                lang: "es".to_string(),
+                href: "/es/".to_string(),
+            },
+        ];
+        before.result.keywords = vec![keyword("plumber", 0.9), keyword("drain", 0.7)];
+
+        let mut after = page("/new", "index.html", "Home");
+        let seo = &mut after.result.existing_seo;
+        seo.has_description = true;
+        seo.description = Some("24/7   plumbers in Springfield".to_string());
+        seo.schema_types = vec!["Plumber".to_string()];
+        seo.hreflang = vec![Hreflang {
+            lang: "en".to_string(),
+            href: "/".to_string(),
+        }];
+        seo.noindex = true;
+        after.result.keywords = vec![keyword("plumber", 0.9), keyword("welcome", 0.8)];
+
+        let comparison =
+            SiteComparison::new(&site("/old", vec![before]), &site("/new", vec![after]));
+        let changes: Vec<(ChangeKind, &str, &str)> = comparison.pages[0]
+            .changes
+            .iter()
+            .map(|c| (c.kind, c.before.as_str(), c.after.as_str()))
+            .collect();
+
+        // The description only differs in whitespace
+        assert_eq!(
+            changes,
+            vec![
+                (ChangeKind::Title, "Acme Plumbing | Springfield", "Home"),
+                (ChangeKind::SchemaLost, "FAQPage", "Plumber"),
+                (ChangeKind::HreflangLost, "es", "en"),
+                (ChangeKind::Noindexed, "indexable", "noindex"),
+                (ChangeKind::Keywords, "drain", "welcome"),
+                (ChangeKind::ScoreDropped, "50", "30"),
+            ]
+        );
+        assert_eq!(comparison.keywords_lost, vec!["drain"]);
+        assert_eq!(comparison.score_delta(), -20);
+    }
+
+    #[test]
+    fn test_canonical_match_fallback() {
+        let canonical = |path: &str, title: &str, href: &str| {
+            let mut page = page("/old", path, title);
+            page.result.existing_seo.canonical = Some(href.to_string());
+            page
+        };
+        let old = site(
+            "/old",
+            vec![
+                // Absolute canonical on another host: only the path counts
+                canonical(
+                    "services/drains.html",
+                    "Drains",
+                    "https://old.acme.com/drains/",
+                ),
+                // Printable copy: matched to the page naming the same canonical
+                canonical("print/pipes.html", "Pipes", "/pipes/"),
+                // Canonical to a page the new build doesn't have
+                canonical("services/boilers.html", "Boilers", "/boilers/"),
+            ],
+        );
+        let mut pipes = page("/new", "services/pipes.html", "Pipes");
+        pipes.result.existing_seo.canonical = Some("https://acme.com/pipes/".to_string());
+        let new = site(
+            "/new",
+            vec![page("/new", "drains/index.html", "Drains"), pipes],
+        );
+        let comparison = SiteComparison::new(&old, &new);
+
+        let matched: Vec<(&str, &str, PageMatch)> = comparison
+            .pages
+            .iter()
+            .map(|p| (p.path.as_str(), p.new.to_str().unwrap(), p.matched_by))
+            .collect();
+        assert_eq!(
+            matched,
+            vec![
+                (
+                    "/services/drains.html",
+                    "/new/drains/index.html",
+                    PageMatch::Canonical
+                ),
+                (
+                    "/print/pipes.html",
+                    "/new/services/pipes.html",
+                    PageMatch::Canonical
+                ),
+            ]
+        );
+        let removed: Vec<&str> = comparison.removed.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(removed, vec!["/services/boilers.html"]);
+        assert!(comparison.added.is_empty());
+    }
+
+    #[test]
+    fn test_removed_page_covered_by_splat_redirect() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(
+            root.join("_redirects"),
+            "/blog/*  /news/:splat  301\n/team  /about/\n",
+        )
+        .unwrap();
+        let old = site(
+            "/old",
+            vec![
+                page("/old", "blog/2024/pipes.html", "Frozen pipes"),
+                page("/old", "team/index.html", "Team"),
+                page("/old", "careers.html", "Careers"),
+            ],
+        );
+        let new = site(
+            root,
+            vec![
+                page(root, "news/2024/pipes.html", "Frozen pipes"),
+                page(root, "about/index.html", "About"),
+            ],
+        );
+        let comparison = SiteComparison::new(&old, &new);
+
+        let removed: Vec<(&str, Option<&str>)> = comparison
+            .removed
+            .iter()
+            .map(|p| (p.path.as_str(), p.redirect.as_deref()))
+            .collect();
+        assert_eq!(
+            removed,
+            vec![
+                ("/blog/2024/pipes.html", Some("/news/2024/pipes.html")),
+                // Matched through its `/team` alias
+                ("/team/", Some("/about/")),
+                ("/careers.html", None),
+            ]
+        );
+        assert_eq!(comparison.unredirected().count(), 1);
+    }
+
+    #[test]
+    fn test_page_names_needing_encoding() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(
+            root.join("_redirects"),
+            "/alte%20seite.html  /neue-seite/\n",
+        )
+        .unwrap();
+        let old = site(
+            "/old",
+            vec![
+                page("/old", "our team.html", "Team"),
+                page("/old", "über-uns.html", "Über uns"),
+                page("/old", "alte seite.html", "Alt"),
+            ],
+        );
+        let new = site(
+            root,
+            vec![
+                page(root, "our team.html", "Team"),
+                page(root, "über-uns.html", "Über uns"),
+                page(root, "neue-seite/index.html", "Neu"),
+            ],
+        );
+        let comparison = SiteComparison::new(&old, &new);
+
+        assert_eq!(comparison.pages.len(), 2);
+        assert_eq!(comparison.changed().count(), 0);
+        let removed: Vec<(&str, Option<&str>)> = comparison
+            .removed
+            .iter()
+            .map(|p| (p.path.as_str(), p.redirect.as_deref()))
+            .collect();
+        assert_eq!(removed, vec![("/alte seite.html", Some("/neue-seite/"))]);
+        assert_eq!(comparison.added, vec!["/neue-seite/"]);
+    }
+}
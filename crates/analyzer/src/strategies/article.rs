@@ -0,0 +1,570 @@
+//! Article analyzer - author, dates, section and tags of article pages
+//!
+//! Reads `article:*` Open Graph tags, Article microdata, `<time datetime>`,
+//! bylines, `rel=author` links and category/tag links. Dates outside meta tags
+//! and microdata only count on pages that look like a single article, so event
+//! listings and archives don't pass their dates off as publication dates.
+
+use super::entities::visible_text;
+use crate::{
+    AnalysisResult, AnalyzerError, AnalyzerStrategy, ArticleMeta, Author, DateFallback, DateSource,
+};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat, Utc};
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use std::path::Path;
+use std::process::Command;
+
+/// `<meta name>` values carrying a publication date
+const PUBLISHED_META: [&str; 7] = [
+    "date",
+    "pubdate",
+    "publish-date",
+    "publish_date",
+    "dc.date",
+    "dc.date.issued",
+    "parsely-pub-date",
+];
+
+/// `<meta name>` values carrying a modification date
+const MODIFIED_META: [&str; 3] = ["last-modified", "revised", "dc.date.modified"];
+
+/// Byline elements of common blog themes
+const BYLINE_SELECTORS: &str =
+    ".byline, .author, .author-name, .entry-author, .post-author, .article-author";
+
+/// Longer bylines are sentences, not names
+const MAX_AUTHOR_WORDS: usize = 6;
+
+/// Analyzer for article metadata
+pub struct ArticleAnalyzer {
+    byline_prefix: Regex,
+    date_text: Regex,
+}
+
+impl ArticleAnalyzer {
+    pub fn new() -> Self {
+        const MONTH: &str = r"(?:jan|feb|mar|apr|may|jun|jul|aug|sep|sept|oct|nov|dec)[a-z]*\.?";
+
+        Self {
+            byline_prefix: Regex::new(r"(?i)^(?:(?:written|posted|published)\s+)?by\s+|^author:\s*")
+                .unwrap(),
+            date_text: Regex::new(&format!(
+                r"(?i)\b{MONTH}\s+\d{{1,2}}(?:st|nd|rd|th)?,?\s+\d{{4}}\b|\b\d{{1,2}}(?:st|nd|rd|th)?\s+{MONTH}\s+\d{{4}}\b|\b\d{{4}}-\d{{2}}-\d{{2}}\b"
+            ))
+            .unwrap(),
+        }
+    }
+
+    fn add_author(&self, authors: &mut Vec<Author>, name: &str, url: Option<&str>) {
+        let name = collapse(&self.byline_prefix.replace(name.trim(), ""));
+        let words = name.split_whitespace().count();
+        if words == 0 || words > MAX_AUTHOR_WORDS || name.contains("://") {
+            return;
+        }
+        let author = Author {
+            name,
+            url: url.filter(|u| !u.is_empty()).map(str::to_string),
+        };
+        match authors.iter_mut().find(|a| a.same_author(&author)) {
+            Some(existing) if existing.url.is_none() => existing.url = author.url,
+            Some(_) => {}
+            None => authors.push(author),
+        }
+    }
+
+    fn authors(&self, document: &Html) -> Vec<Author> {
+        let mut authors: Vec<Author> = Vec::new();
+        for name in meta_values(document, "name", "author") {
+            for name in name.split([',', '&']).flat_map(|n| n.split(" and ")) {
+                self.add_author(&mut authors, name, None);
+            }
+        }
+
+        let selector = Selector::parse("a[rel~=author]").unwrap();
+        for link in document.select(&selector).filter(|el| !in_comments(*el)) {
+            self.add_author(&mut authors, &visible_text(link), link.value().attr("href"));
+        }
+
+        let selector = Selector::parse("[itemprop=author]").unwrap();
+        let name = Selector::parse("[itemprop=name]").unwrap();
+        let url = Selector::parse("[itemprop=url]").unwrap();
+        for author in document.select(&selector) {
+            let name = author
+                .select(&name)
+                .next()
+                .map(|n| {
+                    n.value()
+                        .attr("content")
+                        .map(str::to_string)
+                        .unwrap_or_else(|| visible_text(n))
+                })
+                .or_else(|| author.value().attr("content").map(str::to_string))
+                .unwrap_or_else(|| visible_text(author));
+            let url = author
+                .select(&url)
+                .next()
+                .and_then(|u| u.value().attr("href").or_else(|| u.value().attr("content")))
+                .or_else(|| author.value().attr("href"));
+            self.add_author(&mut authors, &name, url);
+        }
+
+        let selector = Selector::parse(BYLINE_SELECTORS).unwrap();
+        let link = Selector::parse("a[href]").unwrap();
+        for byline in document.select(&selector).filter(|el| !in_comments(*el)) {
+            // A linked name wins over the byline text, which may carry a date
+            match byline.select(&link).next() {
+                Some(a) => self.add_author(&mut authors, &visible_text(a), a.value().attr("href")),
+                None => {
+                    let text = collapse(&visible_text(byline));
+                    let name = text.split([' ', '|', '·']).collect::<Vec<_>>();
+                    let end = name.iter().position(|w| *w == "on" || w.is_empty());
+                    let name = name[..end.unwrap_or(name.len())].join(" ");
+                    self.add_author(&mut authors, &name, None);
+                }
+            }
+        }
+
+        // `article:author` is usually a profile URL rather than a name
+        for value in meta_values(document, "property", "article:author") {
+            if value.starts_with("http") {
+                if let [only] = authors.as_mut_slice() {
+                    only.url.get_or_insert(value);
+                }
+            } else {
+                self.add_author(&mut authors, &value, None);
+            }
+        }
+
+        authors
+    }
+
+    fn published(&self, document: &Html, is_article: bool) -> Option<String> {
+        meta_values(document, "property", "article:published_time")
+            .into_iter()
+            .chain(itemprop_values(document, "datePublished"))
+            .chain(
+                PUBLISHED_META
+                    .iter()
+                    .flat_map(|n| meta_values(document, "name", n)),
+            )
+            .find_map(|d| normalize_date(&d))
+            .or_else(|| {
+                is_article
+                    .then(|| self.time_element(document, |el| !is_update(el)))
+                    .flatten()
+            })
+    }
+
+    fn modified(&self, document: &Html, is_article: bool) -> Option<String> {
+        meta_values(document, "property", "article:modified_time")
+            .into_iter()
+            .chain(meta_values(document, "property", "og:updated_time"))
+            .chain(itemprop_values(document, "dateModified"))
+            .chain(
+                MODIFIED_META
+                    .iter()
+                    .flat_map(|n| meta_values(document, "name", n)),
+            )
+            .find_map(|d| normalize_date(&d))
+            .or_else(|| {
+                is_article
+                    .then(|| self.time_element(document, is_update))
+                    .flatten()
+            })
+    }
+
+    /// Date of the first `<time>` element, or dated byline, matching `wanted`
+    fn time_element(&self, document: &Html, wanted: fn(ElementRef) -> bool) -> Option<String> {
+        let times = Selector::parse("time").unwrap();
+        let dated =
+            Selector::parse(".published, .entry-date, .post-date, .posted-on, .updated, .byline")
+                .unwrap();
+
+        document
+            .select(&times)
+            .filter(|el| wanted(*el) && !in_comments(*el))
+            .find_map(|el| {
+                el.value()
+                    .attr("datetime")
+                    .and_then(normalize_date)
+                    .or_else(|| self.date_in_text(&visible_text(el)))
+            })
+            .or_else(|| {
+                document
+                    .select(&dated)
+                    .filter(|el| wanted(*el) && !in_comments(*el))
+                    .find_map(|el| self.date_in_text(&visible_text(el)))
+            })
+    }
+
+    fn date_in_text(&self, text: &str) -> Option<String> {
+        self.date_text
+            .find_iter(text)
+            .find_map(|m| normalize_date(m.as_str()))
+    }
+
+    fn section(&self, document: &Html) -> Option<String> {
+        meta_values(document, "property", "article:section")
+            .into_iter()
+            .chain(itemprop_values(document, "articleSection"))
+            .chain(link_texts(
+                document,
+                "a[rel~=category], .cat-links a, .post-category a",
+            ))
+            .next()
+    }
+
+    fn tags(&self, document: &Html) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        let found = meta_values(document, "property", "article:tag")
+            .into_iter()
+            .chain(link_texts(
+                document,
+                "a[rel~=tag]:not([rel~=category]), .tags a, .tag-links a, .post-tags a",
+            ));
+
+        for tag in found {
+            let tag = tag.trim_start_matches('#').trim().to_string();
+            if !tag.is_empty() && !tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+                tags.push(tag);
+            }
+        }
+        tags
+    }
+}
+
+impl Default for ArticleAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnalyzerStrategy for ArticleAnalyzer {
+    fn name(&self) -> &'static str {
+        "article_analyzer"
+    }
+
+    fn analyze(&self, content: &str) -> Result<AnalysisResult, AnalyzerError> {
+        let document = Html::parse_document(content);
+        let is_article = is_article_page(&document);
+
+        let mut article = ArticleMeta {
+            authors: self.authors(&document),
+            published: self.published(&document, is_article),
+            modified: self.modified(&document, is_article),
+            date_source: None,
+            section: self.section(&document),
+            tags: self.tags(&document),
+        };
+        if article.published.is_some() || article.modified.is_some() {
+            article.date_source = Some(DateSource::Content);
+        }
+
+        Ok(AnalysisResult {
+            article: (is_article || !article.is_empty()).then_some(article),
+            ..Default::default()
+        })
+    }
+}
+
+/// Fill dates the article doesn't state from the file or its git history
+pub(crate) fn fill_missing_dates(article: &mut ArticleMeta, path: &Path, fallback: DateFallback) {
+    if article.published.is_some() && article.modified.is_some() {
+        return;
+    }
+    let dates = match fallback {
+        DateFallback::None => None,
+        DateFallback::Mtime => file_times(path),
+        DateFallback::Git => git_dates(path),
+    };
+    let Some((published, modified, source)) = dates else {
+        return;
+    };
+
+    if article.published.is_none() && article.modified.is_none() {
+        article.date_source = Some(source);
+    }
+    article.published.get_or_insert(published);
+    article.modified.get_or_insert(modified);
+}
+
+fn file_times(path: &Path) -> Option<(String, String, DateSource)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?;
+    // Not every filesystem records a creation time
+    let created = metadata.created().unwrap_or(modified);
+
+    let format = |t| DateTime::<Utc>::from(t).to_rfc3339_opts(SecondsFormat::Secs, true);
+    Some((format(created), format(modified), DateSource::FileTimes))
+}
+
+/// Author dates of the first and latest commits touching `path`
+fn git_dates(path: &Path) -> Option<(String, String, DateSource)> {
+    let output = Command::new("git")
+        .args(["log", "--follow", "--format=%aI", "--"])
+        .arg(path.file_name()?)
+        .current_dir(
+            path.parent()
+                .filter(|p| !p.as_os_str().is_empty())
+                .unwrap_or(Path::new(".")),
+        )
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let log = String::from_utf8_lossy(&output.stdout);
+    let dates: Vec<&str> = log
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    let (latest, first) = (dates.first()?, dates.last()?);
+    Some((
+        first.to_string(),
+        latest.to_string(),
+        DateSource::GitHistory,
+    ))
+}
+
+/// Normalize a date to ISO 8601: date-times keep their time and offset,
+/// written dates like `March 5, 2024` become `2024-03-05`
+pub(super) fn normalize_date(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    if DateTime::parse_from_rfc3339(raw).is_ok() {
+        return Some(raw.to_string());
+    }
+    if let Ok(dt) = DateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M:%S%z") {
+        return Some(dt.to_rfc3339_opts(SecondsFormat::Secs, true));
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(raw, format) {
+            return Some(dt.format("%Y-%m-%dT%H:%M:%S").to_string());
+        }
+    }
+
+    let written = strip_ordinals(raw);
+    ["%Y-%m-%d", "%B %d %Y", "%b %d %Y", "%d %B %Y", "%d %b %Y"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(&written, format).ok())
+        .map(|d| d.format("%Y-%m-%d").to_string())
+}
+
+/// `5th March, 2024` as `5 March 2024`; ISO dates pass through
+fn strip_ordinals(raw: &str) -> String {
+    raw.replace(['.', ','], " ")
+        .split_whitespace()
+        .map(|w| {
+            let digits = w.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+            if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) {
+                digits.to_string()
+            } else {
+                w.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A single article: `og:type=article`, `article:*` tags, Article microdata
+/// or JSON-LD, or exactly one `<article>` element
+pub(super) fn is_article_page(document: &Html) -> bool {
+    let explicit = Selector::parse(
+        r#"meta[property="og:type"][content="article"], meta[property^="article:"], [itemtype*="Article"], [itemtype*="BlogPosting"]"#,
+    )
+    .unwrap();
+    if document.select(&explicit).next().is_some() {
+        return true;
+    }
+
+    let scripts = Selector::parse(r#"script[type="application/ld+json"]"#).unwrap();
+    let in_json_ld = document.select(&scripts).any(|s| {
+        let body: String = s.text().collect();
+        ["\"Article\"", "\"BlogPosting\"", "\"NewsArticle\""]
+            .iter()
+            .any(|t| body.contains(t))
+    });
+
+    let articles = Selector::parse("article").unwrap();
+    in_json_ld || document.select(&articles).count() == 1
+}
+
+/// Dates marked as updates rather than publication
+fn is_update(element: ElementRef) -> bool {
+    std::iter::once(element)
+        .chain(element.ancestors().filter_map(ElementRef::wrap).take(2))
+        .any(|el| {
+            el.value().classes().any(|c| {
+                let c = c.to_lowercase();
+                c.contains("updated") || c.contains("modified")
+            }) || el.value().attr("itemprop") == Some("dateModified")
+        })
+}
+
+fn in_comments(element: ElementRef) -> bool {
+    element.ancestors().filter_map(ElementRef::wrap).any(|el| {
+        el.value()
+            .classes()
+            .any(|c| c.to_lowercase().contains("comment"))
+            || el.value().id().is_some_and(|id| id.contains("comment"))
+    })
+}
+
+/// Non-empty `content` of `<meta {attr}="{value}">`, matching case-insensitively
+fn meta_values(document: &Html, attr: &str, value: &str) -> Vec<String> {
+    let selector = Selector::parse("meta[content]").unwrap();
+    document
+        .select(&selector)
+        .filter(|el| {
+            el.value()
+                .attr(attr)
+                .is_some_and(|v| v.eq_ignore_ascii_case(value))
+        })
+        .filter_map(|el| el.value().attr("content"))
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .collect()
+}
+
+fn itemprop_values(document: &Html, name: &str) -> Vec<String> {
+    let Ok(selector) = Selector::parse(&format!("[itemprop={}]", name)) else {
+        return Vec::new();
+    };
+    document
+        .select(&selector)
+        .map(|el| {
+            let attrs = el.value();
+            attrs
+                .attr("content")
+                .or_else(|| attrs.attr("datetime"))
+                .map(str::to_string)
+                .unwrap_or_else(|| collapse(&visible_text(el)))
+        })
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+fn link_texts(document: &Html, selectors: &str) -> Vec<String> {
+    let Ok(selector) = Selector::parse(selectors) else {
+        return Vec::new();
+    };
+    document
+        .select(&selector)
+        .map(|el| collapse(&visible_text(el)))
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+fn collapse(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article(html: &str) -> Option<ArticleMeta> {
+        ArticleAnalyzer::new().analyze(html).unwrap().article
+    }
+
+    #[test]
+    fn test_blog_post_byline_and_links() {
+        let html = r#"<html><head><title>Winterizing Your Pipes</title></head><body>
+<article class="post">
+  <h1>Winterizing Your Pipes</h1>
+  <div class="entry-meta">
+    <span class="posted-on"><time class="entry-date published" datetime="2024-01-08T09:30:00+00:00">January 8, 2024</time>
+    <time class="updated" datetime="2024-02-01T12:00:00+00:00">February 1, 2024</time></span>
+    <span class="byline">by <a class="url fn n" href="/author/dana/" rel="author">Dana Whitfield</a></span>
+  </div>
+  <p>Frozen pipes burst.</p>
+  <footer>
+    <span class="cat-links"><a href="/category/maintenance/" rel="category tag">Maintenance</a></span>
+    <span class="tags-links"><a href="/tag/winter/" rel="tag">Winter</a> <a href="/tag/pipes/" rel="tag">Pipes</a></span>
+  </footer>
+</article>
+<section class="comments"><div class="comment-author"><span class="author">Spammy McSpam</span> <time datetime="2024-03-01">March 1</time></div></section>
+</body></html>"#;
+        let meta = article(html).unwrap();
+
+        assert_eq!(
+            meta.authors,
+            vec![Author {
+                name: "Dana Whitfield".to_string(),
+                url: Some("/author/dana/".to_string()),
+            }]
+        );
+        assert_eq!(meta.published.as_deref(), Some("2024-01-08T09:30:00+00:00"));
+        assert_eq!(meta.modified.as_deref(), Some("2024-02-01T12:00:00+00:00"));
+        assert_eq!(meta.date_source, Some(DateSource::Content));
+        assert_eq!(meta.section.as_deref(), Some("Maintenance"));
+        assert_eq!(meta.tags, vec!["Winter", "Pipes"]);
+    }
+
+    #[test]
+    fn test_meta_tags_and_listing_pages() {
+        let html = r#"<html><head>
+<meta property="article:published_time" content="2024-05-01T10:00:00Z">
+<meta property="article:author" content="https://example.com/team/lee">
+<meta name="author" content="Lee Park">
+<meta property="article:section" content="Guides">
+<meta property="article:tag" content="drains">
+</head><body><h1>Drain care</h1></body></html>"#;
+        let meta = article(html).unwrap();
+        assert_eq!(meta.published.as_deref(), Some("2024-05-01T10:00:00Z"));
+        assert_eq!(
+            meta.authors[0].url.as_deref(),
+            Some("https://example.com/team/lee")
+        );
+        assert_eq!(meta.section.as_deref(), Some("Guides"));
+        assert_eq!(meta.tags, vec!["drains"]);
+
+        // Event dates on a listing are not publication dates
+        let listing = r#"<html><body>
+<article><h2>Spring workshop</h2><time datetime="2024-04-12">April 12</time></article>
+<article><h2>Fall workshop</h2><time datetime="2024-10-03">October 3</time></article>
+</body></html>"#;
+        assert!(article(listing).is_none());
+    }
+
+    #[test]
+    fn test_dates_and_file_fallback() {
+        assert_eq!(
+            normalize_date("March 5, 2024").as_deref(),
+            Some("2024-03-05")
+        );
+        assert_eq!(
+            normalize_date("5th Mar 2024").as_deref(),
+            Some("2024-03-05")
+        );
+        assert_eq!(
+            normalize_date("2024-03-05T08:00:00+0100").as_deref(),
+            Some("2024-03-05T08:00:00+01:00")
+        );
+        assert_eq!(normalize_date("last Tuesday"), None);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("post.html");
+        std::fs::write(&path, "<article><h1>Post</h1></article>").unwrap();
+
+        let mut meta = ArticleMeta::default();
+        fill_missing_dates(&mut meta, &path, DateFallback::None);
+        assert!(meta.published.is_none());
+
+        fill_missing_dates(&mut meta, &path, DateFallback::Mtime);
+        assert!(meta.published.is_some() && meta.modified.is_some());
+        assert_eq!(meta.date_source, Some(DateSource::FileTimes));
+
+        // Dates stated in the content are kept
+        let mut meta = ArticleMeta {
+            published: Some("2024-03-05".to_string()),
+            date_source: Some(DateSource::Content),
+            ..Default::default()
+        };
+        fill_missing_dates(&mut meta, &path, DateFallback::Mtime);
+        assert_eq!(meta.published.as_deref(), Some("2024-03-05"));
+        assert_eq!(meta.date_source, Some(DateSource::Content));
+    }
+}
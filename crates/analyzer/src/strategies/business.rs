@@ -1,181 +1,213 @@
 //! Business type detection analyzer
+//!
+//! Indicators come from the business taxonomy and are matched as whole words
+//! or phrases, weighted by where they appear, so a term in the title counts
+//! for more than one in the body. Evidence pools per `BusinessType` category
+//! for the ranked distribution, then the deepest well-evidenced taxonomy node
+//! of the winning category becomes the subtype.
+
+use crate::{
+    rank_business_types, AnalysisResult, AnalyzerError, AnalyzerStrategy, BusinessConfig,
+    BusinessSubtype, BusinessType, LocationWeights, Taxonomy,
+};
+use scraper::{ElementRef, Html, Selector};
+
+/// Page regions indicators are matched in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Location {
+    Title,
+    Description,
+    H1,
+    Heading,
+    Nav,
+    Body,
+}
+
+impl Location {
+    fn weight(self, weights: &LocationWeights) -> f32 {
+        match self {
+            Self::Title => weights.title,
+            Self::H1 => weights.h1,
+            Self::Description => weights.description,
+            Self::Heading => weights.heading,
+            Self::Nav => weights.nav,
+            Self::Body => weights.body,
+        }
+    }
+}
 
-use crate::{AnalysisResult, AnalyzerError, AnalyzerStrategy, BusinessType};
-use scraper::{Html, Selector};
-use std::collections::HashMap;
+/// Indicator phrase, pre-tokenized, with its weight
+struct Indicator {
+    words: Vec<String>,
+    weight: f32,
+}
 
 /// Analyzer that detects the type of business/website
 pub struct BusinessTypeAnalyzer {
-    type_indicators: HashMap<BusinessType, Vec<&'static str>>,
+    taxonomy: Taxonomy,
+    /// Indicators of each taxonomy node, in node order
+    node_indicators: Vec<Vec<Indicator>>,
+    weights: LocationWeights,
+    /// Non-root nodes need at least this much evidence of their own to be chosen,
+    /// so a single passing mention doesn't make a site a `RoofingContractor`
+    min_subtype_score: f32,
+    max_counted_matches: usize,
 }
 
 impl BusinessTypeAnalyzer {
     pub fn new() -> Self {
-        let mut indicators: HashMap<BusinessType, Vec<&'static str>> = HashMap::new();
-
-        indicators.insert(
-            BusinessType::Ecommerce,
-            vec![
-                "cart", "checkout", "buy", "shop", "store", "product", "price",
-                "add to cart", "purchase", "order", "shipping", "payment",
-                "catalog", "inventory", "sale", "discount", "coupon",
-            ],
-        );
-
-        indicators.insert(
-            BusinessType::SaaS,
-            vec![
-                "saas", "software", "platform", "dashboard", "api", "integration",
-                "subscription", "trial", "demo", "features", "pricing", "plans",
-                "enterprise", "startup", "cloud", "automation", "workflow",
-            ],
-        );
-
-        indicators.insert(
-            BusinessType::Blog,
-            vec![
-                "blog", "article", "post", "author", "published", "read more",
-                "comments", "tags", "category", "archive", "recent posts",
-            ],
-        );
-
-        indicators.insert(
-            BusinessType::Portfolio,
-            vec![
-                "portfolio", "projects", "work", "case study", "client",
-                "designer", "developer", "freelance", "hire me", "about me",
-            ],
-        );
-
-        indicators.insert(
-            BusinessType::Service,
-            vec![
-                "service", "consulting", "solutions", "expertise", "professional",
-                "team", "approach", "methodology", "process", "engagement",
-                "migration", "assessment", "audit", "implementation",
-            ],
-        );
-
-        indicators.insert(
-            BusinessType::Agency,
-            vec![
-                "agency", "creative", "marketing", "branding", "campaigns",
-                "clients", "results", "strategy", "digital", "media",
-            ],
-        );
-
-        indicators.insert(
-            BusinessType::LocalBusiness,
-            vec![
-                "location", "address", "hours", "visit us", "directions",
-                "local", "near", "store hours", "call us", "contact",
-            ],
-        );
-
-        indicators.insert(
-            BusinessType::Restaurant,
-            vec![
-                "menu", "restaurant", "dining", "reservation", "food",
-                "cuisine", "chef", "table", "delivery", "takeout", "order online",
-            ],
-        );
-
-        indicators.insert(
-            BusinessType::Education,
-            vec![
-                "course", "learn", "student", "teacher", "education",
-                "training", "curriculum", "enroll", "class", "lesson",
-                "certification", "degree", "workshop",
-            ],
-        );
-
-        indicators.insert(
-            BusinessType::Healthcare,
-            vec![
-                "health", "medical", "doctor", "patient", "clinic",
-                "hospital", "treatment", "appointment", "care", "wellness",
-                "diagnosis", "symptoms", "therapy",
-            ],
-        );
-
-        indicators.insert(
-            BusinessType::RealEstate,
-            vec![
-                "property", "real estate", "home", "house", "apartment",
-                "listing", "rent", "sale", "mortgage", "agent", "broker",
-                "bedroom", "bathroom", "sqft",
-            ],
-        );
+        Self::with_taxonomy(Taxonomy::builtin())
+    }
 
-        indicators.insert(
-            BusinessType::Technology,
-            vec![
-                "technology", "tech", "innovation", "engineering", "development",
-                "infrastructure", "security", "data", "ai", "machine learning",
-                "blockchain", "cloud computing", "devops",
-            ],
-        );
+    /// Create analyzer from the `[business]` config section, loading its taxonomy
+    pub fn with_config(config: &BusinessConfig) -> Result<Self, AnalyzerError> {
+        let mut taxonomy = match config.taxonomy {
+            Some(ref path) => Taxonomy::load(path)?,
+            None => Taxonomy::builtin(),
+        };
+        if !config.node.is_empty() {
+            taxonomy.extend(config.node.clone())?;
+        }
 
-        indicators.insert(
-            BusinessType::NonProfit,
-            vec![
-                "nonprofit", "charity", "donate", "volunteer", "mission",
-                "cause", "foundation", "community", "impact", "support",
-            ],
-        );
+        Ok(Self::build(taxonomy, config))
+    }
 
-        Self { type_indicators: indicators }
+    /// Classify against a custom taxonomy, e.g. one with user-defined subtypes
+    pub fn with_taxonomy(taxonomy: Taxonomy) -> Self {
+        Self::build(taxonomy, &BusinessConfig::default())
     }
 
-    fn extract_all_text(&self, html: &str) -> String {
-        let document = Html::parse_document(html);
-        let mut text = String::new();
+    fn build(taxonomy: Taxonomy, config: &BusinessConfig) -> Self {
+        let node_indicators = taxonomy
+            .nodes()
+            .iter()
+            .map(|node| {
+                let strong = node.strong.iter().map(|phrase| (phrase, 2.0));
+                let regular = node.indicators.iter().map(|phrase| (phrase, 1.0));
+                strong
+                    .chain(regular)
+                    .map(|(phrase, weight)| Indicator {
+                        words: tokenize(phrase),
+                        weight,
+                    })
+                    .collect()
+            })
+            .collect();
 
-        // Get title
-        if let Ok(selector) = Selector::parse("title") {
-            if let Some(el) = document.select(&selector).next() {
-                text.push_str(&el.text().collect::<String>());
-                text.push(' ');
-            }
+        Self {
+            taxonomy,
+            node_indicators,
+            weights: config.weights.clone(),
+            min_subtype_score: config.min_subtype_score,
+            max_counted_matches: config.max_counted_matches,
         }
+    }
 
-        // Get meta description
-        if let Ok(selector) = Selector::parse("meta[name='description']") {
-            if let Some(el) = document.select(&selector).next() {
-                if let Some(content) = el.value().attr("content") {
-                    text.push_str(content);
-                    text.push(' ');
-                }
-            }
-        }
+    /// Text of each page region, in document order within a region
+    fn extract_sections(&self, document: &Html) -> Vec<(Location, String)> {
+        let mut sections = Vec::new();
 
-        // Get headings
-        for tag in ["h1", "h2", "h3"] {
-            if let Ok(selector) = Selector::parse(tag) {
+        let mut push_all = |location: Location, selector: &str| {
+            if let Ok(selector) = Selector::parse(selector) {
                 for el in document.select(&selector) {
-                    text.push_str(&el.text().collect::<String>());
-                    text.push(' ');
+                    sections.push((location, el.text().collect::<Vec<_>>().join(" ")));
                 }
             }
-        }
+        };
+        push_all(Location::Title, "title");
+        push_all(Location::H1, "h1");
+        push_all(Location::Heading, "h2, h3");
+        push_all(Location::Nav, "nav a, header a");
 
-        // Get nav links
-        if let Ok(selector) = Selector::parse("nav a, header a") {
-            for el in document.select(&selector) {
-                text.push_str(&el.text().collect::<String>());
-                text.push(' ');
+        if let Ok(selector) = Selector::parse("meta[name='description']") {
+            if let Some(content) = document
+                .select(&selector)
+                .next()
+                .and_then(|el| el.value().attr("content"))
+            {
+                sections.push((Location::Description, content.to_string()));
             }
         }
 
-        // Get main content
-        if let Ok(selector) = Selector::parse("main, article, section, .content") {
-            for el in document.select(&selector) {
-                text.push_str(&el.text().collect::<String>());
-                text.push(' ');
-            }
+        // Main content if marked up, otherwise the whole body
+        let body = ["main", "body"].iter().find_map(|tag| {
+            Selector::parse(tag)
+                .ok()
+                .and_then(|sel| document.select(&sel).next())
+        });
+        if let Some(body) = body {
+            sections.push((Location::Body, visible_text(body)));
         }
 
-        text.to_lowercase()
+        sections
+    }
+
+    /// Weighted evidence for each taxonomy node's own indicators
+    fn score(&self, sections: &[(Location, Vec<String>)]) -> Vec<f32> {
+        self.node_indicators
+            .iter()
+            .map(|indicators| {
+                let mut score = 0.0;
+                for indicator in indicators {
+                    for (location, tokens) in sections {
+                        let count =
+                            count_phrase(tokens, &indicator.words).min(self.max_counted_matches);
+                        if count > 0 {
+                            // First match counts fully, repeats add half each
+                            let matches = 1.0 + (count - 1) as f32 / 2.0;
+                            score += indicator.weight * location.weight(&self.weights) * matches;
+                        }
+                    }
+                }
+                score
+            })
+            .collect()
+    }
+
+    /// Deepest evidenced node of `category`, scored by the evidence along its path
+    fn best_subtype(
+        &self,
+        category: &BusinessType,
+        node_scores: &[f32],
+    ) -> Option<BusinessSubtype> {
+        let nodes = self.taxonomy.nodes();
+        let index_of = |id: &str| nodes.iter().position(|n| n.id == id);
+
+        let candidates = nodes.iter().enumerate().filter_map(|(i, node)| {
+            let own = node_scores[i];
+            let threshold = if node.parent.is_some() {
+                self.min_subtype_score
+            } else {
+                f32::MIN_POSITIVE
+            };
+            if own < threshold || self.taxonomy.category(&node.id) != *category {
+                return None;
+            }
+            let path = self.taxonomy.path(&node.id);
+            let path_score: f32 = path
+                .iter()
+                .filter_map(|n| index_of(&n.id))
+                .map(|j| node_scores[j])
+                .sum();
+            Some((i, path, path_score))
+        });
+
+        // Highest path score, then the deeper node, then table order
+        let (i, path, score) = candidates.max_by(|a, b| {
+            a.2.partial_cmp(&b.2)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.1.len().cmp(&b.1.len()))
+                .then(b.0.cmp(&a.0))
+        })?;
+
+        Some(BusinessSubtype {
+            id: nodes[i].id.clone(),
+            path: path.iter().map(|n| n.id.clone()).collect(),
+            schema_type: nodes[i].schema_type.clone(),
+            category: category.clone(),
+            score,
+            confidence: 0.0,
+        })
     }
 
     fn detect_language(&self, html: &str) -> Option<String> {
@@ -215,38 +247,48 @@ impl AnalyzerStrategy for BusinessTypeAnalyzer {
     }
 
     fn analyze(&self, content: &str) -> Result<AnalysisResult, AnalyzerError> {
-        let text = self.extract_all_text(content);
+        let document = Html::parse_document(content);
+        let sections = self.extract_sections(&document);
         let language = self.detect_language(content);
 
-        // Score each business type
-        let mut scores: HashMap<BusinessType, u32> = HashMap::new();
-
-        for (biz_type, indicators) in &self.type_indicators {
-            let mut score = 0u32;
-            for indicator in indicators {
-                if text.contains(indicator) {
-                    score += 1;
-                    // Bonus for multiple occurrences
-                    score += text.matches(indicator).count().saturating_sub(1) as u32 / 2;
-                }
-            }
-            if score > 0 {
-                scores.insert(biz_type.clone(), score);
-            }
-        }
-
-        // Find highest scoring type
-        let business_type = scores
-            .into_iter()
-            .max_by_key(|(_, score)| *score)
-            .map(|(biz_type, _)| biz_type)
+        let tokenized: Vec<(Location, Vec<String>)> = sections
+            .iter()
+            .map(|(location, text)| (*location, tokenize(text)))
+            .collect();
+        let node_scores = self.score(&tokenized);
+        let category_scores = self
+            .taxonomy
+            .nodes()
+            .iter()
+            .zip(&node_scores)
+            .map(|(node, score)| (self.taxonomy.category(&node.id), *score))
+            .collect();
+        let business_type_scores = rank_business_types(category_scores);
+        let business_type = business_type_scores
+            .first()
+            .map(|s| s.business_type.clone())
             .unwrap_or(BusinessType::Unknown);
 
+        let business_subtype =
+            self.best_subtype(&business_type, &node_scores)
+                .map(|mut subtype| {
+                    subtype.confidence = (subtype.score / business_type_scores[0].score).min(1.0);
+                    subtype
+                });
+
         // Generate content summary
+        let text = sections
+            .iter()
+            .map(|(_, text)| text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase();
         let content_summary = self.generate_summary(&text);
 
         Ok(AnalysisResult {
             business_type,
+            business_type_scores,
+            business_subtype,
             language,
             content_summary: Some(content_summary),
             ..Default::default()
@@ -258,7 +300,7 @@ impl BusinessTypeAnalyzer {
     fn generate_summary(&self, text: &str) -> String {
         // Extract first meaningful sentences
         let sentences: Vec<&str> = text
-            .split(|c| c == '.' || c == '!' || c == '?')
+            .split(['.', '!', '?'])
             .map(|s| s.trim())
             .filter(|s| s.len() > 20 && s.len() < 200)
             .take(3)
@@ -267,3 +309,169 @@ impl BusinessTypeAnalyzer {
         sentences.join(". ")
     }
 }
+
+/// Lowercase alphanumeric words
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// Occurrences of `phrase` as a whole-word sequence in `tokens`
+fn count_phrase(tokens: &[String], phrase: &[String]) -> usize {
+    if phrase.is_empty() || tokens.len() < phrase.len() {
+        return 0;
+    }
+    tokens
+        .windows(phrase.len())
+        .filter(|window| *window == phrase)
+        .count()
+}
+
+/// Element text without script, style and template content
+fn visible_text(element: ElementRef) -> String {
+    let mut text = String::new();
+    for node in element.descendants() {
+        let Some(t) = node.value().as_text() else {
+            continue;
+        };
+        let hidden = node.ancestors().any(|a| {
+            a.value()
+                .as_element()
+                .map(|e| matches!(e.name(), "script" | "style" | "noscript" | "template"))
+                .unwrap_or(false)
+        });
+        if !hidden {
+            text.push_str(t);
+            text.push(' ');
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whole_word_matching() {
+        let html = r#"<html><head><title>Careers</title></head>
+<body><main><p>We maintain a careful career ladder for every engineer.</p></main></body></html>"#;
+        let result = BusinessTypeAnalyzer::new().analyze(html).unwrap();
+
+        // "maintain" must not match "ai", "career" must not match "care"
+        assert!(result
+            .business_type_scores
+            .iter()
+            .all(|s| s.business_type != BusinessType::Healthcare));
+        assert_eq!(result.business_type, BusinessType::Unknown);
+    }
+
+    #[test]
+    fn test_ranked_distribution_with_secondary() {
+        let html = r#"<html><head><title>Trattoria Roma - Italian Restaurant</title></head>
+<body>
+  <nav><a href="/menu">Menu</a><a href="/shop">Shop</a></nav>
+  <h1>Italian restaurant in Boston</h1>
+  <main><p>Book a table or order online. Our shop sells pasta, sauces and gift cards with free shipping.</p></main>
+</body></html>"#;
+        let result = BusinessTypeAnalyzer::new().analyze(html).unwrap();
+
+        assert_eq!(result.business_type, BusinessType::Restaurant);
+        let total: f32 = result
+            .business_type_scores
+            .iter()
+            .map(|s| s.confidence)
+            .sum();
+        assert!((total - 1.0).abs() < 1e-4);
+        assert!(result
+            .business_type_scores
+            .windows(2)
+            .all(|w| w[0].confidence >= w[1].confidence));
+        assert!(result
+            .secondary_business_types()
+            .iter()
+            .any(|s| s.business_type == BusinessType::Ecommerce));
+    }
+
+    #[test]
+    fn test_ties_are_deterministic() {
+        // One equally weighted body indicator each for Ecommerce and Blog
+        let html = "<html><body><p>checkout blog</p></body></html>";
+        let analyzer = BusinessTypeAnalyzer::new();
+
+        for _ in 0..5 {
+            let result = analyzer.analyze(html).unwrap();
+            assert_eq!(result.business_type, BusinessType::Ecommerce);
+        }
+    }
+
+    #[test]
+    fn test_client_subtypes() {
+        let cases = [
+            (
+                "Denver Plumbing Co. | 24/7 Plumber",
+                "Licensed plumbers for clogged drains, water heater repairs and leaks. Call us for a free estimate.",
+                "Plumber",
+            ),
+            (
+                "Bright Smile Family Dentistry",
+                "Our dentists offer cleanings, fillings and teeth whitening. New patients welcome.",
+                "Dentist",
+            ),
+            (
+                "Harper & Cole Law Firm",
+                "Experienced attorneys in personal injury and family law. Free consultation.",
+                "Attorney",
+            ),
+            (
+                "The Lakeside Hotel",
+                "Boutique hotel with 40 rooms and suites, concierge and room service. Book your stay.",
+                "Hotel",
+            ),
+        ];
+
+        let analyzer = BusinessTypeAnalyzer::new();
+        for (title, body, schema_type) in cases {
+            let html = format!(
+                "<html><head><title>{}</title></head><body><h1>{}</h1><p>{}</p></body></html>",
+                title, title, body
+            );
+            let result = analyzer.analyze(&html).unwrap();
+            let subtype = result.business_subtype.as_ref().unwrap();
+
+            assert_eq!(subtype.schema_type, schema_type, "{}", title);
+            assert_eq!(subtype.category, result.business_type);
+            assert_eq!(result.schema_type(), schema_type);
+        }
+    }
+
+    #[test]
+    fn test_user_defined_subtype() {
+        let mut taxonomy = Taxonomy::builtin();
+        taxonomy
+            .extend(vec![crate::TaxonomyNode {
+                id: "pool_service".to_string(),
+                parent: Some("home_and_construction".to_string()),
+                category: None,
+                schema_type: "HomeAndConstructionBusiness".to_string(),
+                strong: vec!["pool cleaning".to_string(), "pool service".to_string()],
+                indicators: vec!["chlorine".to_string()],
+            }])
+            .unwrap();
+
+        let html = "<html><head><title>Sunny Pool Service</title></head>\
+            <body><p>Weekly pool cleaning and chlorine balancing.</p></body></html>";
+        let result = BusinessTypeAnalyzer::with_taxonomy(taxonomy)
+            .analyze(html)
+            .unwrap();
+
+        let subtype = result.business_subtype.unwrap();
+        assert_eq!(subtype.id, "pool_service");
+        assert_eq!(
+            subtype.path,
+            vec!["local_business", "home_and_construction", "pool_service"]
+        );
+    }
+}
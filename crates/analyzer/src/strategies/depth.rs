@@ -0,0 +1,284 @@
+//! Content depth analyzer - how much a page says, and what kind of page it is
+//!
+//! Measures the main content only: `<main>`, else a single `<article>`, else
+//! the body without its header, footer, navigation and sidebars. Richness is
+//! the moving-average type-token ratio, which unlike the plain ratio doesn't
+//! fall just because a page is long. The page role comes from the markup
+//! (listings, product and article markup) and is refined from the path
+//! under the site root by `infer_role`.
+
+use super::article::is_article_page;
+use crate::{
+    is_html_file, AnalysisResult, AnalyzerError, AnalyzerStrategy, ContentDepth, PageRole,
+};
+use scraper::{ElementRef, Html, Selector};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Words per window of the moving-average type-token ratio
+const RICHNESS_WINDOW: usize = 100;
+
+/// Elements whose text is never main content
+const SKIPPED_ELEMENTS: [&str; 6] = ["nav", "aside", "script", "style", "noscript", "template"];
+
+/// Page chrome, skipped when the whole body stands in for the main content
+const CHROME_ELEMENTS: [&str; 2] = ["header", "footer"];
+
+/// Repeated items that make a page a listing
+const MIN_LISTING_ITEMS: usize = 3;
+
+/// Path segments naming a role; the directory index of each is a listing
+const ARTICLE_SEGMENTS: [&str; 8] = [
+    "blog", "news", "articles", "article", "posts", "post", "guides", "insights",
+];
+const PRODUCT_SEGMENTS: [&str; 6] = ["products", "product", "shop", "store", "item", "p"];
+const CATEGORY_SEGMENTS: [&str; 6] = [
+    "category",
+    "categories",
+    "collections",
+    "collection",
+    "tags",
+    "topics",
+];
+
+/// Analyzer for main-content depth and page role
+pub struct ContentDepthAnalyzer;
+
+impl ContentDepthAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Element holding the main content, and whether it is the whole body
+    fn main_content(document: &Html) -> Option<(ElementRef<'_>, bool)> {
+        let main = Selector::parse("main, [role=main]").unwrap();
+        let articles = Selector::parse("article").unwrap();
+        let body = Selector::parse("body").unwrap();
+
+        if let Some(main) = document.select(&main).next() {
+            return Some((main, false));
+        }
+        let mut articles = document.select(&articles);
+        if let (Some(article), None) = (articles.next(), articles.next()) {
+            return Some((article, false));
+        }
+        document.select(&body).next().map(|body| (body, true))
+    }
+
+    fn role(document: &Html) -> PageRole {
+        let products = Selector::parse(r#"[itemtype*="schema.org/Product"]"#).unwrap();
+        let articles = Selector::parse("article").unwrap();
+        let product_meta = Selector::parse(
+            r#"meta[property="og:type"][content="product"], meta[property^="product:"], [itemprop=price], button[name=add-to-cart], form[action*=cart]"#,
+        )
+        .unwrap();
+        let scripts = Selector::parse(r#"script[type="application/ld+json"]"#).unwrap();
+
+        let product_items = document.select(&products).count();
+        if product_items >= MIN_LISTING_ITEMS
+            || document.select(&articles).count() >= MIN_LISTING_ITEMS
+        {
+            return PageRole::Category;
+        }
+        let product_json_ld = document.select(&scripts).any(|s| {
+            let body: String = s.text().collect();
+            body.contains("\"Product\"") && !body.contains("\"ItemList\"")
+        });
+        if product_items == 1 || product_json_ld || document.select(&product_meta).next().is_some()
+        {
+            return PageRole::Product;
+        }
+        if is_article_page(document) {
+            return PageRole::Article;
+        }
+        PageRole::Page
+    }
+}
+
+impl Default for ContentDepthAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnalyzerStrategy for ContentDepthAnalyzer {
+    fn name(&self) -> &'static str {
+        "content_depth_analyzer"
+    }
+
+    fn analyze(&self, content: &str) -> Result<AnalysisResult, AnalyzerError> {
+        let document = Html::parse_document(content);
+        let Some((main, whole_body)) = Self::main_content(&document) else {
+            return Ok(AnalysisResult::default());
+        };
+
+        // The element and its ancestors up to `main` are all content
+        let in_content = |element: ElementRef| {
+            std::iter::once(element)
+                .chain(element.ancestors().filter_map(ElementRef::wrap))
+                .take_while(|el| el.id() != main.id())
+                .all(|el| {
+                    let name = el.value().name();
+                    !(SKIPPED_ELEMENTS.contains(&name)
+                        || (whole_body && CHROME_ELEMENTS.contains(&name))
+                        || el.value().attr("role") == Some("navigation"))
+                })
+        };
+
+        let text: Vec<&str> = main
+            .descendants()
+            .filter_map(|node| {
+                let text = node.value().as_text()?;
+                let parent = node.parent().and_then(ElementRef::wrap)?;
+                in_content(parent).then_some(&**text)
+            })
+            .collect();
+        let words: Vec<String> = text
+            .join(" ")
+            .split(|c: char| !c.is_alphanumeric() && c != '\'' && c != '’')
+            .map(|w| w.trim_matches(['\'', '’']).to_lowercase())
+            .filter(|w| w.chars().any(char::is_alphabetic))
+            .collect();
+
+        let headings = Selector::parse("h2, h3, h4").unwrap();
+        let sections = main.select(&headings).filter(|h| in_content(*h)).count();
+
+        Ok(AnalysisResult {
+            content_depth: vec![ContentDepth {
+                page: None,
+                role: Self::role(&document),
+                words: words.len() as u32,
+                unique_terms: words.iter().collect::<HashSet<_>>().len() as u32,
+                richness: richness(&words),
+                sections: sections as u32,
+            }],
+            ..Default::default()
+        })
+    }
+}
+
+/// Refine the markup-only role from `path`, relative to the site root: the
+/// root index is the home page, `blog/post.html` an article and
+/// `blog/index.html` a listing of them
+pub(crate) fn infer_role(depth: &mut ContentDepth, path: &Path) {
+    if path.components().count() == 1 && is_html_file(path) && path.file_stem().is_some_and(|s| s == "index") {
+        depth.role = PageRole::Home;
+        return;
+    }
+    if depth.role != PageRole::Page {
+        return;
+    }
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    let is_index = stem.eq_ignore_ascii_case("index");
+    let dirs: Vec<String> = path
+        .parent()
+        .into_iter()
+        .flat_map(|p| p.components())
+        .filter_map(|c| c.as_os_str().to_str())
+        .map(str::to_ascii_lowercase)
+        .collect();
+    let named = |segments: &[&str]| dirs.iter().any(|d| segments.contains(&d.as_str()));
+
+    depth.role = if named(&CATEGORY_SEGMENTS) {
+        PageRole::Category
+    } else if named(&ARTICLE_SEGMENTS) || named(&PRODUCT_SEGMENTS) {
+        // The index of `blog/` lists posts; `blog/2024/index.html` is a post
+        let listing = is_index
+            && dirs.last().is_some_and(|d| {
+                ARTICLE_SEGMENTS.contains(&d.as_str()) || PRODUCT_SEGMENTS.contains(&d.as_str())
+            });
+        match (listing, named(&ARTICLE_SEGMENTS)) {
+            (true, _) => PageRole::Category,
+            (false, true) => PageRole::Article,
+            (false, false) => PageRole::Product,
+        }
+    } else {
+        PageRole::Page
+    };
+}
+
+/// Moving-average type-token ratio; the plain ratio for short texts
+fn richness(words: &[String]) -> f32 {
+    if words.len() <= RICHNESS_WINDOW {
+        if words.is_empty() {
+            return 0.0;
+        }
+        return words.iter().collect::<HashSet<_>>().len() as f32 / words.len() as f32;
+    }
+
+    let mut counts: HashMap<&str, u32> = HashMap::new();
+    for word in &words[..RICHNESS_WINDOW] {
+        *counts.entry(word).or_default() += 1;
+    }
+    let mut total = counts.len();
+    for i in RICHNESS_WINDOW..words.len() {
+        let leaving = words[i - RICHNESS_WINDOW].as_str();
+        if let Some(count) = counts.get_mut(leaving) {
+            *count -= 1;
+            if *count == 0 {
+                counts.remove(leaving);
+            }
+        }
+        *counts.entry(&words[i]).or_default() += 1;
+        total += counts.len();
+    }
+    let windows = words.len() - RICHNESS_WINDOW + 1;
+    total as f32 / (windows * RICHNESS_WINDOW) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_main_content_only() {
+        let html = r#"<html><body>
+<header><nav><a href="/">Home</a> <a href="/blog/">Blog</a> <a href="/contact">Contact us today</a></nav></header>
+<main>
+  <h1>Drain cleaning</h1>
+  <p>We clear blocked drains the same day across Springfield.</p>
+  <aside>Related: water heater repair and leak detection</aside>
+  <h2>Prices</h2>
+  <p>Call-outs start at $89 with no hidden fees.</p>
+</main>
+<footer>© 2024 Acme Plumbing. All rights reserved.</footer>
+</body></html>"#;
+        let depth = &ContentDepthAnalyzer::new()
+            .analyze(html)
+            .unwrap()
+            .content_depth[0];
+
+        // "Drain cleaning" + 9 + "Prices" + 8, the $89 is not a word
+        assert_eq!(depth.words, 2 + 9 + 1 + 8);
+        assert_eq!(depth.sections, 1);
+        assert_eq!(depth.role, PageRole::Page);
+        assert!(depth.richness > 0.8);
+    }
+
+    #[test]
+    fn test_roles_from_markup_and_path() {
+        let role = |html: &str, path: &str| {
+            let mut depth = ContentDepthAnalyzer::new()
+                .analyze(html)
+                .unwrap()
+                .content_depth[0]
+                .clone();
+            infer_role(&mut depth, Path::new(path));
+            depth.role
+        };
+        let listing = "<html><body><article>a</article><article>b</article><article>c</article></body></html>";
+        let product = r#"<html><head><meta property="og:type" content="product"></head><body><p>Widget</p></body></html>"#;
+        let plain = "<html><body><p>Text</p></body></html>";
+
+        assert_eq!(role(listing, "index.html"), PageRole::Home);
+        assert_eq!(role(listing, "blog/index.html"), PageRole::Category);
+        assert_eq!(role(product, "widget.html"), PageRole::Product);
+        assert_eq!(role(plain, "blog/first-post.html"), PageRole::Article);
+        assert_eq!(role(plain, "blog/index.html"), PageRole::Category);
+        assert_eq!(role(plain, "shop/red-kettle/index.html"), PageRole::Product);
+        assert_eq!(role(plain, "about.html"), PageRole::Page);
+
+        let repeated = vec!["word".to_string(); 300];
+        assert!(richness(&repeated) < 0.05);
+    }
+}
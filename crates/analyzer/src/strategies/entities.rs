@@ -0,0 +1,742 @@
+//! Entity analyzer - business contact details in page content
+//!
+//! Footers and contact pages carry the phone number, email, address, opening
+//! hours and social links the schema.org injector needs but that are rarely
+//! passed on the command line. Values from markup (`tel:`/`mailto:` links,
+//! microdata) are trusted more than matches in free text, and free text in a
+//! footer or contact block more than text elsewhere on the page.
+
+use crate::{
+    AnalysisResult, AnalyzerError, AnalyzerStrategy, ContactEntities, DetectedEntity, EntityConfig,
+    PostalAddress, SocialProfile,
+};
+use regex::{Captures, Regex};
+use scraper::{ElementRef, Html, Selector};
+
+/// Phone numbering plan of a supported country
+struct Country {
+    code: &'static str,
+    calling_code: &'static str,
+    /// Prefix dialled before national numbers, dropped in E.164
+    trunk_prefix: &'static str,
+    national_lengths: &'static [usize],
+}
+
+#[rustfmt::skip]
+const COUNTRIES: [Country; 12] = [
+    Country { code: "US", calling_code: "1", trunk_prefix: "1", national_lengths: &[10] },
+    Country { code: "CA", calling_code: "1", trunk_prefix: "1", national_lengths: &[10] },
+    Country { code: "GB", calling_code: "44", trunk_prefix: "0", national_lengths: &[9, 10] },
+    Country { code: "IE", calling_code: "353", trunk_prefix: "0", national_lengths: &[7, 8, 9] },
+    Country { code: "AU", calling_code: "61", trunk_prefix: "0", national_lengths: &[9] },
+    Country { code: "NZ", calling_code: "64", trunk_prefix: "0", national_lengths: &[8, 9, 10] },
+    Country { code: "DE", calling_code: "49", trunk_prefix: "0", national_lengths: &[6, 7, 8, 9, 10, 11] },
+    Country { code: "FR", calling_code: "33", trunk_prefix: "0", national_lengths: &[9] },
+    Country { code: "NL", calling_code: "31", trunk_prefix: "0", national_lengths: &[9] },
+    Country { code: "ES", calling_code: "34", trunk_prefix: "", national_lengths: &[9] },
+    Country { code: "IT", calling_code: "39", trunk_prefix: "", national_lengths: &[6, 7, 8, 9, 10, 11] },
+    Country { code: "IN", calling_code: "91", trunk_prefix: "0", national_lengths: &[10] },
+];
+
+#[rustfmt::skip]
+const US_STATES: [&str; 51] = [
+    "AL", "AK", "AZ", "AR", "CA", "CO", "CT", "DE", "DC", "FL", "GA", "HI", "ID",
+    "IL", "IN", "IA", "KS", "KY", "LA", "ME", "MD", "MA", "MI", "MN", "MS", "MO",
+    "MT", "NE", "NV", "NH", "NJ", "NM", "NY", "NC", "ND", "OH", "OK", "OR", "PA",
+    "RI", "SC", "SD", "TN", "TX", "UT", "VT", "VA", "WA", "WV", "WI", "WY",
+];
+
+/// Social networks by host, with the first path segments that name a
+/// profile when the profile is not the first segment itself
+#[rustfmt::skip]
+const SOCIAL_NETWORKS: [(&str, &str, &[&str]); 12] = [
+    ("facebook.com", "facebook", &[]),
+    ("instagram.com", "instagram", &[]),
+    ("twitter.com", "twitter", &[]),
+    ("x.com", "x", &[]),
+    ("linkedin.com", "linkedin", &["company", "in", "school"]),
+    ("youtube.com", "youtube", &["channel", "c", "user"]),
+    ("tiktok.com", "tiktok", &[]),
+    ("pinterest.com", "pinterest", &[]),
+    ("yelp.com", "yelp", &["biz"]),
+    ("github.com", "github", &[]),
+    ("threads.net", "threads", &[]),
+    ("mastodon.social", "mastodon", &[]),
+];
+
+/// First path segments of share buttons, posts and site pages, not profiles
+#[rustfmt::skip]
+const NON_PROFILE_SEGMENTS: [&str; 20] = [
+    "sharer", "sharer.php", "share", "share.php", "intent", "dialog", "hashtag",
+    "search", "watch", "p", "pin", "embed", "plugins", "home", "login", "signup",
+    "privacy", "legal", "policies", "help",
+];
+
+/// Elements whose text is never rendered as page content
+const HIDDEN_ELEMENTS: [&str; 4] = ["script", "style", "noscript", "template"];
+
+/// Blocks where contact details are expected
+const CONTACT_BLOCKS: &str = "footer, address, [id*=contact], [class*=contact]";
+
+/// Where a value was found, from most to least trusted
+#[derive(Clone, Copy)]
+enum Source {
+    Markup,
+    ContactBlock,
+    Body,
+}
+
+impl Source {
+    fn phone(self) -> f32 {
+        match self {
+            Self::Markup => 0.95,
+            Self::ContactBlock => 0.75,
+            Self::Body => 0.6,
+        }
+    }
+
+    fn email(self) -> f32 {
+        match self {
+            Self::Markup => 0.95,
+            Self::ContactBlock => 0.85,
+            Self::Body => 0.7,
+        }
+    }
+
+    fn address(self) -> f32 {
+        match self {
+            Self::Markup => 0.95,
+            Self::ContactBlock => 0.85,
+            Self::Body => 0.65,
+        }
+    }
+
+    fn hours(self) -> f32 {
+        match self {
+            Self::Markup => 0.95,
+            Self::ContactBlock => 0.8,
+            Self::Body => 0.65,
+        }
+    }
+
+    fn social(self) -> f32 {
+        match self {
+            Self::Markup => 0.95,
+            Self::ContactBlock => 0.9,
+            Self::Body => 0.7,
+        }
+    }
+}
+
+/// Analyzer for phone numbers, emails, postal addresses, opening hours and
+/// social profiles
+pub struct EntityAnalyzer {
+    default_country: String,
+    phone: Regex,
+    email: Regex,
+    us_address: Regex,
+    uk_address: Regex,
+    hours: Regex,
+    time: Regex,
+}
+
+impl EntityAnalyzer {
+    pub fn new() -> Self {
+        Self::with_config(&EntityConfig::default())
+    }
+
+    /// Create analyzer from the `[entities]` section
+    pub fn with_config(config: &EntityConfig) -> Self {
+        const STREET: &str = r"\b\d{1,6}[ \t]+(?:[A-Za-z0-9.'-]+[ \t]+){0,5}?(?:Street|St|Avenue|Ave|Road|Rd|Boulevard|Blvd|Drive|Dr|Lane|Ln|Way|Court|Ct|Place|Pl|Parkway|Pkwy|Highway|Hwy|Circle|Cir|Terrace|Ter|Square|Sq)\b\.?(?:,?\s+(?:Suite|Ste\.?|Unit|Apt\.?|#)\s*[A-Za-z0-9-]+)?";
+        const DAY: &str = r"(mon(?:day)?|tue(?:s(?:day)?)?|wed(?:nesday)?|thu(?:r(?:s(?:day)?)?)?|fri(?:day)?|sat(?:urday)?|sun(?:day)?)s?\b\.?";
+        const TIME: &str = r"\d{1,2}(?::\d{2})?\s*(?:[ap]\.?m\.?)?";
+
+        Self {
+            default_country: config.default_country.to_uppercase(),
+            phone: Regex::new(
+                r"(?:(?:\+|00)\d{1,3}[ \t.-]?)?(?:\(\d{1,4}\)[ \t.-]?)?\d{2,5}(?:[ \t.-]\d{2,6}){1,3}\b",
+            )
+            .unwrap(),
+            email: Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}").unwrap(),
+            us_address: Regex::new(&format!(
+                r"(?P<street>{STREET})\s*[,\n]\s*(?P<city>[A-Z][A-Za-z.' -]{{1,40}}?)\s*,\s*(?P<region>[A-Z]{{2}})\s+(?P<postal>\d{{5}}(?:-\d{{4}})?)\b"
+            ))
+            .unwrap(),
+            uk_address: Regex::new(&format!(
+                r"(?P<street>{STREET})\s*[,\n]\s*(?P<city>[A-Z][A-Za-z' -]{{1,40}}?)\s*,?\s+(?P<postal>[A-Z]{{1,2}}\d[A-Z\d]?\s*\d[A-Z]{{2}})\b"
+            ))
+            .unwrap(),
+            hours: Regex::new(&format!(
+                r"(?i)\b{DAY}(?:\s*(?:-|–|—|to|through|thru)\s*{DAY})?\s*:?\s*(?P<opens>{TIME})\s*(?:-|–|—|to)\s*(?P<closes>{TIME})"
+            ))
+            .unwrap(),
+            time: Regex::new(r"(?i)^(\d{1,2})(?::(\d{2}))?\s*(?:([ap])\.?m\.?)?$").unwrap(),
+        }
+    }
+
+    /// Country of the page: the `<html lang>` region, else the first address
+    pub(super) fn page_country(&self, document: &Html, entities: &ContactEntities) -> String {
+        let lang_region = Selector::parse("html[lang]")
+            .ok()
+            .and_then(|sel| document.select(&sel).next())
+            .and_then(|html| html.value().attr("lang"))
+            .and_then(|lang| lang.split(['-', '_']).nth(1))
+            .map(|region| region.to_uppercase())
+            .filter(|region| calling_code(region).is_some());
+
+        lang_region
+            .or_else(|| {
+                entities
+                    .addresses
+                    .iter()
+                    .find_map(|a| a.value.country.clone())
+                    .filter(|country| calling_code(country).is_some())
+            })
+            .unwrap_or_else(|| self.default_country.clone())
+    }
+
+    fn scan_addresses(&self, text: &str, source: Source, entities: &mut ContactEntities) {
+        for (_, address) in self.find_addresses(text) {
+            push(&mut entities.addresses, address, source.address());
+        }
+    }
+
+    /// US and UK postal addresses in `text`, with the text they were parsed from
+    pub(super) fn find_addresses(&self, text: &str) -> Vec<(String, PostalAddress)> {
+        let mut addresses = Vec::new();
+        for caps in self.us_address.captures_iter(text) {
+            if !US_STATES.contains(&&caps["region"]) {
+                continue;
+            }
+            addresses.push((
+                collapse(&caps[0]),
+                PostalAddress {
+                    street: collapse(&caps["street"]),
+                    city: collapse(&caps["city"]),
+                    region: Some(caps["region"].to_string()),
+                    postal_code: caps["postal"].to_string(),
+                    country: Some("US".to_string()),
+                },
+            ));
+        }
+        for caps in self.uk_address.captures_iter(text) {
+            addresses.push((
+                collapse(&caps[0]),
+                PostalAddress {
+                    street: collapse(&caps["street"]),
+                    city: collapse(&caps["city"]),
+                    region: None,
+                    postal_code: caps["postal"].to_string(),
+                    country: Some("GB".to_string()),
+                },
+            ));
+        }
+        addresses
+    }
+
+    fn scan_text(&self, text: &str, source: Source, country: &str, entities: &mut ContactEntities) {
+        for (_, phone) in self.find_phones(text, country) {
+            push(&mut entities.phones, phone, source.phone());
+        }
+
+        for m in self.email.find_iter(text) {
+            if let Some(email) = self.normalize_email(m.as_str()) {
+                push(&mut entities.emails, email, source.email());
+            }
+        }
+
+        for caps in self.hours.captures_iter(text) {
+            if let Some(hours) = self.opening_hours(&caps) {
+                push(&mut entities.opening_hours, hours, source.hours());
+            }
+        }
+    }
+
+    /// Phone numbers in `text` as written and in E.164 form
+    pub(super) fn find_phones(&self, text: &str, country: &str) -> Vec<(String, String)> {
+        self.phone
+            .find_iter(text)
+            .filter(|m| {
+                let before = text[..m.start()].chars().next_back();
+                let after = text[m.end()..].chars().next();
+                !before.is_some_and(|c| c.is_alphanumeric())
+                    && !after.is_some_and(|c| c.is_alphanumeric())
+            })
+            .filter_map(|m| {
+                let phone = normalize_phone(m.as_str(), country)?;
+                Some((m.as_str().trim().to_string(), phone))
+            })
+            .collect()
+    }
+
+    fn normalize_email(&self, raw: &str) -> Option<String> {
+        let email = raw.trim().trim_end_matches('.').to_lowercase();
+        let m = self.email.find(&email)?;
+        if m.start() != 0 || m.end() != email.len() {
+            return None;
+        }
+
+        let domain = email.rsplit('@').next()?;
+        let tld = domain.rsplit('.').next()?;
+        // `logo@2x.png`-style asset names and documentation placeholders
+        let placeholder = ["example.com", "example.org", "domain.com", "email.com"];
+        if ["png", "jpg", "jpeg", "gif", "svg", "webp", "avif"].contains(&tld)
+            || placeholder.contains(&domain)
+        {
+            return None;
+        }
+        Some(email)
+    }
+
+    /// schema.org `openingHours` value, e.g. `Mo-Fr 08:00-17:00`
+    fn opening_hours(&self, caps: &Captures) -> Option<String> {
+        let days = match (caps.get(1), caps.get(2)) {
+            (Some(first), Some(last)) => {
+                format!("{}-{}", day_code(first.as_str())?, day_code(last.as_str())?)
+            }
+            (Some(day), None) => day_code(day.as_str())?.to_string(),
+            _ => return None,
+        };
+
+        let (opens, opens_pm) = self.parse_time(&caps["opens"])?;
+        let (closes, closes_pm) = self.parse_time(&caps["closes"])?;
+        // Bare numbers like "Sat 3-4" are too ambiguous to be hours
+        let explicit = |t: &str| t.contains(':') || t.to_lowercase().contains('m');
+        if !explicit(&caps["opens"]) && !explicit(&caps["closes"]) {
+            return None;
+        }
+
+        // "9-5pm" means 9am; "9am-5" means 5pm
+        let opens_pm = opens_pm.or_else(|| closes_pm.map(|pm| pm && opens.0 < closes.0));
+        let closes_pm = closes_pm.or_else(|| opens_pm.map(|pm| pm || closes.0 <= opens.0));
+
+        Some(format!(
+            "{} {}-{}",
+            days,
+            to_24h(opens, opens_pm)?,
+            to_24h(closes, closes_pm)?
+        ))
+    }
+
+    /// Hour and minute, plus whether a meridiem says pm
+    fn parse_time(&self, raw: &str) -> Option<((u32, u32), Option<bool>)> {
+        let caps = self.time.captures(raw.trim())?;
+        let hour = caps[1].parse().ok()?;
+        let minute = caps.get(2).map_or(Some(0), |m| m.as_str().parse().ok())?;
+        let pm = caps.get(3).map(|m| m.as_str().eq_ignore_ascii_case("p"));
+        Some(((hour, minute), pm))
+    }
+
+    fn scan_markup(&self, document: &Html, country: &str, entities: &mut ContactEntities) {
+        let links = Selector::parse("a[href]").unwrap();
+        for link in document.select(&links) {
+            let href = link.value().attr("href").unwrap_or("").trim();
+            let lower = href.to_lowercase();
+
+            if let Some(number) = lower.strip_prefix("tel:") {
+                let number = number
+                    .split([';', ','])
+                    .next()
+                    .unwrap_or("")
+                    .replace("%20", " ");
+                if let Some(phone) = normalize_phone(&number, country) {
+                    push(&mut entities.phones, phone, Source::Markup.phone());
+                }
+            } else if let Some(address) = lower.strip_prefix("mailto:") {
+                let recipients = address.split('?').next().unwrap_or("");
+                for email in recipients
+                    .split(',')
+                    .filter_map(|e| self.normalize_email(e))
+                {
+                    push(&mut entities.emails, email, Source::Markup.email());
+                }
+            } else if let Some(profile) = social_profile(href) {
+                let rel_me = link
+                    .value()
+                    .attr("rel")
+                    .is_some_and(|rel| rel.split_whitespace().any(|r| r == "me"));
+                let source = if link.value().attr("itemprop") == Some("sameAs") || rel_me {
+                    Source::Markup
+                } else if in_site_chrome(link) {
+                    Source::ContactBlock
+                } else {
+                    Source::Body
+                };
+                push(&mut entities.social_profiles, profile, source.social());
+            }
+        }
+
+        let itemprop = |name: &str| Selector::parse(&format!("[itemprop={}]", name)).unwrap();
+        for element in document.select(&itemprop("telephone")) {
+            if let Some(phone) = normalize_phone(&microdata_value(element), country) {
+                push(&mut entities.phones, phone, Source::Markup.phone());
+            }
+        }
+        for element in document.select(&itemprop("email")) {
+            let value = microdata_value(element);
+            let value = value.trim_start_matches("mailto:");
+            if let Some(email) = self.normalize_email(value) {
+                push(&mut entities.emails, email, Source::Markup.email());
+            }
+        }
+        for element in document.select(&itemprop("openingHours")) {
+            let hours = collapse(&microdata_value(element));
+            if !hours.is_empty() {
+                push(&mut entities.opening_hours, hours, Source::Markup.hours());
+            }
+        }
+
+        let addresses = Selector::parse("[itemtype*=PostalAddress]").unwrap();
+        for element in document.select(&addresses) {
+            let field = |name: &str| {
+                element
+                    .select(&itemprop(name))
+                    .next()
+                    .map(|e| collapse(&microdata_value(e)))
+                    .filter(|v| !v.is_empty())
+            };
+            let (Some(street), Some(city)) = (field("streetAddress"), field("addressLocality"))
+            else {
+                continue;
+            };
+            let address = PostalAddress {
+                street,
+                city,
+                region: field("addressRegion"),
+                postal_code: field("postalCode").unwrap_or_default(),
+                country: field("addressCountry").and_then(|c| country_code(&c)),
+            };
+            push(&mut entities.addresses, address, Source::Markup.address());
+        }
+    }
+}
+
+impl Default for EntityAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnalyzerStrategy for EntityAnalyzer {
+    fn name(&self) -> &'static str {
+        "entity_analyzer"
+    }
+
+    fn analyze(&self, content: &str) -> Result<AnalysisResult, AnalyzerError> {
+        let document = Html::parse_document(content);
+        let body_selector = Selector::parse("body").unwrap();
+        let Some(body) = document.select(&body_selector).next() else {
+            return Ok(AnalysisResult::default());
+        };
+
+        let contact_selector = Selector::parse(CONTACT_BLOCKS).unwrap();
+        let mut blocks: Vec<(String, Source)> = document
+            .select(&contact_selector)
+            .map(|el| (visible_text(el), Source::ContactBlock))
+            .collect();
+        blocks.push((visible_text(body), Source::Body));
+
+        let mut entities = ContactEntities::default();
+        for (text, source) in &blocks {
+            self.scan_addresses(text, *source, &mut entities);
+        }
+        self.scan_markup(
+            &document,
+            &self.page_country(&document, &entities),
+            &mut entities,
+        );
+
+        let country = self.page_country(&document, &entities);
+        for (text, source) in &blocks {
+            self.scan_text(text, *source, &country, &mut entities);
+        }
+
+        // Sort most confident first
+        entities.merge(ContactEntities::default());
+
+        Ok(AnalysisResult {
+            entities,
+            ..Default::default()
+        })
+    }
+}
+
+/// Calling code of a supported country, e.g. `44` for `GB`
+pub(crate) fn calling_code(country: &str) -> Option<&'static str> {
+    COUNTRIES
+        .iter()
+        .find(|c| c.code.eq_ignore_ascii_case(country))
+        .map(|c| c.calling_code)
+}
+
+/// Normalize a phone number to E.164, assuming `country` for national numbers
+pub(crate) fn normalize_phone(raw: &str, country: &str) -> Option<String> {
+    let raw = raw.trim().replace("(0)", "");
+    let digits: String = raw.chars().filter(|c| c.is_ascii_digit()).collect();
+
+    let international = if raw.starts_with('+') {
+        Some(digits.as_str())
+    } else {
+        digits.strip_prefix("00")
+    };
+
+    if let Some(number) = international {
+        let mut plans = COUNTRIES
+            .iter()
+            .filter(|c| number.starts_with(c.calling_code))
+            .peekable();
+        let valid = if plans.peek().is_none() {
+            (8..=15).contains(&number.len())
+        } else {
+            plans.any(|c| valid_national(c, &number[c.calling_code.len()..]))
+        };
+        return valid.then(|| format!("+{}", number));
+    }
+
+    let plan = COUNTRIES
+        .iter()
+        .find(|c| c.code.eq_ignore_ascii_case(country))?;
+    let national = match digits.strip_prefix(plan.trunk_prefix) {
+        Some(rest) if !plan.trunk_prefix.is_empty() && valid_national(plan, rest) => rest,
+        _ => digits.as_str(),
+    };
+    valid_national(plan, national).then(|| format!("+{}{}", plan.calling_code, national))
+}
+
+fn valid_national(plan: &Country, national: &str) -> bool {
+    if !plan.national_lengths.contains(&national.len()) {
+        return false;
+    }
+    // North American area codes never start with 0 or 1
+    plan.calling_code != "1" || !national.starts_with(['0', '1'])
+}
+
+/// Profile on a known social network, normalized to `https://host/path`
+fn social_profile(href: &str) -> Option<SocialProfile> {
+    let rest = href
+        .strip_prefix("https://")
+        .or_else(|| href.strip_prefix("http://"))?;
+    let rest = rest.split(['?', '#']).next().unwrap_or("");
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let host = host.to_lowercase();
+    let host = host
+        .strip_prefix("www.")
+        .or_else(|| host.strip_prefix("m."))
+        .unwrap_or(&host);
+
+    let (domain, network, nested) = SOCIAL_NETWORKS
+        .iter()
+        .find(|(domain, _, _)| *domain == host)?;
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let first = segments.first()?.to_lowercase();
+
+    let is_profile = match segments.len() {
+        1 => !NON_PROFILE_SEGMENTS.contains(&first.as_str()) && !nested.contains(&first.as_str()),
+        2 => nested.contains(&first.as_str()),
+        _ => false,
+    };
+    is_profile.then(|| SocialProfile {
+        network: network.to_string(),
+        url: format!("https://{}/{}", domain, segments.join("/")),
+    })
+}
+
+/// Two-letter schema.org day code
+fn day_code(day: &str) -> Option<&'static str> {
+    let code = match day.get(..3)?.to_lowercase().as_str() {
+        "mon" => "Mo",
+        "tue" => "Tu",
+        "wed" => "We",
+        "thu" => "Th",
+        "fri" => "Fr",
+        "sat" => "Sa",
+        "sun" => "Su",
+        _ => return None,
+    };
+    Some(code)
+}
+
+fn to_24h((hour, minute): (u32, u32), pm: Option<bool>) -> Option<String> {
+    let hour = match pm {
+        Some(_) if hour == 0 || hour > 12 => return None,
+        Some(true) if hour < 12 => hour + 12,
+        Some(false) if hour == 12 => 0,
+        _ => hour,
+    };
+    (hour <= 24 && minute < 60).then(|| format!("{:02}:{:02}", hour, minute))
+}
+
+pub(super) fn country_code(country: &str) -> Option<String> {
+    let country = country.trim();
+    match country.to_lowercase().as_str() {
+        "usa" | "united states" | "united states of america" => Some("US".to_string()),
+        "uk" | "united kingdom" | "great britain" => Some("GB".to_string()),
+        _ if country.len() == 2 && country.chars().all(|c| c.is_ascii_alphabetic()) => {
+            Some(country.to_uppercase())
+        }
+        _ => None,
+    }
+}
+
+/// Microdata value: the `content` attribute, else the element text
+pub(super) fn microdata_value(element: ElementRef) -> String {
+    element
+        .value()
+        .attr("content")
+        .map(str::to_string)
+        .unwrap_or_else(|| element.text().collect())
+}
+
+/// Whether a link sits in the header, footer, nav or a contact block
+fn in_site_chrome(element: ElementRef) -> bool {
+    element.ancestors().filter_map(ElementRef::wrap).any(|a| {
+        let el = a.value();
+        matches!(el.name(), "footer" | "header" | "nav" | "address")
+            || ["id", "class"].iter().any(|attr| {
+                el.attr(attr)
+                    .is_some_and(|v| v.contains("contact") || v.contains("social"))
+            })
+    })
+}
+
+/// Rendered text of an element, one line per text node
+pub(super) fn visible_text(element: ElementRef) -> String {
+    element
+        .descendants()
+        .filter_map(|node| {
+            let text = node.value().as_text()?;
+            let hidden = node.ancestors().any(|a| {
+                a.value()
+                    .as_element()
+                    .map(|e| HIDDEN_ELEMENTS.contains(&e.name()))
+                    .unwrap_or(false)
+            });
+            let text = text.trim();
+            (!hidden && !text.is_empty()).then_some(text)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub(super) fn collapse(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn push<T: PartialEq>(entities: &mut Vec<DetectedEntity<T>>, value: T, confidence: f32) {
+    match entities.iter_mut().find(|e| e.value == value) {
+        Some(existing) => existing.confidence = existing.confidence.max(confidence),
+        None => entities.push(DetectedEntity {
+            value,
+            confidence,
+            page: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_footer_contact_details() {
+        let html = r#"<html lang="en"><body>
+<main><p>Family-owned plumbing since 1998. Call 303-555-0142 for a free quote.</p></main>
+<footer>
+  <a href="tel:+1-303-555-0142">(303) 555-0142</a>
+  <a href="mailto:Service@AcmePlumbing.com?subject=Quote">Email us</a>
+  <address>1420 Larimer St, Suite 200<br>Denver, CO 80202</address>
+  <p>Mon–Fri: 8am – 6pm</p>
+  <p>Saturday 9:00-13:00</p>
+  <a href="https://www.facebook.com/acmeplumbing/">Facebook</a>
+  <a href="https://www.facebook.com/sharer/sharer.php?u=https://acme.com">Share</a>
+  <a href="https://linkedin.com/company/acme-plumbing?trk=footer">LinkedIn</a>
+</footer>
+</body></html>"#;
+        let entities = EntityAnalyzer::new().analyze(html).unwrap().entities;
+
+        assert_eq!(entities.phones.len(), 1);
+        assert_eq!(entities.phones[0].value, "+13035550142");
+        assert_eq!(entities.phones[0].confidence, 0.95);
+        assert_eq!(entities.email().unwrap().value, "service@acmeplumbing.com");
+
+        let address = &entities.address().unwrap().value;
+        assert_eq!(address.street, "1420 Larimer St, Suite 200");
+        assert_eq!(address.city, "Denver");
+        assert_eq!(address.region.as_deref(), Some("CO"));
+        assert_eq!(address.postal_code, "80202");
+        assert_eq!(address.country.as_deref(), Some("US"));
+
+        assert_eq!(
+            entities.confident_opening_hours(),
+            vec!["Mo-Fr 08:00-18:00", "Sa 09:00-13:00"]
+        );
+        assert_eq!(
+            entities.confident_social_profiles(),
+            vec![
+                "https://facebook.com/acmeplumbing",
+                "https://linkedin.com/company/acme-plumbing"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_phone_country_normalization() {
+        let analyzer = EntityAnalyzer::new();
+
+        let uk = r#"<html lang="en-GB"><body><footer>
+<p>Bookings: 020 7946 0958 or +44 (0)20 7946 0958</p>
+<p>10 Downing Street, London SW1A 2AA</p>
+</footer></body></html>"#;
+        let entities = analyzer.analyze(uk).unwrap().entities;
+        let phones: Vec<_> = entities.phones.iter().map(|p| p.value.as_str()).collect();
+        assert_eq!(phones, vec!["+442079460958"]);
+        assert_eq!(entities.addresses[0].value.postal_code, "SW1A 2AA");
+
+        // Dates, prices and asset names are not contact details
+        let noise = r#"<html><body>
+<p>Updated 2024-01-15. Plans from 1 200 per year. <img alt="logo@2x.png"> logo@2x.png</p>
+<p>Reach us at 0049 30 901820 or help@example.com</p>
+</body></html>"#;
+        let entities = analyzer.analyze(noise).unwrap().entities;
+        let phones: Vec<_> = entities.phones.iter().map(|p| p.value.as_str()).collect();
+        assert_eq!(phones, vec!["+4930901820"]);
+        assert!(entities.emails.is_empty());
+
+        let config = EntityConfig {
+            default_country: "AU".to_string(),
+        };
+        let au = "<html><body><p>Phone (02) 9374 4000</p></body></html>";
+        let entities = EntityAnalyzer::with_config(&config)
+            .analyze(au)
+            .unwrap()
+            .entities;
+        assert_eq!(entities.phones[0].value, "+61293744000");
+        assert!(entities.phone().is_some());
+    }
+
+    #[test]
+    fn test_entities_merge_across_pages() {
+        let analyzer = EntityAnalyzer::new();
+        let mut home = analyzer
+            .analyze("<html><body><p>Questions? 303.555.0142</p></body></html>")
+            .unwrap()
+            .entities;
+        home.set_page(Path::new("index.html"));
+        let mut contact = analyzer
+            .analyze(r#"<html><body><a href="tel:3035550142">Call</a></body></html>"#)
+            .unwrap()
+            .entities;
+        contact.set_page(Path::new("contact.html"));
+
+        assert_eq!(home.phones[0].confidence, 0.6);
+        home.merge(contact);
+
+        assert_eq!(home.phones.len(), 1);
+        assert_eq!(home.phones[0].confidence, 0.95);
+        assert_eq!(
+            home.phones[0].page.as_deref(),
+            Some(Path::new("contact.html"))
+        );
+    }
+}
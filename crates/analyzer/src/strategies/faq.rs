@@ -0,0 +1,359 @@
+//! FAQ analyzer - question and answer pairs for FAQPage markup
+//!
+//! Recognizes the structures FAQ sections are built from: question headings
+//! followed by answer text, `<details>/<summary>`, `<dl>` term lists, ARIA
+//! accordions, `question`/`answer` class pairs and Question microdata.
+//! Outside an FAQ section a summary, term or accordion label only counts when
+//! it reads like a question, so tabs and glossaries are not mistaken for FAQs.
+
+use super::entities::visible_text;
+use crate::{AnalysisResult, AnalyzerError, AnalyzerStrategy, FaqEntry};
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use std::collections::HashMap;
+
+const HEADINGS: [&str; 6] = ["h1", "h2", "h3", "h4", "h5", "h6"];
+
+/// Longer labels are section intros, not questions
+const MAX_QUESTION_WORDS: usize = 30;
+
+/// Analyzer for FAQ question/answer pairs
+pub struct FaqAnalyzer {
+    interrogative: Regex,
+    question_prefix: Regex,
+    answer_prefix: Regex,
+}
+
+impl FaqAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            interrogative: Regex::new(
+                r"(?i)^(how|what|why|when|where|who|which|can|could|do|does|did|is|are|will|would|should|shall|may)\b",
+            )
+            .unwrap(),
+            question_prefix: Regex::new(r"(?i)^q(?:uestion)?\s*[:.)-]\s*").unwrap(),
+            answer_prefix: Regex::new(r"(?i)^a(?:nswer)?\s*[:.)-]\s*").unwrap(),
+        }
+    }
+
+    fn question(&self, element: ElementRef) -> String {
+        let text = collapse(&visible_text(element));
+        self.question_prefix.replace(&text, "").into_owned()
+    }
+
+    fn answer(&self, text: &str) -> String {
+        let text = collapse(text);
+        self.answer_prefix.replace(&text, "").into_owned()
+    }
+
+    /// Whether a label is a question: phrased as one, or inside an FAQ section
+    fn is_question(&self, element: ElementRef, question: &str) -> bool {
+        let words = question.split_whitespace().count();
+        words > 0
+            && words <= MAX_QUESTION_WORDS
+            && (question.ends_with('?')
+                || self.interrogative.is_match(question)
+                || in_faq_section(element))
+    }
+
+    fn microdata(&self, document: &Html, entries: &mut Vec<FaqEntry>) {
+        let questions = Selector::parse(r#"[itemtype*="schema.org/Question"]"#).unwrap();
+        let name = Selector::parse("[itemprop=name]").unwrap();
+        let answer = Selector::parse("[itemprop=acceptedAnswer]").unwrap();
+        let answer_text = Selector::parse("[itemprop=text]").unwrap();
+
+        for element in document.select(&questions) {
+            let (Some(q), Some(a)) = (element.select(&name).next(), element.select(&answer).next())
+            else {
+                continue;
+            };
+            let a = a.select(&answer_text).next().unwrap_or(a);
+            push(entries, self.question(q), self.answer(&visible_text(a)));
+        }
+    }
+
+    fn details(&self, document: &Html, entries: &mut Vec<FaqEntry>) {
+        let details = Selector::parse("details").unwrap();
+
+        for element in document.select(&details) {
+            let mut summary = None;
+            let mut answer = Vec::new();
+            for child in element.children() {
+                match ElementRef::wrap(child) {
+                    Some(el) if el.value().name() == "summary" && summary.is_none() => {
+                        summary = Some(el)
+                    }
+                    Some(el) => answer.push(visible_text(el)),
+                    None => {
+                        if let Some(text) = child.value().as_text() {
+                            answer.push(text.to_string());
+                        }
+                    }
+                }
+            }
+
+            let Some(summary) = summary else { continue };
+            let question = self.question(summary);
+            if self.is_question(element, &question) {
+                push(entries, question, self.answer(&answer.join(" ")));
+            }
+        }
+    }
+
+    fn definition_lists(&self, document: &Html, entries: &mut Vec<FaqEntry>) {
+        let lists = Selector::parse("dl").unwrap();
+
+        for list in document.select(&lists) {
+            let mut current: Option<(String, Vec<String>)> = None;
+            let terms = list
+                .children()
+                .filter_map(ElementRef::wrap)
+                // Terms may be grouped in a wrapping <div>
+                .flat_map(|el| match el.value().name() {
+                    "div" => el.children().filter_map(ElementRef::wrap).collect(),
+                    _ => vec![el],
+                });
+
+            for el in terms {
+                match el.value().name() {
+                    "dt" => {
+                        if let Some((q, a)) = current.take() {
+                            push(entries, q, self.answer(&a.join(" ")));
+                        }
+                        let question = self.question(el);
+                        if self.is_question(list, &question) {
+                            current = Some((question, Vec::new()));
+                        }
+                    }
+                    "dd" => {
+                        if let Some((_, answer)) = current.as_mut() {
+                            answer.push(visible_text(el));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            if let Some((q, a)) = current {
+                push(entries, q, self.answer(&a.join(" ")));
+            }
+        }
+    }
+
+    /// Accordion toggles pointing at their panel via `aria-controls`
+    fn aria_accordions(&self, document: &Html, entries: &mut Vec<FaqEntry>) {
+        let controls = Selector::parse("[aria-controls]").unwrap();
+        let with_id = Selector::parse("[id]").unwrap();
+        let panels: HashMap<&str, ElementRef> = document
+            .select(&with_id)
+            .filter_map(|el| Some((el.value().attr("id")?, el)))
+            .collect();
+
+        for toggle in document.select(&controls) {
+            let target = toggle.value().attr("aria-controls").unwrap_or("");
+            let Some(panel) = panels.get(target) else {
+                continue;
+            };
+            let question = self.question(toggle);
+            if self.is_question(toggle, &question) {
+                push(entries, question, self.answer(&visible_text(*panel)));
+            }
+        }
+    }
+
+    /// `.faq-question` followed by `.faq-answer` and similar class pairs
+    fn class_pairs(&self, document: &Html, entries: &mut Vec<FaqEntry>) {
+        let questions = Selector::parse("[class*=question]").unwrap();
+
+        for element in document.select(&questions) {
+            if has_class_containing(element, "answer") {
+                continue;
+            }
+            let Some(answer) = element
+                .next_siblings()
+                .filter_map(ElementRef::wrap)
+                .next()
+                .filter(|el| has_class_containing(*el, "answer"))
+            else {
+                continue;
+            };
+            let question = self.question(element);
+            if self.is_question(element, &question) {
+                push(entries, question, self.answer(&visible_text(answer)));
+            }
+        }
+    }
+
+    /// Headings ending in `?`, answered by the content up to the next heading
+    fn question_headings(&self, document: &Html, entries: &mut Vec<FaqEntry>) {
+        let headings = Selector::parse(&HEADINGS.join(", ")).unwrap();
+
+        for heading in document.select(&headings) {
+            let question = self.question(heading);
+            if !question.ends_with('?') || !self.is_question(heading, &question) {
+                continue;
+            }
+
+            let answer: Vec<String> = heading
+                .next_siblings()
+                .filter_map(ElementRef::wrap)
+                .take_while(|el| !is_or_contains_heading(*el))
+                .map(visible_text)
+                .collect();
+            push(entries, question, self.answer(&answer.join(" ")));
+        }
+    }
+}
+
+impl Default for FaqAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnalyzerStrategy for FaqAnalyzer {
+    fn name(&self) -> &'static str {
+        "faq_analyzer"
+    }
+
+    fn analyze(&self, content: &str) -> Result<AnalysisResult, AnalyzerError> {
+        let document = Html::parse_document(content);
+        let mut faq = Vec::new();
+
+        // Explicit structures first, so their answers win over heading runs
+        self.microdata(&document, &mut faq);
+        self.details(&document, &mut faq);
+        self.aria_accordions(&document, &mut faq);
+        self.class_pairs(&document, &mut faq);
+        self.definition_lists(&document, &mut faq);
+        self.question_headings(&document, &mut faq);
+
+        Ok(AnalysisResult {
+            faq,
+            ..Default::default()
+        })
+    }
+}
+
+/// Whether the element sits in a section marked as an FAQ
+fn in_faq_section(element: ElementRef) -> bool {
+    std::iter::once(element)
+        .chain(element.ancestors().filter_map(ElementRef::wrap))
+        .any(|el| {
+            let el = el.value();
+            ["id", "class", "itemtype"].iter().any(|attr| {
+                el.attr(attr)
+                    .is_some_and(|v| v.to_lowercase().contains("faq"))
+            })
+        })
+}
+
+fn has_class_containing(element: ElementRef, needle: &str) -> bool {
+    element
+        .value()
+        .classes()
+        .any(|c| c.to_lowercase().contains(needle))
+}
+
+fn is_or_contains_heading(element: ElementRef) -> bool {
+    HEADINGS.contains(&element.value().name())
+        || element
+            .descendants()
+            .filter_map(ElementRef::wrap)
+            .any(|el| HEADINGS.contains(&el.value().name()))
+}
+
+fn collapse(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn push(entries: &mut Vec<FaqEntry>, question: String, answer: String) {
+    if question.is_empty() || answer.is_empty() {
+        return;
+    }
+    let entry = FaqEntry { question, answer };
+    if !entries.iter().any(|e| e.same_question(&entry)) {
+        entries.push(entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn questions(html: &str) -> Vec<String> {
+        FaqAnalyzer::new()
+            .analyze(html)
+            .unwrap()
+            .faq
+            .into_iter()
+            .map(|e| e.question)
+            .collect()
+    }
+
+    #[test]
+    fn test_headings_details_and_term_lists() {
+        let html = r#"<html><body>
+<h2>How long does a drain cleaning take?</h2>
+<p>Most drains are clear within an hour.</p>
+<p>Main sewer lines can take longer.</p>
+<h2>Our Services</h2>
+<p>We handle leaks, drains and water heaters.</p>
+<details><summary>Do you charge for estimates?</summary><p>No, estimates are always free.</p></details>
+<dl>
+  <dt>Are you licensed?</dt><dd>Yes, in Colorado and Wyoming.</dd>
+  <dt>PEX</dt><dd>Cross-linked polyethylene tubing.</dd>
+</dl>
+</body></html>"#;
+        let faq = FaqAnalyzer::new().analyze(html).unwrap().faq;
+
+        assert_eq!(
+            faq.iter().map(|e| e.question.as_str()).collect::<Vec<_>>(),
+            vec![
+                "Do you charge for estimates?",
+                "Are you licensed?",
+                "How long does a drain cleaning take?"
+            ]
+        );
+        assert_eq!(
+            faq[2].answer,
+            "Most drains are clear within an hour. Main sewer lines can take longer."
+        );
+    }
+
+    #[test]
+    fn test_accordions_and_class_pairs() {
+        let html = r#"<html><body>
+<section id="faq">
+  <div class="accordion-item">
+    <h3><button aria-controls="faq-1">Shipping</button></h3>
+    <div id="faq-1" class="accordion-collapse"><div>Orders ship within 2 business days.</div></div>
+  </div>
+  <div class="faq-item">
+    <div class="faq-question">Q: Can I return a mug?</div>
+    <div class="faq-answer">A: Within 30 days, unused.</div>
+  </div>
+</section>
+<div role="tablist"><button role="tab" aria-controls="tab-1">Specs</button></div>
+<div id="tab-1">Stoneware, 12 oz.</div>
+</body></html>"#;
+        let faq = FaqAnalyzer::new().analyze(html).unwrap().faq;
+
+        assert_eq!(faq.len(), 2);
+        assert_eq!(faq[0].question, "Shipping");
+        assert_eq!(faq[0].answer, "Orders ship within 2 business days.");
+        assert_eq!(faq[1].question, "Can I return a mug?");
+        assert_eq!(faq[1].answer, "Within 30 days, unused.");
+    }
+
+    #[test]
+    fn test_no_pairs_without_structure() {
+        let html = r#"<html><body>
+<h1>Questions about plumbing? Call us.</h1>
+<p>Frequently asked questions are answered by our team on the phone.</p>
+<h2>Why choose us?</h2>
+<h2>Our Team</h2>
+</body></html>"#;
+
+        assert!(questions(html).is_empty());
+    }
+}
@@ -0,0 +1,430 @@
+//! HowTo analyzer - step-by-step instructions for HowTo markup
+//!
+//! Instructions are found under "How to…" headings, as an ordered list or as
+//! numbered step headings, and on pages whose sections are headed "Step 1",
+//! "Step 2", … Supplies and tools come from "You'll need"/"Tools" lists and
+//! the total time from phrases such as "Total time: 45 minutes".
+
+use super::entities::visible_text;
+use crate::{AnalysisResult, AnalyzerError, AnalyzerStrategy, HowTo, HowToStep};
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+
+const HEADINGS: &str = "h1, h2, h3, h4, h5, h6";
+
+/// Fewer steps than this is not a procedure
+const MIN_STEPS: usize = 2;
+
+/// Analyzer for step-by-step instructions
+pub struct HowToAnalyzer {
+    how_to_heading: Regex,
+    step_heading: Regex,
+    requirements_label: Regex,
+    total_time: Regex,
+}
+
+impl HowToAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            how_to_heading: Regex::new(r"(?i)^how\s+to\b").unwrap(),
+            step_heading: Regex::new(
+                r"(?i)^(?:step\s*(?P<n>\d+)\s*[.:)\-–—]?|(?P<num>\d+)\s*[.)])\s*(?P<title>.*)$",
+            )
+            .unwrap(),
+            requirements_label: Regex::new(
+                r"(?i)^(?:what\s+you(?:'ll|’ll|\s+will)?\s+need|you(?:'ll|’ll|\s+will)\s+need|materials|supplies|ingredients|(?P<tools>tools?|equipment))\b[^:]*:?\s*(?P<inline>.*)$",
+            )
+            .unwrap(),
+            total_time: Regex::new(
+                r"(?i)\b(?:total\s+time|time\s+required|time\s+needed|estimated\s+time|duration|takes?)\s*:?\s*(?:about|around|approximately|approx\.?|~)?\s*(?P<first>\d+(?:\.\d+)?)\s*(?P<unit>hours?|hrs?|h|minutes?|mins?|m)\b(?:\s*(?:and\s+)?(?P<minutes>\d+)\s*(?:minutes?|mins?|m)\b)?",
+            )
+            .unwrap(),
+        }
+    }
+
+    /// Step title from a numbered step heading, `Some("")` for an untitled step
+    fn step_title(&self, heading: ElementRef) -> Option<String> {
+        let text = collapse(&visible_text(heading));
+        let caps = self.step_heading.captures(&text)?;
+        let explicit = caps.name("n").is_some();
+        let title = caps["title"].trim().to_string();
+        // "3. Reasons to hire us" style headings need a title; "Step 3" doesn't
+        (explicit || !title.is_empty()).then_some(title)
+    }
+
+    fn guide(&self, name: String, level: u8, section: &[ElementRef]) -> Option<HowTo> {
+        let mut guide = HowTo {
+            name,
+            steps: self.heading_steps(level, section),
+            ..Default::default()
+        };
+        if guide.steps.len() < MIN_STEPS {
+            guide.steps = list_steps(section);
+        }
+        if guide.steps.len() < MIN_STEPS {
+            return None;
+        }
+
+        self.requirements(section, &mut guide);
+
+        let text: Vec<String> = section.iter().map(|el| visible_text(*el)).collect();
+        guide.total_time = self.duration(&text.join("\n"));
+
+        Some(guide)
+    }
+
+    /// Steps from numbered sub-headings, each followed by its text
+    fn heading_steps(&self, level: u8, section: &[ElementRef]) -> Vec<HowToStep> {
+        let mut steps: Vec<HowToStep> = Vec::new();
+        let mut in_step = false;
+
+        for el in section {
+            if let Some(sub_level) = heading_level(*el) {
+                in_step = false;
+                if sub_level > level {
+                    if let Some(title) = self.step_title(*el) {
+                        steps.push(HowToStep {
+                            name: (!title.is_empty()).then(|| title.clone()),
+                            text: title,
+                        });
+                        in_step = true;
+                    }
+                }
+                continue;
+            }
+
+            if let Some(step) = steps.last_mut().filter(|_| in_step) {
+                let text = collapse(&visible_text(*el));
+                if text.is_empty() {
+                    continue;
+                }
+                if step.name.as_deref() == Some(step.text.as_str()) || step.text.is_empty() {
+                    step.text = text;
+                } else {
+                    step.text = format!("{} {}", step.text, text);
+                }
+            }
+        }
+
+        steps.retain(|s| !s.text.is_empty());
+        steps
+    }
+
+    /// Supplies and tools from labelled lists, e.g. "You'll need:" + `<ul>`
+    fn requirements(&self, section: &[ElementRef], guide: &mut HowTo) {
+        for (i, el) in section.iter().enumerate() {
+            if matches!(el.value().name(), "ul" | "ol") {
+                continue;
+            }
+            let label = collapse(&visible_text(*el));
+            if label.split_whitespace().count() > 12 {
+                continue;
+            }
+            let Some(caps) = self.requirements_label.captures(&label) else {
+                continue;
+            };
+
+            let inline = caps["inline"].trim();
+            let items: Vec<String> = if !inline.is_empty() {
+                inline
+                    .split([',', ';'])
+                    .flat_map(|item| item.split(" and "))
+                    .map(|item| item.trim().trim_end_matches('.').to_string())
+                    .filter(|item| !item.is_empty())
+                    .collect()
+            } else {
+                let list = section[i + 1..]
+                    .first()
+                    .and_then(|next| first_list(*next, "ul"));
+                list.map(list_items).unwrap_or_default()
+            };
+
+            let target = if caps.name("tools").is_some() {
+                &mut guide.tools
+            } else {
+                &mut guide.supplies
+            };
+            for item in items {
+                if !target.contains(&item) {
+                    target.push(item);
+                }
+            }
+        }
+    }
+
+    /// ISO 8601 duration of the first stated time, e.g. `PT1H30M`
+    fn duration(&self, text: &str) -> Option<String> {
+        let caps = self.total_time.captures(text)?;
+        let first: f32 = caps["first"].parse().ok()?;
+        let is_hours = caps["unit"].to_lowercase().starts_with('h');
+        let extra: u32 = caps
+            .name("minutes")
+            .and_then(|m| m.as_str().parse().ok())
+            .unwrap_or(0);
+
+        let minutes = if is_hours {
+            (first * 60.0).round() as u32 + extra
+        } else {
+            first.round() as u32
+        };
+        if minutes == 0 {
+            return None;
+        }
+
+        let (hours, minutes) = (minutes / 60, minutes % 60);
+        let mut iso = "PT".to_string();
+        if hours > 0 {
+            iso.push_str(&format!("{}H", hours));
+        }
+        if minutes > 0 {
+            iso.push_str(&format!("{}M", minutes));
+        }
+        Some(iso)
+    }
+}
+
+impl Default for HowToAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnalyzerStrategy for HowToAnalyzer {
+    fn name(&self) -> &'static str {
+        "how_to_analyzer"
+    }
+
+    fn analyze(&self, content: &str) -> Result<AnalysisResult, AnalyzerError> {
+        let document = Html::parse_document(content);
+        let headings = Selector::parse(HEADINGS).unwrap();
+        let mut how_to = Vec::new();
+
+        for heading in document.select(&headings) {
+            let name = collapse(&visible_text(heading));
+            if !self.how_to_heading.is_match(&name) {
+                continue;
+            }
+            let level = heading_level(heading).unwrap_or(1);
+            if let Some(guide) = self.guide(name, level, &section(heading, level)) {
+                how_to.push(guide);
+            }
+        }
+
+        // Pages that are one procedure, headed "Step 1", "Step 2", ...
+        if how_to.is_empty() {
+            let first_step = document.select(&headings).find(|h| {
+                self.step_title(*h).is_some()
+                    && collapse(&visible_text(*h))
+                        .to_lowercase()
+                        .starts_with("step")
+            });
+            if let Some(first) = first_step {
+                let level = heading_level(first).unwrap_or(2).saturating_sub(1);
+                let mut steps = vec![first];
+                steps.extend(section(first, level));
+                if let Some(mut guide) = self.guide(page_name(&document), level, &steps) {
+                    // The time is usually stated in the intro, before step 1
+                    if guide.total_time.is_none() {
+                        let body = Selector::parse("body").unwrap();
+                        guide.total_time = document
+                            .select(&body)
+                            .next()
+                            .and_then(|body| self.duration(&visible_text(body)));
+                    }
+                    how_to.push(guide);
+                }
+            }
+        }
+
+        how_to.retain(|guide| !guide.name.is_empty());
+
+        Ok(AnalysisResult {
+            how_to,
+            ..Default::default()
+        })
+    }
+}
+
+/// Siblings after `heading` up to the next heading of the same or higher rank
+fn section(heading: ElementRef, level: u8) -> Vec<ElementRef> {
+    heading
+        .next_siblings()
+        .filter_map(ElementRef::wrap)
+        .take_while(|el| {
+            let ends_section = |h: ElementRef| heading_level(h).is_some_and(|l| l <= level);
+            !ends_section(*el)
+                && !el
+                    .descendants()
+                    .filter_map(ElementRef::wrap)
+                    .any(ends_section)
+        })
+        .collect()
+}
+
+/// Steps from the first ordered list of the section
+fn list_steps(section: &[ElementRef]) -> Vec<HowToStep> {
+    let Some(list) = section.iter().find_map(|el| first_list(*el, "ol")) else {
+        return Vec::new();
+    };
+
+    list.children()
+        .filter_map(ElementRef::wrap)
+        .filter(|li| li.value().name() == "li")
+        .filter_map(|li| {
+            let text = collapse(&visible_text(li));
+            if text.is_empty() {
+                return None;
+            }
+
+            // "<li><strong>Remove the stopper.</strong> Unscrew…</li>"
+            let lead = li
+                .children()
+                .find_map(ElementRef::wrap)
+                .filter(|el| matches!(el.value().name(), "strong" | "b"))
+                .map(|el| collapse(&visible_text(el)))
+                .filter(|lead| text.starts_with(lead.as_str()) && lead.len() < text.len());
+
+            Some(match lead {
+                Some(lead) => HowToStep {
+                    text: text[lead.len()..].trim().to_string(),
+                    name: Some(lead.trim_end_matches([':', '.']).to_string()),
+                },
+                None => HowToStep { name: None, text },
+            })
+        })
+        .collect()
+}
+
+/// `el` itself if it is a `name` list, else its first descendant list
+fn first_list<'a>(el: ElementRef<'a>, name: &str) -> Option<ElementRef<'a>> {
+    if el.value().name() == name {
+        return Some(el);
+    }
+    let selector = Selector::parse(name).ok()?;
+    el.select(&selector).next()
+}
+
+fn list_items(list: ElementRef) -> Vec<String> {
+    list.children()
+        .filter_map(ElementRef::wrap)
+        .filter(|li| li.value().name() == "li")
+        .map(|li| collapse(&visible_text(li)))
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+fn heading_level(el: ElementRef) -> Option<u8> {
+    match el.value().name() {
+        "h1" => Some(1),
+        "h2" => Some(2),
+        "h3" => Some(3),
+        "h4" => Some(4),
+        "h5" => Some(5),
+        "h6" => Some(6),
+        _ => None,
+    }
+}
+
+/// Page `<h1>`, else `<title>`
+fn page_name(document: &Html) -> String {
+    ["h1", "title"]
+        .iter()
+        .filter_map(|name| Selector::parse(name).ok())
+        .find_map(|sel| {
+            let text = collapse(&document.select(&sel).next()?.text().collect::<String>());
+            (!text.is_empty()).then_some(text)
+        })
+        .unwrap_or_default()
+}
+
+fn collapse(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ordered_list_under_how_to_heading() {
+        let html = r#"<html><body>
+<h2>How to Unclog a Bathroom Sink</h2>
+<p>Total time: 30 minutes</p>
+<h3>What You'll Need</h3>
+<ul><li>Baking soda</li><li>White vinegar</li></ul>
+<p><strong>Tools:</strong> plunger, adjustable wrench and a bucket.</p>
+<ol>
+  <li><strong>Remove the stopper.</strong> Unscrew the pivot nut behind the drain.</li>
+  <li>Pour in half a cup of baking soda followed by vinegar.</li>
+  <li><strong>Plunge.</strong> Work the plunger for a minute, then flush with hot water.</li>
+</ol>
+<h2>Why Drains Clog</h2>
+<ol><li>Hair</li><li>Soap scum</li></ol>
+</body></html>"#;
+        let how_to = HowToAnalyzer::new().analyze(html).unwrap().how_to;
+
+        assert_eq!(how_to.len(), 1);
+        let guide = &how_to[0];
+        assert_eq!(guide.name, "How to Unclog a Bathroom Sink");
+        assert_eq!(guide.total_time.as_deref(), Some("PT30M"));
+        assert_eq!(guide.supplies, vec!["Baking soda", "White vinegar"]);
+        assert_eq!(
+            guide.tools,
+            vec!["plunger", "adjustable wrench", "a bucket"]
+        );
+
+        assert_eq!(guide.steps.len(), 3);
+        assert_eq!(guide.steps[0].name.as_deref(), Some("Remove the stopper"));
+        assert_eq!(
+            guide.steps[0].text,
+            "Unscrew the pivot nut behind the drain."
+        );
+        assert_eq!(guide.steps[1].name, None);
+    }
+
+    #[test]
+    fn test_step_headings() {
+        let html = r#"<html><head><title>Faucet Repair | Acme</title></head><body>
+<h1>Replacing a Faucet Cartridge</h1>
+<p>This repair takes about 1.5 hours.</p>
+<h2>Step 1: Shut off the water</h2>
+<p>Close both valves under the sink.</p>
+<h2>Step 2: Remove the handle</h2>
+<p>Pry off the cap and unscrew the handle.</p>
+<p>Keep the screw somewhere safe.</p>
+<h2>Step 3</h2>
+<p>Pull the old cartridge straight out.</p>
+</body></html>"#;
+        let how_to = HowToAnalyzer::new().analyze(html).unwrap().how_to;
+
+        assert_eq!(how_to.len(), 1);
+        let guide = &how_to[0];
+        assert_eq!(guide.name, "Replacing a Faucet Cartridge");
+        assert_eq!(guide.steps.len(), 3);
+        assert_eq!(guide.steps[0].name.as_deref(), Some("Shut off the water"));
+        assert_eq!(guide.steps[0].text, "Close both valves under the sink.");
+        assert_eq!(
+            guide.steps[1].text,
+            "Pry off the cap and unscrew the handle. Keep the screw somewhere safe."
+        );
+        assert_eq!(guide.steps[2].name, None);
+        assert_eq!(guide.total_time.as_deref(), Some("PT1H30M"));
+    }
+
+    #[test]
+    fn test_no_procedure() {
+        let html = r#"<html><body>
+<h2>How to Reach Us</h2>
+<p>Call or email any time.</p>
+<h2>Our Process</h2>
+<ol><li>Consultation</li><li>Quote</li><li>Install</li></ol>
+<h2>10. Reasons to hire us</h2>
+</body></html>"#;
+
+        assert!(HowToAnalyzer::new()
+            .analyze(html)
+            .unwrap()
+            .how_to
+            .is_empty());
+    }
+}
@@ -0,0 +1,357 @@
+//! Icon analyzer - favicons, app icons and the web app manifest
+//!
+//! Collects `<link rel="icon">`, `apple-touch-icon` and `mask-icon`
+//! declarations, the manifest link and `theme-color`. The manifest and the
+//! image files are read by `inspect_icons` once the page's path is known, so
+//! each icon carries its real format and pixel sizes rather than the declared
+//! ones.
+
+use super::performance::{is_remote, resolve};
+use crate::{
+    AnalysisResult, AnalyzerError, AnalyzerStrategy, IconFormat, IconRef, IconRel, PageIcons,
+    WebManifest,
+};
+use scraper::{Html, Selector};
+use serde_json::Value;
+use std::path::Path;
+
+/// Analyzer for favicon, app icon and manifest declarations
+pub struct IconAnalyzer;
+
+impl IconAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for IconAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnalyzerStrategy for IconAnalyzer {
+    fn name(&self) -> &'static str {
+        "icon_analyzer"
+    }
+
+    fn analyze(&self, content: &str) -> Result<AnalysisResult, AnalyzerError> {
+        let document = Html::parse_document(content);
+        let links = Selector::parse("link[rel][href]").unwrap();
+        let theme = Selector::parse(r#"meta[name="theme-color" i][content]"#).unwrap();
+        let mut page = PageIcons::default();
+
+        for link in document.select(&links) {
+            let attrs = link.value();
+            let href = attrs.attr("href").unwrap_or("").trim();
+            let rel = attrs.attr("rel").unwrap_or("").to_ascii_lowercase();
+            let tokens: Vec<&str> = rel.split_whitespace().collect();
+            if href.is_empty() {
+                continue;
+            }
+
+            let rel = if tokens.contains(&"manifest") {
+                page.manifest_url.get_or_insert_with(|| href.to_string());
+                continue;
+            } else if tokens.contains(&"icon") {
+                IconRel::Icon
+            } else if tokens
+                .iter()
+                .any(|t| matches!(*t, "apple-touch-icon" | "apple-touch-icon-precomposed"))
+            {
+                IconRel::AppleTouchIcon
+            } else if tokens.contains(&"mask-icon") {
+                IconRel::MaskIcon
+            } else {
+                continue;
+            };
+            page.icons.push(icon_ref(
+                href,
+                rel,
+                attrs.attr("sizes"),
+                attrs.attr("type"),
+                None,
+            ));
+        }
+
+        page.theme_color = document
+            .select(&theme)
+            .filter_map(|m| m.value().attr("content"))
+            .map(str::trim)
+            .find(|c| !c.is_empty())
+            .map(str::to_string);
+
+        Ok(AnalysisResult {
+            icons: vec![page],
+            ..Default::default()
+        })
+    }
+}
+
+/// Read the manifest and icon files of `icons`, declared by `page`
+pub(crate) fn inspect_icons(icons: &mut PageIcons, page: &Path) {
+    let base = page.parent().unwrap_or(Path::new(""));
+    for icon in &mut icons.icons {
+        inspect(icon, base);
+    }
+
+    let Some(url) = icons.manifest_url.as_deref() else {
+        return;
+    };
+    if is_remote(url) {
+        return;
+    }
+    let Some(file) = resolve(url, base) else {
+        icons.manifest = Some(WebManifest {
+            path: base.join(url.trim_start_matches('/')),
+            error: Some("file not found".to_string()),
+            ..Default::default()
+        });
+        return;
+    };
+
+    let mut manifest = WebManifest {
+        path: file.clone(),
+        ..Default::default()
+    };
+    let json = std::fs::read_to_string(&file)
+        .map_err(|e| e.to_string())
+        .and_then(|s| serde_json::from_str::<Value>(&s).map_err(|e| e.to_string()));
+    match json {
+        Ok(Value::Object(map)) => {
+            let field = |name: &str| {
+                map.get(name)
+                    .and_then(Value::as_str)
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .map(str::to_string)
+            };
+            manifest.name = field("name");
+            manifest.short_name = field("short_name");
+            manifest.start_url = field("start_url");
+            manifest.display = field("display");
+            manifest.theme_color = field("theme_color");
+            manifest.background_color = field("background_color");
+
+            // Manifest icon URLs are relative to the manifest itself
+            let manifest_dir = file.parent().unwrap_or(base);
+            let entries = map.get("icons").and_then(Value::as_array);
+            for entry in entries.into_iter().flatten() {
+                let Some(src) = entry["src"].as_str().filter(|s| !s.trim().is_empty()) else {
+                    continue;
+                };
+                let mut icon = icon_ref(
+                    src,
+                    IconRel::Manifest,
+                    entry["sizes"].as_str(),
+                    entry["type"].as_str(),
+                    entry["purpose"].as_str(),
+                );
+                inspect(&mut icon, manifest_dir);
+                icons.icons.push(icon);
+            }
+        }
+        Ok(_) => manifest.error = Some("not a JSON object".to_string()),
+        Err(e) => manifest.error = Some(e),
+    }
+    icons.manifest = Some(manifest);
+}
+
+/// `file` at the site root, which clients request when a page declares no icon
+pub(crate) fn root_icon(root: &Path, file: &str, rel: IconRel) -> Option<IconRef> {
+    if !root.join(file).is_file() {
+        return None;
+    }
+    let mut icon = icon_ref(&format!("/{}", file), rel, None, None, None);
+    inspect(&mut icon, root);
+    Some(icon)
+}
+
+fn icon_ref(
+    url: &str,
+    rel: IconRel,
+    sizes: Option<&str>,
+    mime: Option<&str>,
+    purpose: Option<&str>,
+) -> IconRef {
+    let non_empty = |v: Option<&str>| {
+        v.map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    IconRef {
+        url: url.trim().to_string(),
+        rel,
+        sizes: sizes
+            .unwrap_or("")
+            .split_whitespace()
+            .map(str::to_ascii_lowercase)
+            .collect(),
+        mime: non_empty(mime).map(|m| m.to_ascii_lowercase()),
+        purpose: non_empty(purpose),
+        exists: None,
+        format: IconFormat::from_url(url),
+        actual_sizes: Vec::new(),
+    }
+}
+
+fn inspect(icon: &mut IconRef, base: &Path) {
+    if is_remote(&icon.url) {
+        return;
+    }
+    let Some(file) = resolve(&icon.url, base) else {
+        icon.exists = Some(false);
+        return;
+    };
+    icon.exists = Some(true);
+    if let Some((format, sizes)) = std::fs::read(&file).ok().and_then(|b| image_info(&b)) {
+        icon.format = Some(format);
+        icon.actual_sizes = sizes;
+    }
+}
+
+/// Format and pixel sizes from an image's header bytes
+fn image_info(bytes: &[u8]) -> Option<(IconFormat, Vec<(u32, u32)>)> {
+    let u16_le =
+        |at: usize| Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?) as u32);
+    let u16_be =
+        |at: usize| Some(u16::from_be_bytes(bytes.get(at..at + 2)?.try_into().ok()?) as u32);
+    let u24_le = |at: usize| {
+        let b = bytes.get(at..at + 3)?;
+        Some(b[0] as u32 | (b[1] as u32) << 8 | (b[2] as u32) << 16)
+    };
+    let u32_be = |at: usize| Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?));
+
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some((IconFormat::Png, vec![(u32_be(16)?, u32_be(20)?)]));
+    }
+    if bytes.starts_with(&[0, 0, 1, 0]) {
+        // ICONDIR, then one 16-byte entry per image; 0 means 256 pixels
+        let count = u16_le(4)? as usize;
+        let sizes = (0..count)
+            .map_while(|i| bytes.get(6 + i * 16..8 + i * 16))
+            .map(|e| {
+                let edge = |b: u8| if b == 0 { 256 } else { b as u32 };
+                (edge(e[0]), edge(e[1]))
+            })
+            .collect();
+        return Some((IconFormat::Ico, sizes));
+    }
+    if bytes.starts_with(b"GIF8") {
+        return Some((IconFormat::Gif, vec![(u16_le(6)?, u16_le(8)?)]));
+    }
+    if bytes.starts_with(&[0xFF, 0xD8]) {
+        // Walk the segments up to the start-of-frame marker
+        let mut at = 2;
+        while let (Some(&0xFF), Some(&marker)) = (bytes.get(at), bytes.get(at + 1)) {
+            let is_frame = matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+            if is_frame {
+                return Some((IconFormat::Jpeg, vec![(u16_be(at + 7)?, u16_be(at + 5)?)]));
+            }
+            at += 2 + u16_be(at + 2)? as usize;
+        }
+        return Some((IconFormat::Jpeg, Vec::new()));
+    }
+    if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP") {
+        let size = match bytes.get(12..16)? {
+            b"VP8X" => Some((u24_le(24)? + 1, u24_le(27)? + 1)),
+            b"VP8 " => Some((u16_le(26)? & 0x3FFF, u16_le(28)? & 0x3FFF)),
+            b"VP8L" => {
+                let bits = u32::from_le_bytes(bytes.get(21..25)?.try_into().ok()?);
+                Some(((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1))
+            }
+            _ => None,
+        };
+        return Some((IconFormat::WebP, size.into_iter().collect()));
+    }
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(1024)]).to_ascii_lowercase();
+    if head.contains("<svg") {
+        return Some((IconFormat::Svg, Vec::new()));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        bytes.extend(width.to_be_bytes());
+        bytes.extend(height.to_be_bytes());
+        bytes.extend([8, 6, 0, 0, 0]);
+        bytes
+    }
+
+    #[test]
+    fn test_icon_declarations() {
+        let html = r##"<html><head>
+<link rel="shortcut icon" href="/favicon.ico">
+<link rel="icon" type="image/png" sizes="32x32" href="/favicon-32x32.png">
+<link rel="apple-touch-icon" sizes="180x180" href="/apple-touch-icon.png">
+<link rel="mask-icon" href="/safari-pinned-tab.svg" color="#5bbad5">
+<link rel="manifest" href="/site.webmanifest">
+<link rel="stylesheet" href="/site.css">
+<meta name="theme-color" content="#ffffff">
+</head><body></body></html>"##;
+        let result = IconAnalyzer::new().analyze(html).unwrap();
+        let page = &result.icons[0];
+
+        assert_eq!(page.icons_of(IconRel::Icon).count(), 2);
+        assert_eq!(page.icons_of(IconRel::AppleTouchIcon).count(), 1);
+        assert_eq!(page.icons_of(IconRel::MaskIcon).count(), 1);
+        assert_eq!(page.manifest_url.as_deref(), Some("/site.webmanifest"));
+        assert_eq!(page.theme_color.as_deref(), Some("#ffffff"));
+        let png = page
+            .icons
+            .iter()
+            .find(|i| i.url == "/favicon-32x32.png")
+            .unwrap();
+        assert_eq!(png.sizes, vec!["32x32"]);
+        assert_eq!(png.mime.as_deref(), Some("image/png"));
+    }
+
+    #[test]
+    fn test_manifest_and_files_from_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("icons")).unwrap();
+        std::fs::create_dir_all(root.join("blog")).unwrap();
+        std::fs::write(root.join("icons/icon-192.png"), png(192, 192)).unwrap();
+        // Declared 512x512 but really 256x256
+        std::fs::write(root.join("icons/icon-512.png"), png(256, 256)).unwrap();
+        let mut ico = vec![0, 0, 1, 0, 2, 0];
+        ico.extend([16, 16, 0, 0, 1, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        ico.extend([0, 0, 0, 0, 1, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        std::fs::write(root.join("favicon.ico"), ico).unwrap();
+        std::fs::write(
+            root.join("icons/site.webmanifest"),
+            r#"{"name": "Acme Bakery", "short_name": "Acme", "display": "standalone",
+                "icons": [{"src": "icon-192.png", "sizes": "192x192", "type": "image/png"},
+                          {"src": "icon-512.png", "sizes": "512x512", "purpose": "any maskable"}]}"#,
+        )
+        .unwrap();
+
+        let html = r#"<html><head><link rel="icon" href="/favicon.ico">
+<link rel="apple-touch-icon" href="/apple-touch-icon.png">
+<link rel="manifest" href="/icons/site.webmanifest"></head></html>"#;
+        let mut result = IconAnalyzer::new().analyze(html).unwrap();
+        let icons = &mut result.icons[0];
+        inspect_icons(icons, &root.join("blog/post.html"));
+
+        let favicon = &icons.icons[0];
+        assert_eq!(favicon.format, Some(IconFormat::Ico));
+        assert_eq!(favicon.actual_sizes, vec![(16, 16), (256, 256)]);
+        assert_eq!(icons.icons[1].exists, Some(false));
+
+        let manifest = icons.manifest.as_ref().unwrap();
+        assert_eq!(manifest.error, None);
+        assert_eq!(manifest.short_name.as_deref(), Some("Acme"));
+        let from_manifest: Vec<_> = icons.icons_of(IconRel::Manifest).collect();
+        assert_eq!(from_manifest.len(), 2);
+        assert!(from_manifest[0].has_size(192));
+        assert_eq!(from_manifest[1].actual_sizes, vec![(256, 256)]);
+        assert!(!from_manifest[1].has_size(512));
+        assert!(from_manifest[1].has_purpose("maskable"));
+    }
+}
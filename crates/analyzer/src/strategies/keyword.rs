@@ -1,36 +1,38 @@
 //! Keyword extraction and scoring analyzer
 
-use crate::{AnalysisResult, AnalyzerError, AnalyzerStrategy, Keyword};
+use crate::{AnalysisResult, AnalyzerError, AnalyzerStrategy, Keyword, KeywordConfig};
 use regex::Regex;
 use scraper::{Html, Selector};
 use std::collections::HashMap;
 
 /// Analyzer that extracts keywords from HTML content
 pub struct KeywordAnalyzer {
-    stop_words: Vec<&'static str>,
+    stop_words: Vec<String>,
     min_word_length: usize,
     max_keywords: usize,
+    max_phrases: usize,
+    phrase_boost: f32,
 }
 
 impl KeywordAnalyzer {
     pub fn new() -> Self {
+        Self::with_config(&KeywordConfig::default())
+    }
+
+    /// Create analyzer from the `[keywords]` config section
+    pub fn with_config(config: &KeywordConfig) -> Self {
+        let mut stop_words = config.stop_words.clone();
+        stop_words.extend(config.extra_stop_words.iter().cloned());
+        for word in &mut stop_words {
+            *word = word.to_lowercase();
+        }
+
         Self {
-            stop_words: vec![
-                "the", "a", "an", "and", "or", "but", "in", "on", "at", "to", "for",
-                "of", "with", "by", "from", "as", "is", "was", "are", "were", "been",
-                "be", "have", "has", "had", "do", "does", "did", "will", "would",
-                "could", "should", "may", "might", "must", "shall", "can", "need",
-                "this", "that", "these", "those", "i", "you", "he", "she", "it",
-                "we", "they", "what", "which", "who", "when", "where", "why", "how",
-                "all", "each", "every", "both", "few", "more", "most", "other",
-                "some", "such", "no", "nor", "not", "only", "own", "same", "so",
-                "than", "too", "very", "just", "also", "now", "here", "there",
-                "then", "once", "any", "about", "into", "through", "during",
-                "before", "after", "above", "below", "between", "under", "again",
-                "further", "because", "if", "else", "until", "while", "our", "your",
-            ],
-            min_word_length: 3,
-            max_keywords: 50,
+            stop_words,
+            min_word_length: config.min_word_length,
+            max_keywords: config.max_keywords,
+            max_phrases: config.max_phrases,
+            phrase_boost: config.phrase_boost,
         }
     }
 
@@ -45,7 +47,7 @@ impl KeywordAnalyzer {
 
         if let Some(body) = document.select(&body_selector).next() {
             for node in body.descendants() {
-                if let Some(element) = node.value().as_element() {
+                if node.value().as_element().is_some() {
                     // Skip script/style elements
                     if script_selector.matches(&scraper::ElementRef::wrap(node).unwrap()) {
                         continue;
@@ -82,7 +84,7 @@ impl KeywordAnalyzer {
             .find_iter(text)
             .map(|m| m.as_str().to_lowercase())
             .filter(|w| {
-                w.len() >= self.min_word_length && !self.stop_words.contains(&w.as_str())
+                w.len() >= self.min_word_length && !self.stop_words.contains(w)
             })
             .collect()
     }
@@ -134,6 +136,13 @@ impl AnalyzerStrategy for KeywordAnalyzer {
 
     fn analyze(&self, content: &str) -> Result<AnalysisResult, AnalyzerError> {
         let text = self.extract_text(content);
+        Ok(self.analyze_text(text))
+    }
+}
+
+impl KeywordAnalyzer {
+    /// Extract keywords from plain text (already stripped of markup)
+    pub fn analyze_text(&self, text: String) -> AnalysisResult {
         let words = self.tokenize(&text);
         let phrases = self.extract_phrases(&text);
 
@@ -158,18 +167,18 @@ impl AnalyzerStrategy for KeywordAnalyzer {
             .map(|(phrase, frequency)| Keyword {
                 word: phrase,
                 frequency,
-                score: frequency as f32 * 5.0, // Boost phrases
+                score: frequency as f32 * self.phrase_boost, // Boost phrases
                 is_phrase: true,
             })
             .collect();
 
         phrase_keywords.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
-        keywords.extend(phrase_keywords.into_iter().take(10));
+        keywords.extend(phrase_keywords.into_iter().take(self.max_phrases));
 
-        Ok(AnalysisResult {
+        AnalysisResult {
             keywords,
             raw_text: Some(text),
             ..Default::default()
-        })
+        }
     }
 }
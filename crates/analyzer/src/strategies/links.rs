@@ -0,0 +1,205 @@
+//! Link analyzer - hyperlinks with their anchor text and `rel` context
+//!
+//! Records every navigational `<a href>` with what the link audit needs:
+//! anchor text (falling back to a linked image's `alt`), `rel` tokens,
+//! `target="_blank"`, and whether the link is an affiliate link or sits in
+//! sponsored or user-generated content.
+
+use super::entities::visible_text;
+use crate::{AnalysisResult, AnalyzerError, AnalyzerStrategy, Link};
+use scraper::{ElementRef, Html, Selector};
+use url::Url;
+
+/// Affiliate network and link shortener hosts
+#[rustfmt::skip]
+const AFFILIATE_HOSTS: [&str; 18] = [
+    "amzn.to", "shareasale.com", "awin1.com", "click.linksynergy.com",
+    "go.skimresources.com", "anrdoezrs.net", "jdoqocy.com", "tkqlhce.com",
+    "dpbolvw.net", "kqzyfj.com", "pxf.io", "sjv.io", "prf.hn", "avantlink.com",
+    "rstyle.me", "shopstyle.it", "tradedoubler.com", "clickbank.net",
+];
+
+/// Query parameters that carry an affiliate id
+const AFFILIATE_PARAMS: [&str; 6] = [
+    "aff",
+    "affid",
+    "aff_id",
+    "affiliate",
+    "affiliate_id",
+    "afftrack",
+];
+
+/// Class/id fragments of sponsored or ad containers
+const SPONSORED_MARKERS: [&str; 4] = ["sponsor", "affiliate", "advert", "partner-link"];
+
+/// Class/id fragments of user-generated content
+const UGC_MARKERS: [&str; 6] = [
+    "comment",
+    "forum",
+    "bbp-reply",
+    "user-content",
+    "ugc",
+    "guestbook",
+];
+
+/// Ancestors searched for sponsored and user content containers
+const MAX_CONTEXT_DEPTH: usize = 8;
+
+/// Analyzer for hyperlinks
+pub struct LinkAnalyzer;
+
+impl LinkAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn anchor(link: ElementRef) -> String {
+        let text = collapse(&visible_text(link));
+        if !text.is_empty() {
+            return text;
+        }
+        let images = Selector::parse("img[alt]").unwrap();
+        link.select(&images)
+            .filter_map(|img| img.value().attr("alt"))
+            .map(collapse)
+            .find(|alt| !alt.is_empty())
+            .or_else(|| link.value().attr("aria-label").map(collapse))
+            .or_else(|| link.value().attr("title").map(collapse))
+            .unwrap_or_default()
+    }
+}
+
+impl Default for LinkAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnalyzerStrategy for LinkAnalyzer {
+    fn name(&self) -> &'static str {
+        "link_analyzer"
+    }
+
+    fn analyze(&self, content: &str) -> Result<AnalysisResult, AnalyzerError> {
+        let document = Html::parse_document(content);
+        let selector = Selector::parse("a[href]").unwrap();
+
+        let links = document
+            .select(&selector)
+            .filter_map(|link| {
+                let attrs = link.value();
+                let href = attrs.attr("href")?.trim();
+                if !is_navigational(href) {
+                    return None;
+                }
+                let rel: Vec<String> = attrs
+                    .attr("rel")
+                    .unwrap_or("")
+                    .split_whitespace()
+                    .map(str::to_lowercase)
+                    .collect();
+
+                Some(Link {
+                    href: href.to_string(),
+                    anchor: Self::anchor(link),
+                    new_tab: attrs
+                        .attr("target")
+                        .is_some_and(|t| t.eq_ignore_ascii_case("_blank")),
+                    sponsored: is_affiliate_url(href)
+                        || attrs.attr("data-affiliate").is_some()
+                        || in_context(link, &SPONSORED_MARKERS),
+                    user_generated: in_context(link, &UGC_MARKERS),
+                    rel,
+                    page: None,
+                })
+            })
+            .collect();
+
+        Ok(AnalysisResult {
+            links,
+            ..Default::default()
+        })
+    }
+}
+
+/// Links to other documents, not fragments, scripts, mail or phone
+fn is_navigational(href: &str) -> bool {
+    let lower = href.to_ascii_lowercase();
+    !(href.is_empty()
+        || href.starts_with('#')
+        || ["javascript:", "mailto:", "tel:", "sms:", "data:"]
+            .iter()
+            .any(|scheme| lower.starts_with(scheme)))
+}
+
+fn is_affiliate_url(href: &str) -> bool {
+    let Ok(url) = Url::parse(href) else {
+        return false;
+    };
+    let host = url.host_str().unwrap_or("").to_ascii_lowercase();
+    if AFFILIATE_HOSTS
+        .iter()
+        .any(|h| host == *h || host.ends_with(&format!(".{}", h)))
+    {
+        return true;
+    }
+
+    url.query_pairs().any(|(key, value)| {
+        let key = key.to_ascii_lowercase();
+        AFFILIATE_PARAMS.contains(&key.as_str())
+            // Amazon Associates tag
+            || (key == "tag" && host.contains("amazon."))
+            || (key == "utm_medium" && matches!(value.to_ascii_lowercase().as_str(), "affiliate" | "sponsored" | "paid"))
+    })
+}
+
+/// Whether the link or a near ancestor carries one of `markers` in its
+/// class, id or itemtype
+fn in_context(link: ElementRef, markers: &[&str]) -> bool {
+    std::iter::once(link)
+        .chain(link.ancestors().filter_map(ElementRef::wrap))
+        .take(MAX_CONTEXT_DEPTH)
+        .any(|el| {
+            let attrs = el.value();
+            ["class", "id", "itemtype"].iter().any(|name| {
+                attrs.attr(name).is_some_and(|value| {
+                    let value = value.to_ascii_lowercase();
+                    markers.iter().any(|m| value.contains(m))
+                })
+            })
+        })
+}
+
+fn collapse(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_context() {
+        let html = r##"<html><body>
+<p>Read our <a href="/guides/drains">drain guide</a> or <a href="#top">back to top</a>.</p>
+<a href="https://amzn.to/3xYz" target="_blank">Buy the plunger</a>
+<a href="https://www.amazon.com/dp/B00?tag=acme-20" rel="sponsored noopener">Snake</a>
+<a href="https://vendor.example/" class="ad-link"><img src="x.png" alt="Vendor logo"></a>
+<div class="sponsored-box"><a href="https://partner.example/">Partner deal</a></div>
+<ol class="comment-list"><li><a href="http://spam.example/" rel="nofollow">my site</a></li></ol>
+<a href="mailto:hi@acme.com">Email</a>
+</body></html>"##;
+        let links = LinkAnalyzer::new().analyze(html).unwrap().links;
+
+        assert_eq!(links.len(), 6);
+        assert_eq!(links[0].anchor, "drain guide");
+        assert!(!links[0].sponsored && !links[0].user_generated);
+
+        assert!(links[1].sponsored && links[1].new_tab);
+        assert!(links[2].sponsored && links[2].has_rel("sponsored"));
+        assert_eq!(links[3].anchor, "Vendor logo");
+        assert!(!links[3].sponsored);
+        assert!(links[4].sponsored);
+        assert!(links[5].user_generated && links[5].has_rel("nofollow"));
+    }
+}
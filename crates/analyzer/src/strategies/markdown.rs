@@ -0,0 +1,463 @@
+//! Markdown/MDX content analyzer for static site generators
+//!
+//! Reads YAML (`---`) or TOML (`+++`) front matter as used by Hugo, Jekyll,
+//! Eleventy and Docusaurus, and renders the body to plain text so keyword
+//! extraction works on content before the site is built.
+
+use super::article::normalize_date;
+use crate::head_tags::HeadCollector;
+use crate::{
+    AnalysisResult, AnalyzerError, AnalyzerStrategy, ArticleMeta, Author, DateSource, FrontMatter,
+    KeywordAnalyzer, KeywordConfig,
+};
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag};
+use regex::Regex;
+use serde_json::Value;
+use std::path::Path;
+
+/// Repository documents that are not site content
+const NON_CONTENT_FILES: [&str; 6] = [
+    "readme",
+    "changelog",
+    "contributing",
+    "license",
+    "code_of_conduct",
+    "security",
+];
+
+/// Analyzer for `.md`, `.markdown` and `.mdx` sources
+pub struct MarkdownAnalyzer {
+    keywords: KeywordAnalyzer,
+    mdx_statement: Regex,
+    template_tag: Regex,
+}
+
+/// Body text and structural signals of a rendered Markdown document
+#[derive(Default)]
+struct RenderedBody {
+    text: String,
+    first_paragraph: Option<String>,
+    h1_count: u32,
+    img_without_alt: u32,
+}
+
+impl MarkdownAnalyzer {
+    pub fn new() -> Self {
+        Self::with_config(&KeywordConfig::default())
+    }
+
+    /// Create analyzer scoring body text per the `[keywords]` config section
+    pub fn with_config(keywords: &KeywordConfig) -> Self {
+        Self {
+            keywords: KeywordAnalyzer::with_config(keywords),
+            mdx_statement: Regex::new(r"(?m)^(?:import|export)\s.*$").unwrap(),
+            // Hugo shortcodes, Liquid/Nunjucks tags and output expressions
+            template_tag: Regex::new(r"(?s)\{\{[<%].*?[%>]\}\}|\{%.*?%\}|\{\{.*?\}\}").unwrap(),
+        }
+    }
+
+    /// Split `content` into its parsed front matter (with byte offset) and body
+    fn split_front_matter(content: &str) -> (Option<(Value, usize)>, &str) {
+        let content_start = content.len() - content.trim_start_matches('\u{feff}').len();
+        let rest = &content[content_start..];
+
+        let (fence, is_toml) = if rest.starts_with("---") {
+            ("---", false)
+        } else if rest.starts_with("+++") {
+            ("+++", true)
+        } else {
+            return (None, content);
+        };
+
+        let Some(first_newline) = rest.find('\n') else {
+            return (None, content);
+        };
+        let matter_start = first_newline + 1;
+
+        // Closing fence on its own line (YAML also allows `...`)
+        let mut offset = matter_start;
+        let mut close = None;
+        for line in rest[matter_start..].split_inclusive('\n') {
+            let trimmed = line.trim_end();
+            if trimmed == fence || (!is_toml && trimmed == "...") {
+                close = Some((offset, offset + line.len()));
+                break;
+            }
+            offset += line.len();
+        }
+        let Some((matter_end, body_start)) = close else {
+            return (None, content);
+        };
+
+        let raw = &rest[matter_start..matter_end];
+        let parsed = if is_toml {
+            toml::from_str::<toml::Value>(raw)
+                .map(toml_to_json)
+                .map_err(|e| e.to_string())
+        } else {
+            serde_yaml::from_str::<Value>(raw).map_err(|e| e.to_string())
+        };
+
+        let body = &rest[body_start..];
+        match parsed {
+            Ok(value) => (Some((value, content_start + matter_start)), body),
+            Err(e) => {
+                tracing::warn!("Ignoring malformed front matter: {}", e);
+                (None, body)
+            }
+        }
+    }
+
+    fn front_matter_fields(matter: &Value) -> FrontMatter {
+        let text = |key: &str| {
+            matter.get(key).and_then(|v| match v {
+                Value::String(s) => Some(s.clone()),
+                Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            })
+        };
+
+        let tags = match matter.get("tags") {
+            Some(Value::Array(items)) => items
+                .iter()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect(),
+            // Jekyll allows a space-separated string, others use commas
+            Some(Value::String(s)) if s.contains(',') => {
+                s.split(',').map(|t| t.trim().to_string()).collect()
+            }
+            Some(Value::String(s)) => s.split_whitespace().map(String::from).collect(),
+            _ => Vec::new(),
+        };
+
+        let draft = matter
+            .get("draft")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+            || matter.get("published").and_then(Value::as_bool) == Some(false);
+
+        FrontMatter {
+            title: text("title"),
+            description: text("description")
+                .or_else(|| text("summary"))
+                .or_else(|| text("excerpt")),
+            tags,
+            date: text("date"),
+            draft,
+            slug: text("slug"),
+        }
+    }
+
+    /// Author, dates and category of a post; `None` for pages without them
+    fn front_matter_article(matter: &Value, tags: &[String]) -> Option<ArticleMeta> {
+        let date = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|k| matter.get(*k).and_then(Value::as_str))
+                .and_then(normalize_date)
+        };
+        let author = |value: &Value| match value {
+            Value::String(name) => Some(Author {
+                name: name.clone(),
+                url: None,
+            }),
+            Value::Object(_) => Some(Author {
+                name: value.get("name")?.as_str()?.to_string(),
+                url: value.get("url").and_then(Value::as_str).map(String::from),
+            }),
+            _ => None,
+        };
+
+        let authors = match matter.get("authors").or_else(|| matter.get("author")) {
+            Some(Value::Array(items)) => items.iter().filter_map(author).collect(),
+            Some(value) => author(value).into_iter().collect(),
+            None => Vec::new(),
+        };
+        let section = match matter.get("categories").or_else(|| matter.get("category")) {
+            Some(Value::Array(items)) => items.first().and_then(Value::as_str).map(String::from),
+            Some(Value::String(s)) => Some(s.clone()),
+            _ => matter.get("section").and_then(Value::as_str).map(String::from),
+        };
+
+        let mut article = ArticleMeta {
+            authors,
+            published: date(&["date", "published", "pubDate"]),
+            modified: date(&["lastmod", "last_modified_at", "updated", "modified"]),
+            date_source: None,
+            section,
+            tags: Vec::new(),
+        };
+        if article.is_empty() {
+            return None;
+        }
+        if article.published.is_some() || article.modified.is_some() {
+            article.date_source = Some(DateSource::Content);
+        }
+        article.tags = tags.to_vec();
+        Some(article)
+    }
+
+    /// Byte offset of the line declaring `key` in the front matter
+    fn key_offset(content: &str, matter_offset: usize, key: &str) -> usize {
+        let mut offset = matter_offset;
+        for line in content[matter_offset..].split_inclusive('\n') {
+            let trimmed = line.trim_start();
+            if let Some(rest) = trimmed.strip_prefix(key) {
+                if rest.trim_start().starts_with(':') || rest.trim_start().starts_with('=') {
+                    return offset;
+                }
+            }
+            offset += line.len();
+        }
+        matter_offset
+    }
+
+    fn render_body(&self, body: &str) -> RenderedBody {
+        let cleaned = self.mdx_statement.replace_all(body, "");
+        let cleaned = self.template_tag.replace_all(&cleaned, "");
+
+        let mut rendered = RenderedBody::default();
+        let mut paragraph: Option<String> = None;
+        let mut image_alt: Option<String> = None;
+
+        for event in Parser::new_ext(&cleaned, Options::all()) {
+            match event {
+                Event::Start(Tag::Heading(HeadingLevel::H1, ..)) => rendered.h1_count += 1,
+                Event::Start(Tag::Paragraph) if rendered.first_paragraph.is_none() => {
+                    paragraph = Some(String::new());
+                }
+                Event::Start(Tag::Image(..)) => image_alt = Some(String::new()),
+                Event::End(Tag::Image(..)) => {
+                    let alt = image_alt.take().unwrap_or_default();
+                    if alt.trim().is_empty() {
+                        rendered.img_without_alt += 1;
+                    }
+                }
+                Event::End(Tag::Paragraph) => {
+                    if let Some(p) = paragraph.take() {
+                        let p = p.trim().to_string();
+                        if !p.is_empty() {
+                            rendered.first_paragraph = Some(p);
+                        }
+                    }
+                    rendered.text.push('\n');
+                }
+                Event::End(Tag::Heading(..) | Tag::Item | Tag::TableCell) => {
+                    rendered.text.push('\n');
+                }
+                Event::Text(t) | Event::Code(t) => {
+                    if let Some(alt) = image_alt.as_mut() {
+                        alt.push_str(&t);
+                        continue;
+                    }
+                    if let Some(p) = paragraph.as_mut() {
+                        p.push_str(&t);
+                    }
+                    rendered.text.push_str(&t);
+                }
+                Event::SoftBreak | Event::HardBreak => {
+                    if let Some(p) = paragraph.as_mut() {
+                        p.push(' ');
+                    }
+                    rendered.text.push(' ');
+                }
+                _ => {}
+            }
+        }
+
+        rendered
+    }
+}
+
+impl Default for MarkdownAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnalyzerStrategy for MarkdownAnalyzer {
+    fn name(&self) -> &'static str {
+        "markdown_analyzer"
+    }
+
+    fn accepts(&self, path: &Path) -> bool {
+        let is_markdown = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| matches!(e, "md" | "markdown" | "mdx"))
+            .unwrap_or(false);
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+
+        is_markdown && !NON_CONTENT_FILES.contains(&stem.as_str())
+    }
+
+    fn analyze(&self, content: &str) -> Result<AnalysisResult, AnalyzerError> {
+        let (matter, body) = Self::split_front_matter(content);
+        let rendered = self.render_body(body);
+
+        let mut collector = HeadCollector::new(content);
+        let mut article = None;
+        let front_matter = matter.map(|(value, offset)| {
+            let fields = Self::front_matter_fields(&value);
+            article = Self::front_matter_article(&value, &fields.tags);
+            if let Some(ref title) = fields.title {
+                let at = Self::key_offset(content, offset, "title");
+                collector.record("title".to_string(), Some(title.clone()), at);
+            }
+            if let Some(ref description) = fields.description {
+                let key = ["description", "summary", "excerpt"]
+                    .into_iter()
+                    .find(|k| value.get(k).is_some())
+                    .unwrap_or("description");
+                let at = Self::key_offset(content, offset, key);
+                collector.record(
+                    "meta[name=description]".to_string(),
+                    Some(description.clone()),
+                    at,
+                );
+            }
+            if !fields.tags.is_empty() {
+                let at = Self::key_offset(content, offset, "tags");
+                collector.record(
+                    "meta[name=keywords]".to_string(),
+                    Some(fields.tags.join(", ")),
+                    at,
+                );
+            }
+            fields
+        });
+
+        let mut seo = collector.seo;
+        seo.h1_count = rendered.h1_count;
+        seo.img_without_alt = rendered.img_without_alt;
+
+        // Title and description count towards keywords, as they do for HTML
+        let mut text = rendered.text;
+        if let Some(ref fm) = front_matter {
+            for extra in [&fm.title, &fm.description].into_iter().flatten() {
+                text.push(' ');
+                text.push_str(extra);
+            }
+        }
+
+        let mut result = self.keywords.analyze_text(text);
+        result.existing_seo = seo;
+        result.content_summary = front_matter
+            .as_ref()
+            .and_then(|fm| fm.description.clone())
+            .or(rendered.first_paragraph);
+        result.front_matter = front_matter;
+        result.article = article;
+
+        Ok(result)
+    }
+}
+
+/// Convert TOML front matter to JSON, keeping dates as their TOML text
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::from(i),
+        toml::Value::Float(f) => Value::from(f),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(d) => Value::String(d.to_string()),
+        toml::Value::Array(items) => Value::Array(items.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(k, v)| (k, toml_to_json(v)))
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yaml_front_matter() {
+        let source = r#"---
+title: Fixing a Leaky Faucet
+description: Step-by-step guide to repairing a dripping kitchen faucet.
+tags: [plumbing, diy]
+date: 2024-03-02
+slug: leaky-faucet
+---
+
+# Fixing a Leaky Faucet
+
+A dripping faucet wastes water. Plumbing repairs like this take twenty minutes.
+
+{{< youtube abc123 >}}
+
+![](/img/faucet.jpg)
+"#;
+        let result = MarkdownAnalyzer::new().analyze(source).unwrap();
+        let fm = result.front_matter.as_ref().unwrap();
+
+        assert_eq!(fm.title.as_deref(), Some("Fixing a Leaky Faucet"));
+        assert_eq!(fm.tags, vec!["plumbing", "diy"]);
+        assert_eq!(fm.date.as_deref(), Some("2024-03-02"));
+        assert_eq!(fm.slug.as_deref(), Some("leaky-faucet"));
+        assert!(!fm.draft);
+
+        let article = result.article.as_ref().unwrap();
+        assert_eq!(article.published.as_deref(), Some("2024-03-02"));
+        assert_eq!(article.tags, vec!["plumbing", "diy"]);
+
+        let seo = &result.existing_seo;
+        assert!(seo.has_title && seo.has_description);
+        assert_eq!(seo.h1_count, 1);
+        assert_eq!(seo.img_without_alt, 1);
+
+        let description = seo
+            .sources
+            .iter()
+            .find(|s| s.element == "meta[name=description]")
+            .unwrap();
+        assert_eq!(description.line, 3);
+
+        let text = result.raw_text.as_deref().unwrap();
+        assert!(!text.contains("youtube"));
+        assert!(result.keywords.iter().any(|k| k.word == "faucet"));
+    }
+
+    #[test]
+    fn test_toml_front_matter_and_mdx() {
+        let source = r#"+++
+title = "Release notes"
+date = 2024-05-01T10:00:00Z
+draft = true
+tags = "release, changelog"
++++
+import Tabs from '@theme/Tabs';
+
+Our **latest release** ships faster builds.
+"#;
+        let result = MarkdownAnalyzer::new().analyze(source).unwrap();
+        let fm = result.front_matter.unwrap();
+
+        assert!(fm.draft);
+        assert_eq!(fm.date.as_deref(), Some("2024-05-01T10:00:00Z"));
+        assert_eq!(fm.tags, vec!["release", "changelog"]);
+        assert_eq!(
+            result.content_summary.as_deref(),
+            Some("Our latest release ships faster builds.")
+        );
+        assert!(!result.raw_text.unwrap().contains("import"));
+    }
+
+    #[test]
+    fn test_accepts_content_only() {
+        let analyzer = MarkdownAnalyzer::new();
+
+        assert!(analyzer.accepts(Path::new("content/posts/hello.md")));
+        assert!(analyzer.accepts(Path::new("docs/intro.mdx")));
+        assert!(!analyzer.accepts(Path::new("README.md")));
+        assert!(!analyzer.accepts(Path::new("index.html")));
+    }
+}
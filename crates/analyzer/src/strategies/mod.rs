@@ -3,7 +3,44 @@
 mod keyword;
 mod business;
 mod seo_audit;
+mod nextjs;
+mod vue;
+mod svelte;
+mod markdown;
+mod spa;
+mod entities;
+mod faq;
+mod howto;
+mod product;
+mod article;
+mod performance;
+mod links;
+mod snippet;
+mod nap;
+mod icons;
+mod depth;
 
 pub use keyword::KeywordAnalyzer;
 pub use business::BusinessTypeAnalyzer;
 pub use seo_audit::SeoAuditAnalyzer;
+pub use nextjs::NextMetadataAnalyzer;
+pub use vue::{NuxtConfigAnalyzer, VueHeadAnalyzer};
+pub use svelte::SvelteHeadAnalyzer;
+pub use markdown::MarkdownAnalyzer;
+pub use spa::SpaShellAnalyzer;
+pub use entities::EntityAnalyzer;
+pub use faq::FaqAnalyzer;
+pub use howto::HowToAnalyzer;
+pub use product::ProductAnalyzer;
+pub use article::ArticleAnalyzer;
+pub use performance::PerformanceAnalyzer;
+pub use links::LinkAnalyzer;
+pub use snippet::SnippetAnalyzer;
+pub use nap::NapAnalyzer;
+pub use icons::IconAnalyzer;
+pub use depth::ContentDepthAnalyzer;
+pub(crate) use entities::{calling_code, normalize_phone};
+pub(crate) use article::fill_missing_dates;
+pub(crate) use performance::measure_assets;
+pub(crate) use icons::{inspect_icons, root_icon};
+pub(crate) use depth::infer_role;
@@ -0,0 +1,347 @@
+//! NAP analyzer - every statement of the business name, address and phone
+//!
+//! Unlike the entity analyzer, which keeps the best value of each kind, this
+//! records each mention as written so `NapAudit` can compare them across
+//! pages: visible text (including the copyright line for the name), `tel:`
+//! links, and business objects in JSON-LD and microdata.
+
+use super::entities::{collapse, country_code, microdata_value, visible_text, EntityAnalyzer};
+use crate::{
+    AnalysisResult, AnalyzerError, AnalyzerStrategy, ContactEntities, DetectedEntity, EntityConfig,
+    NapMention, NapSource, PostalAddress,
+};
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use serde_json::Value;
+
+/// Schema types that name the business itself
+const BUSINESS_TYPE_HINTS: [&str; 4] = ["Organization", "Business", "Restaurant", "Store"];
+
+/// Analyzer for name, address and phone mentions
+pub struct NapAnalyzer {
+    entities: EntityAnalyzer,
+    copyright: Regex,
+}
+
+impl NapAnalyzer {
+    pub fn new() -> Self {
+        Self::with_config(&EntityConfig::default())
+    }
+
+    /// Create analyzer from the `[entities]` section
+    pub fn with_config(config: &EntityConfig) -> Self {
+        Self {
+            entities: EntityAnalyzer::with_config(config),
+            copyright: Regex::new(
+                r"(?m)(?:©|\(c\)|Copyright)\s*(?:©\s*)?(?:\d{4}(?:\s*[-–]\s*\d{2,4})?\s*)?(?P<name>[A-Z][A-Za-z0-9&'’. -]{1,60}?)\s*(?:[.,|]\s|All rights|$)",
+            )
+            .unwrap(),
+        }
+    }
+
+    fn json_ld(&self, document: &Html, country: &str, mentions: &mut Vec<NapMention>) {
+        let scripts = Selector::parse(r#"script[type="application/ld+json"]"#).unwrap();
+        for script in document.select(&scripts) {
+            let body: String = script.text().collect();
+            if let Ok(value) = serde_json::from_str::<Value>(&body) {
+                self.collect_json_businesses(&value, country, mentions);
+            }
+        }
+    }
+
+    /// Name, phone and address of business objects, including those in an `@graph`
+    fn collect_json_businesses(&self, value: &Value, country: &str, out: &mut Vec<NapMention>) {
+        match value {
+            Value::Array(items) => items
+                .iter()
+                .for_each(|v| self.collect_json_businesses(v, country, out)),
+            Value::Object(map) => {
+                let types: Vec<&str> = match map.get("@type") {
+                    Some(Value::String(t)) => vec![t.as_str()],
+                    Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+                    _ => Vec::new(),
+                };
+                let is_business = !types.is_empty()
+                    && (types.iter().any(|t| is_business_type(t))
+                        || map.contains_key("telephone")
+                        || map.get("address").is_some_and(Value::is_object));
+                if is_business {
+                    if let Some(name) = map.get("name").and_then(Value::as_str) {
+                        out.extend(NapMention::name(name, NapSource::JsonLd));
+                    }
+                    if let Some(phone) = map.get("telephone").and_then(Value::as_str) {
+                        out.extend(NapMention::phone(phone, country, NapSource::JsonLd));
+                    }
+                    match map.get("address") {
+                        Some(address @ Value::Object(_)) => {
+                            if let Some(parsed) = json_address(address) {
+                                out.push(NapMention::address(
+                                    &format_address(&parsed),
+                                    parsed,
+                                    NapSource::JsonLd,
+                                ));
+                            }
+                        }
+                        Some(Value::String(address)) => {
+                            out.extend(self.entities.find_addresses(address).into_iter().map(
+                                |(raw, parsed)| {
+                                    NapMention::address(&raw, parsed, NapSource::JsonLd)
+                                },
+                            ))
+                        }
+                        _ => {}
+                    }
+                }
+                map.values()
+                    .for_each(|v| self.collect_json_businesses(v, country, out));
+            }
+            _ => {}
+        }
+    }
+
+    fn microdata(&self, document: &Html, country: &str, mentions: &mut Vec<NapMention>) {
+        let scopes = Selector::parse("[itemscope][itemtype]").unwrap();
+        let prop = |name: &str| Selector::parse(&format!("[itemprop={}]", name)).unwrap();
+
+        for scope in document.select(&scopes) {
+            let itemtype = scope.value().attr("itemtype").unwrap_or("");
+            // Properties of this item, not of items nested in it
+            let own = |name: &str| {
+                scope
+                    .select(&prop(name))
+                    .filter(|el| nearest_scope(*el) == Some(scope))
+                    .collect::<Vec<_>>()
+            };
+            let phones = own("telephone");
+            let addresses = own("address");
+            if !is_business_type(itemtype) && phones.is_empty() && addresses.is_empty() {
+                continue;
+            }
+
+            for name in own("name") {
+                mentions.extend(NapMention::name(
+                    &microdata_value(name),
+                    NapSource::Microdata,
+                ));
+            }
+            for phone in phones {
+                let value = microdata_value(phone);
+                mentions.extend(NapMention::phone(&value, country, NapSource::Microdata));
+            }
+            for address in addresses {
+                let field = |name: &str| {
+                    address
+                        .select(&prop(name))
+                        .next()
+                        .map(|e| collapse(&microdata_value(e)))
+                        .filter(|v| !v.is_empty())
+                };
+                let (Some(street), Some(city)) = (field("streetAddress"), field("addressLocality"))
+                else {
+                    continue;
+                };
+                let parsed = PostalAddress {
+                    street,
+                    city,
+                    region: field("addressRegion"),
+                    postal_code: field("postalCode").unwrap_or_default(),
+                    country: field("addressCountry").and_then(|c| country_code(&c)),
+                };
+                mentions.push(NapMention::address(
+                    &format_address(&parsed),
+                    parsed,
+                    NapSource::Microdata,
+                ));
+            }
+        }
+    }
+}
+
+impl Default for NapAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnalyzerStrategy for NapAnalyzer {
+    fn name(&self) -> &'static str {
+        "nap_analyzer"
+    }
+
+    fn analyze(&self, content: &str) -> Result<AnalysisResult, AnalyzerError> {
+        let document = Html::parse_document(content);
+        let body_selector = Selector::parse("body").unwrap();
+        let Some(body) = document.select(&body_selector).next() else {
+            return Ok(AnalysisResult::default());
+        };
+        let text = visible_text(body);
+
+        let addresses = self.entities.find_addresses(&text);
+        let found = ContactEntities {
+            addresses: addresses
+                .iter()
+                .map(|(_, address)| DetectedEntity {
+                    value: address.clone(),
+                    confidence: 1.0,
+                    page: None,
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let country = self.entities.page_country(&document, &found);
+
+        let mut mentions = Vec::new();
+        for caps in self.copyright.captures_iter(&text) {
+            mentions.extend(NapMention::name(&caps["name"], NapSource::Text));
+        }
+        for (raw, address) in addresses {
+            mentions.push(NapMention::address(&raw, address, NapSource::Text));
+        }
+        for (raw, _) in self.entities.find_phones(&text, &country) {
+            mentions.extend(NapMention::phone(&raw, &country, NapSource::Text));
+        }
+
+        let links = Selector::parse("a[href]").unwrap();
+        for link in document.select(&links) {
+            let href = link
+                .value()
+                .attr("href")
+                .unwrap_or("")
+                .trim()
+                .to_lowercase();
+            if let Some(number) = href.strip_prefix("tel:") {
+                let number = number
+                    .split([';', ','])
+                    .next()
+                    .unwrap_or("")
+                    .replace("%20", " ");
+                mentions.extend(NapMention::phone(&number, &country, NapSource::TelLink));
+            }
+        }
+
+        self.json_ld(&document, &country, &mut mentions);
+        self.microdata(&document, &country, &mut mentions);
+
+        Ok(AnalysisResult {
+            nap: mentions,
+            ..Default::default()
+        })
+    }
+}
+
+fn json_address(json: &Value) -> Option<PostalAddress> {
+    let field = |name: &str| json[name].as_str().map(collapse).filter(|v| !v.is_empty());
+    let country = match &json["addressCountry"] {
+        Value::String(c) => country_code(c),
+        country => country["name"].as_str().and_then(country_code),
+    };
+    Some(PostalAddress {
+        street: field("streetAddress")?,
+        city: field("addressLocality")?,
+        region: field("addressRegion"),
+        postal_code: field("postalCode").unwrap_or_default(),
+        country,
+    })
+}
+
+/// `1420 Larimer St, Denver, CO 80202`
+fn format_address(address: &PostalAddress) -> String {
+    let region = match &address.region {
+        Some(region) => format!("{} {}", region, address.postal_code),
+        None => address.postal_code.clone(),
+    };
+    format!("{}, {}, {}", address.street, address.city, region.trim())
+}
+
+fn is_business_type(itemtype: &str) -> bool {
+    BUSINESS_TYPE_HINTS
+        .iter()
+        .any(|hint| itemtype.contains(hint))
+}
+
+fn nearest_scope(element: ElementRef) -> Option<ElementRef> {
+    element
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .find(|a| a.value().attr("itemscope").is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NapField;
+
+    #[test]
+    fn test_mentions_from_every_source() {
+        let html = r#"<html lang="en-US"><head>
+<script type="application/ld+json">{"@context": "https://schema.org", "@graph": [
+  {"@type": "Plumber", "name": "Acme Plumbing LLC", "telephone": "+1-303-555-0142",
+   "address": {"@type": "PostalAddress", "streetAddress": "1420 Larimer Street",
+     "addressLocality": "Denver", "addressRegion": "CO", "postalCode": "80202"}},
+  {"@type": "WebPage", "name": "Drain cleaning"}
+]}</script>
+</head><body>
+<main><h1>Drain cleaning</h1><p>Call <a href="tel:303-555-0142">(303) 555-0142</a> today.</p></main>
+<footer>
+  <p>1420 Larimer St, Denver, CO 80202</p>
+  <p>© 2024 Acme Plumbing. All rights reserved.</p>
+</footer>
+</body></html>"#;
+        let nap = NapAnalyzer::new().analyze(html).unwrap().nap;
+        let of = |field, source| -> Vec<&str> {
+            nap.iter()
+                .filter(|m| m.field == field && m.source == source)
+                .map(|m| m.value.as_str())
+                .collect()
+        };
+
+        assert_eq!(
+            of(NapField::Name, NapSource::JsonLd),
+            vec!["Acme Plumbing LLC"]
+        );
+        assert_eq!(of(NapField::Name, NapSource::Text), vec!["Acme Plumbing"]);
+        assert_eq!(
+            of(NapField::Phone, NapSource::TelLink),
+            vec!["303-555-0142"]
+        );
+        assert_eq!(of(NapField::Phone, NapSource::Text), vec!["(303) 555-0142"]);
+        assert_eq!(
+            of(NapField::Address, NapSource::JsonLd),
+            vec!["1420 Larimer Street, Denver, CO 80202"]
+        );
+        assert_eq!(
+            of(NapField::Address, NapSource::Text),
+            vec!["1420 Larimer St, Denver, CO 80202"]
+        );
+        assert!(nap
+            .iter()
+            .filter(|m| m.field == NapField::Phone)
+            .all(|m| m.key == "+13035550142"));
+    }
+
+    #[test]
+    fn test_microdata_business() {
+        let html = r#"<html><body>
+<div itemscope itemtype="https://schema.org/Dentist">
+  <span itemprop="name">Bright Smiles Dental</span>
+  <span itemprop="telephone">020 7946 0018</span>
+  <div itemprop="address" itemscope itemtype="https://schema.org/PostalAddress">
+    <span itemprop="streetAddress">12 Baker Street</span>
+    <span itemprop="addressLocality">London</span>
+    <span itemprop="postalCode">NW1 6XE</span>
+    <meta itemprop="addressCountry" content="GB">
+  </div>
+</div>
+</body></html>"#;
+        let nap = NapAnalyzer::new().analyze(html).unwrap().nap;
+        let microdata: Vec<_> = nap
+            .iter()
+            .filter(|m| m.source == NapSource::Microdata)
+            .collect();
+
+        assert_eq!(microdata.len(), 3);
+        assert_eq!(microdata[0].value, "Bright Smiles Dental");
+        // Nested PostalAddress has no `name` of its own to report
+        assert_eq!(microdata[2].value, "12 Baker Street, London, NW1 6XE");
+    }
+}
@@ -0,0 +1,324 @@
+//! Next.js source analyzer - reads metadata without building the site
+//!
+//! Understands the App Router `export const metadata` object and the
+//! literal returned from `generateMetadata`, plus `<Head>` blocks used by
+//! the Pages Router (`next/head` and `next/document`).
+
+use crate::head_tags::HeadCollector;
+use crate::js_object::{find_closing, parse_object_at, JsValue};
+use crate::{AnalysisResult, AnalyzerError, AnalyzerStrategy};
+use regex::Regex;
+use std::path::{Component, Path};
+
+/// Analyzer for Next.js `layout.tsx`, `page.tsx` and `pages/` sources
+pub struct NextMetadataAnalyzer {
+    metadata_export: Regex,
+    generate_metadata: Regex,
+    return_object: Regex,
+    head_block: Regex,
+    next_import: Regex,
+    json_ld: Regex,
+}
+
+impl NextMetadataAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            metadata_export: Regex::new(r"export\s+const\s+metadata\s*(?::\s*[\w.<>]+\s*)?=\s*\{")
+                .unwrap(),
+            generate_metadata: Regex::new(
+                r"(?:function\s+generateMetadata\s*(?:<[^>]*>)?\s*\(|const\s+generateMetadata\s*(?::\s*[\w.<>]+\s*)?=)",
+            )
+            .unwrap(),
+            return_object: Regex::new(r"return\s*\(?\s*\{").unwrap(),
+            head_block: Regex::new(r"(?s)<Head(?:\s[^>]*)?>(.*?)</Head>").unwrap(),
+            next_import: Regex::new(r#"from\s+['"]next(?:/head|/document)?['"]"#).unwrap(),
+            json_ld: Regex::new(r"application/ld\+json").unwrap(),
+        }
+    }
+
+    const SOURCE_EXTENSIONS: [&'static str; 4] = ["tsx", "jsx", "ts", "js"];
+
+    /// App Router `layout`/`page` files and Pages Router pages (excluding API routes)
+    fn is_next_source(path: &Path) -> bool {
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        if !Self::SOURCE_EXTENSIONS.contains(&ext) {
+            return false;
+        }
+
+        let dirs: Vec<&str> = path
+            .parent()
+            .map(|p| {
+                p.components()
+                    .filter_map(|c| match c {
+                        Component::Normal(s) => s.to_str(),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+        let in_app = dirs.contains(&"app") && matches!(stem, "layout" | "page");
+        let in_pages = dirs
+            .iter()
+            .position(|d| *d == "pages")
+            .map(|i| dirs.get(i + 1) != Some(&"api"))
+            .unwrap_or(false);
+
+        in_app || in_pages
+    }
+
+    /// Object literal assigned to `export const metadata`
+    fn static_metadata(&self, source: &str) -> Option<JsValue> {
+        let m = self.metadata_export.find(source)?;
+        parse_object_at(source, m.end() - 1).map(|(value, _)| value)
+    }
+
+    /// Object literals returned from `generateMetadata`
+    fn generated_metadata(&self, source: &str) -> Vec<JsValue> {
+        let Some(m) = self.generate_metadata.find(source) else {
+            return Vec::new();
+        };
+
+        // Locate the parameter list, then the function body or arrow expression
+        let Some(params_open) = source[m.start()..].find('(').map(|i| m.start() + i) else {
+            return Vec::new();
+        };
+        let Some(params_close) = find_closing(source, params_open) else {
+            return Vec::new();
+        };
+
+        let rest = &source[params_close + 1..];
+        let body_start = match (rest.find('{'), rest.find("=>")) {
+            (Some(brace), Some(arrow)) if arrow < brace => {
+                let after_arrow = params_close + 1 + arrow + 2;
+                let trimmed = source[after_arrow..].trim_start();
+                let offset = source.len() - trimmed.len();
+                // `=> ({ ... })` returns the object directly
+                if trimmed.starts_with('(') {
+                    return source[offset + 1..]
+                        .find('{')
+                        .and_then(|i| parse_object_at(source, offset + 1 + i))
+                        .map(|(value, _)| vec![value])
+                        .unwrap_or_default();
+                }
+                offset
+            }
+            (Some(brace), _) => params_close + 1 + brace,
+            _ => return Vec::new(),
+        };
+
+        let Some(body_end) = find_closing(source, body_start) else {
+            return Vec::new();
+        };
+        let body = &source[body_start..body_end];
+
+        self.return_object
+            .find_iter(body)
+            .filter_map(|r| parse_object_at(source, body_start + r.end() - 1))
+            .map(|(value, _)| value)
+            .collect()
+    }
+
+    /// Map a Next.js `Metadata` object onto head declarations
+    fn apply_metadata(collector: &mut HeadCollector, metadata: &JsValue) {
+        if let Some(title) = metadata.property("title") {
+            let literal = match &title.value {
+                JsValue::Object(_) => title
+                    .value
+                    .get("absolute")
+                    .or_else(|| title.value.get("default"))
+                    .and_then(JsValue::as_str),
+                other => other.as_str(),
+            };
+            collector.record("title".to_string(), literal.map(String::from), title.offset);
+        }
+
+        let simple = [
+            ("description", "meta[name=description]"),
+            ("openGraph", "meta[property=og:*]"),
+            ("twitter", "meta[name=twitter:*]"),
+            ("viewport", "meta[name=viewport]"),
+        ];
+        for (key, element) in simple {
+            if let Some(prop) = metadata.property(key) {
+                let value = prop.value.as_str().map(String::from);
+                collector.record(element.to_string(), value, prop.offset);
+            }
+        }
+
+        if let Some(canonical) = metadata
+            .get("alternates")
+            .and_then(|a| a.property("canonical"))
+        {
+            let value = canonical.value.as_str().map(String::from);
+            collector.record("link[rel=canonical]".to_string(), value, canonical.offset);
+        }
+    }
+}
+
+impl Default for NextMetadataAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnalyzerStrategy for NextMetadataAnalyzer {
+    fn name(&self) -> &'static str {
+        "next_metadata_analyzer"
+    }
+
+    fn accepts(&self, path: &Path) -> bool {
+        Self::is_next_source(path)
+    }
+
+    fn analyze(&self, content: &str) -> Result<AnalysisResult, AnalyzerError> {
+        let static_metadata = self.static_metadata(content);
+        let generated = self.generated_metadata(content);
+        let head_blocks: Vec<_> = self
+            .head_block
+            .captures_iter(content)
+            .filter_map(|c| c.get(1))
+            .collect();
+
+        let is_next = static_metadata.is_some()
+            || !generated.is_empty()
+            || !head_blocks.is_empty()
+            || self.next_import.is_match(content);
+
+        if !is_next {
+            return Ok(AnalysisResult::default());
+        }
+
+        let mut collector = HeadCollector::new(content);
+
+        for metadata in static_metadata.iter().chain(generated.iter()) {
+            Self::apply_metadata(&mut collector, metadata);
+        }
+        for block in &head_blocks {
+            collector.html_fragment(block.start(), block.as_str());
+        }
+        // JSON-LD rendered outside <Head> (e.g. in the page body)
+        for m in self.json_ld.find_iter(content) {
+            if !head_blocks.iter().any(|b| b.range().contains(&m.start())) {
+                collector.record(
+                    "script[type=application/ld+json]".to_string(),
+                    None,
+                    m.start(),
+                );
+            }
+        }
+        collector.template_signals(content);
+
+        // Next.js emits charset and viewport tags on every page by default
+        let mut seo = collector.seo;
+        seo.has_charset = true;
+        seo.has_viewport = true;
+
+        Ok(AnalysisResult {
+            existing_seo: seo,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_router_static_metadata() {
+        let source = r#"
+import type { Metadata } from 'next'
+
+export const metadata: Metadata = {
+  title: { default: 'Acme Plumbing', template: '%s | Acme' },
+  description: 'Emergency plumbing in Springfield',
+  openGraph: { type: 'website' },
+  alternates: { canonical: '/' },
+}
+
+export default function RootLayout({ children }: { children: React.ReactNode }) {
+  return <html lang="en"><body>{children}</body></html>
+}
+"#;
+        let result = NextMetadataAnalyzer::new().analyze(source).unwrap();
+        let seo = result.existing_seo;
+
+        assert_eq!(seo.title.as_deref(), Some("Acme Plumbing"));
+        assert_eq!(
+            seo.description.as_deref(),
+            Some("Emergency plumbing in Springfield")
+        );
+        assert!(seo.has_og_tags);
+        assert!(seo.has_canonical);
+        assert!(!seo.has_twitter_cards);
+        assert!(seo.has_charset && seo.has_viewport);
+    }
+
+    #[test]
+    fn test_generate_metadata_return_literal() {
+        let source = r#"
+export async function generateMetadata({ params }: Props): Promise<Metadata> {
+  const post = await getPost(params.slug)
+  if (!post) {
+    return { title: 'Not found' }
+  }
+  return {
+    title: post.title,
+    description: post.excerpt,
+    twitter: { card: 'summary_large_image' },
+  }
+}
+"#;
+        let result = NextMetadataAnalyzer::new().analyze(source).unwrap();
+        let seo = result.existing_seo;
+
+        assert!(seo.has_title);
+        assert!(seo.has_description);
+        assert!(seo.has_twitter_cards);
+        assert_eq!(seo.title.as_deref(), Some("Not found"));
+        assert_eq!(seo.description, None);
+    }
+
+    #[test]
+    fn test_pages_router_head() {
+        let source = r#"
+import Head from 'next/head'
+
+export default function About() {
+  return (
+    <>
+      <Head>
+        <title>About Us</title>
+        <meta name="description" content="Who we are" />
+        <meta property="og:title" content="About Us" />
+      </Head>
+      <h1>About</h1>
+      <img src="/team.jpg" />
+    </>
+  )
+}
+"#;
+        let result = NextMetadataAnalyzer::new().analyze(source).unwrap();
+        let seo = result.existing_seo;
+
+        assert_eq!(seo.title.as_deref(), Some("About Us"));
+        assert_eq!(seo.description.as_deref(), Some("Who we are"));
+        assert!(seo.has_og_tags);
+        assert_eq!(seo.h1_count, 1);
+        assert_eq!(seo.img_without_alt, 1);
+    }
+
+    #[test]
+    fn test_accepts_next_sources_only() {
+        let analyzer = NextMetadataAnalyzer::new();
+
+        assert!(analyzer.accepts(Path::new("app/layout.tsx")));
+        assert!(analyzer.accepts(Path::new("src/app/blog/[slug]/page.tsx")));
+        assert!(analyzer.accepts(Path::new("pages/about.jsx")));
+        assert!(!analyzer.accepts(Path::new("pages/api/hello.ts")));
+        assert!(!analyzer.accepts(Path::new("app/components/Button.tsx")));
+        assert!(!analyzer.accepts(Path::new("index.html")));
+    }
+}
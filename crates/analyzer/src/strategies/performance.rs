@@ -0,0 +1,327 @@
+//! Performance analyzer - static page weight without a browser
+//!
+//! Counts DOM nodes and nesting depth, the share of the HTML that is visible
+//! text, render-blocking `<head>` scripts and inline script/style bytes, and
+//! collects the CSS, JS, font and image files a page references. Their on-disk
+//! sizes are filled in by `measure_assets` once the page's path is known.
+
+use super::entities::visible_text;
+use crate::{
+    AnalysisResult, AnalyzerError, AnalyzerStrategy, AssetKind, AssetRef, PagePerformance,
+};
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Script types browsers don't execute, so they never block rendering
+const DATA_SCRIPT_TYPES: [&str; 4] = [
+    "application/ld+json",
+    "application/json",
+    "text/template",
+    "text/x-template",
+];
+
+/// Analyzer for static performance signals
+pub struct PerformanceAnalyzer;
+
+impl PerformanceAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn assets(document: &Html) -> Vec<AssetRef> {
+        let selector = Selector::parse(
+            "link[rel~=stylesheet][href], link[rel~=preload][href], script[src], img[src], source[srcset], video[poster]",
+        )
+        .unwrap();
+        let mut assets: Vec<AssetRef> = Vec::new();
+
+        for el in document.select(&selector) {
+            let attrs = el.value();
+            let (url, kind) = match attrs.name() {
+                "link" if attrs.attr("rel").is_some_and(|r| r.contains("stylesheet")) => {
+                    (attrs.attr("href"), Some(AssetKind::Css))
+                }
+                "link" => {
+                    let href = attrs.attr("href");
+                    let kind = match attrs.attr("as") {
+                        Some("font") => Some(AssetKind::Font),
+                        Some("style") => Some(AssetKind::Css),
+                        Some("script") => Some(AssetKind::Js),
+                        Some("image") => Some(AssetKind::Image),
+                        _ => href.and_then(AssetKind::from_url),
+                    };
+                    (href, kind)
+                }
+                "script" => (attrs.attr("src"), Some(AssetKind::Js)),
+                // The first candidate stands in for the set
+                "source" => (
+                    attrs
+                        .attr("srcset")
+                        .and_then(|s| s.split(',').next())
+                        .and_then(|c| c.split_whitespace().next()),
+                    Some(AssetKind::Image),
+                ),
+                "video" => (attrs.attr("poster"), Some(AssetKind::Image)),
+                _ => (attrs.attr("src"), Some(AssetKind::Image)),
+            };
+            if let (Some(url), Some(kind)) = (url, kind) {
+                push(&mut assets, url, kind);
+            }
+        }
+
+        // Fonts and backgrounds of inline stylesheets
+        let styles = Selector::parse("style").unwrap();
+        for style in document.select(&styles) {
+            for url in css_urls(&style.text().collect::<String>()) {
+                if let Some(kind) = AssetKind::from_url(&url) {
+                    push(&mut assets, &url, kind);
+                }
+            }
+        }
+
+        assets
+    }
+
+    fn render_blocking_scripts(document: &Html) -> Vec<String> {
+        let selector = Selector::parse("head script[src]").unwrap();
+        document
+            .select(&selector)
+            .filter(|el| {
+                let attrs = el.value();
+                attrs.attr("async").is_none()
+                    && attrs.attr("defer").is_none()
+                    && attrs.attr("type") != Some("module")
+            })
+            .filter_map(|el| el.value().attr("src").map(str::to_string))
+            .collect()
+    }
+
+    fn inline_bytes(document: &Html) -> (u64, u64) {
+        let scripts = Selector::parse("script:not([src])").unwrap();
+        let styles = Selector::parse("style").unwrap();
+        let styled = Selector::parse("[style]").unwrap();
+
+        let script_bytes = document
+            .select(&scripts)
+            .filter(|el| {
+                let kind = el.value().attr("type").unwrap_or("").to_ascii_lowercase();
+                !DATA_SCRIPT_TYPES.contains(&kind.as_str())
+            })
+            .map(|el| text_bytes(el))
+            .sum();
+        let style_bytes = document.select(&styles).map(text_bytes).sum::<u64>()
+            + document
+                .select(&styled)
+                .filter_map(|el| el.value().attr("style"))
+                .map(|s| s.len() as u64)
+                .sum::<u64>();
+
+        (script_bytes, style_bytes)
+    }
+}
+
+impl Default for PerformanceAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnalyzerStrategy for PerformanceAnalyzer {
+    fn name(&self) -> &'static str {
+        "performance_analyzer"
+    }
+
+    fn analyze(&self, content: &str) -> Result<AnalysisResult, AnalyzerError> {
+        let document = Html::parse_document(content);
+
+        let mut dom_nodes = 0;
+        let mut max_depth = 0;
+        for node in document.root_element().descendants() {
+            if node.value().is_element() {
+                dom_nodes += 1;
+                let depth = node.ancestors().filter(|a| a.value().is_element()).count() + 1;
+                max_depth = max_depth.max(depth as u32);
+            }
+        }
+
+        let body = Selector::parse("body").unwrap();
+        let text = document
+            .select(&body)
+            .next()
+            .map(visible_text)
+            .unwrap_or_default();
+        let text: String = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        let (inline_script_bytes, inline_style_bytes) = Self::inline_bytes(&document);
+
+        Ok(AnalysisResult {
+            performance: vec![PagePerformance {
+                page: None,
+                dom_nodes,
+                max_depth,
+                html_bytes: content.len() as u64,
+                text_bytes: text.len() as u64,
+                assets: Self::assets(&document),
+                render_blocking_scripts: Self::render_blocking_scripts(&document),
+                inline_script_bytes,
+                inline_style_bytes,
+            }],
+            ..Default::default()
+        })
+    }
+}
+
+/// Fill in on-disk asset sizes for `page`, adding the fonts and images its
+/// local stylesheets reference
+pub(crate) fn measure_assets(performance: &mut PagePerformance, page: &Path) {
+    let base = page.parent().unwrap_or(Path::new(""));
+    let mut stylesheet_assets = Vec::new();
+
+    for asset in &mut performance.assets {
+        let Some(file) = resolve(&asset.url, base) else {
+            continue;
+        };
+        asset.bytes = std::fs::metadata(&file).ok().map(|m| m.len());
+
+        if asset.kind == AssetKind::Css {
+            let css = std::fs::read_to_string(&file).unwrap_or_default();
+            let css_dir = file.parent().unwrap_or(base);
+            for url in css_urls(&css) {
+                let Some(kind) = AssetKind::from_url(&url) else {
+                    continue;
+                };
+                let bytes = resolve(&url, css_dir)
+                    .and_then(|f| std::fs::metadata(f).ok())
+                    .map(|m| m.len());
+                stylesheet_assets.push(AssetRef { url, kind, bytes });
+            }
+        }
+    }
+
+    for asset in stylesheet_assets {
+        if !performance.assets.iter().any(|a| a.url == asset.url) {
+            performance.assets.push(asset);
+        }
+    }
+}
+
+/// Local file an asset URL points to. Root-relative URLs are looked up
+/// against each ancestor of `base`, as the site root isn't known here.
+pub(super) fn resolve(url: &str, base: &Path) -> Option<PathBuf> {
+    let path = url.split(['?', '#']).next()?;
+    if path.is_empty() || is_remote(path) {
+        return None;
+    }
+
+    match path.strip_prefix('/') {
+        Some(rooted) => base
+            .ancestors()
+            .map(|dir| dir.join(rooted))
+            .find(|candidate| candidate.is_file()),
+        None => Some(base.join(path)).filter(|f| f.is_file()),
+    }
+}
+
+/// Absolute, protocol-relative and `data:` URLs, which have no local file
+pub(super) fn is_remote(url: &str) -> bool {
+    url.starts_with("//") || url.contains("://") || url.starts_with("data:")
+}
+
+/// `url(...)` references in a stylesheet
+fn css_urls(css: &str) -> Vec<String> {
+    static URL: OnceLock<Regex> = OnceLock::new();
+    let url = URL.get_or_init(|| Regex::new(r#"url\(\s*['"]?([^'")]+?)['"]?\s*\)"#).unwrap());
+
+    url.captures_iter(css)
+        .map(|c| c[1].trim().to_string())
+        .filter(|u| !u.starts_with("data:"))
+        .collect()
+}
+
+fn push(assets: &mut Vec<AssetRef>, url: &str, kind: AssetKind) {
+    let url = url.trim();
+    if url.is_empty() || url.starts_with("data:") || assets.iter().any(|a| a.url == url) {
+        return;
+    }
+    assets.push(AssetRef {
+        url: url.to_string(),
+        kind,
+        bytes: None,
+    });
+}
+
+fn text_bytes(element: ElementRef) -> u64 {
+    element.text().map(|t| t.trim().len() as u64).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markup_measurements() {
+        let html = r#"<html><head>
+<script src="/js/jquery.js"></script>
+<script src="/js/app.js" defer></script>
+<script type="module" src="/js/island.js"></script>
+<script type="application/ld+json">{"@type": "Organization"}</script>
+<script>window.dataLayer = [];</script>
+<link rel="stylesheet" href="css/site.css">
+<link rel="preload" href="/fonts/inter.woff2" as="font" crossorigin>
+<style>.hero { background: url('/img/hero.webp'); }</style>
+</head><body>
+<div><div><p style="color: red">Fresh bread daily.</p></div></div>
+<img src="data:image/gif;base64,R0lGOD"><img src="https://cdn.example/logo.png">
+</body></html>"#;
+        let result = PerformanceAnalyzer::new().analyze(html).unwrap();
+        let perf = &result.performance[0];
+
+        assert_eq!(perf.render_blocking_scripts, vec!["/js/jquery.js"]);
+        assert_eq!(perf.max_depth, 5);
+        assert_eq!(perf.text_bytes, "Fresh bread daily.".len() as u64);
+        assert_eq!(
+            perf.inline_script_bytes,
+            "window.dataLayer = [];".len() as u64
+        );
+        assert!(perf.inline_style_bytes > "color: red".len() as u64);
+        assert_eq!(perf.asset_count(AssetKind::Js), 3);
+        assert_eq!(perf.asset_count(AssetKind::Css), 1);
+        assert_eq!(perf.asset_count(AssetKind::Font), 1);
+        // The inline data URI is not a request
+        assert_eq!(perf.asset_count(AssetKind::Image), 2);
+    }
+
+    #[test]
+    fn test_on_disk_asset_sizes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("blog/css")).unwrap();
+        std::fs::create_dir_all(root.join("fonts")).unwrap();
+        std::fs::write(root.join("app.js"), "x".repeat(1000)).unwrap();
+        std::fs::write(
+            root.join("blog/css/post.css"),
+            "@font-face { src: url(\"/fonts/serif.woff2\") format('woff2'); }",
+        )
+        .unwrap();
+        std::fs::write(root.join("fonts/serif.woff2"), vec![0u8; 300]).unwrap();
+
+        let page = root.join("blog/post.html");
+        let html = r#"<html><head><script src="/app.js"></script>
+<link rel="stylesheet" href="css/post.css"><script src="https://cdn.example/x.js"></script></head></html>"#;
+        let mut result = PerformanceAnalyzer::new().analyze(html).unwrap();
+        let perf = &mut result.performance[0];
+        measure_assets(perf, &page);
+
+        assert_eq!(perf.asset_bytes(AssetKind::Js), 1000);
+        assert_eq!(perf.asset_bytes(AssetKind::Font), 300);
+        assert!(perf.asset_bytes(AssetKind::Css) > 0);
+        let remote = perf
+            .assets
+            .iter()
+            .find(|a| a.url.starts_with("https"))
+            .unwrap();
+        assert_eq!(remote.bytes, None);
+    }
+}
@@ -0,0 +1,761 @@
+//! Product analyzer - product data for Product/Offer/AggregateRating markup
+//!
+//! Structured sources are read first: JSON-LD and microdata Products, the
+//! Shopify product JSON and `data-product-*` attributes on product cards.
+//! Pages that look like a product page (an Open Graph `product` type or an
+//! add-to-cart control) also get a product assembled from Open Graph product
+//! tags and the visible storefront markup: title, price, stock, SKU and rating.
+
+use super::entities::visible_text;
+use crate::{
+    AggregateRating, AnalysisResult, AnalyzerError, AnalyzerStrategy, Availability, Product,
+};
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use serde_json::Value;
+
+const CURRENCY_CODES: &str = "USD|EUR|GBP|CAD|AUD|NZD|JPY|INR|CHF|SEK|NOK|DKK";
+
+/// Product title elements of common storefront themes, most specific first
+const TITLE_SELECTORS: &str =
+    ".product_title, .product-title, .product__title, .product-name, [itemprop=name], h1";
+
+/// Gallery images beyond this many are thumbnails and variants
+const MAX_IMAGES: usize = 10;
+
+/// Analyzer for e-commerce product data
+pub struct ProductAnalyzer {
+    price: Regex,
+    add_to_cart: Regex,
+    rating_value: Regex,
+    rating_count: Regex,
+    sku_text: Regex,
+    gtin_text: Regex,
+}
+
+impl ProductAnalyzer {
+    pub fn new() -> Self {
+        const AMOUNT: &str = r"\d{1,3}(?:[.,\x{a0} ]\d{3})+(?:[.,]\d{1,2})?|\d+(?:[.,]\d{1,2})?";
+
+        Self {
+            price: Regex::new(&format!(
+                r"(?P<pre>(?:US|AU|CA|NZ|A|C)?\$|€|£|¥|₹|\b(?:{CURRENCY_CODES})\b)\s?(?P<amount>{AMOUNT})|(?P<amount2>{AMOUNT})\s?(?P<post>€|£|\b(?:{CURRENCY_CODES})\b)"
+            ))
+            .unwrap(),
+            add_to_cart: Regex::new(
+                r"(?i)\b(?:add\s+to\s+(?:cart|bag|basket)|buy\s+(?:it\s+)?now|pre-?order|sold\s+out|out\s+of\s+stock)\b",
+            )
+            .unwrap(),
+            rating_value: Regex::new(
+                r"(?i)(?:rated\s+)?(?P<value>\d(?:[.,]\d{1,2})?)\s*(?:out\s+of|/)\s*(?P<best>5|10)\b|rated\s+(?P<rated>\d(?:\.\d{1,2})?)",
+            )
+            .unwrap(),
+            rating_count: Regex::new(r"(?i)(?P<count>\d[\d,]*)\s*(?:customer\s+)?(?:reviews?|ratings?)\b")
+                .unwrap(),
+            sku_text: Regex::new(r"(?i)\b(?:sku|item\s*(?:no|number|#))\s*[:#.]?\s*(?P<sku>[A-Z0-9][A-Z0-9._/-]{1,40})")
+                .unwrap(),
+            gtin_text: Regex::new(r"(?i)\b(?:gtin|upc|ean|isbn)(?:-?1[03])?\s*[:#]?\s*(?P<gtin>\d[\d-]{6,16}\d)\b")
+                .unwrap(),
+        }
+    }
+
+    /// Products from JSON-LD, including those in an `@graph`
+    fn json_ld(&self, document: &Html) -> Vec<Product> {
+        let scripts = Selector::parse(r#"script[type="application/ld+json"]"#).unwrap();
+        let mut products = Vec::new();
+
+        for script in document.select(&scripts) {
+            let body: String = script.text().collect();
+            if let Ok(value) = serde_json::from_str::<Value>(&body) {
+                collect_json_products(&value, &mut products);
+            }
+        }
+        products
+    }
+
+    fn microdata(&self, document: &Html) -> Vec<Product> {
+        let scopes = Selector::parse(r#"[itemtype*="schema.org/Product"]"#).unwrap();
+        let mut products = Vec::new();
+
+        for scope in document.select(&scopes) {
+            let Some(name) = itemprop(scope, "name") else {
+                continue;
+            };
+
+            let brand = prop_element(scope, "brand")
+                .map(|brand| itemprop(brand, "name").unwrap_or_else(|| microdata_value(brand)));
+            let gtin = ["gtin", "gtin13", "gtin12", "gtin14", "gtin8"]
+                .iter()
+                .find_map(|p| itemprop(scope, p));
+            let images = Selector::parse("[itemprop=image]")
+                .map(|sel| scope.select(&sel).map(microdata_value).collect())
+                .unwrap_or_default();
+
+            let offer = prop_element(scope, "offers");
+            let rating = prop_element(scope, "aggregateRating").and_then(|r| {
+                rating(
+                    itemprop(r, "ratingValue")?.parse().ok()?,
+                    itemprop(r, "reviewCount")
+                        .or_else(|| itemprop(r, "ratingCount"))?
+                        .parse()
+                        .ok()?,
+                    itemprop(r, "bestRating").and_then(|b| b.parse().ok()),
+                )
+            });
+
+            products.push(Product {
+                name,
+                description: itemprop(scope, "description"),
+                price: offer
+                    .and_then(|o| itemprop(o, "price").or_else(|| itemprop(o, "lowPrice")))
+                    .and_then(|p| normalize_price(&p)),
+                currency: offer.and_then(|o| itemprop(o, "priceCurrency")),
+                availability: offer
+                    .and_then(|o| itemprop(o, "availability"))
+                    .and_then(|a| Availability::parse(&a)),
+                sku: itemprop(scope, "sku"),
+                gtin,
+                brand,
+                images,
+                rating,
+                page: None,
+            });
+        }
+        products
+    }
+
+    /// Shopify themes embed the product as JSON, prices in cents
+    fn shopify(&self, document: &Html) -> Vec<Product> {
+        let scripts =
+            Selector::parse("script[data-product-json], script[id^=ProductJson]").unwrap();
+        let mut products = Vec::new();
+
+        for script in document.select(&scripts) {
+            let body: String = script.text().collect();
+            let Ok(json) = serde_json::from_str::<Value>(&body) else {
+                continue;
+            };
+            let Some(name) = json_str(&json["title"]) else {
+                continue;
+            };
+
+            let variant = &json["variants"][0];
+            let price = match &variant["price"] {
+                Value::Number(cents) => cents.as_f64().map(|c| format!("{:.2}", c / 100.0)),
+                other => json_str(other).and_then(|p| normalize_price(&p)),
+            };
+            let availability = variant["available"]
+                .as_bool()
+                .or_else(|| json["available"].as_bool())
+                .map(|available| {
+                    if available {
+                        Availability::InStock
+                    } else {
+                        Availability::OutOfStock
+                    }
+                });
+
+            products.push(Product {
+                name,
+                description: json_str(&json["description"]).map(|d| strip_tags(&d)),
+                price,
+                currency: None,
+                availability,
+                sku: json_str(&variant["sku"]),
+                gtin: json_str(&variant["barcode"]).filter(|b| is_gtin(b)),
+                brand: json_str(&json["vendor"]),
+                images: json_strings(&json["images"]),
+                rating: None,
+                page: None,
+            });
+        }
+        products
+    }
+
+    /// Product cards carrying their data in `data-*` attributes
+    fn data_attributes(&self, document: &Html) -> Vec<Product> {
+        let cards = Selector::parse("[data-product-name], [data-product-title]").unwrap();
+        let img = Selector::parse("img[src]").unwrap();
+        let mut products = Vec::new();
+
+        for card in document.select(&cards) {
+            let el = card.value();
+            let attr = |names: &[&str]| {
+                names
+                    .iter()
+                    .find_map(|n| el.attr(n))
+                    .map(|v| v.trim().to_string())
+                    .filter(|v| !v.is_empty())
+            };
+            let Some(name) = attr(&["data-product-name", "data-product-title"]) else {
+                continue;
+            };
+
+            let image = attr(&["data-product-image", "data-image"]).or_else(|| {
+                card.select(&img)
+                    .next()
+                    .and_then(|i| i.value().attr("src"))
+                    .map(str::to_string)
+            });
+
+            products.push(Product {
+                name,
+                description: None,
+                price: attr(&["data-product-price", "data-price"])
+                    .and_then(|p| normalize_price(&p)),
+                currency: attr(&["data-product-currency", "data-currency"]),
+                availability: attr(&[
+                    "data-availability",
+                    "data-stock",
+                    "data-product-availability",
+                ])
+                .and_then(|a| Availability::parse(&a)),
+                sku: attr(&["data-product-sku", "data-sku"]),
+                gtin: attr(&["data-gtin", "data-barcode"]).filter(|g| is_gtin(g)),
+                brand: attr(&["data-product-brand", "data-brand"]),
+                images: image.into_iter().collect(),
+                rating: None,
+                page: None,
+            });
+        }
+        products
+    }
+
+    /// Product assembled from Open Graph tags and the visible storefront markup
+    fn page_product(&self, document: &Html) -> Option<Product> {
+        let meta = |property: &str| meta_content(document, property);
+        let is_product_type = meta("og:type").is_some_and(|t| t.starts_with("product"));
+
+        let controls = Selector::parse(
+            "button, input[type=submit], input[type=button], a[class*=cart], [name=add-to-cart]",
+        )
+        .unwrap();
+        let cart_control = document.select(&controls).find(|el| {
+            let label = el
+                .value()
+                .attr("value")
+                .map(str::to_string)
+                .unwrap_or_else(|| visible_text(*el));
+            el.value().attr("name") == Some("add-to-cart") || self.add_to_cart.is_match(&label)
+        });
+        if !is_product_type && cart_control.is_none() {
+            return None;
+        }
+
+        let name = first_text(document, TITLE_SELECTORS).or_else(|| meta("og:title"))?;
+
+        let (price, currency) =
+            match meta("product:price:amount").or_else(|| meta("og:price:amount")) {
+                Some(amount) => (
+                    normalize_price(&amount),
+                    meta("product:price:currency").or_else(|| meta("og:price:currency")),
+                ),
+                None => self.visible_price(document).unzip(),
+            };
+        let currency = currency.or_else(|| meta("product:price:currency"));
+
+        let availability = meta("product:availability")
+            .or_else(|| meta("og:availability"))
+            .and_then(|a| Availability::parse(&a))
+            .or_else(|| {
+                first_text(document, "[class*=stock], [class*=availability]")
+                    .and_then(|a| Availability::parse(&a))
+            })
+            .or_else(|| {
+                let control = cart_control?;
+                let label = visible_text(control);
+                let disabled = control.value().attr("disabled").is_some();
+                Some(match Availability::parse(&label) {
+                    Some(a) if a != Availability::InStock => a,
+                    _ if disabled => Availability::OutOfStock,
+                    _ => Availability::InStock,
+                })
+            });
+
+        let body = Selector::parse("body").unwrap();
+        let text = document
+            .select(&body)
+            .next()
+            .map(visible_text)
+            .unwrap_or_default();
+
+        let sku = meta("product:retailer_item_id")
+            .or_else(|| {
+                first_text(
+                    document,
+                    ".sku, [class*=product-sku], [class*=product__sku]",
+                )
+                .filter(|s| !s.contains(' '))
+            })
+            .or_else(|| self.sku_text.captures(&text).map(|c| c["sku"].to_string()));
+        let gtin = self
+            .gtin_text
+            .captures(&text)
+            .map(|c| c["gtin"].replace('-', ""))
+            .filter(|g| is_gtin(g));
+
+        let mut images: Vec<String> = Vec::new();
+        let gallery = Selector::parse(
+            "meta[property='og:image'], [class*=product] img[src], [class*=gallery] img[src]",
+        )
+        .unwrap();
+        for el in document.select(&gallery) {
+            let src = el
+                .value()
+                .attr("content")
+                .or_else(|| el.value().attr("src"));
+            if let Some(src) = src.filter(|s| !s.is_empty()) {
+                if !images.iter().any(|i| i == src) && images.len() < MAX_IMAGES {
+                    images.push(src.to_string());
+                }
+            }
+        }
+
+        Some(Product {
+            name,
+            description: first_text(
+                document,
+                "[class*=product-description], [class*=product__description], .woocommerce-product-details__short-description",
+            )
+            .or_else(|| meta("og:description"))
+            .or_else(|| meta_name(document, "description")),
+            price,
+            currency,
+            availability,
+            sku,
+            gtin,
+            brand: meta("product:brand")
+                .or_else(|| meta("og:brand"))
+                .or_else(|| first_text(document, "[class*=product-brand], [class*=product__vendor], [class*=product-vendor]")),
+            images,
+            rating: self.visible_rating(document),
+            page: None,
+        })
+    }
+
+    /// Current price and currency shown in the price block; struck-through
+    /// regular prices are skipped
+    fn visible_price(&self, document: &Html) -> Option<(String, String)> {
+        let blocks = Selector::parse("[class*=price], [itemprop=price]").unwrap();
+        let current = Selector::parse("ins").unwrap();
+
+        document
+            .select(&blocks)
+            .find_map(|block| {
+                let block = block.select(&current).next().unwrap_or(block);
+                self.parse_price(&text_without_struck(block))
+            })
+            .or_else(|| {
+                let main = Selector::parse("main, body").unwrap();
+                let text = visible_text(document.select(&main).next()?);
+                self.parse_price(&text)
+            })
+    }
+
+    fn parse_price(&self, text: &str) -> Option<(String, String)> {
+        self.price.captures_iter(text).find_map(|caps| {
+            let amount = caps.name("amount").or_else(|| caps.name("amount2"))?;
+            let symbol = caps.name("pre").or_else(|| caps.name("post"))?;
+            Some((
+                normalize_price(amount.as_str())?,
+                currency_code(symbol.as_str())?,
+            ))
+        })
+    }
+
+    fn visible_rating(&self, document: &Html) -> Option<AggregateRating> {
+        let blocks = Selector::parse("[class*=rating], [class*=review]").unwrap();
+
+        document.select(&blocks).find_map(|block| {
+            let text = visible_text(block);
+            let data = |name: &str| block.value().attr(name).map(str::trim);
+
+            let (value, best) = match self.rating_value.captures(&text) {
+                Some(caps) => {
+                    let value = caps.name("value").or_else(|| caps.name("rated"))?;
+                    let best = caps.name("best").and_then(|b| b.as_str().parse().ok());
+                    (value.as_str().replace(',', ".").parse().ok()?, best)
+                }
+                None => (data("data-rating")?.parse().ok()?, None),
+            };
+            let count = self
+                .rating_count
+                .captures(&text)
+                .and_then(|c| c["count"].replace(',', "").parse().ok())
+                .or_else(|| data("data-review-count")?.parse().ok())?;
+
+            rating(value, count, best)
+        })
+    }
+}
+
+impl Default for ProductAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnalyzerStrategy for ProductAnalyzer {
+    fn name(&self) -> &'static str {
+        "product_analyzer"
+    }
+
+    fn analyze(&self, content: &str) -> Result<AnalysisResult, AnalyzerError> {
+        let document = Html::parse_document(content);
+
+        let mut products: Vec<Product> = Vec::new();
+        let structured = self
+            .json_ld(&document)
+            .into_iter()
+            .chain(self.microdata(&document))
+            .chain(self.shopify(&document));
+        for product in structured {
+            add(&mut products, product);
+        }
+
+        // The page's own product completes a single structured description
+        // and stands in when there is none
+        if let Some(page_product) = self.page_product(&document) {
+            match products.as_mut_slice() {
+                [only] => only.fill_from(&page_product),
+                [] if page_product.price.is_some() || page_product.sku.is_some() => {
+                    products.push(page_product)
+                }
+                _ => {}
+            }
+        }
+
+        for product in self.data_attributes(&document) {
+            add(&mut products, product);
+        }
+
+        products.retain(|p| !p.name.is_empty());
+
+        Ok(AnalysisResult {
+            products,
+            ..Default::default()
+        })
+    }
+}
+
+fn add(products: &mut Vec<Product>, product: Product) {
+    match products.iter_mut().find(|p| p.same_product(&product)) {
+        Some(existing) => existing.fill_from(&product),
+        None => products.push(product),
+    }
+}
+
+fn collect_json_products(value: &Value, out: &mut Vec<Product>) {
+    match value {
+        Value::Array(items) => items.iter().for_each(|v| collect_json_products(v, out)),
+        Value::Object(map) => {
+            let is_product = match &map.get("@type") {
+                Some(Value::String(t)) => t == "Product",
+                Some(Value::Array(types)) => types.iter().any(|t| t == "Product"),
+                _ => false,
+            };
+            if is_product {
+                if let Some(product) = json_product(value) {
+                    out.push(product);
+                }
+            } else {
+                map.values().for_each(|v| collect_json_products(v, out));
+            }
+        }
+        _ => {}
+    }
+}
+
+fn json_product(json: &Value) -> Option<Product> {
+    let offer = match &json["offers"] {
+        Value::Array(offers) => offers.first().unwrap_or(&Value::Null),
+        offer => offer,
+    };
+    let price = json_str(&offer["price"])
+        .or_else(|| json_str(&offer["lowPrice"]))
+        .and_then(|p| normalize_price(&p));
+    let brand = json_str(&json["brand"]).or_else(|| json_str(&json["brand"]["name"]));
+    let gtin = ["gtin", "gtin13", "gtin12", "gtin14", "gtin8"]
+        .iter()
+        .find_map(|key| json_str(&json[*key]));
+
+    let rating_json = &json["aggregateRating"];
+    let rating = json_str(&rating_json["ratingValue"]).and_then(|value| {
+        let count = json_str(&rating_json["reviewCount"])
+            .or_else(|| json_str(&rating_json["ratingCount"]))?;
+        rating(
+            value.parse().ok()?,
+            count.parse().ok()?,
+            json_str(&rating_json["bestRating"]).and_then(|b| b.parse().ok()),
+        )
+    });
+
+    Some(Product {
+        name: json_str(&json["name"])?,
+        description: json_str(&json["description"]),
+        price,
+        currency: json_str(&offer["priceCurrency"]),
+        availability: json_str(&offer["availability"]).and_then(|a| Availability::parse(&a)),
+        sku: json_str(&json["sku"]),
+        gtin,
+        brand,
+        images: json_strings(&json["image"]),
+        rating,
+        page: None,
+    })
+}
+
+/// A JSON string or number as text
+fn json_str(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Image URLs from a string, an array or `ImageObject`s
+fn json_strings(value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(items) => items.iter().flat_map(json_strings).collect(),
+        Value::Object(_) => json_str(&value["url"])
+            .or_else(|| json_str(&value["src"]))
+            .into_iter()
+            .collect(),
+        other => json_str(other).into_iter().collect(),
+    }
+}
+
+fn rating(value: f32, count: u32, best: Option<f32>) -> Option<AggregateRating> {
+    let best = best.unwrap_or(5.0);
+    (count > 0 && value > 0.0 && value <= best).then_some(AggregateRating { value, count, best })
+}
+
+/// Normalize `1,299.00`, `1.299,00`, `24,00` and `1 299` to `1299.00`
+fn normalize_price(raw: &str) -> Option<String> {
+    let cleaned: String = raw
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == '.' || *c == ',')
+        .collect();
+    let decimal = match cleaned.rfind(['.', ',']) {
+        Some(pos) => {
+            let separator = cleaned.as_bytes()[pos] as char;
+            let decimals = cleaned.len() - pos - 1;
+            let mixed = cleaned.contains('.') && cleaned.contains(',');
+            let repeated = cleaned.matches(separator).count() > 1;
+            (mixed || (!repeated && decimals <= 2)).then_some(pos)
+        }
+        None => None,
+    };
+
+    let number: String = cleaned
+        .char_indices()
+        .filter_map(|(i, c)| match c {
+            '0'..='9' => Some(c),
+            _ if Some(i) == decimal => Some('.'),
+            _ => None,
+        })
+        .collect();
+    let value: f64 = number.parse().ok()?;
+    Some(format!("{:.2}", value))
+}
+
+fn currency_code(symbol: &str) -> Option<String> {
+    let code = match symbol.trim() {
+        "$" | "US$" => "USD",
+        "A$" | "AU$" => "AUD",
+        "C$" | "CA$" => "CAD",
+        "NZ$" => "NZD",
+        "€" => "EUR",
+        "£" => "GBP",
+        "¥" => "JPY",
+        "₹" => "INR",
+        code if CURRENCY_CODES.split('|').any(|c| c == code) => code,
+        _ => return None,
+    };
+    Some(code.to_string())
+}
+
+fn is_gtin(value: &str) -> bool {
+    matches!(value.len(), 8 | 12 | 13 | 14) && value.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Element with `itemprop` `name` within `scope`
+fn prop_element<'a>(scope: ElementRef<'a>, name: &str) -> Option<ElementRef<'a>> {
+    let selector = Selector::parse(&format!("[itemprop={}]", name)).ok()?;
+    scope.select(&selector).next()
+}
+
+fn itemprop(scope: ElementRef, name: &str) -> Option<String> {
+    prop_element(scope, name)
+        .map(microdata_value)
+        .filter(|v| !v.is_empty())
+}
+
+/// Microdata value: `content`, `href` or `src`, else the element text
+fn microdata_value(element: ElementRef) -> String {
+    let el = element.value();
+    el.attr("content")
+        .or_else(|| el.attr("href"))
+        .or_else(|| el.attr("src"))
+        .map(|v| v.trim().to_string())
+        .unwrap_or_else(|| collapse(&visible_text(element)))
+}
+
+fn meta_content(document: &Html, property: &str) -> Option<String> {
+    let selector = Selector::parse(&format!("meta[property='{}']", property)).ok()?;
+    document
+        .select(&selector)
+        .next()?
+        .value()
+        .attr("content")
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+}
+
+fn meta_name(document: &Html, name: &str) -> Option<String> {
+    let selector = Selector::parse(&format!("meta[name='{}']", name)).ok()?;
+    document
+        .select(&selector)
+        .next()?
+        .value()
+        .attr("content")
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+}
+
+fn first_text(document: &Html, selectors: &str) -> Option<String> {
+    let selector = Selector::parse(selectors).ok()?;
+    document
+        .select(&selector)
+        .map(|el| collapse(&visible_text(el)))
+        .find(|text| !text.is_empty())
+}
+
+/// Text of an element without `<del>`/`<s>` regular prices
+fn text_without_struck(element: ElementRef) -> String {
+    element
+        .descendants()
+        .filter_map(|node| {
+            let text = node.value().as_text()?;
+            let struck = node.ancestors().any(|a| {
+                a.value()
+                    .as_element()
+                    .is_some_and(|e| matches!(e.name(), "del" | "s" | "strike"))
+            });
+            (!struck).then(|| text.to_string())
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn strip_tags(html: &str) -> String {
+    let fragment = Html::parse_fragment(html);
+    collapse(&fragment.root_element().text().collect::<String>())
+}
+
+fn collapse(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storefront_product_page() {
+        let html = r#"<html><head>
+<meta property="og:type" content="product">
+<meta property="og:image" content="https://shop.example/mug.jpg">
+</head><body>
+<div class="product">
+  <h1 class="product_title">Speckled Stoneware Mug</h1>
+  <p class="price"><del>$30.00</del> <ins>$24.00</ins></p>
+  <p class="stock in-stock">12 in stock</p>
+  <span class="sku">MUG-SPK-12</span>
+  <div class="star-rating">Rated 4.50 out of 5 based on <span>12</span> customer reviews</div>
+  <img src="/img/mug-side.jpg">
+  <button type="submit" name="add-to-cart">Add to cart</button>
+</div>
+</body></html>"#;
+        let products = ProductAnalyzer::new().analyze(html).unwrap().products;
+
+        assert_eq!(products.len(), 1);
+        let mug = &products[0];
+        assert_eq!(mug.name, "Speckled Stoneware Mug");
+        assert_eq!(mug.price.as_deref(), Some("24.00"));
+        assert_eq!(mug.currency.as_deref(), Some("USD"));
+        assert_eq!(mug.availability, Some(Availability::InStock));
+        assert_eq!(mug.sku.as_deref(), Some("MUG-SPK-12"));
+        assert_eq!(
+            mug.images,
+            vec!["https://shop.example/mug.jpg", "/img/mug-side.jpg"]
+        );
+        assert_eq!(
+            mug.rating,
+            Some(AggregateRating {
+                value: 4.5,
+                count: 12,
+                best: 5.0
+            })
+        );
+    }
+
+    #[test]
+    fn test_microdata_and_shopify_json() {
+        let microdata = r#"<html><body>
+<div itemscope itemtype="https://schema.org/Product">
+  <h1 itemprop="name">Espresso Grinder</h1>
+  <div itemprop="brand" itemscope itemtype="https://schema.org/Brand"><span itemprop="name">Baratza</span></div>
+  <meta itemprop="gtin13" content="0850004960012">
+  <div itemprop="offers" itemscope itemtype="https://schema.org/Offer">
+    <span itemprop="price" content="1299.00">1.299,00 €</span>
+    <meta itemprop="priceCurrency" content="EUR">
+    <link itemprop="availability" href="https://schema.org/OutOfStock">
+  </div>
+</div>
+</body></html>"#;
+        let products = ProductAnalyzer::new().analyze(microdata).unwrap().products;
+        let grinder = &products[0];
+        assert_eq!(grinder.brand.as_deref(), Some("Baratza"));
+        assert_eq!(grinder.gtin.as_deref(), Some("0850004960012"));
+        assert_eq!(grinder.price.as_deref(), Some("1299.00"));
+        assert_eq!(grinder.currency.as_deref(), Some("EUR"));
+        assert_eq!(grinder.availability, Some(Availability::OutOfStock));
+
+        let shopify = r#"<html><body>
+<script type="application/json" data-product-json>{"title":"Pour-Over Kettle","vendor":"Fellow","description":"<p>Precise <b>pour</b>.</p>","images":["//cdn.shop/kettle.jpg"],"variants":[{"price":8900,"sku":"KTL-1","barcode":"812345678901","available":true}]}</script>
+</body></html>"#;
+        let products = ProductAnalyzer::new().analyze(shopify).unwrap().products;
+        let kettle = &products[0];
+        assert_eq!(kettle.price.as_deref(), Some("89.00"));
+        assert_eq!(kettle.description.as_deref(), Some("Precise pour."));
+        assert_eq!(kettle.gtin.as_deref(), Some("812345678901"));
+        assert_eq!(kettle.availability, Some(Availability::InStock));
+
+        assert_eq!(normalize_price("1.299,00").as_deref(), Some("1299.00"));
+        assert_eq!(normalize_price("24,00").as_deref(), Some("24.00"));
+        assert_eq!(normalize_price("1,299").as_deref(), Some("1299.00"));
+    }
+
+    #[test]
+    fn test_listing_cards_and_non_product_pages() {
+        let listing = r#"<html><body>
+<div data-product-name="Travel Mug" data-price="18.5" data-sku="TM-1" data-availability="in_stock"><img src="/tm.jpg"></div>
+<div data-product-name="Camp Mug" data-price="22" data-sku="CM-1" data-availability="sold out"></div>
+</body></html>"#;
+        let products = ProductAnalyzer::new().analyze(listing).unwrap().products;
+        assert_eq!(products.len(), 2);
+        assert_eq!(products[0].price.as_deref(), Some("18.50"));
+        assert_eq!(products[0].images, vec!["/tm.jpg"]);
+        assert_eq!(products[1].availability, Some(Availability::OutOfStock));
+
+        let pricing = r#"<html><body><h1>Pricing</h1><p>Plans from $29/month.</p>
+<a class="button" href="/signup">Start free trial</a></body></html>"#;
+        assert!(ProductAnalyzer::new()
+            .analyze(pricing)
+            .unwrap()
+            .products
+            .is_empty());
+    }
+}
@@ -1,7 +1,8 @@
 //! SEO audit analyzer - checks existing SEO elements
 
-use crate::{AnalysisResult, AnalyzerError, AnalyzerStrategy, ExistingSeo};
-use scraper::{Html, Selector};
+use crate::{AnalysisResult, AnalyzerError, AnalyzerStrategy, ExistingSeo, Hreflang};
+use scraper::{ElementRef, Html, Selector};
+use serde_json::Value;
 
 /// Analyzer that audits existing SEO elements
 pub struct SeoAuditAnalyzer;
@@ -35,6 +36,53 @@ impl SeoAuditAnalyzer {
         })
     }
 
+    /// Types of the top-level JSON-LD objects, `@graph` items included, and
+    /// of microdata items not nested in another item
+    fn schema_types(document: &Html) -> Vec<String> {
+        let scripts = Selector::parse("script[type='application/ld+json']").unwrap();
+        let items = Selector::parse("[itemscope][itemtype]").unwrap();
+
+        let mut types: Vec<String> = Vec::new();
+        for script in document.select(&scripts) {
+            let body: String = script.text().collect();
+            if let Ok(value) = serde_json::from_str::<Value>(&body) {
+                json_ld_types(&value, &mut types);
+            }
+        }
+        for item in document.select(&items) {
+            let nested = item
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .any(|a| a.value().attr("itemscope").is_some());
+            if !nested {
+                let itemtype = item.value().attr("itemtype").unwrap_or_default();
+                types.extend(itemtype.split_whitespace().map(schema_name));
+            }
+        }
+        let mut unique = Vec::new();
+        for schema_type in types {
+            if !unique.contains(&schema_type) {
+                unique.push(schema_type);
+            }
+        }
+        unique
+    }
+
+    fn hreflang(document: &Html) -> Vec<Hreflang> {
+        let links = Selector::parse("link[rel='alternate'][hreflang]").unwrap();
+        document
+            .select(&links)
+            .filter_map(|link| {
+                let lang = link.value().attr("hreflang")?.trim();
+                let href = link.value().attr("href")?.trim();
+                (!lang.is_empty() && !href.is_empty()).then(|| Hreflang {
+                    lang: lang.to_ascii_lowercase(),
+                    href: href.to_string(),
+                })
+            })
+            .collect()
+    }
+
     fn count_elements(document: &Html, selector_str: &str) -> u32 {
         Selector::parse(selector_str)
             .ok()
@@ -79,6 +127,23 @@ impl AnalyzerStrategy for SeoAuditAnalyzer {
 
         // Check canonical
         let has_canonical = Self::check_selector(&document, "link[rel='canonical']");
+        let canonical = Self::get_attr(&document, "link[rel='canonical']", "href")
+            .map(|href| href.trim().to_string())
+            .filter(|href| !href.is_empty());
+
+        // Check robots noindex
+        let noindex = Self::get_attr(&document, "meta[name='robots']", "content")
+            .is_some_and(|c| c.to_ascii_lowercase().contains("noindex"));
+
+        // Check meta refresh redirect
+        let meta_refresh = Self::get_attr(&document, "meta[http-equiv='refresh' i]", "content")
+            .and_then(|content| refresh_url(&content));
+
+        // Check site name
+        let site_name = Self::get_attr(&document, "meta[property='og:site_name']", "content")
+            .or_else(|| Self::get_attr(&document, "meta[name='application-name']", "content"))
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty());
 
         // Check viewport
         let has_viewport = Self::check_selector(&document, "meta[name='viewport']");
@@ -95,6 +160,10 @@ impl AnalyzerStrategy for SeoAuditAnalyzer {
         let images_with_alt = Self::count_elements(&document, "img[alt]:not([alt=''])");
         let img_without_alt = total_images.saturating_sub(images_with_alt);
 
+        // Check structured data types and language alternates
+        let schema_types = Self::schema_types(&document);
+        let hreflang = Self::hreflang(&document);
+
         let existing_seo = ExistingSeo {
             has_title,
             title,
@@ -104,10 +173,17 @@ impl AnalyzerStrategy for SeoAuditAnalyzer {
             has_twitter_cards,
             has_schema,
             has_canonical,
+            canonical,
+            noindex,
+            meta_refresh,
+            site_name,
+            schema_types,
+            hreflang,
             has_viewport,
             has_charset,
             h1_count,
             img_without_alt,
+            sources: Vec::new(),
         };
 
         Ok(AnalysisResult {
@@ -117,6 +193,48 @@ impl AnalyzerStrategy for SeoAuditAnalyzer {
     }
 }
 
+/// `@type` of JSON-LD objects at the top level or in an `@graph`
+fn json_ld_types(value: &Value, out: &mut Vec<String>) {
+    match value {
+        Value::Array(items) => items.iter().for_each(|v| json_ld_types(v, out)),
+        Value::Object(map) => {
+            match map.get("@type") {
+                Some(Value::String(t)) => out.push(schema_name(t)),
+                Some(Value::Array(types)) => {
+                    out.extend(types.iter().filter_map(Value::as_str).map(schema_name))
+                }
+                _ => {}
+            }
+            if let Some(graph) = map.get("@graph") {
+                json_ld_types(graph, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `https://schema.org/Product` as `Product`
+fn schema_name(schema_type: &str) -> String {
+    schema_type
+        .trim_end_matches('/')
+        .rsplit(['/', ':'])
+        .next()
+        .unwrap_or(schema_type)
+        .to_string()
+}
+
+/// Target of a refresh `content` such as `0; url='/new/'`; `None` for a plain reload
+fn refresh_url(content: &str) -> Option<String> {
+    let (_, rest) = content.split_once([';', ','])?;
+    let rest = rest.trim();
+    let url = match rest.get(..4) {
+        Some(key) if key.eq_ignore_ascii_case("url=") => &rest[4..],
+        _ => rest,
+    };
+    let url = url.trim().trim_matches(|c| c == '\'' || c == '"').trim();
+    (!url.is_empty()).then(|| url.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,8 +249,10 @@ mod tests {
     <meta name="viewport" content="width=device-width, initial-scale=1.0">
     <meta name="description" content="Test description">
     <meta property="og:title" content="OG Title">
+    <meta property="og:site_name" content="Example Co">
     <meta name="twitter:card" content="summary">
     <link rel="canonical" href="https://example.com">
+    <link rel="alternate" hreflang="en-GB" href="https://example.com/uk/">
     <title>Test Page</title>
     <script type="application/ld+json">{"@type": "Organization"}</script>
 </head>
@@ -152,6 +272,18 @@ mod tests {
         assert!(result.existing_seo.has_twitter_cards);
         assert!(result.existing_seo.has_schema);
         assert!(result.existing_seo.has_canonical);
+        assert_eq!(result.existing_seo.canonical.as_deref(), Some("https://example.com"));
+        assert!(!result.existing_seo.noindex);
+        assert_eq!(result.existing_seo.site_name.as_deref(), Some("Example Co"));
+        assert_eq!(result.existing_seo.meta_refresh, None);
+        assert_eq!(result.existing_seo.schema_types, vec!["Organization"]);
+        assert_eq!(
+            result.existing_seo.hreflang,
+            vec![Hreflang {
+                lang: "en-gb".to_string(),
+                href: "https://example.com/uk/".to_string()
+            }]
+        );
         assert!(result.existing_seo.has_viewport);
         assert!(result.existing_seo.has_charset);
         assert_eq!(result.existing_seo.h1_count, 1);
@@ -166,6 +298,7 @@ mod tests {
 <html>
 <head>
     <title>Basic Page</title>
+    <meta http-equiv="Refresh" content="0; URL='/new-page/'">
 </head>
 <body>
     <h1>Hello</h1>
@@ -183,5 +316,8 @@ mod tests {
         assert!(!result.existing_seo.has_og_tags);
         assert_eq!(result.existing_seo.h1_count, 2);
         assert_eq!(result.existing_seo.img_without_alt, 1);
+        assert_eq!(result.existing_seo.meta_refresh.as_deref(), Some("/new-page/"));
+        assert!(result.existing_seo.schema_types.is_empty());
+        assert!(result.existing_seo.hreflang.is_empty());
     }
 }
@@ -0,0 +1,258 @@
+//! Snippet analyzer - featured-snippet readiness of question headings
+//!
+//! Answer boxes are filled from the block right below a heading that asks
+//! the searcher's question: a 40-60 word paragraph, a short list or a table.
+//! Each question heading is scored on how close its answer comes to one of
+//! those shapes, with hints for restructuring it.
+
+use super::entities::visible_text;
+use crate::{AnalysisResult, AnalyzerError, AnalyzerStrategy, SnippetCandidate, SnippetFormat};
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+
+/// Paragraph answers Google quotes in full
+const PARAGRAPH_WORDS: std::ops::RangeInclusive<u32> = 40..=60;
+
+/// List answers show up to eight items before "More items…"
+const LIST_ITEMS: std::ops::RangeInclusive<u32> = 3..=8;
+
+/// List items longer than this read as paragraphs
+const MAX_ITEM_WORDS: u32 = 20;
+
+/// Longer headings are statements, not questions
+const MAX_QUESTION_WORDS: usize = 20;
+
+/// Analyzer for featured-snippet readiness
+pub struct SnippetAnalyzer {
+    interrogative: Regex,
+    steps: Regex,
+}
+
+impl SnippetAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            interrogative: Regex::new(
+                r"(?i)^(how|what|why|when|where|who|which|can|do|does|is|are|should|will)\b",
+            )
+            .unwrap(),
+            steps: Regex::new(r"(?i)^(how (to|do|can)|what are the steps)\b").unwrap(),
+        }
+    }
+
+    fn is_question(&self, heading: &str) -> bool {
+        let words = heading.split_whitespace().count();
+        words > 0
+            && words <= MAX_QUESTION_WORDS
+            && (heading.ends_with('?') || self.interrogative.is_match(heading))
+    }
+
+    fn candidate(&self, question: String, answer: Option<ElementRef>) -> SnippetCandidate {
+        let mut candidate = SnippetCandidate {
+            question,
+            ..Default::default()
+        };
+        let Some(answer) = answer else {
+            candidate.hints.push(
+                "Answer directly below the heading with a 40-60 word paragraph, a list or a table"
+                    .to_string(),
+            );
+            return candidate;
+        };
+
+        let hints = &mut candidate.hints;
+        match answer.value().name() {
+            "p" => {
+                let words = word_count(&visible_text(answer));
+                candidate.format = SnippetFormat::Paragraph;
+                candidate.answer_words = words;
+                candidate.score = if PARAGRAPH_WORDS.contains(&words) {
+                    100
+                } else if !(20..=120).contains(&words) {
+                    40
+                } else {
+                    70
+                };
+                if words < *PARAGRAPH_WORDS.start() {
+                    hints.push(format!(
+                        "Expand the answer from {} to 40-60 words so it stands on its own",
+                        words
+                    ));
+                } else if words > *PARAGRAPH_WORDS.end() {
+                    hints.push(format!(
+                        "Tighten the first paragraph from {} to 40-60 words and move details below it",
+                        words
+                    ));
+                }
+            }
+            "ul" | "ol" => {
+                let items: Vec<u32> = answer
+                    .children()
+                    .filter_map(ElementRef::wrap)
+                    .filter(|li| li.value().name() == "li")
+                    .map(|li| word_count(&visible_text(li)))
+                    .collect();
+                let count = items.len() as u32;
+                candidate.format = SnippetFormat::List;
+                candidate.items = count;
+                candidate.answer_words = items.iter().sum();
+                candidate.score = 100;
+
+                if count < *LIST_ITEMS.start() {
+                    candidate.score -= 40;
+                    hints.push("List at least 3 items".to_string());
+                } else if count > *LIST_ITEMS.end() {
+                    candidate.score -= 20;
+                    hints.push(format!(
+                        "Only 8 of {} items show in the snippet; lead with the most important",
+                        count
+                    ));
+                }
+                if items.iter().any(|&words| words > MAX_ITEM_WORDS) {
+                    candidate.score -= 20;
+                    hints.push(format!(
+                        "Shorten list items to under {} words and explain them below",
+                        MAX_ITEM_WORDS
+                    ));
+                }
+                if answer.value().name() == "ul" && self.steps.is_match(&candidate.question) {
+                    candidate.score -= 10;
+                    hints.push("Use an ordered list for steps".to_string());
+                }
+            }
+            "table" => {
+                let rows = Selector::parse("tr").unwrap();
+                let header = Selector::parse("th").unwrap();
+                let count = answer.select(&rows).count() as u32;
+                candidate.format = SnippetFormat::Table;
+                candidate.items = count;
+                candidate.answer_words = word_count(&visible_text(answer));
+                candidate.score = 100;
+
+                if answer.select(&header).next().is_none() {
+                    candidate.score -= 20;
+                    hints.push("Add a header row with <th> cells".to_string());
+                }
+                if count < 3 {
+                    candidate.score -= 30;
+                    hints.push("A table answer needs at least 2 data rows".to_string());
+                }
+            }
+            _ => {
+                candidate.format = SnippetFormat::Other;
+                candidate.score = 20;
+                hints.push(
+                    "Replace the block below the heading with a 40-60 word paragraph, a list or a table"
+                        .to_string(),
+                );
+            }
+        }
+        candidate
+    }
+}
+
+impl Default for SnippetAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnalyzerStrategy for SnippetAnalyzer {
+    fn name(&self) -> &'static str {
+        "snippet_analyzer"
+    }
+
+    fn analyze(&self, content: &str) -> Result<AnalysisResult, AnalyzerError> {
+        let document = Html::parse_document(content);
+        let headings = Selector::parse("h2, h3, h4, h5, h6").unwrap();
+
+        let snippets = document
+            .select(&headings)
+            .filter_map(|heading| {
+                let question = collapse(&visible_text(heading));
+                self.is_question(&question)
+                    .then(|| self.candidate(question, answer_block(heading)))
+            })
+            .collect();
+
+        Ok(AnalysisResult {
+            snippets,
+            ..Default::default()
+        })
+    }
+}
+
+/// First content block after `heading`, looking inside wrapper elements;
+/// `None` when a heading comes first
+fn answer_block(heading: ElementRef) -> Option<ElementRef> {
+    let block = heading
+        .next_siblings()
+        .filter_map(ElementRef::wrap)
+        .find(|el| !visible_text(*el).trim().is_empty())?;
+    unwrap_block(block)
+}
+
+fn unwrap_block(block: ElementRef) -> Option<ElementRef> {
+    match block.value().name() {
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => None,
+        "div" | "section" | "article" => match block.children().find_map(ElementRef::wrap) {
+            Some(first) => unwrap_block(first),
+            None => Some(block),
+        },
+        _ => Some(block),
+    }
+}
+
+fn word_count(text: &str) -> u32 {
+    text.split_whitespace().count() as u32
+}
+
+fn collapse(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(n: usize) -> String {
+        vec!["word"; n].join(" ")
+    }
+
+    #[test]
+    fn test_answer_formats() {
+        let html = format!(
+            r#"<html><body>
+<h2>What is a backflow preventer?</h2><p>{}</p>
+<h2>How do I unclog a drain?</h2><ul><li>Boil water</li><li>Plunge</li><li>Use a snake</li></ul>
+<h3>Which pipe is best?</h3><div><table><tr><th>Pipe</th><th>Life</th></tr><tr><td>PEX</td><td>50 years</td></tr><tr><td>Copper</td><td>70 years</td></tr></table></div>
+<h2>Why hire a plumber?</h2><p>{}</p>
+<h2>Our services</h2><p>Drains.</p>
+<h2>Can I pay online?</h2><h3>Cards</h3>
+</body></html>"#,
+            words(50),
+            words(150)
+        );
+        let snippets = SnippetAnalyzer::new().analyze(&html).unwrap().snippets;
+
+        assert_eq!(snippets.len(), 5);
+        assert_eq!(snippets[0].format, SnippetFormat::Paragraph);
+        assert_eq!((snippets[0].score, snippets[0].answer_words), (100, 50));
+        assert!(snippets[0].hints.is_empty());
+
+        assert_eq!(
+            (snippets[1].format, snippets[1].items),
+            (SnippetFormat::List, 3)
+        );
+        assert_eq!(snippets[1].hints, vec!["Use an ordered list for steps"]);
+
+        assert_eq!(snippets[2].format, SnippetFormat::Table);
+        assert_eq!(snippets[2].score, 100);
+
+        assert_eq!(snippets[3].score, 40);
+        assert!(snippets[3].hints[0].starts_with("Tighten"));
+
+        assert_eq!(snippets[4].question, "Can I pay online?");
+        assert_eq!(snippets[4].format, SnippetFormat::Missing);
+        assert_eq!(snippets[4].score, 0);
+    }
+}
@@ -0,0 +1,277 @@
+//! Single-page app analyzer - detects empty client-rendered shells
+//!
+//! React/Vite/Vue builds often ship an `index.html` whose body is only a
+//! mount point, so crawlers that don't execute JavaScript see no content.
+//! Text embedded in hydration payloads (`__NEXT_DATA__`, `window.__NUXT__`,
+//! `__APOLLO_STATE__`, inline JSON) is recovered as a best-effort substitute.
+
+use crate::{
+    AnalysisResult, AnalyzerError, AnalyzerStrategy, ClientRendering, KeywordAnalyzer,
+    KeywordConfig, SpaConfig,
+};
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use serde_json::Value;
+
+/// Common SPA mount points, checked in order
+const MOUNT_POINTS: [&str; 8] = [
+    "#root",
+    "#app",
+    "#__next",
+    "#__nuxt",
+    "#___gatsby",
+    "#svelte",
+    "app-root",
+    "[data-reactroot]",
+];
+
+/// Elements whose text is never rendered as page content
+const HIDDEN_ELEMENTS: [&str; 4] = ["script", "style", "noscript", "template"];
+
+/// Analyzer for client-rendered HTML shells and hydration payloads
+pub struct SpaShellAnalyzer {
+    keywords: KeywordAnalyzer,
+    shell_max_words: u32,
+    global_assignment: Regex,
+    string_literal: Regex,
+}
+
+impl SpaShellAnalyzer {
+    pub fn new() -> Self {
+        Self::with_config(&SpaConfig::default(), &KeywordConfig::default())
+    }
+
+    /// Create analyzer from the `[spa]` section; recovered text is scored per `[keywords]`
+    pub fn with_config(config: &SpaConfig, keywords: &KeywordConfig) -> Self {
+        Self {
+            keywords: KeywordAnalyzer::with_config(keywords),
+            shell_max_words: config.shell_max_words,
+            global_assignment: Regex::new(r"window\.(__[A-Z][A-Z0-9_]*__)\s*=").unwrap(),
+            string_literal: Regex::new(r#""(?:[^"\\]|\\.)*""#).unwrap(),
+        }
+    }
+
+    fn visible_words(element: ElementRef) -> u32 {
+        element
+            .descendants()
+            .filter_map(|node| {
+                let text = node.value().as_text()?;
+                let hidden = node.ancestors().any(|a| {
+                    a.value()
+                        .as_element()
+                        .map(|e| HIDDEN_ELEMENTS.contains(&e.name()))
+                        .unwrap_or(false)
+                });
+                (!hidden).then(|| text.split_whitespace().count() as u32)
+            })
+            .sum()
+    }
+
+    /// Named hydration payloads and their script bodies
+    fn hydration_payloads<'a>(&self, document: &'a Html) -> Vec<(String, &'a str)> {
+        let script_selector = Selector::parse("script").unwrap();
+        let mut payloads = Vec::new();
+
+        for script in document.select(&script_selector) {
+            let Some(body) = script.first_child().and_then(|n| n.value().as_text()) else {
+                continue;
+            };
+            let body: &str = body;
+            let element = script.value();
+            let script_type = element.attr("type").unwrap_or("").to_lowercase();
+
+            if script_type == "application/ld+json" {
+                continue;
+            }
+
+            if let Some(id) = element.attr("id").filter(|id| id.starts_with("__")) {
+                payloads.push((id.to_string(), body));
+            } else if script_type == "application/json" {
+                let name = element
+                    .attr("id")
+                    .map(|id| format!("script#{}", id))
+                    .unwrap_or_else(|| "script[type=application/json]".to_string());
+                payloads.push((name, body));
+            } else if let Some(caps) = self.global_assignment.captures(body) {
+                let start = caps.get(0).unwrap().end();
+                payloads.push((caps[1].to_string(), &body[start..]));
+            }
+        }
+
+        payloads
+    }
+
+    /// Human-readable strings from a payload, JSON first, string literals otherwise
+    fn payload_text(&self, payload: &str) -> Vec<String> {
+        let trimmed = payload.trim().trim_end_matches(';').trim_end();
+        let mut strings = Vec::new();
+
+        match serde_json::from_str::<Value>(trimmed) {
+            Ok(value) => collect_strings(&value, &mut strings),
+            // Nuxt 2 serializes state as a JS function call, not JSON
+            Err(_) => {
+                for m in self.string_literal.find_iter(trimmed) {
+                    if let Ok(s) = serde_json::from_str::<String>(m.as_str()) {
+                        strings.push(s);
+                    }
+                }
+            }
+        }
+
+        strings.retain(|s| looks_like_prose(s));
+        strings
+    }
+}
+
+impl Default for SpaShellAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnalyzerStrategy for SpaShellAnalyzer {
+    fn name(&self) -> &'static str {
+        "spa_shell_analyzer"
+    }
+
+    fn analyze(&self, content: &str) -> Result<AnalysisResult, AnalyzerError> {
+        let document = Html::parse_document(content);
+        let body_selector = Selector::parse("body").unwrap();
+        let Some(body) = document.select(&body_selector).next() else {
+            return Ok(AnalysisResult::default());
+        };
+
+        let visible_words = Self::visible_words(body);
+
+        let mount = MOUNT_POINTS.iter().find_map(|selector| {
+            let sel = Selector::parse(selector).ok()?;
+            document.select(&sel).next().map(|el| (*selector, el))
+        });
+        let has_bundle = Selector::parse("script[src], script[type=module]")
+            .map(|sel| document.select(&sel).next().is_some())
+            .unwrap_or(false);
+
+        let empty_shell = visible_words < self.shell_max_words
+            && match mount {
+                Some((_, element)) => Self::visible_words(element) == 0,
+                None => has_bundle,
+            };
+
+        let mut rendering = ClientRendering {
+            empty_shell,
+            mount_point: mount.map(|(selector, _)| selector.to_string()),
+            visible_words,
+            hydration_sources: Vec::new(),
+        };
+
+        let mut recovered: Vec<String> = Vec::new();
+        for (name, payload) in self.hydration_payloads(&document) {
+            let strings = self.payload_text(payload);
+            if strings.is_empty() {
+                continue;
+            }
+            rendering.hydration_sources.push(name);
+            for s in strings {
+                if !recovered.contains(&s) {
+                    recovered.push(s);
+                }
+            }
+        }
+
+        if recovered.is_empty() {
+            return Ok(AnalysisResult {
+                rendering,
+                ..Default::default()
+            });
+        }
+
+        let mut result = self.keywords.analyze_text(recovered.join("\n"));
+        // Only stand in for page text when the static HTML has none
+        if !empty_shell {
+            result.raw_text = None;
+        }
+        result.rendering = rendering;
+
+        Ok(result)
+    }
+}
+
+fn collect_strings(value: &Value, out: &mut Vec<String>) {
+    match value {
+        Value::String(s) => out.push(s.clone()),
+        Value::Array(items) => items.iter().for_each(|v| collect_strings(v, out)),
+        Value::Object(map) => map.values().for_each(|v| collect_strings(v, out)),
+        _ => {}
+    }
+}
+
+/// Whether a payload string reads like page copy rather than an id, URL or markup
+fn looks_like_prose(s: &str) -> bool {
+    let s = s.trim();
+    if s.split_whitespace().count() < 3 || s.contains("://") || s.starts_with(['<', '{', '[', '/'])
+    {
+        return false;
+    }
+
+    let visible: Vec<char> = s.chars().filter(|c| !c.is_whitespace()).collect();
+    let letters = visible.iter().filter(|c| c.is_alphabetic()).count();
+    letters * 10 >= visible.len() * 7
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_react_shell() {
+        let html = r#"<!DOCTYPE html>
+<html lang="en">
+<head><title>React App</title></head>
+<body>
+  <noscript>You need to enable JavaScript to run this app.</noscript>
+  <div id="root"></div>
+  <script type="module" src="/assets/index-4f2a.js"></script>
+</body>
+</html>"#;
+        let result = SpaShellAnalyzer::new().analyze(html).unwrap();
+
+        assert!(result.rendering.empty_shell);
+        assert_eq!(result.rendering.mount_point.as_deref(), Some("#root"));
+        assert_eq!(result.rendering.visible_words, 0);
+        assert!(result.keywords.is_empty());
+    }
+
+    #[test]
+    fn test_server_rendered_next_page() {
+        let html = r#"<html><body>
+<div id="__next"><h1>Emergency Plumbing</h1><p>Licensed plumbers available around the clock across Denver and the surrounding suburbs for leaks and repairs.</p></div>
+<script id="__NEXT_DATA__" type="application/json">{"props":{"pageProps":{"intro":"Licensed plumbers available around the clock","slug":"emergency"}},"page":"/services/[slug]"}</script>
+</body></html>"#;
+        let result = SpaShellAnalyzer::new().analyze(html).unwrap();
+
+        assert!(!result.rendering.empty_shell);
+        assert_eq!(result.rendering.hydration_sources, vec!["__NEXT_DATA__"]);
+        assert!(result.keywords.iter().any(|k| k.word == "plumbers"));
+        assert!(result.raw_text.is_none());
+    }
+
+    #[test]
+    fn test_shell_with_state_payloads() {
+        let html = r#"<html><body>
+<div id="app"></div>
+<script>window.__APOLLO_STATE__={"Product:1":{"name":"Handmade ceramic coffee mugs","price":"24.00"}};</script>
+<script>window.__NUXT__=(function(a){return {data:[{title:"Small batch pottery studio in Portland"}]}}("x"));</script>
+</body></html>"#;
+        let result = SpaShellAnalyzer::new().analyze(html).unwrap();
+
+        assert!(result.rendering.empty_shell);
+        assert_eq!(
+            result.rendering.hydration_sources,
+            vec!["__APOLLO_STATE__", "__NUXT__"]
+        );
+        let text = result.raw_text.unwrap();
+        assert!(text.contains("ceramic coffee mugs"));
+        assert!(text.contains("pottery studio"));
+        assert!(!text.contains("24.00"));
+    }
+}
@@ -0,0 +1,103 @@
+//! Svelte source analyzer - reads `<svelte:head>` blocks
+
+use crate::head_tags::HeadCollector;
+use crate::{AnalysisResult, AnalyzerError, AnalyzerStrategy};
+use regex::Regex;
+use std::path::Path;
+
+/// Analyzer for `.svelte` components (including SvelteKit `+page.svelte`)
+pub struct SvelteHeadAnalyzer {
+    head_block: Regex,
+    script_or_style: Regex,
+}
+
+impl SvelteHeadAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            head_block: Regex::new(r"(?s)<svelte:head>(.*?)</svelte:head>").unwrap(),
+            script_or_style: Regex::new(r"(?s)<script\b.*?</script>|<style\b.*?</style>").unwrap(),
+        }
+    }
+}
+
+impl Default for SvelteHeadAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnalyzerStrategy for SvelteHeadAnalyzer {
+    fn name(&self) -> &'static str {
+        "svelte_head_analyzer"
+    }
+
+    fn accepts(&self, path: &Path) -> bool {
+        path.extension().map(|ext| ext == "svelte").unwrap_or(false)
+    }
+
+    fn analyze(&self, content: &str) -> Result<AnalysisResult, AnalyzerError> {
+        let mut collector = HeadCollector::new(content);
+
+        for caps in self.head_block.captures_iter(content) {
+            let inner = caps.get(1).unwrap();
+            collector.html_fragment(inner.start(), inner.as_str());
+        }
+
+        let markup = self.script_or_style.replace_all(content, "");
+        collector.template_signals(&markup);
+
+        Ok(AnalysisResult {
+            existing_seo: collector.seo,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_svelte_head() {
+        let source = r#"<script>
+  export let data;
+</script>
+
+<svelte:head>
+  <title>{data.post.title} | Blog</title>
+  <meta name="description" content="Latest posts from the team" />
+  <link rel="canonical" href="https://blog.example.com/" />
+  {@html `<script type="application/ld+json">${JSON.stringify(data.schema)}</script>`}
+</svelte:head>
+
+<h1>{data.post.title}</h1>
+<img src={data.post.cover} alt="" />
+"#;
+        let seo = SvelteHeadAnalyzer::new()
+            .analyze(source)
+            .unwrap()
+            .existing_seo;
+
+        assert!(seo.has_title);
+        assert_eq!(seo.title, None);
+        assert_eq!(
+            seo.description.as_deref(),
+            Some("Latest posts from the team")
+        );
+        assert!(seo.has_canonical);
+        assert!(seo.has_schema);
+        assert_eq!(seo.h1_count, 1);
+        assert_eq!(seo.img_without_alt, 1);
+
+        let canonical = seo
+            .sources
+            .iter()
+            .find(|s| s.element == "link[rel=canonical]")
+            .unwrap();
+        assert_eq!(canonical.line, 8);
+        assert_eq!(
+            canonical.value.as_deref(),
+            Some("https://blog.example.com/")
+        );
+    }
+}
@@ -0,0 +1,230 @@
+//! Vue and Nuxt source analyzers
+//!
+//! Vue single-file components declare head tags through Unhead composables
+//! (`useHead`, `useSeoMeta` and their server variants) or Nuxt's `<Head>`
+//! components. Site-wide defaults live in `nuxt.config.*` under `app.head`.
+
+use crate::head_tags::HeadCollector;
+use crate::js_object::{find_closing, parse_object_at};
+use crate::{AnalysisResult, AnalyzerError, AnalyzerStrategy};
+use regex::Regex;
+use std::path::Path;
+
+/// Analyzer for `.vue` single-file components
+pub struct VueHeadAnalyzer {
+    composable: Regex,
+    template: Regex,
+    head_component: Regex,
+}
+
+impl VueHeadAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            composable: Regex::new(r"\b(useHead|useServerHead|useSeoMeta|useServerSeoMeta)\s*\(")
+                .unwrap(),
+            template: Regex::new(r"(?s)<template[^>]*>(.*)</template>").unwrap(),
+            head_component: Regex::new(r"(?s)<Head>(.*?)</Head>").unwrap(),
+        }
+    }
+}
+
+impl Default for VueHeadAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnalyzerStrategy for VueHeadAnalyzer {
+    fn name(&self) -> &'static str {
+        "vue_head_analyzer"
+    }
+
+    fn accepts(&self, path: &Path) -> bool {
+        path.extension().map(|ext| ext == "vue").unwrap_or(false)
+    }
+
+    fn analyze(&self, content: &str) -> Result<AnalysisResult, AnalyzerError> {
+        let mut collector = HeadCollector::new(content);
+
+        for caps in self.composable.captures_iter(content) {
+            let call = caps.get(0).unwrap();
+            let open = call.end() - 1;
+            let Some(close) = find_closing(content, open) else {
+                continue;
+            };
+            // First object literal inside the call, also covers `useHead(() => ({...}))`
+            let Some(brace) = content[open..close].find('{').map(|i| open + i) else {
+                continue;
+            };
+            let Some((head, _)) = parse_object_at(content, brace) else {
+                continue;
+            };
+
+            match &caps[1] {
+                "useSeoMeta" | "useServerSeoMeta" => collector.seo_meta(&head),
+                _ => collector.unhead(&head),
+            }
+        }
+
+        if let Some(template) = self.template.captures(content).and_then(|c| c.get(1)) {
+            for block in self.head_component.captures_iter(template.as_str()) {
+                let inner = block.get(1).unwrap();
+                collector.html_fragment(template.start() + inner.start(), inner.as_str());
+            }
+            collector.template_signals(template.as_str());
+        }
+
+        Ok(AnalysisResult {
+            language: collector.language,
+            existing_seo: collector.seo,
+            ..Default::default()
+        })
+    }
+}
+
+/// Analyzer for site-wide head defaults in `nuxt.config.*`
+pub struct NuxtConfigAnalyzer {
+    config_object: Regex,
+}
+
+impl NuxtConfigAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            config_object: Regex::new(r"(defineNuxtConfig\s*\(|export\s+default)\s*\{").unwrap(),
+        }
+    }
+}
+
+impl Default for NuxtConfigAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnalyzerStrategy for NuxtConfigAnalyzer {
+    fn name(&self) -> &'static str {
+        "nuxt_config_analyzer"
+    }
+
+    fn accepts(&self, path: &Path) -> bool {
+        path.file_name()
+            .and_then(|n| n.to_str())
+            .map(|n| matches!(n, "nuxt.config.ts" | "nuxt.config.js" | "nuxt.config.mjs"))
+            .unwrap_or(false)
+    }
+
+    fn analyze(&self, content: &str) -> Result<AnalysisResult, AnalyzerError> {
+        let Some(caps) = self.config_object.captures(content) else {
+            return Ok(AnalysisResult::default());
+        };
+        let Some((config, _)) = parse_object_at(content, caps.get(0).unwrap().end() - 1) else {
+            return Ok(AnalysisResult::default());
+        };
+
+        let mut collector = HeadCollector::new(content);
+
+        // Nuxt 3 nests head under `app`, Nuxt 2 uses a top-level `head`
+        let head = config
+            .get("app")
+            .and_then(|app| app.get("head"))
+            .or_else(|| config.get("head"));
+        if let Some(head) = head {
+            collector.unhead(head);
+        }
+
+        let mut seo = collector.seo;
+
+        // Nuxt 3 renders charset and viewport unless explicitly overridden
+        if caps[1].starts_with("defineNuxtConfig") {
+            seo.has_charset = true;
+            seo.has_viewport = true;
+        }
+
+        Ok(AnalysisResult {
+            language: collector.language,
+            existing_seo: seo,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vue_use_head_and_seo_meta() {
+        let source = r#"<script setup lang="ts">
+const { data } = await useFetch('/api/page')
+
+useHead({
+  title: 'Pricing',
+  htmlAttrs: { lang: 'en-US' },
+  link: [{ rel: 'canonical', href: 'https://acme.com/pricing' }],
+  script: [{ type: 'application/ld+json', children: JSON.stringify(schema) }],
+})
+
+useSeoMeta({
+  description: () => data.value.summary,
+  ogTitle: 'Pricing | Acme',
+})
+</script>
+
+<template>
+  <main>
+    <h1>Pricing</h1>
+    <img src="/plans.png" :alt="altText">
+    <img src="/logo.png">
+  </main>
+</template>
+"#;
+        let result = VueHeadAnalyzer::new().analyze(source).unwrap();
+        let seo = &result.existing_seo;
+
+        assert_eq!(seo.title.as_deref(), Some("Pricing"));
+        assert!(seo.has_description);
+        assert_eq!(seo.description, None);
+        assert!(seo.has_canonical && seo.has_schema && seo.has_og_tags);
+        assert_eq!(seo.h1_count, 1);
+        assert_eq!(seo.img_without_alt, 1);
+        assert_eq!(result.language.as_deref(), Some("en"));
+
+        let title = seo.sources.iter().find(|s| s.element == "title").unwrap();
+        assert_eq!(title.line, 5);
+    }
+
+    #[test]
+    fn test_nuxt_config_app_head() {
+        let source = r#"export default defineNuxtConfig({
+  modules: ['@nuxt/image'],
+  app: {
+    head: {
+      title: 'Acme Dental',
+      meta: [
+        { name: 'description', content: 'Family dentistry in Austin' },
+        { property: 'og:site_name', content: 'Acme Dental' },
+      ],
+    },
+  },
+})
+"#;
+        let analyzer = NuxtConfigAnalyzer::new();
+        assert!(analyzer.accepts(Path::new("nuxt.config.ts")));
+
+        let seo = analyzer.analyze(source).unwrap().existing_seo;
+        assert_eq!(seo.title.as_deref(), Some("Acme Dental"));
+        assert_eq!(
+            seo.description.as_deref(),
+            Some("Family dentistry in Austin")
+        );
+        assert_eq!(seo.site_name.as_deref(), Some("Acme Dental"));
+        assert!(seo.has_og_tags && seo.has_charset && seo.has_viewport);
+
+        let description = seo
+            .sources
+            .iter()
+            .find(|s| s.element == "meta[name=description]")
+            .unwrap();
+        assert_eq!(description.line, 7);
+    }
+}
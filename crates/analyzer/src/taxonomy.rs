@@ -0,0 +1,240 @@
+//! Hierarchical business taxonomy
+//!
+//! `BusinessType` names the broad category of a site; the taxonomy refines it
+//! into subtypes such as LocalBusiness → HomeAndConstructionBusiness → Plumber,
+//! each mapped to a schema.org type. The built-in tree lives in
+//! `data/taxonomy.toml` and can be extended with user-defined nodes.
+
+use crate::{AnalyzerError, BusinessType};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const BUILTIN_TAXONOMY: &str = include_str!("../data/taxonomy.toml");
+
+/// Maximum nesting depth, guards against parent cycles
+const MAX_DEPTH: usize = 16;
+
+/// A node of the business taxonomy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaxonomyNode {
+    /// Unique identifier, e.g. `plumber`
+    pub id: String,
+
+    /// Parent node id; root nodes have none
+    #[serde(default)]
+    pub parent: Option<String>,
+
+    /// Category of a root node; children inherit their root's category
+    #[serde(default)]
+    pub category: Option<BusinessType>,
+
+    /// Most specific schema.org type, e.g. `Plumber`
+    pub schema_type: String,
+
+    /// Near-unambiguous indicators, weighted double
+    #[serde(default)]
+    pub strong: Vec<String>,
+
+    /// Indicator words and phrases
+    #[serde(default)]
+    pub indicators: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct TaxonomyFile {
+    #[serde(default)]
+    node: Vec<TaxonomyNode>,
+}
+
+/// Business taxonomy, validated so every node resolves to a root category
+#[derive(Debug, Clone)]
+pub struct Taxonomy {
+    nodes: Vec<TaxonomyNode>,
+}
+
+impl Taxonomy {
+    /// The built-in taxonomy
+    pub fn builtin() -> Self {
+        Self::from_toml(BUILTIN_TAXONOMY).expect("built-in taxonomy is valid")
+    }
+
+    /// Parse a taxonomy from TOML (`[[node]]` tables)
+    pub fn from_toml(content: &str) -> Result<Self, AnalyzerError> {
+        let file: TaxonomyFile =
+            toml::from_str(content).map_err(|e| AnalyzerError::InvalidTaxonomy(e.to_string()))?;
+        let taxonomy = Self { nodes: file.node };
+        taxonomy.validate()?;
+        Ok(taxonomy)
+    }
+
+    /// Load the built-in taxonomy extended with the nodes in `path`
+    pub fn load(path: &Path) -> Result<Self, AnalyzerError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| AnalyzerError::FileRead(path.to_path_buf(), e))?;
+        let file: TaxonomyFile =
+            toml::from_str(&content).map_err(|e| AnalyzerError::InvalidTaxonomy(e.to_string()))?;
+
+        let mut taxonomy = Self::builtin();
+        taxonomy.extend(file.node)?;
+        Ok(taxonomy)
+    }
+
+    /// Add user-defined nodes; a node with an existing id replaces it
+    pub fn extend(&mut self, nodes: Vec<TaxonomyNode>) -> Result<(), AnalyzerError> {
+        for node in nodes {
+            match self.nodes.iter_mut().find(|n| n.id == node.id) {
+                Some(existing) => *existing = node,
+                None => self.nodes.push(node),
+            }
+        }
+        self.validate()
+    }
+
+    pub fn nodes(&self) -> &[TaxonomyNode] {
+        &self.nodes
+    }
+
+    pub fn node(&self, id: &str) -> Option<&TaxonomyNode> {
+        self.nodes.iter().find(|n| n.id == id)
+    }
+
+    /// Nodes from the root down to `id`
+    pub fn path(&self, id: &str) -> Vec<&TaxonomyNode> {
+        let mut path = Vec::new();
+        let mut current = self.node(id);
+        while let Some(node) = current {
+            path.push(node);
+            current = node.parent.as_deref().and_then(|p| self.node(p));
+        }
+        path.reverse();
+        path
+    }
+
+    /// Category of the root `id` descends from
+    pub fn category(&self, id: &str) -> BusinessType {
+        self.path(id)
+            .first()
+            .and_then(|root| root.category.clone())
+            .unwrap_or_default()
+    }
+
+    fn validate(&self) -> Result<(), AnalyzerError> {
+        let invalid = |msg: String| Err(AnalyzerError::InvalidTaxonomy(msg));
+
+        for (i, node) in self.nodes.iter().enumerate() {
+            if self.nodes[..i].iter().any(|n| n.id == node.id) {
+                return invalid(format!("duplicate node '{}'", node.id));
+            }
+            if node.schema_type.trim().is_empty() {
+                return invalid(format!("node '{}' has no schema_type", node.id));
+            }
+
+            match (&node.parent, &node.category) {
+                (None, None) | (None, Some(BusinessType::Unknown)) => {
+                    return invalid(format!("root node '{}' needs a category", node.id));
+                }
+                (Some(parent), _) if self.node(parent).is_none() => {
+                    return invalid(format!(
+                        "node '{}' has unknown parent '{}'",
+                        node.id, parent
+                    ));
+                }
+                (Some(_), Some(_)) => {
+                    return invalid(format!(
+                        "node '{}' sets a category but is not a root",
+                        node.id
+                    ));
+                }
+                _ => {}
+            }
+
+            let mut depth = 0;
+            let mut current = node;
+            while let Some(parent) = current.parent.as_deref().and_then(|p| self.node(p)) {
+                depth += 1;
+                if depth > MAX_DEPTH {
+                    return invalid(format!("node '{}' has a parent cycle", node.id));
+                }
+                current = parent;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Taxonomy {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_paths() {
+        let taxonomy = Taxonomy::builtin();
+
+        let path: Vec<_> = taxonomy
+            .path("plumber")
+            .iter()
+            .map(|n| n.schema_type.as_str())
+            .collect();
+        assert_eq!(
+            path,
+            vec!["LocalBusiness", "HomeAndConstructionBusiness", "Plumber"]
+        );
+        assert_eq!(taxonomy.category("hotel"), BusinessType::LocalBusiness);
+        assert_eq!(taxonomy.category("dentist"), BusinessType::Healthcare);
+        assert_eq!(taxonomy.node("attorney").unwrap().schema_type, "Attorney");
+    }
+
+    #[test]
+    fn test_user_subtypes() {
+        let user = r#"
+[[node]]
+id = "pool_service"
+parent = "home_and_construction"
+schema_type = "HomeAndConstructionBusiness"
+strong = ["pool cleaning"]
+"#;
+        let file: TaxonomyFile = toml::from_str(user).unwrap();
+        let mut taxonomy = Taxonomy::builtin();
+        taxonomy.extend(file.node).unwrap();
+
+        assert_eq!(
+            taxonomy.category("pool_service"),
+            BusinessType::LocalBusiness
+        );
+        assert_eq!(taxonomy.path("pool_service").len(), 3);
+    }
+
+    #[test]
+    fn test_invalid_taxonomies() {
+        let orphan = "[[node]]\nid = \"x\"\nparent = \"missing\"\nschema_type = \"Thing\"\n";
+        assert!(Taxonomy::from_toml(orphan).is_err());
+
+        let rootless = "[[node]]\nid = \"x\"\nschema_type = \"Thing\"\n";
+        assert!(Taxonomy::from_toml(rootless).is_err());
+
+        let cycle = r#"
+[[node]]
+id = "a"
+parent = "b"
+schema_type = "Thing"
+
+[[node]]
+id = "b"
+parent = "a"
+schema_type = "Thing"
+"#;
+        assert!(Taxonomy::from_toml(cycle).is_err());
+
+        let typo =
+            "[[node]]\nid = \"x\"\ncategory = \"Blog\"\nschema_type = \"Blog\"\nindicator = []\n";
+        assert!(Taxonomy::from_toml(typo).is_err());
+    }
+}
@@ -0,0 +1,550 @@
+//! Trust audit - site-level E-E-A-T signals
+//!
+//! Checks that a site has the pages and markup visitors and quality raters
+//! look for before trusting it: about, contact, privacy and terms pages,
+//! author bios on articles, an address and phone for local businesses, one
+//! organization name, and assets served over HTTPS. Each item records the
+//! pages it was found on and the pages that lack it.
+
+use crate::canonical_audit::url_path;
+use crate::{is_html_file, BusinessType, FileAnalysis, ENTITY_MIN_CONFIDENCE};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Separators between a page title and the site name
+const TITLE_SEPARATORS: [&str; 5] = [" | ", " - ", " – ", " — ", " · "];
+
+/// Legal suffixes ignored when comparing organization names
+const LEGAL_SUFFIXES: [&str; 8] = ["inc", "llc", "ltd", "co", "corp", "gmbh", "plc", "company"];
+
+/// Insecure URLs quoted in an item's detail before the rest are counted
+const MAX_URLS_LISTED: usize = 3;
+
+/// A trust signal checked across the site
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum TrustSignal {
+    AboutPage,
+    ContactPage,
+    PrivacyPolicy,
+    TermsPage,
+    /// Article authors linked to a profile page
+    AuthorBios,
+    /// Postal address, for local businesses
+    PhysicalAddress,
+    /// Phone number, for local businesses
+    Phone,
+    /// Every page uses the same organization name
+    ConsistentName,
+    /// No `http://` CSS, JS, font or image URLs
+    HttpsAssets,
+}
+
+impl TrustSignal {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::AboutPage => "about page",
+            Self::ContactPage => "contact page",
+            Self::PrivacyPolicy => "privacy policy",
+            Self::TermsPage => "terms of service",
+            Self::AuthorBios => "author bios on articles",
+            Self::PhysicalAddress => "physical address",
+            Self::Phone => "phone number",
+            Self::ConsistentName => "consistent organization name",
+            Self::HttpsAssets => "HTTPS asset URLs",
+        }
+    }
+
+    /// Share of the trust score, out of 100 for all signals
+    fn weight(&self) -> u32 {
+        match self {
+            Self::AboutPage => 10,
+            Self::ContactPage => 15,
+            Self::PrivacyPolicy => 15,
+            Self::TermsPage => 10,
+            Self::AuthorBios => 10,
+            Self::PhysicalAddress => 10,
+            Self::Phone => 10,
+            Self::ConsistentName => 10,
+            Self::HttpsAssets => 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrustStatus {
+    Present,
+    /// Found on some pages but missing or wrong on others
+    Partial,
+    Missing,
+    /// Doesn't apply to this site, e.g. author bios without articles
+    NotApplicable,
+}
+
+/// One line of the trust scorecard
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrustItem {
+    pub signal: TrustSignal,
+    pub status: TrustStatus,
+
+    /// Pages the signal was found on
+    pub found: Vec<PathBuf>,
+
+    /// Pages that should have it but don't
+    pub missing: Vec<PathBuf>,
+
+    pub detail: Option<String>,
+}
+
+/// Site-wide trust scorecard
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrustAudit {
+    pub items: Vec<TrustItem>,
+
+    /// Weighted share of the applicable signals present (0-100)
+    pub score: u32,
+}
+
+impl TrustAudit {
+    /// Audit the pages in `files`, served from `root`; address and phone
+    /// only count for local business types
+    pub fn new(root: &Path, files: &[FileAnalysis], business_type: BusinessType) -> Self {
+        let pages: Vec<&FileAnalysis> = files.iter().filter(|f| is_html_file(&f.path)).collect();
+
+        let mut items = vec![
+            page_item(root, &pages, TrustSignal::AboutPage),
+            page_item(root, &pages, TrustSignal::ContactPage),
+            page_item(root, &pages, TrustSignal::PrivacyPolicy),
+            page_item(root, &pages, TrustSignal::TermsPage),
+            author_bios(&pages),
+        ];
+        if business_type.is_local() {
+            items.push(contact_item(files, TrustSignal::PhysicalAddress));
+            items.push(contact_item(files, TrustSignal::Phone));
+        } else {
+            items.push(not_applicable(TrustSignal::PhysicalAddress));
+            items.push(not_applicable(TrustSignal::Phone));
+        }
+        items.push(organization_name(&pages));
+        items.push(https_assets(files));
+
+        let (earned, possible) = items.iter().fold((0, 0), |(earned, possible), item| {
+            let weight = item.signal.weight();
+            match item.status {
+                TrustStatus::Present => (earned + weight * 2, possible + weight * 2),
+                TrustStatus::Partial => (earned + weight, possible + weight * 2),
+                TrustStatus::Missing => (earned, possible + weight * 2),
+                TrustStatus::NotApplicable => (earned, possible),
+            }
+        });
+        let score = (earned * 100).checked_div(possible).unwrap_or(0);
+
+        Self { items, score }
+    }
+
+    /// Items that are missing or only partly present
+    pub fn gaps(&self) -> impl Iterator<Item = &TrustItem> {
+        self.items
+            .iter()
+            .filter(|i| matches!(i.status, TrustStatus::Missing | TrustStatus::Partial))
+    }
+
+    pub fn item(&self, signal: TrustSignal) -> Option<&TrustItem> {
+        self.items.iter().find(|i| i.signal == signal)
+    }
+}
+
+fn trust_item(signal: TrustSignal, found: Vec<PathBuf>, missing: Vec<PathBuf>) -> TrustItem {
+    let status = match (found.is_empty(), missing.is_empty()) {
+        (false, true) => TrustStatus::Present,
+        (false, false) => TrustStatus::Partial,
+        (true, _) => TrustStatus::Missing,
+    };
+    TrustItem {
+        signal,
+        status,
+        found,
+        missing,
+        detail: None,
+    }
+}
+
+fn not_applicable(signal: TrustSignal) -> TrustItem {
+    TrustItem {
+        signal,
+        status: TrustStatus::NotApplicable,
+        found: Vec::new(),
+        missing: Vec::new(),
+        detail: None,
+    }
+}
+
+/// About, contact, privacy or terms page, recognized by URL slug or title
+fn page_item(root: &Path, pages: &[&FileAnalysis], signal: TrustSignal) -> TrustItem {
+    let found = pages
+        .iter()
+        .filter(|page| {
+            let path = url_path(root, &page.path);
+            let slug = path
+                .trim_end_matches('/')
+                .rsplit('/')
+                .next()
+                .unwrap_or("")
+                .trim_end_matches(".html")
+                .trim_end_matches(".htm")
+                .to_lowercase();
+            let title = page
+                .result
+                .existing_seo
+                .title
+                .as_deref()
+                .unwrap_or("")
+                .to_lowercase();
+            is_trust_page(signal, &slug, &title)
+        })
+        .map(|page| page.path.clone())
+        .collect();
+    trust_item(signal, found, Vec::new())
+}
+
+fn is_trust_page(signal: TrustSignal, slug: &str, title: &str) -> bool {
+    match signal {
+        TrustSignal::AboutPage => {
+            slug.starts_with("about")
+                || matches!(slug, "company" | "who-we-are" | "our-story")
+                || title.starts_with("about")
+        }
+        TrustSignal::ContactPage => slug.starts_with("contact") || title.starts_with("contact"),
+        TrustSignal::PrivacyPolicy => slug.contains("privacy") || title.starts_with("privacy"),
+        TrustSignal::TermsPage => {
+            slug.starts_with("terms")
+                || matches!(slug, "tos" | "legal" | "conditions")
+                || title.starts_with("terms")
+        }
+        _ => false,
+    }
+}
+
+/// Article pages whose authors link to a profile page
+fn author_bios(pages: &[&FileAnalysis]) -> TrustItem {
+    let articles: Vec<(&FileAnalysis, bool)> = pages
+        .iter()
+        .filter_map(|page| {
+            let article = page.result.article.as_ref()?;
+            (!article.authors.is_empty() || article.published.is_some())
+                .then(|| (*page, article.authors.iter().any(|a| a.url.is_some())))
+        })
+        .collect();
+    if articles.is_empty() {
+        return not_applicable(TrustSignal::AuthorBios);
+    }
+
+    let (with_bio, without_bio): (Vec<_>, Vec<_>) = articles.into_iter().partition(|(_, bio)| *bio);
+    let paths = |pages: Vec<(&FileAnalysis, bool)>| {
+        pages.into_iter().map(|(p, _)| p.path.clone()).collect()
+    };
+    trust_item(TrustSignal::AuthorBios, paths(with_bio), paths(without_bio))
+}
+
+/// Confident address or phone sightings, by page
+fn contact_item(files: &[FileAnalysis], signal: TrustSignal) -> TrustItem {
+    let found = files
+        .iter()
+        .filter(|file| {
+            let entities = &file.result.entities;
+            let confident = |confidence: f32| confidence >= ENTITY_MIN_CONFIDENCE;
+            match signal {
+                TrustSignal::PhysicalAddress => {
+                    entities.addresses.iter().any(|e| confident(e.confidence))
+                }
+                _ => entities.phones.iter().any(|e| confident(e.confidence)),
+            }
+        })
+        .map(|file| file.path.clone())
+        .collect();
+    trust_item(signal, found, Vec::new())
+}
+
+/// Organization name from `og:site_name`, else the title segment most
+/// pages share; pages spelling it differently are flagged
+fn organization_name(pages: &[&FileAnalysis]) -> TrustItem {
+    let candidates: Vec<(&FileAnalysis, Vec<&str>)> = pages
+        .iter()
+        .map(|page| {
+            let seo = &page.result.existing_seo;
+            let names = match (&seo.site_name, &seo.title) {
+                (Some(name), _) => vec![name.as_str()],
+                (None, Some(title)) => title_segments(title),
+                (None, None) => Vec::new(),
+            };
+            (*page, names)
+        })
+        .collect();
+
+    let mut counts: BTreeMap<String, u32> = BTreeMap::new();
+    for (_, names) in &candidates {
+        let mut keys: Vec<String> = names.iter().map(|n| name_key(n)).collect();
+        keys.dedup();
+        for key in keys.into_iter().filter(|k| !k.is_empty()) {
+            *counts.entry(key).or_default() += 1;
+        }
+    }
+    let Some(site_key) = counts
+        .iter()
+        .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(key, _)| key.clone())
+    else {
+        return trust_item(TrustSignal::ConsistentName, Vec::new(), Vec::new());
+    };
+
+    // Spelling of the name on each page that names the organization
+    let mut spellings: BTreeMap<&str, Vec<PathBuf>> = BTreeMap::new();
+    let mut mismatched = Vec::new();
+    for (page, names) in &candidates {
+        match names.iter().find(|n| name_key(n) == site_key) {
+            Some(name) => spellings.entry(name).or_default().push(page.path.clone()),
+            // A declared site name that isn't the organization's
+            None if page.result.existing_seo.site_name.is_some() => {
+                mismatched.push((names[0], page.path.clone()))
+            }
+            None => {}
+        }
+    }
+
+    let Some((&name, _)) = spellings.iter().max_by_key(|(_, pages)| pages.len()) else {
+        return trust_item(TrustSignal::ConsistentName, Vec::new(), Vec::new());
+    };
+    let found = spellings.remove(name).unwrap_or_default();
+    let mut variants: Vec<String> = Vec::new();
+    let mut missing = Vec::new();
+    for (spelling, pages) in spellings {
+        variants.push(format!("\"{}\" ({})", spelling, pages.len()));
+        missing.extend(pages);
+    }
+    for (spelling, page) in mismatched {
+        variants.push(format!("\"{}\" (1)", spelling));
+        missing.push(page);
+    }
+
+    let mut item = trust_item(TrustSignal::ConsistentName, found, missing);
+    item.detail = Some(if variants.is_empty() {
+        format!("\"{}\"", name)
+    } else {
+        format!("\"{}\", also {}", name, variants.join(", "))
+    });
+    item
+}
+
+/// First and last segments of `Page | Site` style titles
+fn title_segments(title: &str) -> Vec<&str> {
+    let Some(separator) = TITLE_SEPARATORS.iter().find(|s| title.contains(*s)) else {
+        return Vec::new();
+    };
+    let segments: Vec<&str> = title.split(separator).map(str::trim).collect();
+    let mut ends = vec![segments[0], segments[segments.len() - 1]];
+    ends.dedup();
+    ends
+}
+
+/// Lowercase words without punctuation and legal suffixes
+pub(crate) fn name_key(name: &str) -> String {
+    let lower = name.to_lowercase();
+    let mut words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    while words.len() > 1 && LEGAL_SUFFIXES.contains(words.last().unwrap()) {
+        words.pop();
+    }
+    words.join(" ")
+}
+
+/// Pages referencing `http://` assets
+fn https_assets(files: &[FileAnalysis]) -> TrustItem {
+    let measured: Vec<(&FileAnalysis, Vec<&str>)> = files
+        .iter()
+        .filter(|f| !f.result.performance.is_empty())
+        .map(|file| {
+            let insecure = file
+                .result
+                .performance
+                .iter()
+                .flat_map(|p| &p.assets)
+                .map(|a| a.url.as_str())
+                .filter(|url| url.to_ascii_lowercase().starts_with("http://"))
+                .collect();
+            (file, insecure)
+        })
+        .collect();
+    if measured.is_empty() {
+        return not_applicable(TrustSignal::HttpsAssets);
+    }
+
+    let mut urls: Vec<&str> = measured
+        .iter()
+        .flat_map(|(_, urls)| urls.iter().copied())
+        .collect();
+    urls.sort_unstable();
+    urls.dedup();
+    let (secure, insecure): (Vec<_>, Vec<_>) =
+        measured.into_iter().partition(|(_, urls)| urls.is_empty());
+
+    let mut item = trust_item(
+        TrustSignal::HttpsAssets,
+        secure.into_iter().map(|(f, _)| f.path.clone()).collect(),
+        insecure.into_iter().map(|(f, _)| f.path.clone()).collect(),
+    );
+    if !urls.is_empty() {
+        let mut listed: Vec<String> = urls
+            .iter()
+            .take(MAX_URLS_LISTED)
+            .map(|u| u.to_string())
+            .collect();
+        if urls.len() > MAX_URLS_LISTED {
+            listed.push(format!("{} more", urls.len() - MAX_URLS_LISTED));
+        }
+        item.detail = Some(listed.join(", "));
+    }
+    item
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::titled;
+    use crate::{ArticleMeta, AssetKind, AssetRef, Author, DetectedEntity, PagePerformance};
+
+    fn paths(pages: &[PathBuf]) -> Vec<String> {
+        pages
+            .iter()
+            .map(|p| p.strip_prefix("/site").unwrap().display().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_trust_scorecard() {
+        let mut home = titled(
+            "/site",
+            "index.html",
+            "Acme Plumbing | Emergency Plumber in Denver",
+        );
+        home.result.entities.phones.push(DetectedEntity {
+            value: "+13035550142".to_string(),
+            confidence: 0.9,
+            page: None,
+        });
+        home.result.performance.push(PagePerformance {
+            assets: vec![AssetRef {
+                url: "http://cdn.example.com/jquery.js".to_string(),
+                kind: AssetKind::Js,
+                bytes: None,
+            }],
+            ..Default::default()
+        });
+        let mut post = titled(
+            "/site",
+            "blog/drains/index.html",
+            "Unclogging drains - Acme Plumbing LLC",
+        );
+        post.result.article = Some(ArticleMeta {
+            authors: vec![Author {
+                name: "Jo Smith".to_string(),
+                url: None,
+            }],
+            ..Default::default()
+        });
+        post.result.performance.push(PagePerformance::default());
+        let files = vec![
+            home,
+            titled("/site", "about-us.html", "About Us | Acme Plumbing"),
+            titled("/site", "contact/index.html", "Contact | Acme Plumbing"),
+            titled(
+                "/site",
+                "legal/privacy-policy.html",
+                "Privacy Policy | Acme Plumbing",
+            ),
+            post,
+        ];
+
+        let audit = TrustAudit::new(Path::new("/site"), &files, BusinessType::LocalBusiness);
+        let status = |signal| audit.item(signal).unwrap().status;
+
+        assert_eq!(
+            paths(&audit.item(TrustSignal::AboutPage).unwrap().found),
+            vec!["about-us.html"]
+        );
+        assert_eq!(
+            paths(&audit.item(TrustSignal::ContactPage).unwrap().found),
+            vec!["contact/index.html"]
+        );
+        assert_eq!(status(TrustSignal::PrivacyPolicy), TrustStatus::Present);
+        assert_eq!(status(TrustSignal::TermsPage), TrustStatus::Missing);
+        assert_eq!(
+            paths(&audit.item(TrustSignal::AuthorBios).unwrap().missing),
+            vec!["blog/drains/index.html"]
+        );
+        assert_eq!(status(TrustSignal::PhysicalAddress), TrustStatus::Missing);
+        assert_eq!(status(TrustSignal::Phone), TrustStatus::Present);
+
+        let name = audit.item(TrustSignal::ConsistentName).unwrap();
+        assert_eq!(name.status, TrustStatus::Partial);
+        assert_eq!(
+            name.detail.as_deref(),
+            Some("\"Acme Plumbing\", also \"Acme Plumbing LLC\" (1)")
+        );
+
+        let https = audit.item(TrustSignal::HttpsAssets).unwrap();
+        assert_eq!(paths(&https.missing), vec!["index.html"]);
+        assert_eq!(
+            https.detail.as_deref(),
+            Some("http://cdn.example.com/jquery.js")
+        );
+
+        assert_eq!(audit.gaps().count(), 5);
+        assert_eq!(audit.score, 60);
+    }
+
+    #[test]
+    fn test_not_applicable_signals() {
+        let mut about = titled("/site", "about.html", "About");
+        about.result.existing_seo.site_name = Some("Acme".to_string());
+        let files = vec![titled("/site", "index.html", "Acme"), about];
+
+        let audit = TrustAudit::new(Path::new("/site"), &files, BusinessType::SaaS);
+        let status = |signal| audit.item(signal).unwrap().status;
+
+        assert_eq!(status(TrustSignal::AuthorBios), TrustStatus::NotApplicable);
+        assert_eq!(
+            status(TrustSignal::PhysicalAddress),
+            TrustStatus::NotApplicable
+        );
+        assert_eq!(status(TrustSignal::HttpsAssets), TrustStatus::NotApplicable);
+        assert_eq!(status(TrustSignal::ConsistentName), TrustStatus::Present);
+        // About (10) and name (10) of the 60 points that apply
+        assert_eq!(audit.score, 33);
+    }
+
+    #[test]
+    fn test_trust_pages_by_slug_or_title() {
+        let cases = [
+            (TrustSignal::AboutPage, "our-story", "", true),
+            (TrustSignal::AboutPage, "team", "about acme", true),
+            (TrustSignal::AboutPage, "blog", "all about drains", false),
+            (TrustSignal::ContactPage, "contact-us", "", true),
+            (TrustSignal::PrivacyPolicy, "cookie-and-privacy", "", true),
+            (TrustSignal::TermsPage, "tos", "", true),
+            (TrustSignal::TermsPage, "pricing", "pricing terms", false),
+            (TrustSignal::Phone, "contact", "contact", false),
+        ];
+        for (signal, slug, title, expected) in cases {
+            assert_eq!(
+                is_trust_page(signal, slug, title),
+                expected,
+                "{:?} {} {}",
+                signal,
+                slug,
+                title
+            );
+        }
+    }
+}
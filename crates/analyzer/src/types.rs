@@ -1,10 +1,14 @@
 //! Core types for analysis results
 
+use crate::{
+    AccessLog, AuditWeights, CanonicalAudit, CrawlAudit, IconAudit, LinkAudit, NapAudit,
+    NapMention, RedirectAudit, SearchConsoleData, TrustAudit,
+};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Result of analyzing HTML content
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct AnalysisResult {
     /// Extracted keywords with frequency and relevance scores
     pub keywords: Vec<Keyword>,
@@ -12,6 +16,12 @@ pub struct AnalysisResult {
     /// Detected business/service type
     pub business_type: BusinessType,
 
+    /// Ranked business type distribution, most likely first
+    pub business_type_scores: Vec<BusinessTypeScore>,
+
+    /// Most specific taxonomy node within the business type
+    pub business_subtype: Option<BusinessSubtype>,
+
     /// Detected language
     pub language: Option<String>,
 
@@ -26,9 +36,100 @@ pub struct AnalysisResult {
 
     /// Raw text content (for ML processing)
     pub raw_text: Option<String>,
+
+    /// Front matter of Markdown sources
+    pub front_matter: Option<FrontMatter>,
+
+    /// Client-side rendering signals
+    pub rendering: ClientRendering,
+
+    /// Business contact details found in the content
+    pub entities: ContactEntities,
+
+    /// Question/answer pairs found in the content
+    pub faq: Vec<FaqEntry>,
+
+    /// Step-by-step instructions found in the content
+    pub how_to: Vec<HowTo>,
+
+    /// Products offered in the content
+    pub products: Vec<Product>,
+
+    /// Author, dates, section and tags of an article page
+    pub article: Option<ArticleMeta>,
+
+    /// Static page weight and markup measurements, one per page
+    pub performance: Vec<PagePerformance>,
+
+    /// Navigational links, for the anchor text and `rel` audit
+    pub links: Vec<Link>,
+
+    /// Question headings and how well their answers fit a featured snippet
+    pub snippets: Vec<SnippetCandidate>,
+
+    /// Every statement of the business name, address and phone
+    pub nap: Vec<NapMention>,
+
+    /// Favicons, app icons and web app manifest, one per page
+    pub icons: Vec<PageIcons>,
+
+    /// Main-content depth and role, one per page
+    pub content_depth: Vec<ContentDepth>,
+
+    /// Search Console clicks, impressions and positions of the page and its queries
+    #[serde(default)]
+    pub search_performance: Vec<SearchPerformance>,
 }
 
 impl AnalysisResult {
+    /// Record `page` as the source of every page-level item without one.
+    /// Every field is listed so a new one has to be sorted into page-level
+    /// or site-wide here.
+    pub fn stamp_page(&mut self, page: &Path) {
+        let AnalysisResult {
+            keywords: _,
+            business_type: _,
+            business_type_scores: _,
+            business_subtype: _,
+            language: _,
+            existing_seo,
+            content_summary: _,
+            sentiment_score: _,
+            raw_text: _,
+            front_matter: _,
+            rendering: _,
+            entities,
+            faq: _,
+            how_to: _,
+            products,
+            article: _,
+            performance,
+            links,
+            snippets,
+            nap,
+            icons,
+            content_depth,
+            search_performance,
+        } = self;
+
+        entities.set_page(page);
+        let pages = existing_seo
+            .sources
+            .iter_mut()
+            .map(|s| &mut s.file)
+            .chain(products.iter_mut().map(|p| &mut p.page))
+            .chain(performance.iter_mut().map(|p| &mut p.page))
+            .chain(links.iter_mut().map(|l| &mut l.page))
+            .chain(snippets.iter_mut().map(|s| &mut s.page))
+            .chain(nap.iter_mut().map(|m| &mut m.page))
+            .chain(icons.iter_mut().map(|i| &mut i.page))
+            .chain(content_depth.iter_mut().map(|d| &mut d.page))
+            .chain(search_performance.iter_mut().map(|r| &mut r.page));
+        for p in pages {
+            p.get_or_insert_with(|| page.to_path_buf());
+        }
+    }
+
     /// Merge another result into this one
     pub fn merge(&mut self, other: AnalysisResult) {
         // Merge keywords (dedupe by word)
@@ -38,10 +139,20 @@ impl AnalysisResult {
             }
         }
 
-        // Take non-default values
-        if other.business_type != BusinessType::Unknown {
+        // Pool business type evidence, otherwise take non-default values
+        if !other.business_type_scores.is_empty() {
+            let pooled = self
+                .business_type_scores
+                .drain(..)
+                .chain(other.business_type_scores)
+                .map(|s| (s.business_type, s.score))
+                .collect();
+            self.business_type_scores = rank_business_types(pooled);
+            self.business_type = self.business_type_scores[0].business_type.clone();
+        } else if other.business_type != BusinessType::Unknown {
             self.business_type = other.business_type;
         }
+        self.merge_subtype(other.business_subtype);
         if other.language.is_some() {
             self.language = other.language;
         }
@@ -51,14 +162,93 @@ impl AnalysisResult {
         if other.sentiment_score.is_some() {
             self.sentiment_score = other.sentiment_score;
         }
-        if other.raw_text.is_some() {
-            self.raw_text = other.raw_text;
+        // Keep populated text over an empty one
+        if other.raw_text.as_deref().is_some_and(|t| !t.trim().is_empty())
+            || self.raw_text.is_none()
+        {
+            self.raw_text = other.raw_text.or(self.raw_text.take());
+        }
+        if other.front_matter.is_some() {
+            self.front_matter = other.front_matter;
+        }
+        self.rendering.merge(other.rendering);
+        self.entities.merge(other.entities);
+        for entry in other.faq {
+            if !self.faq.iter().any(|e| e.same_question(&entry)) {
+                self.faq.push(entry);
+            }
+        }
+        for guide in other.how_to {
+            if !self.how_to.iter().any(|h| h.name.eq_ignore_ascii_case(&guide.name)) {
+                self.how_to.push(guide);
+            }
+        }
+        for product in other.products {
+            if !self.products.iter().any(|p| p.same_product(&product)) {
+                self.products.push(product);
+            }
+        }
+        self.performance.extend(other.performance);
+        self.links.extend(other.links);
+        self.snippets.extend(other.snippets);
+        self.nap.extend(other.nap);
+        self.icons.extend(other.icons);
+        self.content_depth.extend(other.content_depth);
+        self.search_performance.extend(other.search_performance);
+        match (&mut self.article, other.article) {
+            (Some(article), Some(other)) => article.merge(other),
+            (article @ None, other) => *article = other,
+            _ => {}
         }
 
         // Merge existing SEO (OR operation)
         self.existing_seo.merge(other.existing_seo);
     }
 
+    /// Pool subtype evidence, keeping the strongest subtype of the business type
+    fn merge_subtype(&mut self, other: Option<BusinessSubtype>) {
+        let mut candidates: Vec<BusinessSubtype> = self.business_subtype.take().into_iter().collect();
+        if let Some(other) = other {
+            match candidates.iter_mut().find(|c| c.id == other.id) {
+                Some(same) => same.score += other.score,
+                None => candidates.push(other),
+            }
+        }
+
+        let category_score = self
+            .business_type_scores
+            .first()
+            .map(|s| s.score)
+            .unwrap_or(0.0);
+        self.business_subtype = candidates
+            .into_iter()
+            .filter(|c| c.category == self.business_type)
+            .max_by(|a, b| a.score.partial_cmp(&b.score).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|mut best| {
+                if category_score > 0.0 {
+                    best.confidence = (best.score / category_score).min(1.0);
+                }
+                best
+            });
+    }
+
+    /// Most specific schema.org type for the site's business
+    pub fn schema_type(&self) -> &str {
+        self.business_subtype
+            .as_ref()
+            .map(|s| s.schema_type.as_str())
+            .unwrap_or_else(|| self.business_type.schema_type())
+    }
+
+    /// Business types besides the primary one with meaningful confidence
+    pub fn secondary_business_types(&self) -> Vec<&BusinessTypeScore> {
+        self.business_type_scores
+            .iter()
+            .skip(1)
+            .filter(|s| s.confidence >= SECONDARY_TYPE_MIN_CONFIDENCE)
+            .collect()
+    }
+
     /// Get top N keywords by score
     pub fn top_keywords(&self, n: usize) -> Vec<&Keyword> {
         let mut sorted: Vec<_> = self.keywords.iter().collect();
@@ -68,7 +258,7 @@ impl AnalysisResult {
 }
 
 /// Extracted keyword with metadata
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Keyword {
     pub word: String,
     pub frequency: u32,
@@ -76,8 +266,799 @@ pub struct Keyword {
     pub is_phrase: bool,
 }
 
+/// Front matter fields of a Markdown/MDX source
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FrontMatter {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    /// Publication date as written in the source
+    pub date: Option<String>,
+    pub draft: bool,
+    pub slug: Option<String>,
+}
+
+/// Question and answer pair for FAQPage markup
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FaqEntry {
+    pub question: String,
+    pub answer: String,
+}
+
+impl FaqEntry {
+    /// Whether both ask the same question, ignoring case and punctuation
+    pub fn same_question(&self, other: &FaqEntry) -> bool {
+        let key = |q: &str| -> String {
+            q.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect()
+        };
+        key(&self.question) == key(&other.question)
+    }
+}
+
+/// A hyperlink and the attributes the link audit checks
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Link {
+    pub href: String,
+
+    /// Visible text, else the `alt` of a linked image or `aria-label`
+    pub anchor: String,
+
+    /// `rel` tokens, lowercased
+    pub rel: Vec<String>,
+
+    /// `target="_blank"`
+    pub new_tab: bool,
+
+    /// Affiliate URL or inside a sponsored/ad container
+    pub sponsored: bool,
+
+    /// Inside comments, forum posts or other user content
+    pub user_generated: bool,
+
+    pub page: Option<PathBuf>,
+}
+
+impl Link {
+    pub fn has_rel(&self, token: &str) -> bool {
+        self.rel.iter().any(|r| r == token)
+    }
+}
+
+/// Shape of the block right below a question heading
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SnippetFormat {
+    Paragraph,
+    List,
+    Table,
+    /// Something else, such as an image or a widget
+    Other,
+    /// Another heading follows directly
+    #[default]
+    Missing,
+}
+
+/// A question heading and the readiness of its answer for a featured snippet
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SnippetCandidate {
+    pub question: String,
+    pub page: Option<PathBuf>,
+    pub format: SnippetFormat,
+
+    /// Words in the answer block
+    pub answer_words: u32,
+
+    /// Items of a list answer, rows of a table answer
+    pub items: u32,
+
+    /// Readiness (0-100)
+    pub score: u32,
+
+    /// How to restructure the answer, empty when it is ready
+    pub hints: Vec<String>,
+}
+
+/// Static performance measurements of a page, no browser needed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PagePerformance {
+    pub page: Option<PathBuf>,
+
+    /// Element count
+    pub dom_nodes: u32,
+
+    /// Deepest element nesting, `<html>` being 1
+    pub max_depth: u32,
+
+    pub html_bytes: u64,
+
+    /// Visible text, whitespace collapsed
+    pub text_bytes: u64,
+
+    /// CSS, JS, font and image files the page references
+    pub assets: Vec<AssetRef>,
+
+    /// `<head>` scripts without `async` or `defer`
+    pub render_blocking_scripts: Vec<String>,
+
+    /// `<script>` bodies, structured data excluded
+    pub inline_script_bytes: u64,
+
+    /// `<style>` bodies and `style` attributes
+    pub inline_style_bytes: u64,
+}
+
+impl PagePerformance {
+    /// Share of the HTML that is visible text, 0.0-1.0
+    pub fn text_ratio(&self) -> f32 {
+        if self.html_bytes == 0 {
+            return 0.0;
+        }
+        self.text_bytes as f32 / self.html_bytes as f32
+    }
+
+    pub fn asset_count(&self, kind: AssetKind) -> usize {
+        self.assets.iter().filter(|a| a.kind == kind).count()
+    }
+
+    /// On-disk size of the local assets of `kind`; remote assets are not counted
+    pub fn asset_bytes(&self, kind: AssetKind) -> u64 {
+        self.assets
+            .iter()
+            .filter(|a| a.kind == kind)
+            .filter_map(|a| a.bytes)
+            .sum()
+    }
+}
+
+/// Asset referenced by a page or its stylesheets
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetRef {
+    pub url: String,
+    pub kind: AssetKind,
+
+    /// On-disk size; `None` for remote or missing files
+    pub bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AssetKind {
+    Css,
+    Js,
+    Font,
+    Image,
+}
+
+impl AssetKind {
+    /// Kind of a file by extension, ignoring query strings
+    pub fn from_url(url: &str) -> Option<Self> {
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        let ext = path.rsplit_once('.')?.1.to_ascii_lowercase();
+        match ext.as_str() {
+            "css" => Some(Self::Css),
+            "js" | "mjs" => Some(Self::Js),
+            "woff" | "woff2" | "ttf" | "otf" | "eot" => Some(Self::Font),
+            "png" | "jpg" | "jpeg" | "gif" | "webp" | "avif" | "svg" | "ico" => Some(Self::Image),
+            _ => None,
+        }
+    }
+}
+
+/// What a page is for, which decides how much content it needs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PageRole {
+    Home,
+    /// A single product or service offer
+    Product,
+    /// Blog post, guide or news story
+    Article,
+    /// Listing of products or articles
+    Category,
+    /// About, contact, service and other standalone pages
+    #[default]
+    Page,
+}
+
+impl PageRole {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Home => "home page",
+            Self::Product => "product page",
+            Self::Article => "article",
+            Self::Category => "category page",
+            Self::Page => "page",
+        }
+    }
+}
+
+/// Word count, vocabulary and structure of a page's main content
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContentDepth {
+    pub page: Option<PathBuf>,
+    pub role: PageRole,
+
+    /// Words in the main content, navigation and boilerplate excluded
+    pub words: u32,
+
+    /// Distinct words, ignoring case
+    pub unique_terms: u32,
+
+    /// Moving-average type-token ratio over 100-word windows (0.0-1.0);
+    /// low values mean repetitive text
+    pub richness: f32,
+
+    /// `<h2>`-`<h4>` subheadings in the main content
+    pub sections: u32,
+}
+
+/// One row of a Search Console performance export
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchPerformance {
+    /// Analyzed page the row is for, once attached
+    pub page: Option<PathBuf>,
+
+    /// Page URL as exported; `None` in a queries-only export
+    pub url: Option<String>,
+
+    /// Search query; `None` for page totals
+    pub query: Option<String>,
+
+    pub clicks: u32,
+    pub impressions: u32,
+
+    /// Click-through rate (0.0-1.0)
+    pub ctr: f32,
+
+    /// Average position, 1.0 being the top result; 0.0 without impressions
+    pub position: f32,
+}
+
+/// Favicon, app icon and web app manifest declarations of a page
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PageIcons {
+    pub page: Option<PathBuf>,
+
+    /// `<link>` icons, followed by the icons of the manifest
+    pub icons: Vec<IconRef>,
+
+    /// `<link rel="manifest">` URL
+    pub manifest_url: Option<String>,
+
+    /// The manifest read from disk, `None` when not linked or remote
+    pub manifest: Option<WebManifest>,
+
+    /// `<meta name="theme-color">`
+    pub theme_color: Option<String>,
+}
+
+impl PageIcons {
+    pub fn icons_of(&self, rel: IconRel) -> impl Iterator<Item = &IconRef> {
+        self.icons.iter().filter(move |i| i.rel == rel)
+    }
+}
+
+/// An icon declared by a `<link>` or a manifest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IconRef {
+    pub url: String,
+    pub rel: IconRel,
+
+    /// Declared `sizes`, such as `32x32` or `any`
+    pub sizes: Vec<String>,
+
+    /// Declared MIME type
+    pub mime: Option<String>,
+
+    /// Manifest `purpose`, such as `maskable`
+    pub purpose: Option<String>,
+
+    /// Whether the file exists on disk; `None` for remote URLs
+    pub exists: Option<bool>,
+
+    /// Format read from the file, or guessed from the URL when remote
+    pub format: Option<IconFormat>,
+
+    /// Pixel sizes of the images in the file (an ICO holds several);
+    /// empty for SVG and files that couldn't be read
+    pub actual_sizes: Vec<(u32, u32)>,
+}
+
+impl IconRef {
+    /// Largest square edge the icon renders at without upscaling; SVG has no limit
+    pub fn max_edge(&self) -> Option<u32> {
+        if self.format == Some(IconFormat::Svg) {
+            return Some(u32::MAX);
+        }
+        self.actual_sizes
+            .iter()
+            .map(|&(w, h)| w.min(h))
+            .max()
+            .or_else(|| self.declared_sizes().map(|(w, h)| w.min(h)).max())
+    }
+
+    /// `sizes` parsed into pixel dimensions, `any` skipped
+    pub fn declared_sizes(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.sizes.iter().filter_map(|s| {
+            let (w, h) = s.to_ascii_lowercase().split_once('x').map(|(w, h)| {
+                (w.parse::<u32>().ok(), h.parse::<u32>().ok())
+            })?;
+            Some((w?, h?))
+        })
+    }
+
+    /// Whether the file has an image of exactly `edge`×`edge` pixels, or is an SVG
+    pub fn has_size(&self, edge: u32) -> bool {
+        self.format == Some(IconFormat::Svg)
+            || self.actual_sizes.contains(&(edge, edge))
+            || (self.actual_sizes.is_empty() && self.declared_sizes().any(|s| s == (edge, edge)))
+    }
+
+    pub fn has_purpose(&self, purpose: &str) -> bool {
+        self.purpose
+            .as_deref()
+            .is_some_and(|p| p.split_whitespace().any(|t| t.eq_ignore_ascii_case(purpose)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum IconRel {
+    /// `rel="icon"` or `rel="shortcut icon"`
+    Icon,
+    /// `rel="apple-touch-icon"`
+    AppleTouchIcon,
+    /// Safari pinned tab `rel="mask-icon"`
+    MaskIcon,
+    /// Listed in the web app manifest
+    Manifest,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum IconFormat {
+    Ico,
+    Png,
+    Svg,
+    Gif,
+    Jpeg,
+    WebP,
+}
+
+impl IconFormat {
+    /// Format by extension, ignoring query strings
+    pub fn from_url(url: &str) -> Option<Self> {
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        let ext = path.rsplit_once('.')?.1.to_ascii_lowercase();
+        match ext.as_str() {
+            "ico" => Some(Self::Ico),
+            "png" => Some(Self::Png),
+            "svg" => Some(Self::Svg),
+            "gif" => Some(Self::Gif),
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "webp" => Some(Self::WebP),
+            _ => None,
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Ico => "image/x-icon",
+            Self::Png => "image/png",
+            Self::Svg => "image/svg+xml",
+            Self::Gif => "image/gif",
+            Self::Jpeg => "image/jpeg",
+            Self::WebP => "image/webp",
+        }
+    }
+}
+
+/// Fields of a web app manifest (`manifest.json`, `site.webmanifest`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WebManifest {
+    /// File the manifest was read from
+    pub path: PathBuf,
+    pub name: Option<String>,
+    pub short_name: Option<String>,
+    pub start_url: Option<String>,
+    pub display: Option<String>,
+    pub theme_color: Option<String>,
+    pub background_color: Option<String>,
+
+    /// Why the file couldn't be parsed, in which case the fields are empty
+    pub error: Option<String>,
+}
+
+/// Article metadata for `article:*` Open Graph tags and BlogPosting markup
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArticleMeta {
+    pub authors: Vec<Author>,
+    /// ISO 8601 date or date-time
+    pub published: Option<String>,
+    /// ISO 8601 date or date-time
+    pub modified: Option<String>,
+    /// Where `published` and `modified` came from
+    pub date_source: Option<DateSource>,
+    pub section: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl ArticleMeta {
+    pub fn is_empty(&self) -> bool {
+        self.authors.is_empty()
+            && self.published.is_none()
+            && self.modified.is_none()
+            && self.section.is_none()
+            && self.tags.is_empty()
+    }
+
+    /// Fill missing fields from `other` and add its authors and tags
+    pub fn merge(&mut self, other: ArticleMeta) {
+        for author in other.authors {
+            match self.authors.iter_mut().find(|a| a.same_author(&author)) {
+                Some(existing) => {
+                    if existing.url.is_none() {
+                        existing.url = author.url;
+                    }
+                }
+                None => self.authors.push(author),
+            }
+        }
+        if self.published.is_none() && self.modified.is_none() {
+            self.date_source = other.date_source;
+        }
+        if self.published.is_none() {
+            self.published = other.published;
+        }
+        if self.modified.is_none() {
+            self.modified = other.modified;
+        }
+        if self.section.is_none() {
+            self.section = other.section;
+        }
+        for tag in other.tags {
+            if !self.tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+                self.tags.push(tag);
+            }
+        }
+    }
+}
+
+/// Article author, with a profile page when linked
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Author {
+    pub name: String,
+    pub url: Option<String>,
+}
+
+impl Author {
+    pub fn same_author(&self, other: &Author) -> bool {
+        self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+
+/// Origin of an article's dates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DateSource {
+    /// Meta tags, `<time>` elements or front matter
+    Content,
+    /// File creation and modification times
+    FileTimes,
+    /// First and last commit touching the file
+    GitHistory,
+}
+
+/// Step-by-step instructions for HowTo markup
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HowTo {
+    /// Title of the instructions, e.g. `How to unclog a drain`
+    pub name: String,
+
+    pub steps: Vec<HowToStep>,
+
+    /// Consumed materials
+    pub supplies: Vec<String>,
+
+    /// Equipment used but not consumed
+    pub tools: Vec<String>,
+
+    /// ISO 8601 duration, e.g. `PT1H30M`
+    pub total_time: Option<String>,
+}
+
+/// One step of a `HowTo`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HowToStep {
+    /// Short step title, when the step has one
+    pub name: Option<String>,
+    pub text: String,
+}
+
+/// Product offered on a page, for Product/Offer/AggregateRating markup
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Product {
+    pub name: String,
+    pub description: Option<String>,
+
+    /// Decimal price with a `.` separator, e.g. `1299.00`
+    pub price: Option<String>,
+
+    /// ISO 4217 currency code, e.g. `USD`
+    pub currency: Option<String>,
+
+    pub availability: Option<Availability>,
+    pub sku: Option<String>,
+    pub gtin: Option<String>,
+    pub brand: Option<String>,
+    pub images: Vec<String>,
+    pub rating: Option<AggregateRating>,
+
+    /// Page the product was found on
+    pub page: Option<PathBuf>,
+}
+
+impl Product {
+    /// Whether both describe the same product on the same page
+    pub fn same_product(&self, other: &Product) -> bool {
+        if self.page != other.page {
+            return false;
+        }
+        match (&self.sku, &other.sku) {
+            (Some(a), Some(b)) => a == b,
+            _ => self.name.eq_ignore_ascii_case(&other.name),
+        }
+    }
+
+    /// Fill fields this product lacks from another description of it
+    pub fn fill_from(&mut self, other: &Product) {
+        fn fill<T: Clone>(field: &mut Option<T>, other: &Option<T>) {
+            if field.is_none() {
+                *field = other.clone();
+            }
+        }
+        fill(&mut self.description, &other.description);
+        fill(&mut self.price, &other.price);
+        fill(&mut self.currency, &other.currency);
+        fill(&mut self.availability, &other.availability);
+        fill(&mut self.sku, &other.sku);
+        fill(&mut self.gtin, &other.gtin);
+        fill(&mut self.brand, &other.brand);
+        fill(&mut self.rating, &other.rating);
+        if self.name.is_empty() {
+            self.name = other.name.clone();
+        }
+        for image in &other.images {
+            if !self.images.contains(image) {
+                self.images.push(image.clone());
+            }
+        }
+    }
+}
+
+/// schema.org `ItemAvailability`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Availability {
+    InStock,
+    OutOfStock,
+    PreOrder,
+    BackOrder,
+    LimitedAvailability,
+    Discontinued,
+}
+
+impl Availability {
+    /// Parse schema.org URLs, Open Graph values and storefront labels such as "Sold out"
+    pub fn parse(value: &str) -> Option<Self> {
+        let v: String = value
+            .rsplit('/')
+            .next()
+            .unwrap_or(value)
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect();
+
+        // Checked before "available", which "unavailable" contains
+        if v.contains("outofstock") || v.contains("soldout") || v.contains("unavailable") {
+            Some(Self::OutOfStock)
+        } else if v.contains("discontinued") {
+            Some(Self::Discontinued)
+        } else if v.contains("preorder") {
+            Some(Self::PreOrder)
+        } else if v.contains("backorder") {
+            Some(Self::BackOrder)
+        } else if v.contains("limited") || v.contains("onlyafewleft") {
+            Some(Self::LimitedAvailability)
+        } else if v.contains("instock") || v.contains("available") || v == "true" {
+            Some(Self::InStock)
+        } else {
+            None
+        }
+    }
+
+    /// schema.org URL, e.g. `https://schema.org/InStock`
+    pub fn schema_url(&self) -> String {
+        format!("https://schema.org/{:?}", self)
+    }
+}
+
+/// Review rating summary of a product
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AggregateRating {
+    pub value: f32,
+
+    /// Number of reviews or ratings
+    pub count: u32,
+
+    /// Top of the rating scale
+    pub best: f32,
+}
+
+/// Client-side rendering signals of an HTML page
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClientRendering {
+    /// Static HTML has no crawlable content, only an app mount point
+    pub empty_shell: bool,
+
+    /// Mount point selector, e.g. `#root`
+    pub mount_point: Option<String>,
+
+    /// Words of visible text in the static HTML
+    pub visible_words: u32,
+
+    /// Hydration payloads content was recovered from, e.g. `__NEXT_DATA__`
+    pub hydration_sources: Vec<String>,
+}
+
+impl ClientRendering {
+    pub fn merge(&mut self, other: ClientRendering) {
+        self.empty_shell = self.empty_shell || other.empty_shell;
+        if other.mount_point.is_some() {
+            self.mount_point = other.mount_point;
+        }
+        self.visible_words += other.visible_words;
+        for source in other.hydration_sources {
+            if !self.hydration_sources.contains(&source) {
+                self.hydration_sources.push(source);
+            }
+        }
+    }
+}
+
+/// Detected entities below this confidence are not used to fill in config
+pub const ENTITY_MIN_CONFIDENCE: f32 = 0.5;
+
+/// A value extracted from page content
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DetectedEntity<T> {
+    pub value: T,
+
+    /// 0.0-1.0; markup such as `tel:` links and microdata scores above free text
+    pub confidence: f32,
+
+    /// Page the value was found on
+    pub page: Option<PathBuf>,
+}
+
+/// Business contact details found in page content, most confident first
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContactEntities {
+    /// Phone numbers in E.164 form, e.g. `+13035550142`
+    pub phones: Vec<DetectedEntity<String>>,
+
+    /// Lowercased email addresses
+    pub emails: Vec<DetectedEntity<String>>,
+
+    pub addresses: Vec<DetectedEntity<PostalAddress>>,
+
+    /// schema.org `openingHours` values, e.g. `Mo-Fr 08:00-17:00`
+    pub opening_hours: Vec<DetectedEntity<String>>,
+
+    pub social_profiles: Vec<DetectedEntity<SocialProfile>>,
+}
+
+impl ContactEntities {
+    pub fn is_empty(&self) -> bool {
+        self.phones.is_empty()
+            && self.emails.is_empty()
+            && self.addresses.is_empty()
+            && self.opening_hours.is_empty()
+            && self.social_profiles.is_empty()
+    }
+
+    /// Merge entities, keeping the most confident sighting of each value
+    pub fn merge(&mut self, other: ContactEntities) {
+        merge_entities(&mut self.phones, other.phones);
+        merge_entities(&mut self.emails, other.emails);
+        merge_entities(&mut self.addresses, other.addresses);
+        merge_entities(&mut self.opening_hours, other.opening_hours);
+        merge_entities(&mut self.social_profiles, other.social_profiles);
+    }
+
+    /// Attribute entities without a page to `page`
+    pub fn set_page(&mut self, page: &Path) {
+        let pages = self
+            .phones
+            .iter_mut()
+            .map(|e| &mut e.page)
+            .chain(self.emails.iter_mut().map(|e| &mut e.page))
+            .chain(self.addresses.iter_mut().map(|e| &mut e.page))
+            .chain(self.opening_hours.iter_mut().map(|e| &mut e.page))
+            .chain(self.social_profiles.iter_mut().map(|e| &mut e.page));
+        for p in pages {
+            p.get_or_insert_with(|| page.to_path_buf());
+        }
+    }
+
+    /// Most confident phone number usable without review
+    pub fn phone(&self) -> Option<&DetectedEntity<String>> {
+        confident(&self.phones)
+    }
+
+    pub fn email(&self) -> Option<&DetectedEntity<String>> {
+        confident(&self.emails)
+    }
+
+    pub fn address(&self) -> Option<&DetectedEntity<PostalAddress>> {
+        confident(&self.addresses)
+    }
+
+    /// Opening hours usable without review, one entry per day range
+    pub fn confident_opening_hours(&self) -> Vec<&str> {
+        self.opening_hours
+            .iter()
+            .filter(|e| e.confidence >= ENTITY_MIN_CONFIDENCE)
+            .map(|e| e.value.as_str())
+            .collect()
+    }
+
+    /// Social profile URLs usable without review, for `sameAs`
+    pub fn confident_social_profiles(&self) -> Vec<&str> {
+        self.social_profiles
+            .iter()
+            .filter(|e| e.confidence >= ENTITY_MIN_CONFIDENCE)
+            .map(|e| e.value.url.as_str())
+            .collect()
+    }
+}
+
+fn merge_entities<T: PartialEq>(into: &mut Vec<DetectedEntity<T>>, other: Vec<DetectedEntity<T>>) {
+    for entity in other {
+        match into.iter_mut().find(|e| e.value == entity.value) {
+            Some(existing) if existing.confidence < entity.confidence => *existing = entity,
+            Some(_) => {}
+            None => into.push(entity),
+        }
+    }
+    into.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
+}
+
+fn confident<T>(entities: &[DetectedEntity<T>]) -> Option<&DetectedEntity<T>> {
+    entities.first().filter(|e| e.confidence >= ENTITY_MIN_CONFIDENCE)
+}
+
+/// Postal address found in page content
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PostalAddress {
+    pub street: String,
+    pub city: String,
+    pub region: Option<String>,
+    pub postal_code: String,
+
+    /// ISO 3166-1 alpha-2 code, if known
+    pub country: Option<String>,
+}
+
+/// Link to the business' profile on a social network
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SocialProfile {
+    /// Network name, e.g. `facebook`
+    pub network: String,
+
+    /// Canonical profile URL
+    pub url: String,
+}
+
 /// Detected business/service type
-#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum BusinessType {
     #[default]
     Unknown,
@@ -96,6 +1077,72 @@ pub enum BusinessType {
     Technology,
 }
 
+/// Taxonomy node a site was classified as
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BusinessSubtype {
+    /// Taxonomy node id, e.g. `plumber`
+    pub id: String,
+
+    /// Node ids from the root down, e.g. `local_business`, `home_and_construction`, `plumber`
+    pub path: Vec<String>,
+
+    /// Most specific schema.org type, e.g. `Plumber`
+    pub schema_type: String,
+
+    /// Business type the node's root belongs to
+    pub category: BusinessType,
+
+    /// Weighted indicator evidence along the path
+    pub score: f32,
+
+    /// Share of the category's evidence supporting this path (0.0 - 1.0)
+    pub confidence: f32,
+}
+
+/// Minimum confidence for a business type to be reported as secondary
+pub const SECONDARY_TYPE_MIN_CONFIDENCE: f32 = 0.15;
+
+/// Business type with the classifier evidence behind it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BusinessTypeScore {
+    pub business_type: BusinessType,
+
+    /// Weighted indicator evidence
+    pub score: f32,
+
+    /// Share of the total evidence (0.0 - 1.0)
+    pub confidence: f32,
+}
+
+/// Sum evidence per business type and rank it, ties broken by declaration order
+pub fn rank_business_types(scores: Vec<(BusinessType, f32)>) -> Vec<BusinessTypeScore> {
+    let mut totals: Vec<(BusinessType, f32)> = Vec::new();
+    for (business_type, score) in scores {
+        match totals.iter_mut().find(|(t, _)| *t == business_type) {
+            Some((_, total)) => *total += score,
+            None => totals.push((business_type, score)),
+        }
+    }
+    totals.retain(|(_, score)| *score > 0.0);
+
+    let sum: f32 = totals.iter().map(|(_, score)| score).sum();
+    let mut ranked: Vec<BusinessTypeScore> = totals
+        .into_iter()
+        .map(|(business_type, score)| BusinessTypeScore {
+            business_type,
+            score,
+            confidence: score / sum,
+        })
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.business_type.cmp(&b.business_type))
+    });
+    ranked
+}
+
 impl BusinessType {
     /// Get Schema.org type for this business
     pub fn schema_type(&self) -> &'static str {
@@ -108,18 +1155,26 @@ impl BusinessType {
             Self::SaaS => "SoftwareApplication",
             Self::LocalBusiness => "LocalBusiness",
             Self::Restaurant => "Restaurant",
-            Self::Agency => "Organization",
+            Self::Agency => "ProfessionalService",
             Self::NonProfit => "NGO",
             Self::Education => "EducationalOrganization",
             Self::Healthcare => "MedicalOrganization",
             Self::RealEstate => "RealEstateAgent",
-            Self::Technology => "TechArticle",
+            Self::Technology => "Organization",
         }
     }
+
+    /// Businesses customers visit or call, which should show an address and phone
+    pub fn is_local(&self) -> bool {
+        matches!(
+            self,
+            Self::LocalBusiness | Self::Restaurant | Self::Healthcare | Self::RealEstate
+        )
+    }
 }
 
 /// Existing SEO elements found in HTML
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ExistingSeo {
     pub has_title: bool,
     pub title: Option<String>,
@@ -129,10 +1184,31 @@ pub struct ExistingSeo {
     pub has_twitter_cards: bool,
     pub has_schema: bool,
     pub has_canonical: bool,
+    /// `href` of `<link rel=canonical>`, as written
+    #[serde(default)]
+    pub canonical: Option<String>,
+    /// `noindex` in the robots meta tag
+    #[serde(default)]
+    pub noindex: bool,
+    /// Target URL of `<meta http-equiv=refresh>`, as written
+    #[serde(default)]
+    pub meta_refresh: Option<String>,
+    /// `og:site_name`, or `application-name` without it
+    #[serde(default)]
+    pub site_name: Option<String>,
+    /// Schema.org types of the top-level JSON-LD and microdata items
+    #[serde(default)]
+    pub schema_types: Vec<String>,
+    /// `<link rel=alternate hreflang>` alternates
+    #[serde(default)]
+    pub hreflang: Vec<Hreflang>,
     pub has_viewport: bool,
     pub has_charset: bool,
     pub h1_count: u32,
     pub img_without_alt: u32,
+
+    /// Where each element was declared, for source-level analyzers
+    pub sources: Vec<SeoSource>,
 }
 
 impl ExistingSeo {
@@ -143,10 +1219,12 @@ impl ExistingSeo {
         self.has_twitter_cards = self.has_twitter_cards || other.has_twitter_cards;
         self.has_schema = self.has_schema || other.has_schema;
         self.has_canonical = self.has_canonical || other.has_canonical;
+        self.noindex = self.noindex || other.noindex;
         self.has_viewport = self.has_viewport || other.has_viewport;
         self.has_charset = self.has_charset || other.has_charset;
         self.h1_count += other.h1_count;
         self.img_without_alt += other.img_without_alt;
+        self.sources.extend(other.sources);
 
         if other.title.is_some() {
             self.title = other.title;
@@ -154,23 +1232,67 @@ impl ExistingSeo {
         if other.description.is_some() {
             self.description = other.description;
         }
+        if other.canonical.is_some() {
+            self.canonical = other.canonical;
+        }
+        if other.meta_refresh.is_some() {
+            self.meta_refresh = other.meta_refresh;
+        }
+        if other.site_name.is_some() {
+            self.site_name = other.site_name;
+        }
+        for schema_type in other.schema_types {
+            if !self.schema_types.contains(&schema_type) {
+                self.schema_types.push(schema_type);
+            }
+        }
+        self.hreflang.extend(other.hreflang);
     }
 
     /// Calculate SEO completeness score (0-100)
     pub fn completeness_score(&self) -> u32 {
+        self.weighted_score(&AuditWeights::default())
+    }
+
+    /// Calculate SEO completeness score (0-100) with custom element weights
+    pub fn weighted_score(&self, weights: &AuditWeights) -> u32 {
         let mut score = 0u32;
-        if self.has_title { score += 15; }
-        if self.has_description { score += 15; }
-        if self.has_og_tags { score += 20; }
-        if self.has_twitter_cards { score += 15; }
-        if self.has_schema { score += 20; }
-        if self.has_canonical { score += 5; }
-        if self.has_viewport { score += 5; }
-        if self.has_charset { score += 5; }
-        score
+        if self.has_title { score += weights.title; }
+        if self.has_description { score += weights.description; }
+        if self.has_og_tags { score += weights.og_tags; }
+        if self.has_twitter_cards { score += weights.twitter_cards; }
+        if self.has_schema { score += weights.schema; }
+        if self.has_canonical { score += weights.canonical; }
+        if self.has_viewport { score += weights.viewport; }
+        if self.has_charset { score += weights.charset; }
+        score * 100 / weights.total().max(1)
     }
 }
 
+/// Language alternate of a page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hreflang {
+    /// Language and optional region, e.g. `en-gb`, or `x-default`
+    pub lang: String,
+    pub href: String,
+}
+
+/// Location of an SEO declaration in the project sources
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeoSource {
+    /// Declared element, e.g. `title`, `meta[name=description]`, `link[rel=canonical]`
+    pub element: String,
+
+    /// Literal value, if it is not computed at runtime
+    pub value: Option<String>,
+
+    /// File the declaration was found in
+    pub file: Option<PathBuf>,
+
+    /// 1-based line number
+    pub line: u32,
+}
+
 /// Detected web framework
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Framework {
@@ -181,26 +1303,81 @@ pub enum Framework {
     Vue,
     Nuxt,
     Svelte,
+    SvelteKit,
     Vite,
     Angular,
+    Remix,
+    Astro,
+    Gatsby,
+    Hugo,
+    Jekyll,
+    Eleventy,
+    Docusaurus,
     Unknown,
 }
 
 impl Framework {
-    /// Get the injection target file pattern
+    /// Get the primary injection target file
     pub fn injection_target(&self) -> &'static str {
+        self.injection_targets()[0]
+    }
+
+    /// Get all candidate injection target files, most preferred first
+    pub fn injection_targets(&self) -> &'static [&'static str] {
         match self {
-            Self::VanillaHtml => "index.html",
-            Self::React => "public/index.html",
-            Self::NextJs => "app/layout.tsx or pages/_document.tsx",
-            Self::Vue => "index.html",
-            Self::Nuxt => "nuxt.config.ts",
-            Self::Svelte => "src/app.html",
-            Self::Vite => "index.html",
-            Self::Angular => "src/index.html",
-            Self::Unknown => "index.html",
+            Self::VanillaHtml => &["index.html", "index.htm"],
+            Self::React => &["public/index.html", "index.html"],
+            Self::NextJs => &[
+                "app/layout.tsx",
+                "app/layout.jsx",
+                "app/layout.js",
+                "src/app/layout.tsx",
+                "pages/_document.tsx",
+                "pages/_document.jsx",
+                "pages/_document.js",
+                "src/pages/_document.tsx",
+            ],
+            Self::Vue => &["index.html", "public/index.html"],
+            Self::Nuxt => &["nuxt.config.ts", "nuxt.config.js", "app.vue"],
+            Self::Svelte => &["index.html", "public/index.html"],
+            Self::SvelteKit => &["src/app.html"],
+            Self::Vite => &["index.html"],
+            Self::Angular => &["src/index.html"],
+            Self::Remix => &["app/root.tsx", "app/root.jsx"],
+            Self::Astro => &[
+                "src/layouts/Layout.astro",
+                "src/layouts/BaseLayout.astro",
+                "src/pages/index.astro",
+            ],
+            Self::Gatsby => &["gatsby-ssr.js", "gatsby-ssr.tsx", "src/html.js"],
+            // Base layouts before head partials, which have no `</head>` to inject at
+            Self::Hugo => &["layouts/_default/baseof.html", "layouts/partials/head.html"],
+            Self::Jekyll => &["_layouts/default.html", "_includes/head.html"],
+            Self::Eleventy => &["_includes/base.njk", "_includes/layouts/base.njk"],
+            Self::Docusaurus => &["docusaurus.config.js", "docusaurus.config.ts"],
+            Self::Unknown => &["index.html"],
         }
     }
+
+    /// Find the first injection target that exists under `root`
+    pub fn find_injection_target(&self, root: &Path) -> Option<PathBuf> {
+        self.injection_targets()
+            .iter()
+            .map(|target| root.join(target))
+            .find(|path| path.exists())
+    }
+}
+
+/// Result of framework detection
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FrameworkDetection {
+    pub framework: Framework,
+
+    /// Confidence in the detection (0.0 - 1.0)
+    pub confidence: f32,
+
+    /// Signals that led to the detection
+    pub evidence: Vec<String>,
 }
 
 /// Analysis of a single file
@@ -217,9 +1394,86 @@ pub struct DirectoryAnalysis {
     pub main_file: Option<PathBuf>,
     pub files: Vec<FileAnalysis>,
     pub framework: Framework,
+
+    /// Confidence in the framework detection (0.0 - 1.0)
+    pub framework_confidence: f32,
+
+    /// Signals that led to the framework detection
+    pub framework_evidence: Vec<String>,
+
+    /// Element weights for the SEO completeness score
+    #[serde(default)]
+    pub audit_weights: AuditWeights,
+
+    /// Search Console rows not tied to an analyzed page, such as queries
+    #[serde(default)]
+    pub search_console: Vec<SearchPerformance>,
 }
 
 impl DirectoryAnalysis {
+    /// SEO completeness score (0-100) of the merged result, using the configured weights
+    pub fn seo_score(&self) -> u32 {
+        self.merged_result()
+            .existing_seo
+            .weighted_score(&self.audit_weights)
+    }
+
+    /// Anchor text and `rel` audit of the links on all pages
+    pub fn link_audit(&self, site_url: Option<&str>) -> LinkAudit {
+        let links: Vec<Link> = self
+            .files
+            .iter()
+            .flat_map(|f| f.result.links.iter().cloned())
+            .collect();
+        LinkAudit::new(&links, site_url)
+    }
+
+    /// Canonical consistency across the HTML pages
+    pub fn canonical_audit(&self, site_url: Option<&str>) -> CanonicalAudit {
+        CanonicalAudit::new(&self.root, &self.files, site_url)
+    }
+
+    /// Hosting redirect rules and meta refreshes checked against the pages
+    /// and their internal links
+    pub fn redirect_audit(&self, site_url: Option<&str>) -> RedirectAudit {
+        RedirectAudit::new(&self.root, &self.files, site_url)
+    }
+
+    /// Search bot crawls of `log` joined with the HTML pages
+    pub fn crawl_audit(&self, log: &AccessLog, site_url: Option<&str>) -> CrawlAudit {
+        CrawlAudit::new(&self.root, &self.files, log, site_url)
+    }
+
+    /// Trust signals of the site: legal and contact pages, author bios,
+    /// local contact details, organization naming and HTTPS assets
+    pub fn trust_audit(&self) -> TrustAudit {
+        let business_type = self.merged_result().business_type;
+        TrustAudit::new(&self.root, &self.files, business_type)
+    }
+
+    /// Favicons, app icons and web app manifest across the HTML pages
+    pub fn icon_audit(&self) -> IconAudit {
+        IconAudit::new(&self.root, &self.files)
+    }
+
+    /// Name, address and phone consistency across the pages, plus
+    /// `configured` values such as those of the injector's `SeoConfig`
+    pub fn nap_audit(&self, configured: &[NapMention]) -> NapAudit {
+        let mentions: Vec<NapMention> = self
+            .files
+            .iter()
+            .flat_map(|f| f.result.nap.iter().cloned())
+            .chain(configured.iter().cloned())
+            .collect();
+        NapAudit::new(&mentions)
+    }
+
+    /// Attach imported Search Console rows to the pages they are for,
+    /// leaving out rows for hosts other than `site_url`'s
+    pub fn attach_search_console(&mut self, data: &SearchConsoleData, site_url: Option<&str>) {
+        data.attach(self, site_url);
+    }
+
     /// Get merged analysis from all files
     pub fn merged_result(&self) -> AnalysisResult {
         let mut merged = AnalysisResult::default();
@@ -227,5 +1481,32 @@ impl DirectoryAnalysis {
             merged.merge(file.result.clone());
         }
         merged
+            .search_performance
+            .extend(self.search_console.iter().cloned());
+        merged
+    }
+
+    /// Merged analysis for injecting into `page`: site-wide signals, but only
+    /// the FAQ, HowTo, product, article, performance, link, snippet, content
+    /// depth and search performance data of `page` itself
+    pub fn page_result(&self, page: &Path) -> AnalysisResult {
+        let own = self
+            .files
+            .iter()
+            .find(|f| f.path == page)
+            .map(|f| f.result.clone())
+            .unwrap_or_default();
+        AnalysisResult {
+            faq: own.faq,
+            how_to: own.how_to,
+            products: own.products,
+            article: own.article,
+            performance: own.performance,
+            links: own.links,
+            snippets: own.snippets,
+            content_depth: own.content_depth,
+            search_performance: own.search_performance,
+            ..self.merged_result()
+        }
     }
 }
@@ -8,15 +8,19 @@
 //! - `inject` - Inject optimized SEO metadata
 //! - `run` - Full pipeline (analyze + optimize + inject)
 //! - `report` - Generate detailed SEO report
+//! - `compare` - Compare an old and a new build of a site
 
 use anyhow::{Context, Result};
 use chrono::Utc;
 use clap::{Parser, Subcommand};
 use colored::*;
-use site_ranker_analyzer::{AnalyzerPipeline, DirectoryAnalysis};
-use site_ranker_injector::{InjectorPipeline, SeoConfig};
+use site_ranker_analyzer::{
+    AccessLog, AnalyzerConfig, AnalyzerPipeline, DirectoryAnalysis, IconStatus, NapVariant,
+    SearchConsoleData, SiteComparison, TrustStatus,
+};
+use site_ranker_injector::{Address, InjectorPipeline, SeoConfig};
 use site_ranker_ml_engine::{MlEngine, MlResult, Priority};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
 #[derive(Parser)]
@@ -36,6 +40,10 @@ struct Cli {
     #[arg(short, long, global = true, default_value = "text")]
     format: OutputFormat,
 
+    /// Analyzer configuration file (TOML or JSON)
+    #[arg(short, long, global = true, value_name = "FILE")]
+    config: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -69,6 +77,18 @@ enum Commands {
         /// Output analysis to file
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Site URL, to tell internal from external links
+        #[arg(long)]
+        site_url: Option<String>,
+
+        /// nginx or Apache access log (combined format) to analyze search bot crawls
+        #[arg(long, value_name = "FILE")]
+        access_log: Vec<PathBuf>,
+
+        /// Search Console performance export (queries or pages CSV)
+        #[arg(long, value_name = "FILE")]
+        search_console: Vec<PathBuf>,
     },
 
     /// Inject optimized SEO metadata into website
@@ -97,10 +117,18 @@ enum Commands {
         #[arg(long)]
         image: Option<String>,
 
-        /// Contact email
+        /// Contact email (defaults to one found in the site content)
         #[arg(long)]
         email: Option<String>,
 
+        /// Business phone number (defaults to one found in the site content)
+        #[arg(long)]
+        phone: Option<String>,
+
+        /// Business address as "street, city, state[,] postal code[, country]"
+        #[arg(long, value_parser = parse_address)]
+        address: Option<Address>,
+
         /// Dry run (don't write files)
         #[arg(long)]
         dry_run: bool,
@@ -132,9 +160,17 @@ enum Commands {
         #[arg(long)]
         image: Option<String>,
 
-        /// Contact email
+        /// Contact email (defaults to one found in the site content)
         #[arg(long)]
         email: Option<String>,
+
+        /// Business phone number (defaults to one found in the site content)
+        #[arg(long)]
+        phone: Option<String>,
+
+        /// Business address as "street, city, state[,] postal code[, country]"
+        #[arg(long, value_parser = parse_address)]
+        address: Option<Address>,
     },
 
     /// Generate detailed SEO report
@@ -146,6 +182,33 @@ enum Commands {
         /// Output report file
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Site URL, to tell internal from external links
+        #[arg(long)]
+        site_url: Option<String>,
+
+        /// nginx or Apache access log (combined format) to analyze search bot crawls
+        #[arg(long, value_name = "FILE")]
+        access_log: Vec<PathBuf>,
+
+        /// Search Console performance export (queries or pages CSV)
+        #[arg(long, value_name = "FILE")]
+        search_console: Vec<PathBuf>,
+    },
+
+    /// Compare an old and a new build of a site for lost SEO
+    Compare {
+        /// Directory containing the old build
+        #[arg(value_name = "OLD")]
+        old: PathBuf,
+
+        /// Directory containing the new build
+        #[arg(value_name = "NEW")]
+        new: PathBuf,
+
+        /// Output comparison report file
+        #[arg(short, long)]
+        output: Option<PathBuf>,
     },
 }
 
@@ -165,9 +228,19 @@ async fn main() -> Result<()> {
         .with(filter)
         .init();
 
+    let analyzer = build_analyzer(cli.config.as_deref())?;
+
     match cli.command {
-        Commands::Analyze { directory, output } => {
-            run_analyze(&directory, output.as_deref(), cli.format).await
+        Commands::Analyze {
+            directory,
+            output,
+            site_url,
+            access_log,
+            search_console,
+        } => {
+            let log = read_access_logs(&access_log)?;
+            let search = read_search_console(&search_console)?;
+            run_analyze(&analyzer, &directory, output.as_deref(), site_url.as_deref(), log.as_ref(), search.as_ref(), cli.format).await
         }
         Commands::Inject {
             directory,
@@ -177,10 +250,13 @@ async fn main() -> Result<()> {
             twitter,
             image,
             email,
+            phone,
+            address,
             dry_run,
         } => {
-            let config = build_config(&site_name, &site_url, twitter, image, email);
-            run_inject(&directory, output.as_deref(), &config, dry_run, cli.format).await
+            let contact = Contact { email, phone, address };
+            let config = build_config(&site_name, &site_url, twitter, image, contact);
+            run_inject(&analyzer, &directory, output.as_deref(), &config, dry_run, cli.format).await
         }
         Commands::Run {
             directory,
@@ -190,44 +266,152 @@ async fn main() -> Result<()> {
             twitter,
             image,
             email,
+            phone,
+            address,
+        } => {
+            let contact = Contact { email, phone, address };
+            let config = build_config(&site_name, &site_url, twitter, image, contact);
+            run_full_pipeline(&analyzer, &directory, output.as_deref(), &config, cli.format).await
+        }
+        Commands::Report {
+            directory,
+            output,
+            site_url,
+            access_log,
+            search_console,
         } => {
-            let config = build_config(&site_name, &site_url, twitter, image, email);
-            run_full_pipeline(&directory, output.as_deref(), &config, cli.format).await
+            let log = read_access_logs(&access_log)?;
+            let search = read_search_console(&search_console)?;
+            run_report(&analyzer, &directory, output.as_deref(), site_url.as_deref(), log.as_ref(), search.as_ref(), cli.format).await
         }
-        Commands::Report { directory, output } => {
-            run_report(&directory, output.as_deref(), cli.format).await
+        Commands::Compare { old, new, output } => {
+            run_compare(&analyzer, &old, &new, output.as_deref(), cli.format).await
         }
     }
 }
 
+fn build_analyzer(config_file: Option<&Path>) -> Result<AnalyzerPipeline> {
+    let config = match config_file {
+        Some(path) => AnalyzerConfig::load(path).context("Failed to load analyzer config")?,
+        None => AnalyzerConfig::default(),
+    };
+    AnalyzerPipeline::from_config(&config).context("Failed to build analyzer pipeline")
+}
+
+/// Search bot requests of the given access logs, `None` without logs
+fn read_access_logs(paths: &[PathBuf]) -> Result<Option<AccessLog>> {
+    if paths.is_empty() {
+        return Ok(None);
+    }
+    let mut log = AccessLog::default();
+    for path in paths {
+        log.read(path).context("Failed to read access log")?;
+    }
+    if log.unparsed > 0 {
+        tracing::warn!("{} of {} access log lines not in the combined log format", log.unparsed, log.lines);
+    }
+    Ok(Some(log))
+}
+
+/// Rows of the given Search Console exports, `None` without exports
+fn read_search_console(paths: &[PathBuf]) -> Result<Option<SearchConsoleData>> {
+    if paths.is_empty() {
+        return Ok(None);
+    }
+    let mut data = SearchConsoleData::default();
+    for path in paths {
+        data.read(path).context("Failed to import Search Console export")?;
+    }
+    Ok(Some(data))
+}
+
+/// Contact details given on the command line
+struct Contact {
+    email: Option<String>,
+    phone: Option<String>,
+    address: Option<Address>,
+}
+
 fn build_config(
     site_name: &str,
     site_url: &str,
     twitter: Option<String>,
     image: Option<String>,
-    email: Option<String>,
+    contact: Contact,
 ) -> SeoConfig {
-    SeoConfig::builder()
+    let mut builder = SeoConfig::builder()
         .site_name(site_name)
         .site_url(site_url)
         .twitter_handle(twitter.unwrap_or_default())
-        .default_image(image.unwrap_or_default())
-        .contact_email(email.unwrap_or_default())
-        .build()
+        .default_image(image.unwrap_or_default());
+
+    // Unset contact details fall back to those detected in the content
+    if let Some(email) = contact.email {
+        builder = builder.contact_email(email);
+    }
+    if let Some(phone) = contact.phone {
+        builder = builder.phone(phone);
+    }
+    if let Some(address) = contact.address {
+        builder = builder.address(address);
+    }
+    builder.build()
+}
+
+fn parse_address(s: &str) -> std::result::Result<Address, String> {
+    // Read from the right so the street may contain commas ("Suite 4, 1420 Larimer St");
+    // a trailing part without digits is the country rather than the postal code
+    let mut parts: Vec<&str> = s.split(',').map(str::trim).collect();
+    let country = match parts.last() {
+        Some(last) if parts.len() > 3 && !last.chars().any(|c| c.is_ascii_digit()) => parts.pop(),
+        _ => None,
+    };
+    // State and postal code may share a part, as in "Denver, CO 80202"
+    let last = parts.last().copied().unwrap_or_default();
+    let (state, postal_code) = match last.rsplit_once(char::is_whitespace) {
+        Some((state, code))
+            if !state.chars().any(|c| c.is_ascii_digit())
+                && code.chars().any(|c| c.is_ascii_digit()) =>
+        {
+            parts.pop();
+            (state.trim(), code)
+        }
+        _ => {
+            let postal_code = parts.pop().unwrap_or_default();
+            (parts.pop().unwrap_or_default(), postal_code)
+        }
+    };
+    if parts.len() < 2 || [state, postal_code].iter().chain(&parts).any(|p| p.is_empty()) {
+        return Err("expected \"street, city, state[,] postal code[, country]\"".to_string());
+    }
+    let city = parts.pop().unwrap_or_default();
+    Ok(Address {
+        street: parts.join(", "),
+        city: city.to_string(),
+        state: state.to_string(),
+        postal_code: postal_code.to_string(),
+        country: country.unwrap_or_default().to_string(),
+    })
 }
 
 async fn run_analyze(
-    directory: &PathBuf,
-    output: Option<&std::path::Path>,
+    analyzer: &AnalyzerPipeline,
+    directory: &Path,
+    output: Option<&Path>,
+    site_url: Option<&str>,
+    access_log: Option<&AccessLog>,
+    search_console: Option<&SearchConsoleData>,
     format: OutputFormat,
 ) -> Result<()> {
     println!("\n{}", "🔍 Analyzing website...".cyan().bold());
     println!("{}", "─".repeat(50));
 
-    let analyzer = AnalyzerPipeline::default_pipeline();
-    let analysis = analyzer
+    let mut analysis = analyzer
         .analyze_directory(directory)
         .context("Failed to analyze directory")?;
+    if let Some(data) = search_console {
+        analysis.attach_search_console(data, site_url);
+    }
 
     // Run ML analysis
     let ml_engine = MlEngine::default_engine();
@@ -243,7 +427,7 @@ async fn run_analyze(
             println!("{}", json);
         }
     } else {
-        print_analysis_results(&analysis, &ml_result);
+        print_analysis_results(&analysis, &ml_result, site_url, access_log);
 
         if let Some(path) = output {
             let json = serde_json::to_string_pretty(&analysis)?;
@@ -256,8 +440,9 @@ async fn run_analyze(
 }
 
 async fn run_inject(
-    directory: &PathBuf,
-    output: Option<&std::path::Path>,
+    analyzer: &AnalyzerPipeline,
+    directory: &Path,
+    output: Option<&Path>,
     config: &SeoConfig,
     dry_run: bool,
     format: OutputFormat,
@@ -266,12 +451,18 @@ async fn run_inject(
     println!("{}", "─".repeat(50));
 
     // First analyze
-    let analyzer = AnalyzerPipeline::default_pipeline();
     let analysis = analyzer
         .analyze_directory(directory)
         .context("Failed to analyze directory")?;
 
-    let merged = analysis.merged_result();
+    // State the business name, address and phone the same way on every page
+    let config = &config.clone().with_canonical_nap(&analysis.nap_audit(&config.nap_mentions()));
+
+    // Page-specific markup (FAQ, HowTo, products) comes from the main page only
+    let merged = match &analysis.main_file {
+        Some(main_file) => analysis.page_result(main_file),
+        None => analysis.merged_result(),
+    };
 
     // Generate injections
     let injector = InjectorPipeline::default_pipeline();
@@ -291,7 +482,7 @@ async fn run_inject(
     }
 
     // Inject into files
-    let output_dir = output.unwrap_or(directory.as_path());
+    let output_dir = output.unwrap_or(directory);
 
     if let Some(main_file) = &analysis.main_file {
         let content = std::fs::read_to_string(main_file)?;
@@ -314,15 +505,16 @@ async fn run_inject(
             output_path.display()
         );
     } else {
-        println!("{}", "⚠️  No main HTML file found".yellow());
+        print_missing_main_file(&analysis);
     }
 
     Ok(())
 }
 
 async fn run_full_pipeline(
-    directory: &PathBuf,
-    output: Option<&std::path::Path>,
+    analyzer: &AnalyzerPipeline,
+    directory: &Path,
+    output: Option<&Path>,
     config: &SeoConfig,
     format: OutputFormat,
 ) -> Result<()> {
@@ -331,7 +523,6 @@ async fn run_full_pipeline(
 
     // Step 1: Analyze
     println!("\n{}", "Step 1: Analyzing website...".yellow());
-    let analyzer = AnalyzerPipeline::default_pipeline();
     let analysis = analyzer
         .analyze_directory(directory)
         .context("Failed to analyze directory")?;
@@ -346,12 +537,14 @@ async fn run_full_pipeline(
     // Step 3: Generate & Inject
     println!("{}", "Step 3: Generating and injecting SEO...".yellow());
     let injector = InjectorPipeline::default_pipeline();
+    let config = &config.clone().with_canonical_nap(&analysis.nap_audit(&config.nap_mentions()));
 
-    let output_dir = output.unwrap_or(directory.as_path());
+    let output_dir = output.unwrap_or(directory);
 
     if let Some(main_file) = &analysis.main_file {
         let content = std::fs::read_to_string(main_file)?;
-        let injected = injector.inject(&content, &merged, config)?;
+        let page = analysis.page_result(main_file);
+        let injected = injector.inject(&content, &page, config)?;
 
         let output_path = if output.is_some() {
             std::fs::create_dir_all(output_dir)?;
@@ -363,7 +556,7 @@ async fn run_full_pipeline(
         std::fs::write(&output_path, &injected)?;
 
         if format == OutputFormat::Text {
-            print_analysis_results(&analysis, &ml_result);
+            print_analysis_results(&analysis, &ml_result, Some(&config.site_url), None);
             println!(
                 "\n{} {}",
                 "✅ Optimized file saved to:".green().bold(),
@@ -378,31 +571,37 @@ async fn run_full_pipeline(
             println!("{}", serde_json::to_string_pretty(&result)?);
         }
     } else {
-        println!("{}", "⚠️  No main HTML file found".yellow());
+        print_missing_main_file(&analysis);
     }
 
     Ok(())
 }
 
 async fn run_report(
-    directory: &PathBuf,
-    output: Option<&std::path::Path>,
+    analyzer: &AnalyzerPipeline,
+    directory: &Path,
+    output: Option<&Path>,
+    site_url: Option<&str>,
+    access_log: Option<&AccessLog>,
+    search_console: Option<&SearchConsoleData>,
     _format: OutputFormat,
 ) -> Result<()> {
     println!("\n{}", "📊 Generating SEO Report...".cyan().bold());
     println!("{}", "═".repeat(50));
 
-    let analyzer = AnalyzerPipeline::default_pipeline();
-    let analysis = analyzer
+    let mut analysis = analyzer
         .analyze_directory(directory)
         .context("Failed to analyze directory")?;
+    if let Some(data) = search_console {
+        analysis.attach_search_console(data, site_url);
+    }
 
     let merged = analysis.merged_result();
 
     let ml_engine = MlEngine::default_engine();
     let ml_result = ml_engine.process(&merged).context("ML analysis failed")?;
 
-    let report = generate_report(&analysis, &ml_result);
+    let report = generate_report(&analysis, &ml_result, site_url, access_log);
 
     if let Some(path) = output {
         std::fs::write(path, &report)?;
@@ -414,7 +613,58 @@ async fn run_report(
     Ok(())
 }
 
-fn print_analysis_results(analysis: &DirectoryAnalysis, ml_result: &MlResult) {
+async fn run_compare(
+    analyzer: &AnalyzerPipeline,
+    old: &Path,
+    new: &Path,
+    output: Option<&Path>,
+    format: OutputFormat,
+) -> Result<()> {
+    println!("\n{}", "🔀 Comparing builds...".cyan().bold());
+    println!("{}", "─".repeat(50));
+
+    let comparison = analyzer
+        .compare_directories(old, new)
+        .context("Failed to compare directories")?;
+
+    if format == OutputFormat::Json {
+        let json = serde_json::to_string_pretty(&comparison)?;
+        if let Some(path) = output {
+            std::fs::write(path, &json)?;
+            println!("Comparison saved to: {}", path.display());
+        } else {
+            println!("{}", json);
+        }
+    } else {
+        print_comparison(&comparison);
+
+        if let Some(path) = output {
+            std::fs::write(path, generate_comparison_report(&comparison))?;
+            println!("\n{} {}", "📄 Report saved to:".green(), path.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Point framework sources without an HTML template at their injection target
+fn print_missing_main_file(analysis: &DirectoryAnalysis) {
+    match analysis.framework.find_injection_target(&analysis.root) {
+        Some(target) => println!(
+            "{} add the generated tags to {}",
+            "⚠️  No main HTML file found;".yellow(),
+            target.display()
+        ),
+        None => println!("{}", "⚠️  No main HTML file found".yellow()),
+    }
+}
+
+fn print_analysis_results(
+    analysis: &DirectoryAnalysis,
+    ml_result: &MlResult,
+    site_url: Option<&str>,
+    access_log: Option<&AccessLog>,
+) {
     let merged = analysis.merged_result();
 
     // Header
@@ -424,10 +674,14 @@ fn print_analysis_results(analysis: &DirectoryAnalysis, ml_result: &MlResult) {
 
     // Framework detection
     println!(
-        "\n{} {:?}",
+        "\n{} {:?} (confidence: {:.0}%)",
         "🔧 Framework:".yellow(),
-        analysis.framework
+        analysis.framework,
+        analysis.framework_confidence * 100.0
     );
+    for evidence in &analysis.framework_evidence {
+        println!("   {}", evidence.dimmed());
+    }
 
     if let Some(ref main) = analysis.main_file {
         println!("{} {}", "📄 Main file:".yellow(), main.display());
@@ -435,7 +689,7 @@ fn print_analysis_results(analysis: &DirectoryAnalysis, ml_result: &MlResult) {
 
     println!(
         "{} {}",
-        "📁 HTML files found:".yellow(),
+        "📁 Files analyzed:".yellow(),
         analysis.files.len()
     );
 
@@ -445,6 +699,86 @@ fn print_analysis_results(analysis: &DirectoryAnalysis, ml_result: &MlResult) {
         "🏢 Business Type:".yellow(),
         merged.business_type
     );
+    if let Some(primary) = merged.business_type_scores.first() {
+        println!("   {}", format!("confidence: {:.0}%", primary.confidence * 100.0).dimmed());
+    }
+    if let Some(ref subtype) = merged.business_subtype {
+        println!(
+            "   {} {} ({}, {:.0}%)",
+            "subtype:".dimmed(),
+            subtype.path.join(" → "),
+            subtype.schema_type,
+            subtype.confidence * 100.0
+        );
+    }
+    for secondary in merged.secondary_business_types() {
+        println!(
+            "   {} {:?} ({:.0}%)",
+            "also:".dimmed(),
+            secondary.business_type,
+            secondary.confidence * 100.0
+        );
+    }
+
+    // Contact details
+    let entities = &merged.entities;
+    if !entities.is_empty() {
+        println!("\n{}", "📇 Contact Details:".yellow());
+        let print_entity = |label: &str, value: String, confidence: f32, page: &Option<PathBuf>| {
+            let page = page.as_ref().map(|p| p.display().to_string()).unwrap_or_default();
+            println!(
+                "   {} {} {}",
+                label.dimmed(),
+                value,
+                format!("({:.0}%, {})", confidence * 100.0, page).dimmed()
+            );
+        };
+        if let Some(phone) = entities.phones.first() {
+            print_entity("phone:", phone.value.clone(), phone.confidence, &phone.page);
+        }
+        if let Some(email) = entities.emails.first() {
+            print_entity("email:", email.value.clone(), email.confidence, &email.page);
+        }
+        if let Some(address) = entities.addresses.first() {
+            let a = &address.value;
+            let value = format!("{}, {} {}", a.street, a.city, a.postal_code);
+            print_entity("address:", value, address.confidence, &address.page);
+        }
+        for hours in &entities.opening_hours {
+            print_entity("hours:", hours.value.clone(), hours.confidence, &hours.page);
+        }
+        for profile in &entities.social_profiles {
+            print_entity("social:", profile.value.url.clone(), profile.confidence, &profile.page);
+        }
+    }
+
+    // Articles
+    let articles: Vec<_> = analysis
+        .files
+        .iter()
+        .filter_map(|f| Some((&f.path, f.result.article.as_ref()?)))
+        .collect();
+    if !articles.is_empty() {
+        println!("\n{} {} pages", "📰 Articles:".yellow(), articles.len());
+        for (path, article) in articles {
+            let authors: Vec<_> = article.authors.iter().map(|a| a.name.as_str()).collect();
+            let date = match (&article.published, article.date_source) {
+                (Some(date), Some(source)) => format!("{} ({:?})", date, source),
+                (Some(date), None) => date.clone(),
+                (None, _) => "undated".to_string(),
+            };
+            println!(
+                "   {} {} {}",
+                path.display().to_string().dimmed(),
+                date,
+                if authors.is_empty() {
+                    String::new()
+                } else {
+                    format!("by {}", authors.join(", "))
+                }
+            );
+        }
+    }
 
     // Language
     if let Some(ref lang) = merged.language {
@@ -461,9 +795,9 @@ fn print_analysis_results(analysis: &DirectoryAnalysis, ml_result: &MlResult) {
             "yellow"
         };
         println!(
-            "{} {:.2} ({:?})",
+            "{} {} ({:?})",
             "😊 Sentiment:".yellow(),
-            sentiment.score,
+            format!("{:.2}", sentiment.score).color(sentiment_color),
             sentiment.label
         );
         if !sentiment.power_words.is_empty() {
@@ -498,10 +832,184 @@ fn print_analysis_results(analysis: &DirectoryAnalysis, ml_result: &MlResult) {
     print_check("Canonical URL", seo.has_canonical);
     print_check("Viewport meta", seo.has_viewport);
 
+    // Links
+    let links = analysis.link_audit(site_url);
+    if !links.pages.is_empty() {
+        println!("\n{}", "🔗 Links:".yellow());
+        let (internal, external) = links
+            .pages
+            .iter()
+            .fold((0, 0), |(i, e), p| (i + p.internal, e + p.external));
+        println!("   {} internal, {} external", internal, external);
+        for (issue, count) in links.issue_counts() {
+            println!("   {} {} × {}", "⚠️".yellow(), issue.description(), count);
+        }
+
+        let anchors: Vec<String> = links
+            .anchors
+            .iter()
+            .take(10)
+            .map(|a| {
+                let text = format!("\"{}\" ({})", a.text, a.count);
+                if a.generic {
+                    text.red().to_string()
+                } else {
+                    text
+                }
+            })
+            .collect();
+        if !anchors.is_empty() {
+            println!("   {} {}", "anchors:".dimmed(), anchors.join(", "));
+        }
+
+        for page in links.pages.iter().filter(|p| !p.findings.is_empty()) {
+            let name = page.page.as_ref().map(|p| p.display().to_string()).unwrap_or_default();
+            println!("   {}", name.dimmed());
+            for finding in &page.findings {
+                println!("      {} {} {}", "•".dimmed(), finding.issue.description(), finding.href.dimmed());
+            }
+        }
+
+        for domain in links.domains.iter().take(5) {
+            println!(
+                "   {} {} links from {} pages {}",
+                domain.domain,
+                domain.links,
+                domain.pages,
+                format!("(nofollow {}, sponsored {}, ugc {})", domain.nofollow, domain.sponsored, domain.ugc).dimmed()
+            );
+        }
+    }
+
+    // Canonicals
+    let canonicals = analysis.canonical_audit(site_url);
+    if !canonicals.findings.is_empty() {
+        println!("\n{}", "🧭 Canonicals:".yellow());
+        for finding in &canonicals.findings {
+            println!(
+                "   {} {} {}: {}",
+                "⚠️".yellow(),
+                finding.page.display(),
+                finding.issue.description(),
+                finding.detail.dimmed()
+            );
+        }
+    }
+
+    // Redirects
+    let redirects = analysis.redirect_audit(site_url);
+    if !redirects.rules.is_empty() {
+        println!("\n{} {} rules", "↪️  Redirects:".yellow(), redirects.rules.len());
+        for finding in &redirects.findings {
+            println!(
+                "   {} {} {}: {}",
+                "⚠️".yellow(),
+                finding.from,
+                finding.issue.description(),
+                format!("{} ({})", finding.detail, finding.file.display()).dimmed()
+            );
+        }
+    }
+
+    // Search bot crawls
+    if let Some(log) = access_log {
+        let crawl = analysis.crawl_audit(log, site_url);
+        println!(
+            "\n{} {} bot requests over {} days, {} wasted",
+            "🕷️  Crawl:".yellow(),
+            crawl.hits,
+            crawl.days,
+            crawl.wasted_hits()
+        );
+        for page in crawl.pages.iter().filter(|p| p.hits > 0).take(5) {
+            println!(
+                "   {} {} {}",
+                "•".cyan(),
+                page.path,
+                format!("{} requests, {:.1}/day", page.hits, page.per_day(crawl.days)).dimmed()
+            );
+        }
+        for finding in &crawl.findings {
+            println!(
+                "   {} {} {}: {}",
+                "⚠️".yellow(),
+                finding.url,
+                finding.issue.description(),
+                finding.detail.dimmed()
+            );
+        }
+    }
+
+    // NAP consistency
+    if merged.business_type.is_local() {
+        let nap = analysis.nap_audit(&[]);
+        println!("\n{}", "📍 NAP Consistency:".yellow());
+        for field in nap.fields() {
+            let Some(ref canonical) = field.canonical else {
+                println!("   {} {} not found", "❌".red(), field.field.description());
+                continue;
+            };
+            let icon = if field.mismatches().next().is_some() { "⚠️".yellow() } else { "✅".green() };
+            println!("   {} {}: {}", icon, field.field.description(), canonical);
+            for variant in field.mismatches() {
+                println!("      {} {} {}", "mismatch:".red(), variant.value, nap_sightings(variant).dimmed());
+            }
+            for variant in field.format_variants() {
+                println!("      {} {} {}", "format:".dimmed(), variant.value, nap_sightings(variant).dimmed());
+            }
+        }
+    }
+
+    // Trust signals
+    let trust = analysis.trust_audit();
+    println!("\n{} {}/100", "🛡️  Trust:".yellow(), trust.score);
+    for item in trust.items.iter().filter(|i| i.status != TrustStatus::NotApplicable) {
+        let icon = match item.status {
+            TrustStatus::Present => "✅".green(),
+            TrustStatus::Partial => "⚠️".yellow(),
+            _ => "❌".red(),
+        };
+        let pages: Vec<String> = item.found.iter().take(3).map(|p| p.display().to_string()).collect();
+        println!("   {} {} {}", icon, item.signal.description(), pages.join(", ").dimmed());
+        if let Some(ref detail) = item.detail {
+            println!("      {}", detail.dimmed());
+        }
+        for page in &item.missing {
+            println!("      {} {}", "missing on".dimmed(), page.display());
+        }
+    }
+
+    // Favicons and app icons
+    let icons = analysis.icon_audit();
+    println!("\n{}", "🖼️  Icons:".yellow());
+    for item in icons.items.iter().filter(|i| i.status != IconStatus::NotApplicable) {
+        let icon = match item.status {
+            IconStatus::Present => "✅".green(),
+            IconStatus::Partial => "⚠️".yellow(),
+            _ => "❌".red(),
+        };
+        println!("   {} {} {}", icon, item.check.description(), format!("({})", item.check.display()).dimmed());
+        if let Some(ref detail) = item.detail {
+            println!("      {}", detail.dimmed());
+        }
+        for page in &item.missing {
+            println!("      {} {}", "missing on".dimmed(), page.display());
+        }
+    }
+    for finding in &icons.findings {
+        println!(
+            "   {} {}: {} {}",
+            "⚠️".yellow(),
+            finding.issue.description(),
+            finding.url,
+            finding.detail.dimmed()
+        );
+    }
+
     println!(
         "\n{} {}/100",
         "📈 SEO Score:".yellow(),
-        seo.completeness_score()
+        analysis.seo_score()
     );
 
     println!(
@@ -555,6 +1063,19 @@ fn print_analysis_results(analysis: &DirectoryAnalysis, ml_result: &MlResult) {
     println!("\n{}", "═".repeat(50));
 }
 
+/// `(page text on contact.html, JSON-LD on index.html)`
+fn nap_sightings(variant: &NapVariant) -> String {
+    let sightings: Vec<String> = variant
+        .sightings
+        .iter()
+        .map(|s| match s.page.as_ref().and_then(|p| p.file_name()) {
+            Some(page) => format!("{} on {}", s.source.description(), page.to_string_lossy()),
+            None => s.source.description().to_string(),
+        })
+        .collect();
+    format!("({})", sightings.join(", "))
+}
+
 fn print_check(label: &str, present: bool) {
     if present {
         println!("   {} {}", "✅".green(), label);
@@ -563,7 +1084,12 @@ fn print_check(label: &str, present: bool) {
     }
 }
 
-fn generate_report(analysis: &DirectoryAnalysis, ml_result: &MlResult) -> String {
+fn generate_report(
+    analysis: &DirectoryAnalysis,
+    ml_result: &MlResult,
+    site_url: Option<&str>,
+    access_log: Option<&AccessLog>,
+) -> String {
     let merged = analysis.merged_result();
     let mut report = String::new();
 
@@ -571,10 +1097,29 @@ fn generate_report(analysis: &DirectoryAnalysis, ml_result: &MlResult) -> String
     report.push_str(&format!("Generated: {}\n\n", Utc::now().format("%Y-%m-%d %H:%M:%S UTC")));
 
     report.push_str("## Overview\n\n");
-    report.push_str(&format!("- **Framework**: {:?}\n", analysis.framework));
+    report.push_str(&format!(
+        "- **Framework**: {:?} ({:.0}% confidence)\n",
+        analysis.framework,
+        analysis.framework_confidence * 100.0
+    ));
     report.push_str(&format!("- **Business Type**: {:?}\n", merged.business_type));
-    report.push_str(&format!("- **HTML Files**: {}\n", analysis.files.len()));
-    report.push_str(&format!("- **SEO Score**: {}/100\n", merged.existing_seo.completeness_score()));
+    if let Some(ref subtype) = merged.business_subtype {
+        report.push_str(&format!(
+            "- **Business Subtype**: {} (schema.org `{}`)\n",
+            subtype.path.join(" → "),
+            subtype.schema_type
+        ));
+    }
+    let secondary: Vec<String> = merged
+        .secondary_business_types()
+        .iter()
+        .map(|s| format!("{:?} ({:.0}%)", s.business_type, s.confidence * 100.0))
+        .collect();
+    if !secondary.is_empty() {
+        report.push_str(&format!("- **Secondary Types**: {}\n", secondary.join(", ")));
+    }
+    report.push_str(&format!("- **Files Analyzed**: {}\n", analysis.files.len()));
+    report.push_str(&format!("- **SEO Score**: {}/100\n", analysis.seo_score()));
     report.push_str(&format!("- **Optimization Score**: {}/100\n\n", ml_result.optimization_score));
 
     report.push_str("## SEO Audit\n\n");
@@ -598,6 +1143,229 @@ fn generate_report(analysis: &DirectoryAnalysis, ml_result: &MlResult) -> String
         report.push_str(&format!("**Action**: {}\n\n", rec.action));
     }
 
+    let canonicals = analysis.canonical_audit(site_url);
+    if !canonicals.findings.is_empty() {
+        report.push_str("## Canonicals\n\n");
+        report.push_str("| Page | Issue | Detail |\n");
+        report.push_str("|------|-------|--------|\n");
+        for finding in &canonicals.findings {
+            report.push_str(&format!(
+                "| {} | {} | {} |\n",
+                finding.page.display(),
+                finding.issue.description(),
+                finding.detail
+            ));
+        }
+        report.push('\n');
+    }
+
+    let redirects = analysis.redirect_audit(site_url);
+    if !redirects.rules.is_empty() {
+        report.push_str(&format!("## Redirects ({} rules)\n\n", redirects.rules.len()));
+        if !redirects.findings.is_empty() {
+            report.push_str("| From | Issue | Detail | Declared in |\n");
+            report.push_str("|------|-------|--------|-------------|\n");
+            for finding in &redirects.findings {
+                report.push_str(&format!(
+                    "| {} | {} | {} | {} |\n",
+                    finding.from,
+                    finding.issue.description(),
+                    finding.detail,
+                    finding.file.display()
+                ));
+            }
+            report.push('\n');
+        }
+    }
+
+    if let Some(log) = access_log {
+        let crawl = analysis.crawl_audit(log, site_url);
+        report.push_str(&format!(
+            "## Crawl Budget ({} bot requests over {} days, {} wasted)\n\n",
+            crawl.hits,
+            crawl.days,
+            crawl.wasted_hits()
+        ));
+        report.push_str("| Page | Requests | Per day | Bots | Last crawled |\n");
+        report.push_str("|------|----------|---------|------|--------------|\n");
+        for page in crawl.pages.iter().filter(|p| p.hits > 0) {
+            let bots: Vec<String> = page
+                .bots
+                .iter()
+                .map(|(bot, count)| format!("{} {}", bot.description(), count))
+                .collect();
+            report.push_str(&format!(
+                "| {} | {} | {:.1} | {} | {} |\n",
+                page.path,
+                page.hits,
+                page.per_day(crawl.days),
+                bots.join(", "),
+                page.last_crawled.map(|t| t.format("%Y-%m-%d").to_string()).unwrap_or_default()
+            ));
+        }
+        report.push('\n');
+        if !crawl.findings.is_empty() {
+            report.push_str("| URL | Issue | Bot requests | Detail |\n");
+            report.push_str("|-----|-------|--------------|--------|\n");
+            for finding in &crawl.findings {
+                report.push_str(&format!(
+                    "| {} | {} | {} | {} |\n",
+                    finding.url,
+                    finding.issue.description(),
+                    finding.hits,
+                    finding.detail
+                ));
+            }
+            report.push('\n');
+        }
+    }
+
+    if merged.business_type.is_local() {
+        let nap = analysis.nap_audit(&[]);
+        report.push_str("## NAP Consistency\n\n");
+        report.push_str("| Field | Canonical | Mismatches | Format variants |\n");
+        report.push_str("|-------|-----------|------------|-----------------|\n");
+        for field in nap.fields() {
+            let variants = |variants: Vec<&NapVariant>| {
+                variants
+                    .iter()
+                    .map(|v| format!("{} {}", v.value, nap_sightings(v)))
+                    .collect::<Vec<_>>()
+                    .join("<br>")
+            };
+            report.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                field.field.description(),
+                field.canonical.as_deref().unwrap_or("❌ not found"),
+                variants(field.mismatches().collect()),
+                variants(field.format_variants().collect())
+            ));
+        }
+        report.push('\n');
+    }
+
+    let trust = analysis.trust_audit();
+    report.push_str(&format!("## Trust Signals ({}/100)\n\n", trust.score));
+    report.push_str("| Signal | Status | Found on | Missing on | Detail |\n");
+    report.push_str("|--------|--------|----------|------------|--------|\n");
+    for item in trust.items.iter().filter(|i| i.status != TrustStatus::NotApplicable) {
+        let status = match item.status {
+            TrustStatus::Present => "✅",
+            TrustStatus::Partial => "⚠️ Partial",
+            _ => "❌ Missing",
+        };
+        let pages = |pages: &[PathBuf]| {
+            pages.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", ")
+        };
+        report.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            item.signal.description(),
+            status,
+            pages(&item.found),
+            pages(&item.missing),
+            item.detail.as_deref().unwrap_or("")
+        ));
+    }
+    report.push('\n');
+
+    let icons = analysis.icon_audit();
+    report.push_str("## Favicons and App Icons\n\n");
+    report.push_str("| Check | Shown in | Status | Missing on | Detail |\n");
+    report.push_str("|-------|----------|--------|------------|--------|\n");
+    for item in icons.items.iter().filter(|i| i.status != IconStatus::NotApplicable) {
+        let status = match item.status {
+            IconStatus::Present => "✅",
+            IconStatus::Partial => "⚠️ Partial",
+            _ => "❌ Missing",
+        };
+        let missing: Vec<String> = item.missing.iter().map(|p| p.display().to_string()).collect();
+        report.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            item.check.description(),
+            item.check.display(),
+            status,
+            missing.join(", "),
+            item.detail.as_deref().unwrap_or("")
+        ));
+    }
+    report.push('\n');
+    if !icons.findings.is_empty() {
+        report.push_str("| Icon | Issue | Detail | Page |\n");
+        report.push_str("|------|-------|--------|------|\n");
+        for finding in &icons.findings {
+            report.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                finding.url,
+                finding.issue.description(),
+                finding.detail,
+                finding.page.display()
+            ));
+        }
+        report.push('\n');
+    }
+
+    let links = analysis.link_audit(site_url);
+    if !links.pages.is_empty() {
+        report.push_str("## Links\n\n");
+        let issues = links.issue_counts();
+        if !issues.is_empty() {
+            report.push_str("| Issue | Links |\n");
+            report.push_str("|-------|-------|\n");
+            for (issue, count) in &issues {
+                report.push_str(&format!("| {} | {} |\n", issue.description(), count));
+            }
+            report.push('\n');
+        }
+
+        report.push_str("### Anchor Text\n\n");
+        for anchor in links.anchors.iter().take(10) {
+            report.push_str(&format!(
+                "- \"{}\" ({}){}\n",
+                anchor.text,
+                anchor.count,
+                if anchor.generic { " ⚠️ generic" } else { "" }
+            ));
+        }
+        report.push('\n');
+
+        let flagged: Vec<_> = links.pages.iter().filter(|p| !p.findings.is_empty()).collect();
+        if !flagged.is_empty() {
+            report.push_str("### By Page\n\n");
+            for page in flagged {
+                let name = page.page.as_ref().map(|p| p.display().to_string()).unwrap_or_default();
+                report.push_str(&format!("**{}**\n\n", name));
+                for finding in &page.findings {
+                    report.push_str(&format!(
+                        "- {}: `{}` \"{}\"\n",
+                        finding.issue.description(),
+                        finding.href,
+                        finding.anchor
+                    ));
+                }
+                report.push('\n');
+            }
+        }
+
+        if !links.domains.is_empty() {
+            report.push_str("### External Domains\n\n");
+            report.push_str("| Domain | Links | Pages | nofollow | sponsored | ugc | Issues |\n");
+            report.push_str("|--------|-------|-------|----------|-----------|-----|--------|\n");
+            for domain in &links.domains {
+                report.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {} | {} |\n",
+                    domain.domain,
+                    domain.links,
+                    domain.pages,
+                    domain.nofollow,
+                    domain.sponsored,
+                    domain.ugc,
+                    domain.issues.values().sum::<u32>()
+                ));
+            }
+            report.push('\n');
+        }
+    }
+
     report.push_str("## Top Keywords\n\n");
     for (i, kw) in merged.top_keywords(10).iter().enumerate() {
         report.push_str(&format!("{}. {} (score: {:.2})\n", i + 1, kw.word, kw.score));
@@ -605,3 +1373,177 @@ fn generate_report(analysis: &DirectoryAnalysis, ml_result: &MlResult) -> String
 
     report
 }
+
+fn print_comparison(comparison: &SiteComparison) {
+    let delta = comparison.score_delta();
+    let delta = match delta {
+        d if d < 0 => d.to_string().red(),
+        d if d > 0 => format!("+{}", d).green(),
+        d => d.to_string().normal(),
+    };
+    println!(
+        "\n{} {} → {} ({})",
+        "📈 SEO Score:".yellow(),
+        comparison.score_before,
+        comparison.score_after,
+        delta
+    );
+    println!(
+        "{} {} matched, {} changed, {} removed ({} without redirect), {} added",
+        "📄 Pages:".yellow(),
+        comparison.pages.len(),
+        comparison.changed().count(),
+        comparison.removed.len(),
+        comparison.unredirected().count(),
+        comparison.added.len()
+    );
+
+    if !comparison.removed.is_empty() {
+        println!("\n{}", "🗑️  Removed pages:".yellow());
+        for page in &comparison.removed {
+            match page.redirect {
+                Some(ref target) => println!("   {} {} → {}", "↪️".green(), page.path, target),
+                None => println!("   {} {} {}", "❌".red(), page.path, "no redirect".dimmed()),
+            }
+        }
+    }
+
+    if comparison.changed().next().is_some() {
+        println!("\n{}", "✏️  Changed pages:".yellow());
+        for page in comparison.changed() {
+            println!("   {}", page.path.bold());
+            for change in &page.changes {
+                println!(
+                    "      {} {}: {} → {}",
+                    "⚠️".yellow(),
+                    change.kind.description(),
+                    change.before.dimmed(),
+                    change.after
+                );
+            }
+        }
+    }
+
+    if !comparison.keywords_lost.is_empty() || !comparison.keywords_gained.is_empty() {
+        println!("\n{}", "🔑 Keyword Shifts:".yellow());
+        if !comparison.keywords_lost.is_empty() {
+            println!("   {} {}", "lost:".red(), comparison.keywords_lost.join(", "));
+        }
+        if !comparison.keywords_gained.is_empty() {
+            println!("   {} {}", "gained:".green(), comparison.keywords_gained.join(", "));
+        }
+    }
+}
+
+fn generate_comparison_report(comparison: &SiteComparison) -> String {
+    let mut report = String::new();
+
+    report.push_str("# SEO Comparison Report\n\n");
+    report.push_str(&format!("Generated: {}\n\n", Utc::now().format("%Y-%m-%d %H:%M:%S UTC")));
+
+    report.push_str("## Overview\n\n");
+    report.push_str(&format!("- **Old build**: {}\n", comparison.old_root.display()));
+    report.push_str(&format!("- **New build**: {}\n", comparison.new_root.display()));
+    report.push_str(&format!(
+        "- **SEO Score**: {} → {} ({:+})\n",
+        comparison.score_before,
+        comparison.score_after,
+        comparison.score_delta()
+    ));
+    report.push_str(&format!(
+        "- **Pages**: {} matched, {} changed, {} removed ({} without redirect), {} added\n\n",
+        comparison.pages.len(),
+        comparison.changed().count(),
+        comparison.removed.len(),
+        comparison.unredirected().count(),
+        comparison.added.len()
+    ));
+
+    if !comparison.removed.is_empty() {
+        report.push_str("## Removed Pages\n\n");
+        report.push_str("| Path | Redirect |\n");
+        report.push_str("|------|----------|\n");
+        for page in &comparison.removed {
+            report.push_str(&format!(
+                "| {} | {} |\n",
+                page.path,
+                page.redirect.as_deref().unwrap_or("❌ none")
+            ));
+        }
+        report.push('\n');
+    }
+
+    if comparison.changed().next().is_some() {
+        report.push_str("## Changed Pages\n\n");
+        report.push_str("| Page | Change | Before | After |\n");
+        report.push_str("|------|--------|--------|-------|\n");
+        for page in comparison.changed() {
+            for change in &page.changes {
+                report.push_str(&format!(
+                    "| {} | {} | {} | {} |\n",
+                    page.path,
+                    change.kind.description(),
+                    change.before,
+                    change.after
+                ));
+            }
+        }
+        report.push('\n');
+    }
+
+    if !comparison.added.is_empty() {
+        report.push_str("## Added Pages\n\n");
+        for path in &comparison.added {
+            report.push_str(&format!("- {}\n", path));
+        }
+        report.push('\n');
+    }
+
+    if !comparison.keywords_lost.is_empty() || !comparison.keywords_gained.is_empty() {
+        report.push_str("## Keyword Shifts\n\n");
+        report.push_str(&format!("- **Lost**: {}\n", comparison.keywords_lost.join(", ")));
+        report.push_str(&format!("- **Gained**: {}\n", comparison.keywords_gained.join(", ")));
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_address_from_the_right() {
+        let address = parse_address("Suite 4, 1420 Larimer St, Denver, CO, 80202").unwrap();
+        assert_eq!(address.street, "Suite 4, 1420 Larimer St");
+        assert_eq!(
+            (address.city.as_str(), address.state.as_str(), address.postal_code.as_str()),
+            ("Denver", "CO", "80202")
+        );
+        assert_eq!(address.country, "");
+
+        let address = parse_address("1420 Larimer St, Denver, CO, 80202, US").unwrap();
+        assert_eq!(address.street, "1420 Larimer St");
+        assert_eq!(address.postal_code, "80202");
+        assert_eq!(address.country, "US");
+
+        let address = parse_address("1550 Larimer St, Denver, CO 80202").unwrap();
+        assert_eq!(address.street, "1550 Larimer St");
+        assert_eq!(
+            (address.city.as_str(), address.state.as_str(), address.postal_code.as_str()),
+            ("Denver", "CO", "80202")
+        );
+        let address = parse_address("Suite 4, 1550 Larimer St, Denver, New York 10001, US").unwrap();
+        assert_eq!(address.street, "Suite 4, 1550 Larimer St");
+        assert_eq!(address.state, "New York");
+        assert_eq!(address.country, "US");
+        // A postcode with a space stays whole in its own part
+        let address = parse_address("10 Downing St, London, Greater London, SW1A 2AA").unwrap();
+        assert_eq!(address.state, "Greater London");
+        assert_eq!(address.postal_code, "SW1A 2AA");
+
+        assert!(parse_address("1420 Larimer St, Denver, CO").is_err());
+        assert!(parse_address("Denver, CO 80202").is_err());
+        assert!(parse_address("1420 Larimer St, , CO, 80202").is_err());
+    }
+}
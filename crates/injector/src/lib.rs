@@ -137,7 +137,7 @@ pub fn find_body_end_injection_point(html: &str) -> Option<usize> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use site_ranker_analyzer::{AnalyzerPipeline, BusinessType};
+    use site_ranker_analyzer::AnalyzerPipeline;
 
     const TEST_HTML: &str = r#"<!DOCTYPE html>
 <html lang="en">
@@ -163,9 +163,6 @@ impl InjectorStrategy for MetaTagInjector {
     }
 
     fn inject_content(&self, html: &str, content: &str) -> Result<String, InjectorError> {
-        let injection_point = find_head_injection_point(html)
-            .ok_or(InjectorError::NoInjectionPoint)?;
-
         // Check if we need to replace existing title
         let mut result = html.to_string();
 
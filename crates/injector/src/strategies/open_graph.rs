@@ -14,6 +14,11 @@ impl OpenGraphInjector {
     fn get_og_type(analysis: &AnalysisResult) -> &'static str {
         use site_ranker_analyzer::BusinessType;
 
+        // A dated article is an article whatever the site is about
+        if analysis.article.as_ref().is_some_and(|a| a.published.is_some()) {
+            return "article";
+        }
+
         match analysis.business_type {
             BusinessType::Blog => "article",
             BusinessType::Ecommerce => "product",
@@ -96,6 +101,32 @@ impl InjectorStrategy for OpenGraphInjector {
             tags.push("    <meta property=\"og:image:height\" content=\"630\">".to_string());
         }
 
+        // Add article metadata
+        if let (Some(article), "article") = (&analysis.article, og_type) {
+            let mut property = |name: &str, value: &str| {
+                tags.push(format!(
+                    "    <meta property=\"article:{}\" content=\"{}\">",
+                    name,
+                    html_escape(value)
+                ));
+            };
+            if let Some(ref published) = article.published {
+                property("published_time", published);
+            }
+            if let Some(ref modified) = article.modified {
+                property("modified_time", modified);
+            }
+            for author in &article.authors {
+                property("author", author.url.as_deref().unwrap_or(&author.name));
+            }
+            if let Some(ref section) = article.section {
+                property("section", section);
+            }
+            for tag in &article.tags {
+                property("tag", tag);
+            }
+        }
+
         // Add Facebook App ID if provided
         if let Some(ref app_id) = config.facebook_app_id {
             tags.push(format!(
@@ -7,7 +7,7 @@
 
 use crate::{find_head_injection_point, InjectorError, InjectorStrategy, SeoConfig};
 use serde_json::{json, Value};
-use site_ranker_analyzer::{AnalysisResult, BusinessType};
+use site_ranker_analyzer::{AnalysisResult, ArticleMeta, BusinessType, HowTo, Product};
 
 /// Injector for Schema.org JSON-LD structured data
 pub struct SchemaOrgInjector;
@@ -21,7 +21,7 @@ impl SchemaOrgInjector {
     fn generate_organization(&self, analysis: &AnalysisResult, config: &SeoConfig) -> Value {
         let mut org = json!({
             "@context": "https://schema.org",
-            "@type": analysis.business_type.schema_type(),
+            "@type": analysis.schema_type(),
             "name": config.site_name,
             "url": config.site_url,
         });
@@ -70,8 +70,8 @@ impl SchemaOrgInjector {
             });
         }
 
-        // Add social links placeholder
-        org["sameAs"] = json!([]);
+        // Add social links
+        org["sameAs"] = json!(config.same_as);
 
         org
     }
@@ -164,11 +164,7 @@ impl SchemaOrgInjector {
             BusinessType::LocalBusiness | BusinessType::Restaurant => {
                 let mut local = json!({
                     "@context": "https://schema.org",
-                    "@type": if analysis.business_type == BusinessType::Restaurant {
-                        "Restaurant"
-                    } else {
-                        "LocalBusiness"
-                    },
+                    "@type": analysis.schema_type(),
                     "name": config.site_name,
                     "url": config.site_url
                 });
@@ -188,6 +184,10 @@ impl SchemaOrgInjector {
                     local["telephone"] = json!(phone);
                 }
 
+                if !config.opening_hours.is_empty() {
+                    local["openingHours"] = json!(config.opening_hours);
+                }
+
                 Some(local)
             }
 
@@ -205,24 +205,184 @@ impl SchemaOrgInjector {
         }
     }
 
-    /// Generate FAQ schema from content (if detected)
-    fn generate_faq(&self, analysis: &AnalysisResult) -> Option<Value> {
-        // Look for Q&A patterns in raw text
-        if let Some(ref text) = analysis.raw_text {
-            let text_lower = text.to_lowercase();
-            if text_lower.contains("faq")
-                || text_lower.contains("frequently asked")
-                || text_lower.contains("questions")
-            {
-                // Placeholder - in production would parse actual Q&A
-                return Some(json!({
-                    "@context": "https://schema.org",
-                    "@type": "FAQPage",
-                    "mainEntity": []
-                }));
+    /// Generate BlogPosting (Article outside blogs) schema for an article page
+    fn generate_article(&self, article: &ArticleMeta, analysis: &AnalysisResult, config: &SeoConfig) -> Option<Value> {
+        if article.published.is_none() && article.authors.is_empty() {
+            return None;
+        }
+
+        let headline = config
+            .title_override
+            .clone()
+            .or_else(|| analysis.existing_seo.title.clone())
+            .or_else(|| analysis.front_matter.as_ref().and_then(|fm| fm.title.clone()))
+            .unwrap_or_else(|| config.site_name.clone());
+
+        let mut schema = json!({
+            "@context": "https://schema.org",
+            "@type": if analysis.business_type == BusinessType::Blog { "BlogPosting" } else { "Article" },
+            "headline": headline,
+            "mainEntityOfPage": config.site_url,
+            "publisher": {
+                "@type": "Organization",
+                "name": config.site_name
+            }
+        });
+
+        if !article.authors.is_empty() {
+            schema["author"] = article
+                .authors
+                .iter()
+                .map(|author| {
+                    let mut person = json!({ "@type": "Person", "name": author.name });
+                    if let Some(ref url) = author.url {
+                        person["url"] = json!(url);
+                    }
+                    person
+                })
+                .collect();
+        }
+        if let Some(ref published) = article.published {
+            schema["datePublished"] = json!(published);
+        }
+        if let Some(ref modified) = article.modified {
+            schema["dateModified"] = json!(modified);
+        }
+        if let Some(ref section) = article.section {
+            schema["articleSection"] = json!(section);
+        }
+        if !article.tags.is_empty() {
+            schema["keywords"] = json!(article.tags.join(", "));
+        }
+
+        Some(schema)
+    }
+
+    /// Generate HowTo schema for a set of instructions found in the content
+    fn generate_how_to(&self, guide: &HowTo) -> Value {
+        let steps: Vec<_> = guide
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(i, step)| {
+                let mut value = json!({
+                    "@type": "HowToStep",
+                    "position": i + 1,
+                    "text": step.text
+                });
+                if let Some(ref name) = step.name {
+                    value["name"] = json!(name);
+                }
+                value
+            })
+            .collect();
+
+        let mut how_to = json!({
+            "@context": "https://schema.org",
+            "@type": "HowTo",
+            "name": guide.name,
+            "step": steps
+        });
+
+        if let Some(ref total_time) = guide.total_time {
+            how_to["totalTime"] = json!(total_time);
+        }
+        if !guide.supplies.is_empty() {
+            how_to["supply"] = guide
+                .supplies
+                .iter()
+                .map(|s| json!({ "@type": "HowToSupply", "name": s }))
+                .collect();
+        }
+        if !guide.tools.is_empty() {
+            how_to["tool"] = guide
+                .tools
+                .iter()
+                .map(|t| json!({ "@type": "HowToTool", "name": t }))
+                .collect();
+        }
+
+        how_to
+    }
+
+    /// Generate Product schema with its offer and rating
+    fn generate_product(&self, product: &Product) -> Value {
+        let mut schema = json!({
+            "@context": "https://schema.org",
+            "@type": "Product",
+            "name": product.name
+        });
+
+        if let Some(ref description) = product.description {
+            schema["description"] = json!(description);
+        }
+        if !product.images.is_empty() {
+            schema["image"] = json!(product.images);
+        }
+        if let Some(ref sku) = product.sku {
+            schema["sku"] = json!(sku);
+        }
+        if let Some(ref gtin) = product.gtin {
+            schema["gtin"] = json!(gtin);
+        }
+        if let Some(ref brand) = product.brand {
+            schema["brand"] = json!({ "@type": "Brand", "name": brand });
+        }
+
+        if let Some(ref price) = product.price {
+            let mut offer = json!({
+                "@type": "Offer",
+                "price": price
+            });
+            if let Some(ref currency) = product.currency {
+                offer["priceCurrency"] = json!(currency);
             }
+            if let Some(availability) = product.availability {
+                offer["availability"] = json!(availability.schema_url());
+            }
+            schema["offers"] = offer;
         }
-        None
+
+        if let Some(ref rating) = product.rating {
+            if rating.count > 0 {
+                schema["aggregateRating"] = json!({
+                    "@type": "AggregateRating",
+                    "ratingValue": rating.value,
+                    "reviewCount": rating.count,
+                    "bestRating": rating.best
+                });
+            }
+        }
+
+        schema
+    }
+
+    /// Generate FAQ schema from the Q&A pairs found in the content
+    fn generate_faq(&self, analysis: &AnalysisResult) -> Option<Value> {
+        if analysis.faq.is_empty() {
+            return None;
+        }
+
+        let questions: Vec<_> = analysis
+            .faq
+            .iter()
+            .map(|entry| {
+                json!({
+                    "@type": "Question",
+                    "name": entry.question,
+                    "acceptedAnswer": {
+                        "@type": "Answer",
+                        "text": entry.answer
+                    }
+                })
+            })
+            .collect();
+
+        Some(json!({
+            "@context": "https://schema.org",
+            "@type": "FAQPage",
+            "mainEntity": questions
+        }))
     }
 }
 
@@ -243,6 +403,9 @@ impl InjectorStrategy for SchemaOrgInjector {
             return Ok(String::new());
         }
 
+        // Fall back to contact details found in the content
+        let config = &config.clone().with_detected_contacts(&analysis.entities);
+
         let mut schemas = Vec::new();
 
         // Always add Organization/Business schema
@@ -259,11 +422,30 @@ impl InjectorStrategy for SchemaOrgInjector {
             schemas.push(schema);
         }
 
+        // Add BlogPosting for an article page
+        if let Some(article) = analysis
+            .article
+            .as_ref()
+            .and_then(|a| self.generate_article(a, analysis, config))
+        {
+            schemas.push(article);
+        }
+
         // Add FAQ if detected
         if let Some(faq) = self.generate_faq(analysis) {
             schemas.push(faq);
         }
 
+        // Add HowTo for each set of instructions
+        for guide in &analysis.how_to {
+            schemas.push(self.generate_how_to(guide));
+        }
+
+        // Add Product for each product on the page
+        for product in &analysis.products {
+            schemas.push(self.generate_product(product));
+        }
+
         // Combine into graph
         let graph = json!({
             "@context": "https://schema.org",
@@ -315,6 +497,10 @@ fn indent_json(json: &str, spaces: usize) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use site_ranker_analyzer::{
+        AggregateRating, Author, Availability, BusinessSubtype, ContactEntities, DetectedEntity,
+        FaqEntry, HowToStep, NapAudit, NapMention, NapSource, PostalAddress, SocialProfile,
+    };
 
     #[test]
     fn test_schema_generation() {
@@ -337,4 +523,255 @@ mod tests {
         assert!(result.contains("ProfessionalService"));
         assert!(result.contains("Test Corp"));
     }
+
+    #[test]
+    fn test_schema_uses_business_subtype() {
+        let analysis = AnalysisResult {
+            business_type: BusinessType::LocalBusiness,
+            business_subtype: Some(BusinessSubtype {
+                id: "plumber".to_string(),
+                path: vec![
+                    "local_business".to_string(),
+                    "home_and_construction".to_string(),
+                    "plumber".to_string(),
+                ],
+                schema_type: "Plumber".to_string(),
+                category: BusinessType::LocalBusiness,
+                score: 12.0,
+                confidence: 0.8,
+            }),
+            ..Default::default()
+        };
+
+        let result = SchemaOrgInjector::new()
+            .generate(&analysis, &SeoConfig::default())
+            .unwrap();
+
+        assert!(result.contains("\"@type\": \"Plumber\""));
+        assert!(!result.contains("\"@type\": \"LocalBusiness\""));
+    }
+
+    #[test]
+    fn test_schema_falls_back_to_detected_contacts() {
+        let detected = |value: &str, confidence| DetectedEntity {
+            value: value.to_string(),
+            confidence,
+            page: None,
+        };
+        let analysis = AnalysisResult {
+            business_type: BusinessType::LocalBusiness,
+            entities: ContactEntities {
+                phones: vec![detected("+13035550142", 0.95)],
+                emails: vec![detected("guess@acme.com", 0.3)],
+                addresses: vec![DetectedEntity {
+                    value: PostalAddress {
+                        street: "1420 Larimer St".to_string(),
+                        city: "Denver".to_string(),
+                        region: Some("CO".to_string()),
+                        postal_code: "80202".to_string(),
+                        country: Some("US".to_string()),
+                    },
+                    confidence: 0.85,
+                    page: None,
+                }],
+                opening_hours: vec![detected("Mo-Fr 08:00-18:00", 0.8)],
+                social_profiles: vec![DetectedEntity {
+                    value: SocialProfile {
+                        network: "facebook".to_string(),
+                        url: "https://facebook.com/acme".to_string(),
+                    },
+                    confidence: 0.9,
+                    page: None,
+                }],
+            },
+            ..Default::default()
+        };
+        let config = SeoConfig::builder()
+            .site_name("Acme")
+            .phone("+1-720-555-0100")
+            .build();
+
+        let result = SchemaOrgInjector::new().generate(&analysis, &config).unwrap();
+
+        // Configured values win; low-confidence detections are not used
+        assert!(result.contains("+1-720-555-0100"));
+        assert!(!result.contains("+13035550142"));
+        assert!(!result.contains("guess@acme.com"));
+        assert!(result.contains("\"addressLocality\": \"Denver\""));
+        assert!(result.contains("Mo-Fr 08:00-18:00"));
+        assert!(result.contains("https://facebook.com/acme"));
+    }
+
+    #[test]
+    fn test_schema_uses_canonical_nap() {
+        let text = |value: &str| NapMention::phone(value, "US", NapSource::Text).unwrap();
+        let nap = NapAudit::new(&[
+            text("(303) 555-0142"),
+            text("303.555.0142"),
+            NapMention::phone("720-555-0100", "US", NapSource::Text).unwrap(),
+            NapMention::name("Acme Plumbing LLC", NapSource::JsonLd).unwrap(),
+        ]);
+        let analysis = AnalysisResult {
+            business_type: BusinessType::LocalBusiness,
+            entities: ContactEntities {
+                phones: vec![DetectedEntity {
+                    value: "+17205550100".to_string(),
+                    confidence: 0.95,
+                    page: None,
+                }],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let config = SeoConfig::default().with_canonical_nap(&nap);
+
+        let result = SchemaOrgInjector::new().generate(&analysis, &config).unwrap();
+
+        // The number most pages agree on, not the most confident sighting
+        assert!(result.contains("\"telephone\": \"+13035550142\""));
+        assert!(result.contains("\"name\": \"Acme Plumbing LLC\""));
+    }
+
+    #[test]
+    fn test_faq_schema_only_for_real_pairs() {
+        let injector = SchemaOrgInjector::new();
+        let mut analysis = AnalysisResult {
+            raw_text: Some("Frequently asked questions".to_string()),
+            ..Default::default()
+        };
+
+        let result = injector.generate(&analysis, &SeoConfig::default()).unwrap();
+        assert!(!result.contains("FAQPage"));
+
+        analysis.faq.push(FaqEntry {
+            question: "Do you charge for estimates?".to_string(),
+            answer: "No, estimates are always free.".to_string(),
+        });
+        let result = injector.generate(&analysis, &SeoConfig::default()).unwrap();
+        assert!(result.contains("FAQPage"));
+        assert!(result.contains("\"name\": \"Do you charge for estimates?\""));
+        assert!(result.contains("\"text\": \"No, estimates are always free.\""));
+    }
+
+    #[test]
+    fn test_how_to_schema() {
+        let analysis = AnalysisResult {
+            how_to: vec![HowTo {
+                name: "How to unclog a sink".to_string(),
+                steps: vec![
+                    HowToStep {
+                        name: Some("Remove the stopper".to_string()),
+                        text: "Unscrew the pivot nut.".to_string(),
+                    },
+                    HowToStep {
+                        name: None,
+                        text: "Plunge for a minute.".to_string(),
+                    },
+                ],
+                supplies: vec!["Baking soda".to_string()],
+                tools: vec!["Plunger".to_string()],
+                total_time: Some("PT30M".to_string()),
+            }],
+            ..Default::default()
+        };
+
+        let result = SchemaOrgInjector::new()
+            .generate(&analysis, &SeoConfig::default())
+            .unwrap();
+        let json = result
+            .trim()
+            .trim_start_matches("<script type=\"application/ld+json\">")
+            .trim_end_matches("</script>");
+        let graph: Value = serde_json::from_str(json).unwrap();
+        let how_to = graph["@graph"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|s| s["@type"] == "HowTo")
+            .unwrap();
+
+        assert_eq!(how_to["totalTime"], "PT30M");
+        assert_eq!(how_to["step"][0]["name"], "Remove the stopper");
+        assert_eq!(how_to["step"][1]["position"], 2);
+        assert!(how_to["step"][1].get("name").is_none());
+        assert_eq!(how_to["supply"][0]["@type"], "HowToSupply");
+        assert_eq!(how_to["tool"][0]["name"], "Plunger");
+    }
+
+    #[test]
+    fn test_product_schema() {
+        let mug = Product {
+            name: "Speckled Stoneware Mug".to_string(),
+            price: Some("24.00".to_string()),
+            currency: Some("USD".to_string()),
+            availability: Some(Availability::InStock),
+            sku: Some("MUG-SPK-12".to_string()),
+            brand: Some("Acme Pottery".to_string()),
+            images: vec!["https://shop.example/mug.jpg".to_string()],
+            rating: Some(AggregateRating {
+                value: 4.5,
+                count: 12,
+                best: 5.0,
+            }),
+            ..Default::default()
+        };
+        let unrated = Product {
+            name: "Camp Mug".to_string(),
+            rating: Some(AggregateRating {
+                value: 5.0,
+                count: 0,
+                best: 5.0,
+            }),
+            ..Default::default()
+        };
+
+        let injector = SchemaOrgInjector::new();
+        let schema = injector.generate_product(&mug);
+        assert_eq!(schema["offers"]["@type"], "Offer");
+        assert_eq!(schema["offers"]["price"], "24.00");
+        assert_eq!(schema["offers"]["priceCurrency"], "USD");
+        assert_eq!(schema["offers"]["availability"], "https://schema.org/InStock");
+        assert_eq!(schema["brand"]["@type"], "Brand");
+        assert_eq!(schema["aggregateRating"]["reviewCount"], 12);
+
+        let schema = injector.generate_product(&unrated);
+        assert!(schema.get("offers").is_none());
+        assert!(schema.get("aggregateRating").is_none());
+    }
+
+    #[test]
+    fn test_blog_posting_schema() {
+        let analysis = AnalysisResult {
+            business_type: BusinessType::Blog,
+            article: Some(ArticleMeta {
+                authors: vec![Author {
+                    name: "Dana Whitfield".to_string(),
+                    url: Some("/author/dana/".to_string()),
+                }],
+                published: Some("2024-01-08T09:30:00+00:00".to_string()),
+                section: Some("Maintenance".to_string()),
+                tags: vec!["Winter".to_string(), "Pipes".to_string()],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let config = SeoConfig::builder()
+            .title_override("Winterizing Your Pipes")
+            .build();
+
+        let injector = SchemaOrgInjector::new();
+        let schema = injector
+            .generate_article(analysis.article.as_ref().unwrap(), &analysis, &config)
+            .unwrap();
+        assert_eq!(schema["@type"], "BlogPosting");
+        assert_eq!(schema["headline"], "Winterizing Your Pipes");
+        assert_eq!(schema["author"][0]["url"], "/author/dana/");
+        assert_eq!(schema["datePublished"], "2024-01-08T09:30:00+00:00");
+        assert_eq!(schema["keywords"], "Winter, Pipes");
+
+        // Undated, unattributed pages get no article markup
+        assert!(injector
+            .generate_article(&ArticleMeta::default(), &analysis, &config)
+            .is_none());
+    }
 }
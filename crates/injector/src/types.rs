@@ -1,6 +1,7 @@
 //! Types for SEO injection configuration and output
 
 use serde::{Deserialize, Serialize};
+use site_ranker_analyzer::{ContactEntities, NapAudit, NapMention, NapSource, PostalAddress};
 
 /// Configuration for SEO generation
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +30,12 @@ pub struct SeoConfig {
     /// Business address
     pub address: Option<Address>,
 
+    /// Social profile URLs for schema.org `sameAs`
+    pub same_as: Vec<String>,
+
+    /// schema.org `openingHours` values, e.g. `Mo-Fr 09:00-17:00`
+    pub opening_hours: Vec<String>,
+
     /// Override auto-generated title
     pub title_override: Option<String>,
 
@@ -62,6 +69,8 @@ impl Default for SeoConfig {
             contact_email: None,
             phone: None,
             address: None,
+            same_as: Vec::new(),
+            opening_hours: Vec::new(),
             title_override: None,
             description_override: None,
             extra_keywords: Vec::new(),
@@ -77,6 +86,85 @@ impl SeoConfig {
     pub fn builder() -> SeoConfigBuilder {
         SeoConfigBuilder::default()
     }
+
+    /// Fill contact details the config leaves unset with confident values
+    /// detected in the site content
+    pub fn with_detected_contacts(mut self, entities: &ContactEntities) -> Self {
+        if self.phone.as_deref().map_or(true, str::is_empty) {
+            self.phone = entities.phone().map(|p| p.value.clone());
+        }
+        if self.contact_email.as_deref().map_or(true, str::is_empty) {
+            self.contact_email = entities.email().map(|e| e.value.clone());
+        }
+        if self.address.is_none() {
+            self.address = entities.address().map(|a| Address::from(&a.value));
+        }
+        if self.same_as.is_empty() {
+            self.same_as = entities
+                .confident_social_profiles()
+                .into_iter()
+                .map(str::to_string)
+                .collect();
+        }
+        if self.opening_hours.is_empty() {
+            self.opening_hours = entities
+                .confident_opening_hours()
+                .into_iter()
+                .map(str::to_string)
+                .collect();
+        }
+        self
+    }
+
+    /// Configured name, address and phone, canonical in a `NapAudit`
+    pub fn nap_mentions(&self) -> Vec<NapMention> {
+        // National numbers are read as the address' country, else the locale's
+        let country = self
+            .address
+            .as_ref()
+            .map(|a| a.country.to_uppercase())
+            .filter(|c| !c.is_empty())
+            .or_else(|| self.locale.split('_').nth(1).map(str::to_uppercase))
+            .unwrap_or_else(|| "US".to_string());
+
+        let mut mentions: Vec<NapMention> = NapMention::name(&self.site_name, NapSource::Config)
+            .into_iter()
+            .collect();
+        if let Some(phone) = self.phone.as_deref() {
+            mentions.extend(NapMention::phone(phone, &country, NapSource::Config));
+        }
+        if let Some(address) = &self.address {
+            let value = format!(
+                "{}, {}, {} {}",
+                address.street, address.city, address.state, address.postal_code
+            );
+            mentions.push(NapMention::address(
+                value.trim(),
+                PostalAddress::from(address),
+                NapSource::Config,
+            ));
+        }
+        mentions
+    }
+
+    /// Fill the name, phone and address the config leaves unset with the
+    /// canonical values of `nap`, so every page states them the same way
+    pub fn with_canonical_nap(mut self, nap: &NapAudit) -> Self {
+        if self.site_name.is_empty() {
+            if let Some(name) = &nap.name.canonical {
+                self.site_name = name.clone();
+            }
+        }
+        if self.phone.as_deref().map_or(true, str::is_empty) {
+            if let Some(phone) = &nap.phone.canonical {
+                self.phone = Some(phone.clone());
+            }
+        }
+        if self.address.is_none() {
+            self.address = nap.canonical_address.as_ref().map(Address::from);
+        }
+        self
+    }
 }
 
 /// Builder for SeoConfig
@@ -126,6 +214,16 @@ impl SeoConfigBuilder {
         self
     }
 
+    pub fn same_as(mut self, url: impl Into<String>) -> Self {
+        self.config.same_as.push(url.into());
+        self
+    }
+
+    pub fn opening_hours(mut self, hours: impl Into<String>) -> Self {
+        self.config.opening_hours.push(hours.into());
+        self
+    }
+
     pub fn title_override(mut self, title: impl Into<String>) -> Self {
         self.config.title_override = Some(title.into());
         self
@@ -166,6 +264,31 @@ pub struct Address {
     pub country: String,
 }
 
+impl From<&PostalAddress> for Address {
+    fn from(address: &PostalAddress) -> Self {
+        Self {
+            street: address.street.clone(),
+            city: address.city.clone(),
+            state: address.region.clone().unwrap_or_default(),
+            postal_code: address.postal_code.clone(),
+            country: address.country.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&Address> for PostalAddress {
+    fn from(address: &Address) -> Self {
+        let optional = |s: &str| (!s.is_empty()).then(|| s.to_string());
+        Self {
+            street: address.street.clone(),
+            city: address.city.clone(),
+            region: optional(&address.state),
+            postal_code: address.postal_code.clone(),
+            country: optional(&address.country),
+        }
+    }
+}
+
 /// Generated SEO content
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GeneratedSeo {
@@ -0,0 +1,276 @@
+//! Thin and padded content findings
+//!
+//! Compares the analyzer's per-page content depth with word ranges for the
+//! page's role. The minimum moves with the business type: readers of a blog or
+//! a clinic expect more explanation than diners checking a menu. Findings are
+//! grouped by role and name the worst pages, like the performance findings.
+
+use crate::{
+    MlEngineError, MlResult, MlStrategy, Priority, Recommendation, RecommendationCategory,
+};
+use site_ranker_analyzer::{AnalysisResult, BusinessType, ContentDepth, PageRole};
+
+/// Main-content word range per page role, before the business type scaling
+const ROLE_WORDS: [(PageRole, u32, u32); 5] = [
+    (PageRole::Home, 250, 2500),
+    (PageRole::Product, 200, 2500),
+    (PageRole::Article, 600, 4000),
+    (PageRole::Category, 150, 2000),
+    (PageRole::Page, 200, 3000),
+];
+
+/// Richness below this on a page long enough to measure reads as padding
+const MIN_RICHNESS: f32 = 0.55;
+const MIN_RICHNESS_WORDS: u32 = 300;
+
+/// Long pages need a subheading every this many words
+const WORDS_PER_SECTION: u32 = 400;
+const MIN_SECTIONED_WORDS: u32 = 600;
+
+/// Pages named in a finding before the rest are summarized as a count
+const MAX_PAGES_LISTED: usize = 3;
+
+/// Advisor for thin and padded content
+pub struct ContentDepthAdvisor;
+
+impl ContentDepthAdvisor {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ContentDepthAdvisor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MlStrategy for ContentDepthAdvisor {
+    fn name(&self) -> &'static str {
+        "content_depth_advisor"
+    }
+
+    fn process(&self, analysis: &AnalysisResult) -> Result<MlResult, MlEngineError> {
+        let pages = &analysis.content_depth;
+        let mut recommendations = Vec::new();
+        let mut finding = |priority, message: String, action: &str| {
+            recommendations.push(Recommendation {
+                category: RecommendationCategory::Content,
+                priority,
+                message,
+                action: action.to_string(),
+            })
+        };
+
+        for (role, _, _) in ROLE_WORDS {
+            let (min, max) = word_range(role, &analysis.business_type);
+            let of_role: Vec<_> = pages.iter().filter(|p| p.role == role).collect();
+
+            let mut thin: Vec<_> = of_role
+                .iter()
+                .filter(|p| p.words < min)
+                .map(|p| (*p, p.words))
+                .collect();
+            thin.sort_by_key(|(_, words)| *words);
+            if !thin.is_empty() {
+                finding(
+                    if role == PageRole::Home || thin.iter().any(|(_, words)| *words < min / 3) {
+                        Priority::High
+                    } else {
+                        Priority::Medium
+                    },
+                    format!(
+                        "Thin content on {} (words, {} expected) on {}",
+                        plural(role),
+                        min,
+                        page_list(&thin)
+                    ),
+                    match role {
+                        PageRole::Home => "Say who you serve, what you offer and why to choose you, with links to the key pages",
+                        PageRole::Product => "Add a unique description, specifications, use cases and answers to common questions",
+                        PageRole::Article => "Cover the topic in depth: examples, steps and the questions readers ask next",
+                        PageRole::Category => "Add an introduction to the category and a short summary for each item listed",
+                        PageRole::Page => "Expand the page with the details visitors look for, or merge it into a related page",
+                    },
+                );
+            }
+
+            let mut long: Vec<_> = of_role
+                .iter()
+                .filter(|p| p.words > max)
+                .map(|p| (*p, p.words))
+                .collect();
+            long.sort_by_key(|(_, words)| std::cmp::Reverse(*words));
+            if !long.is_empty() {
+                finding(
+                    Priority::Low,
+                    format!(
+                        "Overlong {} (words, {} at most) on {}",
+                        plural(role),
+                        max,
+                        page_list(&long)
+                    ),
+                    "Split the page by subtopic or trim content that doesn't serve its purpose",
+                );
+            }
+        }
+
+        let mut repetitive: Vec<_> = pages
+            .iter()
+            .filter(|p| p.words >= MIN_RICHNESS_WORDS && p.richness < MIN_RICHNESS)
+            .map(|p| (p, (p.richness * 100.0).round() as u32))
+            .collect();
+        repetitive.sort_by_key(|(_, richness)| *richness);
+        if !repetitive.is_empty() {
+            finding(
+                Priority::Medium,
+                format!(
+                    "Repetitive, padded text (vocabulary richness %) on {}",
+                    page_list(&repetitive)
+                ),
+                "Cut repeated phrases and keyword stuffing; replace filler with specific facts",
+            );
+        }
+
+        let mut unstructured: Vec<_> = pages
+            .iter()
+            .filter(|p| p.words > MIN_SECTIONED_WORDS && p.sections < p.words / WORDS_PER_SECTION)
+            .map(|p| (p, p.words))
+            .collect();
+        unstructured.sort_by_key(|(_, words)| std::cmp::Reverse(*words));
+        if !unstructured.is_empty() {
+            finding(
+                Priority::Low,
+                format!(
+                    "Long text with few subheadings (words) on {}",
+                    page_list(&unstructured)
+                ),
+                "Break the text up with an <h2> or <h3> every 300-400 words",
+            );
+        }
+
+        Ok(MlResult {
+            recommendations,
+            ..Default::default()
+        })
+    }
+}
+
+/// Minimum and maximum main-content words for `role` on a `business` site
+fn word_range(role: PageRole, business: &BusinessType) -> (u32, u32) {
+    let (_, min, max) = ROLE_WORDS
+        .into_iter()
+        .find(|(r, _, _)| *r == role)
+        .unwrap_or(ROLE_WORDS[4]);
+    let scale = match business {
+        BusinessType::Blog
+        | BusinessType::Education
+        | BusinessType::Healthcare
+        | BusinessType::Technology
+        | BusinessType::SaaS => 1.25,
+        BusinessType::Restaurant | BusinessType::LocalBusiness | BusinessType::Portfolio => 0.6,
+        _ => 1.0,
+    };
+    ((min as f32 * scale).round() as u32, max)
+}
+
+fn plural(role: PageRole) -> &'static str {
+    match role {
+        PageRole::Home => "the home page",
+        PageRole::Product => "product pages",
+        PageRole::Article => "articles",
+        PageRole::Category => "category pages",
+        PageRole::Page => "pages",
+    }
+}
+
+/// `about.html (120), team.html (95), 4 more pages`
+fn page_list(pages: &[(&ContentDepth, u32)]) -> String {
+    let mut names: Vec<String> = pages
+        .iter()
+        .take(MAX_PAGES_LISTED)
+        .map(|(page, value)| {
+            let name = page
+                .page
+                .as_ref()
+                .and_then(|p| p.file_name())
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "page".to_string());
+            format!("{} ({})", name, value)
+        })
+        .collect();
+    if pages.len() > MAX_PAGES_LISTED {
+        names.push(format!("{} more pages", pages.len() - MAX_PAGES_LISTED));
+    }
+    names.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn page(name: &str, role: PageRole, words: u32) -> ContentDepth {
+        ContentDepth {
+            page: Some(PathBuf::from(name)),
+            role,
+            words,
+            unique_terms: words / 2,
+            richness: 0.75,
+            sections: words / 300,
+        }
+    }
+
+    fn messages(business_type: BusinessType, pages: Vec<ContentDepth>) -> Vec<String> {
+        let analysis = AnalysisResult {
+            business_type,
+            content_depth: pages,
+            ..Default::default()
+        };
+        ContentDepthAdvisor::new()
+            .process(&analysis)
+            .unwrap()
+            .recommendations
+            .into_iter()
+            .map(|r| r.message)
+            .collect()
+    }
+
+    #[test]
+    fn test_thresholds_follow_role_and_business_type() {
+        let pages = || {
+            vec![
+                page("index.html", PageRole::Home, 400),
+                page("first-post.html", PageRole::Article, 700),
+                page("menu.html", PageRole::Page, 150),
+            ]
+        };
+
+        // A blog post of 700 words is short; a restaurant's 150-word menu isn't
+        assert_eq!(
+            messages(BusinessType::Blog, pages()),
+            [
+                "Thin content on articles (words, 750 expected) on first-post.html (700)",
+                "Thin content on pages (words, 250 expected) on menu.html (150)",
+            ]
+        );
+        assert!(messages(BusinessType::Restaurant, pages()).is_empty());
+    }
+
+    #[test]
+    fn test_padded_and_unstructured_pages() {
+        let mut stuffed = page("plumber-springfield.html", PageRole::Page, 900);
+        stuffed.richness = 0.31;
+        let mut wall = page("guide.html", PageRole::Article, 1800);
+        wall.sections = 1;
+
+        let found = messages(BusinessType::Service, vec![stuffed, wall]);
+        assert_eq!(
+            found,
+            [
+                "Repetitive, padded text (vocabulary richness %) on plumber-springfield.html (31)",
+                "Long text with few subheadings (words) on guide.html (1800)",
+            ]
+        );
+    }
+}
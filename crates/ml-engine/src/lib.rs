@@ -9,6 +9,10 @@
 //! - **Smart keyword density analysis** - Avoid stuffing, find semantic relationships
 //! - **Trend prediction** - Which Schema.org types are gaining SERP features
 //! - **Title optimization** - A/B variant suggestions
+//! - **Static performance audit** - DOM size, render-blocking scripts, page weight
+//! - **Featured-snippet readiness** - Answer shape below question headings
+//! - **Content depth** - Thin and padded pages by page role and business type
+//! - **Search Console insights** - Low-CTR pages and striking-distance queries, by click upside
 //!
 //! ## Architecture
 //! - `MlStrategy` trait defines the contract
@@ -19,11 +23,19 @@ mod error;
 mod sentiment;
 mod optimizer;
 mod trend;
+mod performance;
+mod snippet;
+mod content_depth;
+mod search_console;
 
 pub use error::MlEngineError;
 pub use sentiment::*;
 pub use optimizer::*;
 pub use trend::*;
+pub use performance::*;
+pub use snippet::*;
+pub use content_depth::*;
+pub use search_console::*;
 
 use site_ranker_analyzer::AnalysisResult;
 
@@ -118,6 +130,7 @@ pub enum RecommendationCategory {
     Keywords,
     Schema,
     Performance,
+    Content,
     Social,
     Technical,
 }
@@ -147,6 +160,10 @@ impl MlEngine {
         engine.add(Box::new(SentimentAnalyzer::new()));
         engine.add(Box::new(ContentOptimizer::new()));
         engine.add(Box::new(TrendPredictor::new()));
+        engine.add(Box::new(PerformanceAdvisor::new()));
+        engine.add(Box::new(SnippetAdvisor::new()));
+        engine.add(Box::new(ContentDepthAdvisor::new()));
+        engine.add(Box::new(SearchConsoleAdvisor::new()));
         engine
     }
 
@@ -267,6 +284,16 @@ impl MlEngine {
         }
 
         // Technical recommendations
+        if analysis.rendering.empty_shell {
+            let mount = analysis.rendering.mount_point.as_deref().unwrap_or("<body>");
+            result.recommendations.push(Recommendation {
+                category: RecommendationCategory::Technical,
+                priority: Priority::Critical,
+                message: format!("Page content is client-rendered only (empty {} shell)", mount),
+                action: "Pre-render or server-render content so crawlers see it without JavaScript".to_string(),
+            });
+        }
+
         if analysis.existing_seo.h1_count == 0 {
             result.recommendations.push(Recommendation {
                 category: RecommendationCategory::Technical,
@@ -315,4 +342,21 @@ mod tests {
 
         assert!(!result.recommendations.is_empty());
     }
+
+    #[test]
+    fn test_empty_shell_recommendation() {
+        let mut analysis = AnalysisResult::default();
+        analysis.rendering.empty_shell = true;
+        analysis.rendering.mount_point = Some("#root".to_string());
+
+        let result = MlEngine::default_engine().process(&analysis).unwrap();
+        let shell = result
+            .recommendations
+            .iter()
+            .find(|r| r.message.contains("client-rendered"))
+            .unwrap();
+
+        assert_eq!(shell.priority, Priority::Critical);
+        assert!(shell.message.contains("#root"));
+    }
 }
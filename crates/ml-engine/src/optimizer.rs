@@ -122,7 +122,7 @@ impl ContentOptimizer {
                     "Business".to_string()
                 }
             );
-            if title.len() <= 60 {
+            if title.len() <= self.title_length.1 {
                 suggestions.push(TitleSuggestion {
                     text: title,
                     score: 0.85,
@@ -134,7 +134,7 @@ impl ContentOptimizer {
         // Pattern 2: Question format (curiosity trigger)
         if !site_topic.is_empty() {
             let title = format!("Need {}? Get Expert Help Today", site_topic);
-            if title.len() <= 60 {
+            if title.len() <= self.title_length.1 {
                 suggestions.push(TitleSuggestion {
                     text: title,
                     score: 0.80,
@@ -146,7 +146,7 @@ impl ContentOptimizer {
         // Pattern 3: List/Number format
         if !site_topic.is_empty() {
             let title = format!("Top {} Services | Trusted Experts", site_topic);
-            if title.len() <= 60 {
+            if title.len() <= self.title_length.1 {
                 suggestions.push(TitleSuggestion {
                     text: title,
                     score: 0.75,
@@ -159,7 +159,7 @@ impl ContentOptimizer {
         let year = Utc::now().format("%Y");
         if !site_topic.is_empty() {
             let title = format!("{} Guide {} - Expert Resources", site_topic, year);
-            if title.len() <= 60 {
+            if title.len() <= self.title_length.1 {
                 suggestions.push(TitleSuggestion {
                     text: title,
                     score: 0.78,
@@ -194,7 +194,7 @@ impl ContentOptimizer {
             keywords.get(2).unwrap_or(&default_proven)
         );
 
-        if desc.len() <= 160 {
+        if desc.len() <= self.description_length.1 {
             suggestions.push(DescriptionSuggestion {
                 text: desc,
                 score: 0.90,
@@ -210,7 +210,7 @@ impl ContentOptimizer {
             keywords.get(1).unwrap_or(&keywords[0])
         );
 
-        if desc.len() <= 160 {
+        if desc.len() <= self.description_length.1 {
             suggestions.push(DescriptionSuggestion {
                 text: desc,
                 score: 0.85,
@@ -226,7 +226,7 @@ impl ContentOptimizer {
             capitalize(keywords.get(1).unwrap_or(&keywords[0]))
         );
 
-        if desc.len() <= 160 {
+        if desc.len() <= self.description_length.1 {
             suggestions.push(DescriptionSuggestion {
                 text: desc,
                 score: 0.82,
@@ -304,4 +304,36 @@ mod tests {
 
         assert!(!result.title_suggestions.is_empty());
     }
+
+    #[test]
+    fn test_title_suggestions_fit_title_length() {
+        let keyword = |word: &str, score| Keyword {
+            word: word.to_string(),
+            frequency: 5,
+            score,
+            is_phrase: false,
+        };
+        let analysis = AnalysisResult {
+            keywords: vec![
+                keyword("basement-waterproofing", 0.9),
+                keyword("drains", 0.8),
+            ],
+            ..Default::default()
+        };
+        let titles = |optimizer: ContentOptimizer| -> Vec<String> {
+            let result = optimizer.process(&analysis).unwrap();
+            result.title_suggestions.into_iter().map(|t| t.text).collect()
+        };
+
+        // 50 to 54 characters: all fit the default 60
+        assert_eq!(titles(ContentOptimizer::new()).len(), 4);
+        let short = titles(ContentOptimizer {
+            title_length: (30, 50),
+            ..ContentOptimizer::new()
+        });
+        assert_eq!(
+            short,
+            vec!["Need Basement-waterproofing? Get Expert Help Today"]
+        );
+    }
 }
@@ -0,0 +1,279 @@
+//! Static performance findings
+//!
+//! Turns the analyzer's page measurements into `Performance` recommendations.
+//! Thresholds follow Lighthouse's DOM size audit and common page weight
+//! budgets; each finding names the worst pages rather than repeating per page.
+
+use crate::{
+    MlEngineError, MlResult, MlStrategy, Priority, Recommendation, RecommendationCategory,
+};
+use site_ranker_analyzer::{AnalysisResult, AssetKind, PagePerformance};
+
+/// Lighthouse flags pages above this many elements
+const MAX_DOM_NODES: u32 = 1400;
+const MAX_DOM_DEPTH: u32 = 32;
+
+/// Visible text below this share of the HTML reads as boilerplate-heavy
+const MIN_TEXT_RATIO: f32 = 0.10;
+
+/// HTML smaller than this is too short for the text ratio to mean anything
+const MIN_RATIO_HTML_BYTES: u64 = 10 * 1024;
+
+const MAX_INLINE_SCRIPT_BYTES: u64 = 20 * 1024;
+const MAX_INLINE_STYLE_BYTES: u64 = 20 * 1024;
+
+/// Local asset budgets per page, in bytes
+const ASSET_BUDGETS: [(AssetKind, &str, u64); 4] = [
+    (AssetKind::Js, "JavaScript", 300 * 1024),
+    (AssetKind::Css, "CSS", 100 * 1024),
+    (AssetKind::Font, "Font", 200 * 1024),
+    (AssetKind::Image, "Image", 2 * 1024 * 1024),
+];
+
+/// Pages named in a finding before the rest are summarized as a count
+const MAX_PAGES_LISTED: usize = 3;
+
+/// Advisor for static performance findings
+pub struct PerformanceAdvisor;
+
+impl PerformanceAdvisor {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for PerformanceAdvisor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MlStrategy for PerformanceAdvisor {
+    fn name(&self) -> &'static str {
+        "performance_advisor"
+    }
+
+    fn process(&self, analysis: &AnalysisResult) -> Result<MlResult, MlEngineError> {
+        let pages = &analysis.performance;
+        let mut recommendations = Vec::new();
+        let mut finding = |priority, message: String, action: &str| {
+            recommendations.push(Recommendation {
+                category: RecommendationCategory::Performance,
+                priority,
+                message,
+                action: action.to_string(),
+            })
+        };
+
+        let blocking = failing(pages, |p| p.render_blocking_scripts.len() as u64, 0);
+        if !blocking.is_empty() {
+            let scripts: u64 = blocking.iter().map(|(_, n)| n).sum();
+            finding(
+                Priority::High,
+                format!("{} render-blocking scripts in <head> on {}", scripts, page_list(&blocking)),
+                "Add `defer` (or `async` for independent scripts) so the page renders before they load",
+            );
+        }
+
+        let large_dom = failing(pages, |p| p.dom_nodes as u64, MAX_DOM_NODES as u64);
+        if !large_dom.is_empty() {
+            finding(
+                Priority::Medium,
+                format!("Excessive DOM size (elements) on {}", page_list(&large_dom)),
+                "Keep pages under 1,400 elements: paginate long lists and remove wrapper elements",
+            );
+        }
+
+        let deep_dom = failing(pages, |p| p.max_depth as u64, MAX_DOM_DEPTH as u64);
+        if !deep_dom.is_empty() {
+            finding(
+                Priority::Low,
+                format!(
+                    "DOM nested deeper than {} levels on {}",
+                    MAX_DOM_DEPTH,
+                    page_list(&deep_dom)
+                ),
+                "Flatten nested wrapper elements to speed up style and layout",
+            );
+        }
+
+        let thin_text: Vec<_> = pages
+            .iter()
+            .filter(|p| p.html_bytes >= MIN_RATIO_HTML_BYTES && p.text_ratio() < MIN_TEXT_RATIO)
+            .map(|p| (p, (p.text_ratio() * 100.0).round() as u64))
+            .collect();
+        if !thin_text.is_empty() {
+            finding(
+                Priority::Low,
+                format!(
+                    "Low text-to-HTML ratio (% of markup) on {}",
+                    page_list(&thin_text)
+                ),
+                "Move inline scripts, styles and SVGs to external files and add crawlable text",
+            );
+        }
+
+        let inline_scripts = failing(pages, |p| p.inline_script_bytes, MAX_INLINE_SCRIPT_BYTES);
+        if !inline_scripts.is_empty() {
+            finding(
+                Priority::Medium,
+                format!(
+                    "Inline script bloat (bytes) on {}",
+                    page_list(&inline_scripts)
+                ),
+                "Move large inline scripts to cacheable external files",
+            );
+        }
+
+        let inline_styles = failing(pages, |p| p.inline_style_bytes, MAX_INLINE_STYLE_BYTES);
+        if !inline_styles.is_empty() {
+            finding(
+                Priority::Medium,
+                format!(
+                    "Inline style bloat (bytes) on {}",
+                    page_list(&inline_styles)
+                ),
+                "Inline only critical CSS and load the rest from a stylesheet",
+            );
+        }
+
+        for (kind, label, budget) in ASSET_BUDGETS {
+            let heavy = failing(pages, |p| p.asset_bytes(kind), budget);
+            if !heavy.is_empty() {
+                finding(
+                    Priority::Medium,
+                    format!(
+                        "{} weight over the {} KB budget (bytes) on {}",
+                        label,
+                        budget / 1024,
+                        page_list(&heavy)
+                    ),
+                    match kind {
+                        AssetKind::Js => "Split bundles, drop unused libraries and minify",
+                        AssetKind::Css => "Remove unused CSS and minify stylesheets",
+                        AssetKind::Font => "Subset fonts, serve WOFF2 and limit weights and families",
+                        AssetKind::Image => "Compress images, serve WebP/AVIF and size them to their display dimensions",
+                    },
+                );
+            }
+        }
+
+        Ok(MlResult {
+            recommendations,
+            ..Default::default()
+        })
+    }
+}
+
+/// Pages whose `measure` exceeds `limit`, worst first
+fn failing(
+    pages: &[PagePerformance],
+    measure: impl Fn(&PagePerformance) -> u64,
+    limit: u64,
+) -> Vec<(&PagePerformance, u64)> {
+    let mut failing: Vec<_> = pages
+        .iter()
+        .map(|p| (p, measure(p)))
+        .filter(|(_, value)| *value > limit)
+        .collect();
+    failing.sort_by_key(|(_, value)| std::cmp::Reverse(*value));
+    failing
+}
+
+/// `index.html (1820), about.html (1510), 4 more pages`
+fn page_list(pages: &[(&PagePerformance, u64)]) -> String {
+    let mut names: Vec<String> = pages
+        .iter()
+        .take(MAX_PAGES_LISTED)
+        .map(|(page, value)| {
+            let name = page
+                .page
+                .as_ref()
+                .and_then(|p| p.file_name())
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "page".to_string());
+            format!("{} ({})", name, value)
+        })
+        .collect();
+    if pages.len() > MAX_PAGES_LISTED {
+        names.push(format!("{} more pages", pages.len() - MAX_PAGES_LISTED));
+    }
+    names.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use site_ranker_analyzer::AssetRef;
+    use std::path::PathBuf;
+
+    fn page(name: &str) -> PagePerformance {
+        PagePerformance {
+            page: Some(PathBuf::from(name)),
+            dom_nodes: 200,
+            max_depth: 12,
+            html_bytes: 20_000,
+            text_bytes: 6_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_findings_name_worst_pages() {
+        let mut home = page("index.html");
+        home.render_blocking_scripts =
+            vec!["/js/jquery.js".to_string(), "/js/slider.js".to_string()];
+        home.dom_nodes = 2100;
+        home.assets.push(AssetRef {
+            url: "/js/bundle.js".to_string(),
+            kind: AssetKind::Js,
+            bytes: Some(450 * 1024),
+        });
+        let mut about = page("about.html");
+        about.render_blocking_scripts = vec!["/js/jquery.js".to_string()];
+
+        let analysis = AnalysisResult {
+            performance: vec![about, home, page("contact.html")],
+            ..Default::default()
+        };
+        let recommendations = PerformanceAdvisor::new()
+            .process(&analysis)
+            .unwrap()
+            .recommendations;
+
+        assert!(recommendations
+            .iter()
+            .all(|r| r.category == RecommendationCategory::Performance));
+        let blocking = &recommendations[0];
+        assert_eq!(blocking.priority, Priority::High);
+        assert_eq!(
+            blocking.message,
+            "3 render-blocking scripts in <head> on index.html (2), about.html (1)"
+        );
+        assert!(recommendations
+            .iter()
+            .any(|r| r.message.contains("DOM size") && r.message.contains("index.html (2100)")));
+        assert!(recommendations
+            .iter()
+            .any(|r| r.message.starts_with("JavaScript weight")));
+        assert_eq!(recommendations.len(), 3);
+    }
+
+    #[test]
+    fn test_clean_pages_have_no_findings() {
+        let mut small = page("tiny.html");
+        // Too little HTML for the text ratio to matter
+        small.html_bytes = 2_000;
+        small.text_bytes = 50;
+
+        let analysis = AnalysisResult {
+            performance: vec![page("index.html"), small],
+            ..Default::default()
+        };
+        assert!(PerformanceAdvisor::new()
+            .process(&analysis)
+            .unwrap()
+            .recommendations
+            .is_empty());
+    }
+}
@@ -0,0 +1,266 @@
+//! Search Console findings
+//!
+//! Turns imported clicks, impressions and positions into recommendations
+//! ranked by estimated click upside: the clicks a page or query would get at
+//! the CTR typical for its position, or for a top-3 position, minus the
+//! clicks it gets now. The CTR curve is an average of published
+//! click-through studies for organic results.
+
+use crate::{
+    MlEngineError, MlResult, MlStrategy, Priority, Recommendation, RecommendationCategory,
+};
+use site_ranker_analyzer::{AnalysisResult, SearchPerformance};
+
+/// Typical organic CTR at positions 1-10
+const EXPECTED_CTR: [f32; 10] = [0.28, 0.15, 0.11, 0.08, 0.06, 0.05, 0.04, 0.03, 0.025, 0.02];
+
+/// Typical CTR below the first page
+const BEYOND_FIRST_PAGE_CTR: f32 = 0.01;
+
+/// Pages below this share of the expected CTR need a better snippet
+const LOW_CTR_SHARE: f32 = 0.5;
+
+/// Impressions before a page's CTR is worth judging
+const MIN_PAGE_IMPRESSIONS: u32 = 100;
+
+/// Positions of queries close enough to the top to push
+const STRIKING_DISTANCE: std::ops::RangeInclusive<f32> = 8.0..=20.0;
+
+/// Impressions before a striking-distance query is worth pushing
+const MIN_QUERY_IMPRESSIONS: u32 = 50;
+
+/// Position a pushed query is assumed to reach
+const TARGET_POSITION: f32 = 3.0;
+
+/// Estimated extra clicks for `High` and `Medium` priority
+const HIGH_UPSIDE: f32 = 100.0;
+const MEDIUM_UPSIDE: f32 = 20.0;
+
+/// Pages named in a finding before the rest are summarized as a count
+const MAX_PAGES_LISTED: usize = 3;
+
+/// Advisor for Search Console performance data
+pub struct SearchConsoleAdvisor;
+
+impl SearchConsoleAdvisor {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for SearchConsoleAdvisor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MlStrategy for SearchConsoleAdvisor {
+    fn name(&self) -> &'static str {
+        "search_console_advisor"
+    }
+
+    fn process(&self, analysis: &AnalysisResult) -> Result<MlResult, MlEngineError> {
+        let rows = &analysis.search_performance;
+        let mut ranked: Vec<(f32, Recommendation)> = Vec::new();
+
+        // Page totals with plenty of impressions but few clicks
+        for row in rows.iter().filter(|r| r.query.is_none() && r.url.is_some()) {
+            let expected = expected_ctr(row.position);
+            if row.impressions < MIN_PAGE_IMPRESSIONS || row.ctr >= expected * LOW_CTR_SHARE {
+                continue;
+            }
+            let upside = row.impressions as f32 * (expected - row.ctr);
+            ranked.push((
+                upside,
+                Recommendation {
+                    category: RecommendationCategory::Title,
+                    priority: priority(upside),
+                    message: format!(
+                        "Low CTR on {}: {:.1}% at position {:.1} from {} impressions (~{} more clicks)",
+                        page_name(row),
+                        row.ctr * 100.0,
+                        row.position,
+                        row.impressions,
+                        upside.round()
+                    ),
+                    action: format!(
+                        "Rewrite the title and meta description around the queries it ranks for; results at this position average {:.0}% CTR",
+                        expected * 100.0
+                    ),
+                },
+            ));
+        }
+
+        // Queries just off the top of the first page
+        for row in rows.iter().filter(|r| r.query.is_some()) {
+            if row.impressions < MIN_QUERY_IMPRESSIONS || !STRIKING_DISTANCE.contains(&row.position)
+            {
+                continue;
+            }
+            let upside =
+                row.impressions as f32 * (expected_ctr(TARGET_POSITION) - row.ctr).max(0.0);
+            let query = row.query.as_deref().unwrap_or_default();
+            ranked.push((
+                upside,
+                Recommendation {
+                    category: RecommendationCategory::Keywords,
+                    priority: priority(upside),
+                    message: format!(
+                        "\"{}\" ranks at {:.1} with {} impressions (~{} more clicks in the top 3)",
+                        query,
+                        row.position,
+                        row.impressions,
+                        upside.round()
+                    ),
+                    action: match row.url {
+                        Some(_) => format!(
+                            "Strengthen {} for \"{}\": cover it in a heading and the opening copy, answer related questions and link to the page from related pages",
+                            page_name(row),
+                            query
+                        ),
+                        None => format!(
+                            "Strengthen the page ranking for \"{}\": cover it in a heading and the opening copy, answer related questions and link to the page from related pages",
+                            query
+                        ),
+                    },
+                },
+            ));
+        }
+
+        ranked.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        let mut recommendations: Vec<Recommendation> = ranked.into_iter().map(|(_, r)| r).collect();
+
+        // Analyzed pages the export has no impressions for
+        let unseen: Vec<&SearchPerformance> = rows
+            .iter()
+            .filter(|r| r.page.is_some() && r.query.is_none() && r.impressions == 0)
+            .collect();
+        if !unseen.is_empty() {
+            let mut names: Vec<String> = unseen
+                .iter()
+                .take(MAX_PAGES_LISTED)
+                .map(|r| page_name(r))
+                .collect();
+            if unseen.len() > MAX_PAGES_LISTED {
+                names.push(format!("{} more pages", unseen.len() - MAX_PAGES_LISTED));
+            }
+            recommendations.push(Recommendation {
+                category: RecommendationCategory::Technical,
+                priority: Priority::Medium,
+                message: format!("No search impressions for {}", names.join(", ")),
+                action: "Check the pages are indexable and linked internally, then request indexing in Search Console".to_string(),
+            });
+        }
+
+        Ok(MlResult {
+            recommendations,
+            ..Default::default()
+        })
+    }
+}
+
+/// Typical CTR at `position`
+fn expected_ctr(position: f32) -> f32 {
+    let rank = position.round().max(1.0) as usize;
+    EXPECTED_CTR
+        .get(rank - 1)
+        .copied()
+        .unwrap_or(BEYOND_FIRST_PAGE_CTR)
+}
+
+fn priority(upside: f32) -> Priority {
+    if upside >= HIGH_UPSIDE {
+        Priority::High
+    } else if upside >= MEDIUM_UPSIDE {
+        Priority::Medium
+    } else {
+        Priority::Low
+    }
+}
+
+/// URL path of the row's page, `https://acme.com/blog/drains` as `/blog/drains`
+fn page_name(row: &SearchPerformance) -> String {
+    match row.url.as_deref() {
+        Some(url) => match url.split_once("://") {
+            Some((_, rest)) => rest.find('/').map_or("/", |i| &rest[i..]).to_string(),
+            None => url.to_string(),
+        },
+        None => row
+            .page
+            .as_ref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| "page".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn row(
+        url: Option<&str>,
+        query: Option<&str>,
+        clicks: u32,
+        impressions: u32,
+        position: f32,
+    ) -> SearchPerformance {
+        SearchPerformance {
+            page: url.map(|_| PathBuf::from("/site/page.html")),
+            url: url.map(str::to_string),
+            query: query.map(str::to_string),
+            clicks,
+            impressions,
+            ctr: if impressions > 0 {
+                clicks as f32 / impressions as f32
+            } else {
+                0.0
+            },
+            position,
+        }
+    }
+
+    #[test]
+    fn test_ranked_by_click_upside() {
+        let analysis = AnalysisResult {
+            search_performance: vec![
+                // 1% at position 2 where 15% is typical: ~1,260 clicks
+                row(Some("https://acme.com/services/"), None, 90, 9000, 2.1),
+                // Healthy CTR
+                row(Some("https://acme.com/"), None, 300, 2000, 1.4),
+                // Position 12, 4,000 impressions: ~420 clicks in the top 3
+                row(None, Some("emergency plumber"), 20, 4000, 12.3),
+                // Ranked too low to push, and too few impressions
+                row(None, Some("plumber jobs"), 0, 800, 34.0),
+                row(None, Some("drain snake"), 0, 20, 9.0),
+                row(Some("/about.html"), None, 0, 0, 0.0),
+            ],
+            ..Default::default()
+        };
+        let recommendations = SearchConsoleAdvisor::new()
+            .process(&analysis)
+            .unwrap()
+            .recommendations;
+        let messages: Vec<&str> = recommendations.iter().map(|r| r.message.as_str()).collect();
+
+        assert_eq!(
+            messages,
+            [
+                "Low CTR on /services/: 1.0% at position 2.1 from 9000 impressions (~1260 more clicks)",
+                "\"emergency plumber\" ranks at 12.3 with 4000 impressions (~420 more clicks in the top 3)",
+                "No search impressions for /about.html",
+            ]
+        );
+        assert_eq!(recommendations[0].category, RecommendationCategory::Title);
+        assert_eq!(recommendations[1].priority, Priority::High);
+    }
+
+    #[test]
+    fn test_no_data_no_findings() {
+        assert!(SearchConsoleAdvisor::new()
+            .process(&AnalysisResult::default())
+            .unwrap()
+            .recommendations
+            .is_empty());
+    }
+}
@@ -230,9 +230,10 @@ impl MlStrategy for SentimentAnalyzer {
         let combined_text = format!("{} {} {}", title, description, text);
         let sentiment = self.analyze_text(&combined_text);
 
-        let mut result = MlResult::default();
-        result.sentiment = Some(sentiment);
-        Ok(result)
+        Ok(MlResult {
+            sentiment: Some(sentiment),
+            ..Default::default()
+        })
     }
 }
 
@@ -0,0 +1,123 @@
+//! Featured-snippet findings
+//!
+//! Turns the analyzer's question headings into `Content` recommendations,
+//! one per question whose answer isn't shaped for an answer box yet. The
+//! message carries the readiness score, the action the restructuring hints.
+
+use crate::{
+    MlEngineError, MlResult, MlStrategy, Priority, Recommendation, RecommendationCategory,
+};
+use site_ranker_analyzer::{AnalysisResult, SnippetCandidate};
+
+/// Answers scoring below this are unlikely to be picked up at all
+const LOW_READINESS: u32 = 50;
+
+/// Advisor for featured-snippet readiness
+pub struct SnippetAdvisor;
+
+impl SnippetAdvisor {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for SnippetAdvisor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MlStrategy for SnippetAdvisor {
+    fn name(&self) -> &'static str {
+        "snippet_advisor"
+    }
+
+    fn process(&self, analysis: &AnalysisResult) -> Result<MlResult, MlEngineError> {
+        let recommendations = analysis
+            .snippets
+            .iter()
+            .filter(|snippet| !snippet.hints.is_empty())
+            .map(|snippet| Recommendation {
+                category: RecommendationCategory::Content,
+                priority: if snippet.score < LOW_READINESS {
+                    Priority::Medium
+                } else {
+                    Priority::Low
+                },
+                message: format!(
+                    "Featured snippet readiness {}/100 for \"{}\"{}",
+                    snippet.score,
+                    snippet.question,
+                    page_suffix(snippet)
+                ),
+                action: snippet.hints.join("; "),
+            })
+            .collect();
+
+        Ok(MlResult {
+            recommendations,
+            ..Default::default()
+        })
+    }
+}
+
+/// ` on faq.html`, empty for single-page analyses
+fn page_suffix(snippet: &SnippetCandidate) -> String {
+    snippet
+        .page
+        .as_ref()
+        .and_then(|p| p.file_name())
+        .map(|n| format!(" on {}", n.to_string_lossy()))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use site_ranker_analyzer::SnippetFormat;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_recommendations_for_unready_answers() {
+        let ready = SnippetCandidate {
+            question: "What is a backflow preventer?".to_string(),
+            format: SnippetFormat::Paragraph,
+            answer_words: 48,
+            score: 100,
+            ..Default::default()
+        };
+        let missing = SnippetCandidate {
+            question: "Can I pay online?".to_string(),
+            page: Some(PathBuf::from("site/faq.html")),
+            hints: vec!["Answer directly below the heading".to_string()],
+            ..Default::default()
+        };
+        let long = SnippetCandidate {
+            question: "Why hire a plumber?".to_string(),
+            format: SnippetFormat::Paragraph,
+            answer_words: 90,
+            score: 70,
+            hints: vec!["Tighten the first paragraph".to_string()],
+            ..Default::default()
+        };
+
+        let analysis = AnalysisResult {
+            snippets: vec![ready, missing, long],
+            ..Default::default()
+        };
+        let recommendations = SnippetAdvisor::new()
+            .process(&analysis)
+            .unwrap()
+            .recommendations;
+
+        assert_eq!(recommendations.len(), 2);
+        assert_eq!(recommendations[0].category, RecommendationCategory::Content);
+        assert_eq!(recommendations[0].priority, Priority::Medium);
+        assert_eq!(
+            recommendations[0].message,
+            "Featured snippet readiness 0/100 for \"Can I pay online?\" on faq.html"
+        );
+        assert_eq!(recommendations[1].priority, Priority::Low);
+        assert_eq!(recommendations[1].action, "Tighten the first paragraph");
+    }
+}
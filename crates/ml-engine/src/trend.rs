@@ -219,11 +219,18 @@ impl MlStrategy for TrendPredictor {
 
         // Check if FAQPage would be beneficial
         if applicable_trends.iter().any(|t| t.schema_type == "FAQPage") {
+            let action = match analysis.faq.len() {
+                0 => "Add FAQ section with FAQPage structured data".to_string(),
+                n => format!(
+                    "Mark up the {} Q&A pairs found on the site with FAQPage structured data",
+                    n
+                ),
+            };
             recommendations.push(Recommendation {
                 category: RecommendationCategory::Schema,
                 priority: Priority::High,
                 message: "FAQPage schema is trending - 30%+ CTR increase potential".to_string(),
-                action: "Add FAQ section with FAQPage structured data".to_string(),
+                action,
             });
         }
 